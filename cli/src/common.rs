@@ -11,6 +11,8 @@ use gpiocdev::chip::{chips, is_chip, Chip};
 use gpiocdev::line::{Bias, Drive, EdgeDetection};
 use gpiocdev::request::Config;
 use gpiocdev::AbiVersion;
+#[cfg(feature = "serde")]
+use serde_derive::Deserialize;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
@@ -54,18 +56,19 @@ pub fn actual_abi_version(_opts: &UapiOpts) -> Result<AbiVersion> {
     Ok(AbiVersion::V2)
 }
 
+// The path of the chip identified by number, name or path.
+//
+// Numbers and names map to paths in /dev, as per Chip::from_number() and
+// Chip::from_name(), but without opening the chip.
 fn chip_path_from_id(id: &str) -> PathBuf {
-    if id.chars().all(char::is_numeric) {
-        // from number
-        return format!("/dev/gpiochip{}", id).into();
+    if !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()) {
+        if let Ok(n) = id.parse::<u32>() {
+            return format!("/dev/gpiochip{}", n).into();
+        }
     }
-    if !id.chars().any(|x| x == '/') {
-        // from name
-        let mut p: PathBuf = "/dev".into();
-        p.push(id);
-        return p;
+    if !id.contains('/') {
+        return Path::new("/dev").join(id);
     }
-    // from raw path
     id.into()
 }
 
@@ -217,6 +220,8 @@ impl ActiveLowOpts {
 }
 
 #[derive(Clone, Copy, Debug, ValueEnum)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 pub enum BiasFlags {
     PullUp,
     PullDown,
@@ -249,6 +254,8 @@ impl BiasOpts {
 }
 
 #[derive(Clone, Copy, Debug, ValueEnum)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 pub enum DriveFlags {
     PushPull,
     OpenDrain,
@@ -285,6 +292,8 @@ impl DriveOpts {
 }
 
 #[derive(Clone, Copy, Debug, ValueEnum)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 pub enum EdgeFlags {
     Rising,
     Falling,
@@ -357,8 +366,8 @@ pub fn stringify_attrs(li: &gpiocdev::line::Info, quoted: bool) -> String {
         Some(EventClock::Hte) => attrs.push("event-clock=hte"),
     }
     let db;
-    if li.debounce_period.is_some() {
-        db = format!("debounce-period={:?}", li.debounce_period.unwrap());
+    if let Some(dp) = li.debounce_period {
+        db = format!("debounce-period={:?}", dp);
         attrs.push(&db);
     }
     let consumer;
@@ -452,4 +461,24 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn chip_path_from_id() {
+        use super::chip_path_from_id;
+
+        assert_eq!(chip_path_from_id("3"), PathBuf::from("/dev/gpiochip3"));
+        assert_eq!(chip_path_from_id("03"), PathBuf::from("/dev/gpiochip3"));
+        assert_eq!(
+            chip_path_from_id("gpiochip2"),
+            PathBuf::from("/dev/gpiochip2")
+        );
+        assert_eq!(
+            chip_path_from_id("/dev/gpiochip1"),
+            PathBuf::from("/dev/gpiochip1")
+        );
+        assert_eq!(
+            chip_path_from_id("./gpiochip1"),
+            PathBuf::from("./gpiochip1")
+        );
+    }
 }
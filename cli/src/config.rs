@@ -0,0 +1,547 @@
+// SPDX-FileCopyrightText: 2024 Kent Gibson <warthog618@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::common::{self, emit_error, BiasFlags, DriveFlags, EdgeFlags, LineOpts};
+use anyhow::{anyhow, bail, Result};
+use clap::{Parser, Subcommand};
+use gpiocdev::line::{Bias, Direction, Drive, EdgeDetection, Offset, Value};
+use gpiocdev::request::{Config, Request};
+use gpiocdev::AbiVersion;
+use serde_derive::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+
+#[derive(Debug, Parser)]
+pub struct Opts {
+    #[command(subcommand)]
+    cmd: Action,
+}
+
+#[derive(Debug, Subcommand)]
+enum Action {
+    /// Request and configure the lines described in a configuration file.
+    Apply(ApplyOpts),
+
+    /// Validate a configuration file without requesting any lines.
+    Check(CheckOpts),
+}
+
+#[derive(Debug, Parser)]
+struct ApplyOpts {
+    /// The configuration file
+    ///
+    /// The file format is determined by the extension, either '.toml' or '.json'.
+    #[arg(value_name = "file")]
+    file: PathBuf,
+
+    /// Hold the requested lines until the process is killed
+    ///
+    /// If not specified then the lines are released once the configuration has
+    /// been applied.
+    /// Output values only persist after release if the driver retains them,
+    /// so use --hold to guarantee the output values are maintained.
+    #[arg(long)]
+    hold: bool,
+
+    /// The consumer label applied to requested lines.
+    #[arg(
+        short = 'C',
+        long,
+        value_name = "name",
+        default_value = "gpiocdev-config"
+    )]
+    consumer: String,
+
+    #[command(flatten)]
+    uapi_opts: common::UapiOpts,
+
+    #[command(flatten)]
+    emit: common::EmitOpts,
+}
+
+#[derive(Debug, Parser)]
+struct CheckOpts {
+    /// The configuration file
+    ///
+    /// The file format is determined by the extension, either '.toml' or '.json'.
+    #[arg(value_name = "file")]
+    file: PathBuf,
+
+    #[command(flatten)]
+    uapi_opts: common::UapiOpts,
+
+    #[command(flatten)]
+    emit: common::EmitOpts,
+}
+
+pub fn cmd(opts: &Opts) -> bool {
+    match &opts.cmd {
+        Action::Apply(opts) => apply(opts),
+        Action::Check(opts) => check(opts),
+    }
+}
+
+fn apply(opts: &ApplyOpts) -> bool {
+    let plan = match Plan::from_file(&opts.file, &opts.uapi_opts) {
+        Ok(plan) => plan,
+        Err(errs) => {
+            for e in errs {
+                emit_error(&opts.emit, &e);
+            }
+            return false;
+        }
+    };
+    let mut requests = Vec::new();
+    for cp in plan.chips {
+        let mut bld = Request::from_config(cp.cfg);
+        bld.on_chip(&cp.path).with_consumer(&opts.consumer);
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        bld.using_abi_version(plan.abiv);
+        match bld.request() {
+            Ok(req) => requests.push(req),
+            Err(e) => {
+                emit_error(
+                    &opts.emit,
+                    &anyhow!(e).context(format!("failed to request lines on {}", cp.name)),
+                );
+                return false;
+            }
+        }
+    }
+    if opts.hold {
+        loop {
+            thread::park();
+        }
+    }
+    true
+}
+
+fn check(opts: &CheckOpts) -> bool {
+    match Plan::from_file(&opts.file, &opts.uapi_opts) {
+        Ok(_) => true,
+        Err(errs) => {
+            for e in errs {
+                emit_error(&opts.emit, &e);
+            }
+            false
+        }
+    }
+}
+
+/// The top level of a configuration file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct File {
+    #[serde(default)]
+    lines: Vec<Entry>,
+}
+
+/// The configuration for a single line.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct Entry {
+    /// The line name, or offset if the chip is specified.
+    line: LineId,
+    /// The chip containing the line.
+    ///
+    /// If not specified then the line is searched for on all chips.
+    chip: Option<String>,
+    direction: Option<DirectionFlags>,
+    value: Option<ValueSpec>,
+    #[serde(default)]
+    active_low: bool,
+    bias: Option<BiasFlags>,
+    drive: Option<DriveFlags>,
+    edges: Option<EdgeFlags>,
+    debounce_period: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum LineId {
+    Offset(Offset),
+    Name(String),
+}
+
+impl fmt::Display for LineId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LineId::Offset(offset) => write!(f, "{offset}"),
+            LineId::Name(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum DirectionFlags {
+    Input,
+    Output,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ValueSpec {
+    Bool(bool),
+    Number(u64),
+    Word(String),
+}
+
+impl ValueSpec {
+    fn value(&self) -> Option<Value> {
+        match self {
+            ValueSpec::Bool(false) | ValueSpec::Number(0) => Some(Value::Inactive),
+            ValueSpec::Bool(true) | ValueSpec::Number(1) => Some(Value::Active),
+            ValueSpec::Number(_) => None,
+            ValueSpec::Word(w) => match w.to_lowercase().as_str() {
+                "0" | "inactive" | "off" | "false" => Some(Value::Inactive),
+                "1" | "active" | "on" | "true" => Some(Value::Active),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl Entry {
+    // Convert the entry to a line config, reporting any inconsistencies.
+    fn line_config(&self) -> std::result::Result<gpiocdev::line::Config, String> {
+        let direction = match self.direction {
+            Some(DirectionFlags::Input) => Direction::Input,
+            Some(DirectionFlags::Output) => Direction::Output,
+            None if self.value.is_some() => Direction::Output,
+            None => Direction::Input,
+        };
+        let mut lc = gpiocdev::line::Config {
+            direction: Some(direction),
+            active_low: self.active_low,
+            bias: self.bias.map(Bias::from),
+            ..Default::default()
+        };
+        match direction {
+            Direction::Output => {
+                if self.edges.is_some() {
+                    return Err("edges requires input direction".into());
+                }
+                if self.debounce_period.is_some() {
+                    return Err("debounce-period requires input direction".into());
+                }
+                lc.drive = Some(self.drive.map_or(Drive::PushPull, Drive::from));
+                lc.value = match &self.value {
+                    Some(vs) => Some(
+                        vs.value()
+                            .ok_or_else(|| format!("invalid value '{}'", vs))?,
+                    ),
+                    None => Some(Value::Inactive),
+                };
+            }
+            Direction::Input => {
+                if self.value.is_some() {
+                    return Err("value requires output direction".into());
+                }
+                if self.drive.is_some() {
+                    return Err("drive requires output direction".into());
+                }
+                lc.edge_detection = self.edges.map(EdgeDetection::from);
+                if let Some(dp) = &self.debounce_period {
+                    lc.debounce_period =
+                        Some(common::parse_duration(dp).map_err(|e| e.to_string())?);
+                }
+            }
+        }
+        Ok(lc)
+    }
+}
+
+impl fmt::Display for ValueSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValueSpec::Bool(b) => write!(f, "{b}"),
+            ValueSpec::Number(n) => write!(f, "{n}"),
+            ValueSpec::Word(w) => write!(f, "{w}"),
+        }
+    }
+}
+
+fn load(path: &Path) -> Result<File> {
+    let s = fs::read_to_string(path)
+        .map_err(|e| anyhow!("unable to read '{}': {}", path.display(), e))?;
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => {
+            toml::from_str(&s).map_err(|e| anyhow!("unable to parse '{}': {}", path.display(), e))
+        }
+        #[cfg(feature = "json")]
+        Some("json") => serde_json::from_str(&s)
+            .map_err(|e| anyhow!("unable to parse '{}': {}", path.display(), e)),
+        _ => bail!(
+            "'{}' has an unsupported file type - use '.toml' or '.json'",
+            path.display()
+        ),
+    }
+}
+
+/// The lines to be requested from a chip.
+struct ChipPlan {
+    path: PathBuf,
+    name: String,
+    cfg: Config,
+}
+
+/// The requests required to apply a configuration file.
+struct Plan {
+    #[cfg_attr(not(all(feature = "uapi_v1", feature = "uapi_v2")), allow(dead_code))]
+    abiv: AbiVersion,
+    chips: Vec<ChipPlan>,
+}
+
+impl Plan {
+    /// Load and validate a configuration file, resolving the lines to be requested.
+    ///
+    /// Returns all the problems found with the file, not just the first.
+    fn from_file(
+        path: &Path,
+        uapi_opts: &common::UapiOpts,
+    ) -> std::result::Result<Plan, Vec<anyhow::Error>> {
+        let file = load(path).map_err(|e| vec![e])?;
+        let mut errs = Vec::new();
+        let entry_err = |idx: usize, entry: &Entry, msg: &str| {
+            anyhow!(
+                "{}: lines[{}] '{}': {}",
+                path.display(),
+                idx,
+                entry.line,
+                msg
+            )
+        };
+
+        let mut lcfgs = Vec::new();
+        for (idx, entry) in file.lines.iter().enumerate() {
+            match entry.line_config() {
+                Ok(lc) => lcfgs.push(Some(lc)),
+                Err(msg) => {
+                    errs.push(entry_err(idx, entry, &msg));
+                    lcfgs.push(None);
+                }
+            }
+        }
+
+        // resolve the lines for each distinct chip selection
+        let mut groups: BTreeMap<Option<&str>, Vec<String>> = BTreeMap::new();
+        for entry in &file.lines {
+            let ids = groups.entry(entry.chip.as_deref()).or_default();
+            let id = entry.line.to_string();
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+        let mut abiv = AbiVersion::V2;
+        let mut located = BTreeMap::new();
+        for (chip, ids) in groups {
+            let line_opts = LineOpts {
+                chip: chip.map(String::from),
+                strict: true,
+                by_name: false,
+            };
+            let r = common::Resolver::resolve_lines(&ids, &line_opts, uapi_opts);
+            for e in r.errors {
+                // duplicates are reported below, identifying the entries
+                if let Some(common::Error::DuplicateLine(..)) = e.downcast_ref() {
+                    continue;
+                }
+                errs.push(anyhow!("{}: {}", path.display(), e));
+            }
+            abiv = r.abiv;
+            for (id, co) in r.lines {
+                let ci = &r.chips[co.chip_idx];
+                located.insert((chip, id), (ci.path.clone(), ci.name.clone(), co.offset));
+            }
+        }
+
+        // group the lines by chip, checking for duplicates
+        let mut chips: BTreeMap<PathBuf, (String, Config)> = BTreeMap::new();
+        let mut owners: BTreeMap<(PathBuf, Offset), usize> = BTreeMap::new();
+        for (idx, entry) in file.lines.iter().enumerate() {
+            let key = (entry.chip.as_deref(), entry.line.to_string());
+            let (Some((cpath, cname, offset)), Some(lc)) = (located.get(&key), &lcfgs[idx]) else {
+                continue;
+            };
+            if let Some(first) = owners.insert((cpath.clone(), *offset), idx) {
+                errs.push(entry_err(
+                    idx,
+                    entry,
+                    &format!("duplicates lines[{}] '{}'", first, file.lines[first].line),
+                ));
+                continue;
+            }
+            chips
+                .entry(cpath.clone())
+                .or_insert_with(|| (cname.clone(), Config::default()))
+                .1
+                .with_line(*offset)
+                .from_line_config(lc);
+        }
+
+        let chips: Vec<ChipPlan> = chips
+            .into_iter()
+            .map(|(path, (name, cfg))| ChipPlan { path, name, cfg })
+            .collect();
+        for cp in &chips {
+            if let Err(e) = check_limits(&cp.cfg, abiv) {
+                errs.push(anyhow!("{}: lines on {}: {}", path.display(), cp.name, e));
+            }
+        }
+        if !errs.is_empty() {
+            return Err(errs);
+        }
+        Ok(Plan { abiv, chips })
+    }
+}
+
+// Check that the request for a chip falls within the limits of the uAPI.
+//
+// Uses the same validation as when the lines are requested, so the dry run
+// cannot drift from the library.
+#[cfg_attr(
+    not(all(feature = "uapi_v1", feature = "uapi_v2")),
+    allow(unused_variables)
+)]
+fn check_limits(cfg: &Config, abiv: AbiVersion) -> gpiocdev::Result<()> {
+    let mut bld = Request::from_config(cfg.clone());
+    #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+    bld.using_abi_version(abiv);
+    bld.check()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(toml: &str) -> Entry {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        let f: File = toml::from_str(
+            r#"
+            [[lines]]
+            line = "LED0"
+            value = "on"
+            drive = "open-drain"
+
+            [[lines]]
+            chip = "gpiochip0"
+            line = 3
+            bias = "pull-up"
+            edges = "both"
+            debounce-period = "5ms"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(f.lines.len(), 2);
+        assert_eq!(f.lines[0].line.to_string(), "LED0");
+        assert_eq!(f.lines[1].line.to_string(), "3");
+        assert_eq!(f.lines[1].chip.as_deref(), Some("gpiochip0"));
+
+        let err = toml::from_str::<File>("[[lines]]\nline = \"LED0\"\ncolour = \"red\"\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("line 3"), "{err}");
+        assert!(err.contains("colour"), "{err}");
+    }
+
+    #[test]
+    fn line_config() {
+        let lc = entry("line = \"LED0\"\nvalue = true\n")
+            .line_config()
+            .unwrap();
+        assert_eq!(lc.direction, Some(Direction::Output));
+        assert_eq!(lc.drive, Some(Drive::PushPull));
+        assert_eq!(lc.value, Some(Value::Active));
+
+        let lc = entry("line = \"LED0\"\ndirection = \"output\"\n")
+            .line_config()
+            .unwrap();
+        assert_eq!(lc.value, Some(Value::Inactive));
+
+        let lc = entry(
+            "line = \"BTN\"\nactive-low = true\nedges = \"falling\"\ndebounce-period = \"10ms\"\n",
+        )
+        .line_config()
+        .unwrap();
+        assert_eq!(lc.direction, Some(Direction::Input));
+        assert!(lc.active_low);
+        assert_eq!(lc.edge_detection, Some(EdgeDetection::FallingEdge));
+        assert_eq!(
+            lc.debounce_period,
+            Some(std::time::Duration::from_millis(10))
+        );
+
+        let e = entry("line = \"LED0\"\nvalue = 2\n").line_config();
+        assert_eq!(e.unwrap_err(), "invalid value '2'");
+        let e = entry("line = \"LED0\"\ndirection = \"input\"\nvalue = 1\n").line_config();
+        assert_eq!(e.unwrap_err(), "value requires output direction");
+        let e = entry("line = \"LED0\"\ndrive = \"open-source\"\n").line_config();
+        assert_eq!(e.unwrap_err(), "drive requires output direction");
+        let e = entry("line = \"LED0\"\nvalue = 1\nedges = \"both\"\n").line_config();
+        assert_eq!(e.unwrap_err(), "edges requires input direction");
+        let e = entry("line = \"BTN\"\ndebounce-period = \"5ns\"\n").line_config();
+        assert!(e.is_err());
+    }
+
+    #[test]
+    fn limits() {
+        let mut cfg = Config::default();
+        cfg.with_lines(&[1, 2, 3]).as_input();
+        #[cfg(feature = "uapi_v1")]
+        assert!(check_limits(&cfg, AbiVersion::V1).is_ok());
+        #[cfg(feature = "uapi_v2")]
+        assert!(check_limits(&cfg, AbiVersion::V2).is_ok());
+
+        cfg.with_line(2).as_output(Value::Active);
+        #[cfg(feature = "uapi_v1")]
+        assert!(check_limits(&cfg, AbiVersion::V1).is_err());
+        #[cfg(feature = "uapi_v2")]
+        assert!(check_limits(&cfg, AbiVersion::V2).is_ok());
+
+        let mut cfg = Config::default();
+        cfg.with_lines(&[1, 2])
+            .as_input()
+            .with_edge_detection(EdgeDetection::BothEdges);
+        #[cfg(feature = "uapi_v1")]
+        assert!(check_limits(&cfg, AbiVersion::V1).is_err());
+        #[cfg(feature = "uapi_v2")]
+        assert!(check_limits(&cfg, AbiVersion::V2).is_ok());
+
+        // debounce periods that round to the same microsecond share an attribute
+        let mut cfg = Config::default();
+        for offset in 0..12 {
+            cfg.with_line(offset).as_input().with_debounce_period(
+                std::time::Duration::from_micros(offset as u64 / 2 + 1)
+                    - std::time::Duration::from_nanos(offset as u64 % 2),
+            );
+        }
+        #[cfg(feature = "uapi_v2")]
+        assert!(check_limits(&cfg, AbiVersion::V2).is_ok());
+
+        let mut cfg = Config::default();
+        for offset in 0..12 {
+            cfg.with_line(offset)
+                .as_input()
+                .with_debounce_period(std::time::Duration::from_millis(offset as u64 + 1));
+        }
+        #[cfg(feature = "uapi_v2")]
+        assert_eq!(
+            check_limits(&cfg, AbiVersion::V2).unwrap_err().to_string(),
+            "uAPI ABI v2 supports 10 attrs, configuration requires 12."
+        );
+
+        let offsets: Vec<Offset> = (0..65).collect();
+        let mut cfg = Config::default();
+        cfg.with_lines(&offsets).as_input();
+        assert!(check_limits(&cfg, AbiVersion::V2).is_err());
+    }
+}
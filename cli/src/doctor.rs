@@ -0,0 +1,174 @@
+// SPDX-FileCopyrightText: 2024 Kent Gibson <warthog618@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::common::{self, format_chip_name, format_error, EmitOpts};
+use clap::Parser;
+use gpiocdev::doctor::{self, Finding, Severity};
+use gpiocdev::line::Offset;
+use gpiocdev::request::Config;
+#[cfg(feature = "serde")]
+use serde_derive::Serialize;
+use std::time::Duration;
+
+#[derive(Debug, Parser)]
+pub struct Opts {
+    /// The lines to check
+    ///
+    /// The lines are identified by name or optionally by offset
+    /// if the --chip option is provided.
+    #[arg(value_name = "line", required = true)]
+    line: Vec<String>,
+
+    #[command(flatten)]
+    line_opts: common::LineOpts,
+
+    /// Check the lines as intended to be requested as outputs
+    ///
+    /// By default the lines are checked as inputs.
+    #[arg(short = 'o', long)]
+    output: bool,
+
+    #[command(flatten)]
+    active_low_opts: common::ActiveLowOpts,
+
+    #[command(flatten)]
+    bias_opts: common::BiasOpts,
+
+    /// The debounce period intended for the lines
+    ///
+    /// The period is taken as milliseconds unless otherwise specified.
+    #[arg(short = 'p', long, value_name = "period", value_parser = common::parse_duration)]
+    debounce_period: Option<Duration>,
+
+    #[command(flatten)]
+    uapi_opts: common::UapiOpts,
+
+    #[command(flatten)]
+    emit: common::EmitOpts,
+}
+
+impl Opts {
+    // mutate the config to match the configuration
+    fn apply(&self, config: &mut Config) {
+        if self.output {
+            config.as_output(gpiocdev::line::Value::Inactive);
+        } else {
+            config.as_input();
+        }
+        self.active_low_opts.apply(config);
+        self.bias_opts.apply(config);
+        if let Some(period) = self.debounce_period {
+            config.with_debounce_period(period);
+        }
+    }
+}
+
+pub fn cmd(opts: &Opts) -> bool {
+    let res = do_cmd(opts);
+    res.emit(opts);
+    res.is_success()
+}
+
+fn do_cmd(opts: &Opts) -> CmdResult {
+    let mut res = CmdResult {
+        ..Default::default()
+    };
+    let r = common::Resolver::resolve_lines(&opts.line, &opts.line_opts, &opts.uapi_opts);
+    for e in &r.errors {
+        res.push_error(&opts.emit, e);
+    }
+    let mut seen_lines = Vec::new();
+    for id in &opts.line {
+        if seen_lines.contains(id) {
+            continue;
+        }
+        seen_lines.push(id.clone());
+        let co = match r.lines.get(id) {
+            Some(co) => co,
+            None => continue,
+        };
+        let ci = &r.chips[co.chip_idx];
+        let chip = match common::chip_from_path(&ci.path, r.abiv) {
+            Ok(chip) => chip,
+            Err(e) => {
+                res.push_error(&opts.emit, &e);
+                continue;
+            }
+        };
+        let mut cfg = Config::default();
+        opts.apply(&mut cfg);
+        cfg.with_line(co.offset);
+        let lcfg = cfg.line_config(co.offset).unwrap();
+        res.lines.push(LineFindings {
+            id: id.clone(),
+            chip: ci.name.clone(),
+            offset: co.offset,
+            findings: doctor::check_line(&chip, co.offset, lcfg),
+        });
+    }
+    res
+}
+
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+struct CmdResult {
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    lines: Vec<LineFindings>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    errors: Vec<String>,
+}
+
+impl CmdResult {
+    fn is_success(&self) -> bool {
+        self.errors.is_empty()
+            && self
+                .lines
+                .iter()
+                .flat_map(|l| &l.findings)
+                .all(|f| f.severity < Severity::Error)
+    }
+
+    fn emit(&self, opts: &Opts) {
+        #[cfg(feature = "json")]
+        if opts.emit.json {
+            println!("{}", serde_json::to_string(self).unwrap());
+            return;
+        }
+        self.print(opts);
+    }
+
+    fn push_error(&mut self, opts: &EmitOpts, e: &anyhow::Error) {
+        self.errors.push(format_error(opts, e))
+    }
+
+    fn print(&self, opts: &Opts) {
+        for l in &self.lines {
+            let id = if opts.emit.quoted {
+                format!("\"{}\"", l.id)
+            } else {
+                l.id.clone()
+            };
+            let loc = format!("{} {}", format_chip_name(&l.chip), l.offset);
+            if l.findings.is_empty() {
+                println!("{} ({}): ok", id, loc);
+                continue;
+            }
+            println!("{} ({}):", id, loc);
+            for f in &l.findings {
+                println!("    {}", f);
+            }
+        }
+        for e in &self.errors {
+            eprintln!("{}", e);
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize))]
+struct LineFindings {
+    id: String,
+    chip: String,
+    offset: Offset,
+    findings: Vec<Finding>,
+}
@@ -55,8 +55,11 @@ pub struct Opts {
 
     /// Exit after the specified number of events
     ///
-    /// If not specified then monitoring will continue indefinitely.
-    #[arg(short, long, value_name = "num")]
+    /// If not specified, or 0, then monitoring will continue indefinitely.
+    ///
+    /// May be combined with --idle-timeout, in which case monitoring stops
+    /// when either limit is reached.
+    #[arg(short, long, value_name = "num", visible_alias = "num")]
     num_events: Option<u32>,
 
     /// Specify the source clock for event timestamps
@@ -245,6 +248,7 @@ fn do_cmd(opts: &Opts) -> CmdResult {
         }
     }
     let mut count = 0;
+    let limit = opts.num_events.filter(|n| *n != 0);
     let mut events = Events::with_capacity(r.chips.len());
     let timefmt = opts.timefmt();
     emit_banner(opts);
@@ -266,7 +270,7 @@ fn do_cmd(opts: &Opts) -> CmdResult {
                         match reqs[idx].read_edge_event() {
                             Ok(edge) => {
                                 emit_edge(edge, &r.chips[idx], opts, &timefmt);
-                                if let Some(limit) = opts.num_events {
+                                if let Some(limit) = limit {
                                     count += 1;
                                     if count >= limit {
                                         return res;
@@ -379,14 +383,11 @@ struct Event {
 
 impl Event {
     fn print(&self, ci: &ChipInfo, opts: &Opts) {
-        print!(
-            "{}\t{:09}\t",
-            self.timestamp,
-            event_kind_name(self.edge.kind)
-        );
+        // the event identifies the offset, so only the chip and name are added.
+        print!("{}\t{}\t", self.timestamp, self.edge);
         if let Some(lname) = &self.name {
             if opts.line_opts.chip.is_some() {
-                print!("{} {} ", ci.name, self.edge.offset);
+                print!("{} ", ci.name);
             }
             if opts.emit.quoted {
                 println!("\"{}\"", lname);
@@ -394,22 +395,16 @@ impl Event {
                 println!("{}", lname);
             }
         } else {
-            println!("{} {}", ci.name, self.edge.offset);
+            println!("{}", ci.name);
         }
     }
 }
 
-fn event_kind_name(kind: EdgeKind) -> &'static str {
-    match kind {
-        EdgeKind::Rising => "rising",
-        EdgeKind::Falling => "falling",
-    }
-}
-
 fn event_kind_num(kind: EdgeKind) -> u8 {
     match kind {
         EdgeKind::Rising => 1,
         EdgeKind::Falling => 2,
+        _ => 0,
     }
 }
 
@@ -422,7 +417,7 @@ fn print_edge_formatted(event: &EdgeEvent, format: &str, ci: &ChipInfo) {
                 '%' => print!("%"),
                 'c' => print!("{}", ci.name),
                 'e' => print!("{}", event_kind_num(event.kind)),
-                'E' => print!("{}", event_kind_name(event.kind)),
+                'E' => print!("{}", event.kind),
                 'l' => print!("{}", ci.line_name(&event.offset).unwrap_or("unnamed")),
                 'L' => print!("{}", format_time(event.timestamp_ns, &TimeFmt::Localtime)),
                 'o' => print!("{}", event.offset),
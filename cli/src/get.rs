@@ -31,7 +31,9 @@ pub struct Opts {
     ///
     /// If specified then the line direction is left as-is, making it
     /// possible to read back the values of output lines.
-    #[arg(short, long)]
+    ///
+    /// Cannot be used with --bias, as bias requires the direction to be set.
+    #[arg(short, long, conflicts_with = "bias")]
     as_is: bool,
 
     #[command(flatten)]
@@ -9,6 +9,9 @@ use std::process::ExitCode;
 
 mod chip;
 mod common;
+#[cfg(feature = "config")]
+mod config;
+mod doctor;
 mod edges;
 mod get;
 mod line;
@@ -17,10 +20,18 @@ mod platform;
 mod set;
 
 fn main() -> ExitCode {
+    #[cfg(feature = "test_abi_override")]
+    if let Err(e) = apply_abi_override() {
+        eprintln!("{e:#}");
+        return ExitCode::FAILURE;
+    }
     match Opts::try_parse() {
         Ok(opt) => {
             let res = match opt.cmd {
                 Command::Chip(cfg) => chip::cmd(&cfg),
+                #[cfg(feature = "config")]
+                Command::Config(cfg) => config::cmd(&cfg),
+                Command::Doctor(cfg) => doctor::cmd(&cfg),
                 Command::Edges(cfg) => edges::cmd(&cfg),
                 Command::Get(cfg) => get::cmd(&cfg),
                 Command::Line(cfg) => line::cmd(&cfg),
@@ -39,6 +50,29 @@ fn main() -> ExitCode {
     ExitCode::FAILURE
 }
 
+// The environment variable listing the uAPI versions to force unsupported, e.g. "v2" or "v1,v2".
+#[cfg(feature = "test_abi_override")]
+const ABI_UNSUPPORTED_ENV: &str = "GPIOCDEV_TEST_ABI_UNSUPPORTED";
+
+// Force uAPI versions to appear unsupported, so ABI fallback can be tested.
+//
+// The library additionally requires its ABI override environment variable to be set.
+#[cfg(feature = "test_abi_override")]
+fn apply_abi_override() -> anyhow::Result<()> {
+    let Some(versions) = std::env::var_os(ABI_UNSUPPORTED_ENV) else {
+        return Ok(());
+    };
+    for v in versions.to_string_lossy().split(',') {
+        let abiv = match v.trim().to_ascii_lowercase().as_str() {
+            "v1" => gpiocdev::AbiVersion::V1,
+            "v2" => gpiocdev::AbiVersion::V2,
+            _ => anyhow::bail!("unknown uAPI ABI version '{v}' in {ABI_UNSUPPORTED_ENV}"),
+        };
+        gpiocdev::test::force_abi_unsupported(abiv)?;
+    }
+    Ok(())
+}
+
 #[derive(Parser)]
 #[command(
     name = "gpiocdev",
@@ -60,6 +94,13 @@ enum Command {
     /// Get information about GPIO chips.
     Chip(chip::Opts),
 
+    /// Apply or check a declarative line configuration file.
+    #[cfg(feature = "config")]
+    Config(config::Opts),
+
+    /// Check GPIO lines for common misconfigurations.
+    Doctor(doctor::Opts),
+
     /// Monitor GPIO lines for edge events.
     Edges(edges::Opts),
 
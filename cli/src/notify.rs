@@ -317,15 +317,12 @@ struct Event {
 
 impl Event {
     fn print(&self, ci: &ChipInfo, opts: &Opts) {
-        print!(
-            "{}\t{}\t",
-            self.timestamp,
-            event_kind_name(self.change.kind)
-        );
+        // the event identifies the offset, so only the chip and name are added.
+        print!("{}\t{}\t", self.timestamp, self.change);
 
         if let Some(lname) = ci.line_name(&self.change.info.offset) {
             if opts.line_opts.chip.is_some() {
-                print!("{} {} ", ci.name, self.change.info.offset);
+                print!("{} ", ci.name);
             }
             if opts.emit.quoted {
                 println!("\"{lname}\"");
@@ -333,7 +330,7 @@ impl Event {
                 println!("{lname}");
             }
         } else {
-            println!("{} {}", ci.name, self.change.info.offset);
+            println!("{}", ci.name);
         }
     }
 }
@@ -363,7 +360,7 @@ fn print_change_formatted(event: &InfoChangeEvent, format: &str, ci: &ChipInfo,
                 'c' => print!("{}", ci.name),
                 'C' => print!("{}", format_consumer(&event.info)),
                 'e' => print!("{}", event_kind_num(event.kind)),
-                'E' => print!("{}", event_kind_name(event.kind)),
+                'E' => print!("{}", event.kind),
                 'l' => print!("{}", ci.line_name(&event.info.offset).unwrap_or("unnamed")),
                 'L' => print!(
                     "{}",
@@ -393,19 +390,12 @@ fn print_change_formatted(event: &InfoChangeEvent, format: &str, ci: &ChipInfo,
     println!();
 }
 
-fn event_kind_name(kind: InfoChangeKind) -> &'static str {
-    match kind {
-        InfoChangeKind::Requested => "requested",
-        InfoChangeKind::Released => "released",
-        InfoChangeKind::Reconfigured => "reconfigured",
-    }
-}
-
 fn event_kind_num(kind: InfoChangeKind) -> u8 {
     match kind {
         InfoChangeKind::Requested => 1,
         InfoChangeKind::Released => 2,
         InfoChangeKind::Reconfigured => 3,
+        _ => 0,
     }
 }
 
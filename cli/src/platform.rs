@@ -5,10 +5,10 @@
 use super::common::EmitOpts;
 use clap::Parser;
 use core::fmt;
-use gpiocdev::{AbiSupportKind, AbiVersion};
+use gpiocdev::{platform, AbiSupport, AbiSupportKind, AbiVersion};
 #[cfg(feature = "serde")]
 use serde_derive::Serialize;
-use std::fs;
+
 #[derive(Debug, Parser)]
 #[command(aliases(["p"]))]
 pub struct Opts {
@@ -21,25 +21,22 @@ pub fn cmd(opts: &Opts) -> bool {
         opts: opts.emit,
         ..Default::default()
     };
-    if let Ok(v) = fs::read_to_string("/proc/version") {
-        let mut f = v.split_ascii_whitespace();
-        if let Some(v) = f.nth(2) {
-            p.kernel.version = v.into();
-        }
-    } else {
-        p.kernel.version = "unknown".into();
-    }
-    let versions = [AbiVersion::V1, AbiVersion::V2];
-    for v in versions {
-        match Abi::new(v, &p.kernel.version) {
-            Ok(abi) => {
-                p.abis.push(abi);
-            }
-            Err(e) => {
-                p.errors.push(e.to_string());
-                break;
+    match gpiocdev::platform::summary() {
+        Ok(s) => {
+            p.kernel.version = s.kernel.release;
+            match s.abi_support {
+                Some(support) => {
+                    for v in [AbiVersion::V1, AbiVersion::V2] {
+                        p.abis.push(Abi::new(v, s.features.as_ref(), &support));
+                    }
+                }
+                None => p.errors.push(gpiocdev::Error::NoGpioChips().to_string()),
             }
         }
+        Err(e) => {
+            p.kernel.version = "unknown".into();
+            p.errors.push(e.to_string());
+        }
     }
     p.emit();
     p.errors.is_empty()
@@ -89,13 +86,6 @@ impl Platform {
     }
 }
 
-fn parse_version(version: &str) -> Option<(u32, u32)> {
-    let mut f = version.split('.');
-    let major = f.next()?.parse().ok()?;
-    let minor = f.next()?.parse().ok()?;
-    Some((major, minor))
-}
-
 #[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "lowercase"))]
 enum Features {
     Bias,
@@ -130,35 +120,31 @@ struct Abi {
 }
 
 impl Abi {
-    fn new(v: AbiVersion, kernel: &str) -> gpiocdev::Result<Abi> {
+    fn new(v: AbiVersion, features: Option<&platform::Features>, support: &AbiSupport) -> Abi {
         let mut abi = Abi {
             version: v,
             ..Default::default()
         };
-        if let Some((major, minor)) = parse_version(kernel) {
+        if let Some(f) = features {
             match v {
                 AbiVersion::V1 => {
-                    if major < 5 || (major == 5 && minor < 5) {
+                    if !f.bias {
                         abi.missing_features.push(Features::Bias);
                     }
                 }
                 AbiVersion::V2 => {
-                    if major < 5 || (major == 5 && minor <= 10) {
+                    if !f.realtime_clock {
                         abi.missing_features.push(Features::Realtime);
                     }
-                    if major < 5 || (major == 5 && minor < 19) {
+                    if !f.hte {
                         abi.missing_features.push(Features::Hte);
                     }
                 }
             }
         }
-        if let Err(e) = gpiocdev::supports_abi_version(v) {
-            if let gpiocdev::Error::UnsupportedAbi(_v, k) = e {
-                abi.unsupported_by = Some(k)
-            } else {
-                return Err(e);
-            }
+        if let Err(gpiocdev::Error::UnsupportedAbi(_v, k)) = support.supports(v) {
+            abi.unsupported_by = Some(k)
         }
-        Ok(abi)
+        abi
     }
 }
@@ -0,0 +1,96 @@
+// SPDX-FileCopyrightText: 2024 Kent Gibson <warthog618@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Tests of ABI fallback with ABI versions forced unsupported.
+#![cfg(all(
+    feature = "test_abi_override",
+    feature = "uapi_v1",
+    feature = "uapi_v2"
+))]
+
+use gpiosim::Simpleton;
+use std::process::{Command, Output};
+
+// Run the command with the listed uAPI versions forced unsupported.
+fn run(unsupported: &str, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_gpiocdev"))
+        .env("GPIOCDEV_TEST_ABI_OVERRIDE", "1")
+        .env("GPIOCDEV_TEST_ABI_UNSUPPORTED", unsupported)
+        .env_remove("GPIOCDEV_ABI_VERSION")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn platform() {
+    let _s = Simpleton::new(4);
+    let out = run("v2", &["platform"]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("uAPI ABI v1 is supported."), "{stdout}");
+    assert!(
+        stdout.contains("uAPI ABI v2 is not supported by the kernel."),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn get_auto_abi_falls_back_to_v1() {
+    let s = Simpleton::new(4);
+    let chip = s.dev_path().to_str().unwrap();
+
+    let out = run("v2", &["get", "--chip", chip, "2"]);
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "2=inactive\n");
+
+    let out = run("v1", &["get", "--chip", chip, "2"]);
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
+
+#[test]
+fn get_explicit_unsupported_abi() {
+    let s = Simpleton::new(4);
+    let chip = s.dev_path().to_str().unwrap();
+
+    let out = run(
+        "v2",
+        &["get", "-v", "--abi-version", "v2", "--chip", chip, "2"],
+    );
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("uAPI ABI v2 is not supported by the kernel."),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn get_no_abi_support() {
+    let s = Simpleton::new(4);
+    let chip = s.dev_path().to_str().unwrap();
+
+    let out = run("v1,v2", &["get", "-v", "--chip", chip, "2"]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("uAPI ABI is not supported by the kernel."),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn unknown_version() {
+    let out = run("v3", &["platform"]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("unknown uAPI ABI version 'v3'"), "{stderr}");
+}
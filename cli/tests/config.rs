@@ -0,0 +1,176 @@
+// SPDX-FileCopyrightText: 2024 Kent Gibson <warthog618@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#![cfg(feature = "config")]
+
+use gpiosim::{Bank, Level, Sim};
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::thread;
+use std::time::Duration;
+
+// A config file that is removed when dropped.
+struct ConfigFile(PathBuf);
+
+impl ConfigFile {
+    fn new(name: &str, content: &str) -> ConfigFile {
+        let path = std::env::temp_dir().join(format!("gpiocdev-{}-{}", std::process::id(), name));
+        fs::write(&path, content).unwrap();
+        ConfigFile(path)
+    }
+}
+
+impl Drop for ConfigFile {
+    fn drop(&mut self) {
+        _ = fs::remove_file(&self.0);
+    }
+}
+
+fn gpiocdev(args: &[&str]) -> Command {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_gpiocdev"));
+    cmd.args(args);
+    cmd
+}
+
+fn run(args: &[&str]) -> Output {
+    gpiocdev(args).output().unwrap()
+}
+
+fn sim() -> Sim {
+    gpiosim::builder()
+        .with_bank(
+            Bank::new(8, "config")
+                .name(2, "cfg LED0")
+                .name(5, "cfg BTN"),
+        )
+        .live()
+        .unwrap()
+}
+
+#[test]
+fn apply_and_hold() {
+    let s = sim();
+    let c = &s.chips()[0];
+    let cfg = ConfigFile::new(
+        "apply.toml",
+        &format!(
+            r#"
+            [[lines]]
+            line = "cfg LED0"
+            value = "active"
+
+            [[lines]]
+            chip = "{}"
+            line = 6
+            direction = "output"
+            active-low = true
+            "#,
+            c.dev_path().display()
+        ),
+    );
+    let file = cfg.0.to_str().unwrap();
+
+    let mut child = gpiocdev(&["config", "apply", "--hold", file])
+        .spawn()
+        .unwrap();
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(c.get_level(2).unwrap(), Level::High);
+    assert_eq!(c.get_level(6).unwrap(), Level::High);
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    let out = run(&["config", "apply", file]);
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
+
+#[test]
+fn apply_json() {
+    let s = sim();
+    let c = &s.chips()[0];
+    let cfg = ConfigFile::new(
+        "apply.json",
+        r#"{"lines": [{"line": "cfg BTN", "bias": "pull-up", "edges": "both"}]}"#,
+    );
+    let file = cfg.0.to_str().unwrap();
+
+    let mut child = gpiocdev(&["config", "apply", "--hold", file])
+        .spawn()
+        .unwrap();
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(c.get_level(5).unwrap(), Level::High);
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+#[test]
+fn check() {
+    let _s = sim();
+    let cfg = ConfigFile::new(
+        "check.toml",
+        "[[lines]]\nline = \"cfg LED0\"\nvalue = 1\n\n[[lines]]\nline = \"cfg BTN\"\n",
+    );
+    let out = run(&["config", "check", cfg.0.to_str().unwrap()]);
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
+
+#[test]
+fn check_broken() {
+    let _s = sim();
+
+    // syntax errors report the location in the file
+    let cfg = ConfigFile::new("syntax.toml", "[[lines]]\nline = \"cfg LED0\"\nvalue = \n");
+    let out = run(&["config", "check", cfg.0.to_str().unwrap()]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("line 3"), "{stderr}");
+
+    // semantic errors report the entry
+    let cfg = ConfigFile::new(
+        "broken.toml",
+        r#"
+        [[lines]]
+        line = "cfg LED0"
+        value = "maybe"
+
+        [[lines]]
+        line = "cfg missing"
+
+        [[lines]]
+        line = "cfg BTN"
+
+        [[lines]]
+        line = "cfg BTN"
+        bias = "pull-down"
+        "#,
+    );
+    let out = run(&["config", "check", cfg.0.to_str().unwrap()]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("lines[0] 'cfg LED0': invalid value 'maybe'"),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains("cannot find line 'cfg missing'"),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains("lines[3] 'cfg BTN': duplicates lines[2] 'cfg BTN'"),
+        "{stderr}"
+    );
+
+    // unsupported file type
+    let cfg = ConfigFile::new("broken.yaml", "lines: []\n");
+    let out = run(&["config", "check", cfg.0.to_str().unwrap()]);
+    assert!(!out.status.success());
+}
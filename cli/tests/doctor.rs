@@ -0,0 +1,70 @@
+// SPDX-FileCopyrightText: 2024 Kent Gibson <warthog618@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use gpiosim::{Bank, Direction, Sim};
+use std::process::{Command, Output};
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_gpiocdev"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn sim() -> Sim {
+    gpiosim::builder()
+        .with_bank(
+            Bank::new(8, "doctor")
+                .name(2, "doc LED0")
+                .name(5, "doc hogged")
+                .hog(5, "hogster", Direction::Input),
+        )
+        .live()
+        .unwrap()
+}
+
+#[test]
+fn clean() {
+    let _s = sim();
+    let out = run(&["doctor", "--bias", "pull-up", "doc LED0"]);
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.contains("doc LED0") && stdout.contains(": ok"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn findings() {
+    let s = sim();
+    let chip = s.chips()[0].dev_path().to_str().unwrap().to_string();
+
+    let out = run(&["doctor", "--chip", &chip, "3"]);
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("info[line-unnamed]"), "{stdout}");
+
+    let out = run(&["doctor", "doc hogged"]);
+    assert!(!out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("error[line-in-use]"), "{stdout}");
+    assert!(stdout.contains("hogster"), "{stdout}");
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json() {
+    let _s = sim();
+    let out = run(&["doctor", "--json", "doc hogged"]);
+    assert!(!out.status.success());
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let finding = &v["lines"][0]["findings"][0];
+    assert_eq!(finding["code"], "line-in-use");
+    assert_eq!(finding["severity"], "error");
+}
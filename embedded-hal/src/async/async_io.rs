@@ -86,6 +86,8 @@ impl InputPin {
                 let value = match event.kind {
                     EdgeKind::Rising => Value::Active,
                     EdgeKind::Falling => Value::Inactive,
+                    // ignore edges unknown to this library
+                    _ => continue,
                 };
                 self.config.value = Some(value);
                 return Ok(());
@@ -108,6 +110,8 @@ impl InputPin {
             let nv = match event.kind {
                 EdgeKind::Rising => Value::Active,
                 EdgeKind::Falling => Value::Inactive,
+                // ignore edges unknown to this library
+                _ => continue,
             };
             self.config.value = Some(nv);
             if value == nv {
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use std::path::Path;
+use std::time::Duration;
 
 use crate::{state_to_value, Error};
 use embedded_hal::digital::PinState;
@@ -74,6 +75,43 @@ impl InputPin {
         Ok(self.config.value.unwrap())
     }
 
+    /// Wait for the pin to see the given edge, giving up after the timeout.
+    ///
+    /// Returns true if the edge occurred, or false if the timeout expired.
+    ///
+    /// On timeout no event of the requested kind is consumed, so a subsequent wait
+    /// still sees the next edge.
+    ///
+    /// Requires the Tokio runtime to have the time driver enabled.
+    ///
+    /// ```no_run
+    /// use gpiocdev::line::EdgeDetection;
+    /// use std::time::Duration;
+    /// # use gpiocdev_embedded_hal::Error;
+    ///
+    /// # async fn example() -> Result<(), Error> {
+    /// let mut pin = gpiocdev_embedded_hal::tokio::InputPin::new("/dev/gpiochip0", 4)?;
+    /// if !pin
+    ///     .wait_for_edge_timeout(EdgeDetection::RisingEdge, Duration::from_secs(1))
+    ///     .await?
+    /// {
+    ///     println!("No rising edge within a second.");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn wait_for_edge_timeout(
+        &mut self,
+        edge: EdgeDetection,
+        timeout: Duration,
+    ) -> Result<bool, Error> {
+        // reading an edge event is cancel safe, so the wait may be dropped on timeout.
+        match tokio::time::timeout(timeout, self.wait_for_edge(edge)).await {
+            Ok(res) => res.map(|_| true),
+            Err(_) => Ok(false),
+        }
+    }
+
     async fn wait_for_edge(&mut self, edge: EdgeDetection) -> Result<(), Error> {
         self.enable_edge_detection(edge)?;
         loop {
@@ -82,6 +120,8 @@ impl InputPin {
             self.config.value.replace(match event.kind {
                 EdgeKind::Rising => Value::Active,
                 EdgeKind::Falling => Value::Inactive,
+                // ignore edges unknown to this library
+                _ => continue,
             });
 
             if match edge {
@@ -109,6 +149,8 @@ impl InputPin {
             let nv = match event.kind {
                 EdgeKind::Rising => Value::Active,
                 EdgeKind::Falling => Value::Inactive,
+                // ignore edges unknown to this library
+                _ => continue,
             };
             self.config.value = Some(nv);
             if value == nv {
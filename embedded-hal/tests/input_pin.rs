@@ -177,6 +177,7 @@ fn from_found_line() {
     let offset = 2;
     let fl = gpiocdev::FoundLine {
         chip: s.dev_path().clone(),
+        chip_info: Default::default(),
         info: gpiocdev::line::Info {
             offset,
             ..Default::default()
@@ -399,6 +400,40 @@ mod async_tokio {
         assert!(will_resolve(pin.wait_for_any_edge()).await);
         assert!(!will_resolve(pin.wait_for_any_edge()).await);
     }
+
+    #[tokio::test]
+    async fn wait_for_edge_timeout() {
+        use gpiocdev::line::EdgeDetection;
+
+        let s = Simpleton::new(5);
+
+        let offset = 3;
+        let mut pin = InputPin::new(s.dev_path(), offset).unwrap();
+
+        // known low
+        assert!(!pin
+            .wait_for_edge_timeout(EdgeDetection::RisingEdge, PROPAGATION_DELAY)
+            .await
+            .unwrap());
+        // switched to high
+        s.pullup(offset).unwrap();
+        assert!(pin
+            .wait_for_edge_timeout(EdgeDetection::RisingEdge, PROPAGATION_DELAY)
+            .await
+            .unwrap());
+        assert!(!pin
+            .wait_for_edge_timeout(EdgeDetection::BothEdges, PROPAGATION_DELAY)
+            .await
+            .unwrap());
+        // the edge after a timeout is still seen
+        s.pulldown(offset).unwrap();
+        wait_propagation_delay();
+        assert!(pin
+            .wait_for_edge_timeout(EdgeDetection::FallingEdge, PROPAGATION_DELAY)
+            .await
+            .unwrap());
+        assert!(!will_resolve(pin.wait_for_falling_edge()).await);
+    }
 }
 
 #[cfg(feature = "async_io")]
@@ -212,6 +212,7 @@ fn from_found_line() {
     let offset = 2;
     let fl = gpiocdev::FoundLine {
         chip: s.dev_path().clone(),
+        chip_info: Default::default(),
         info: gpiocdev::line::Info {
             offset,
             ..Default::default()
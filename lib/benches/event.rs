@@ -2,9 +2,9 @@
 //
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use criterion::{criterion_group, criterion_main, Bencher, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, Bencher, Criterion};
 
-use gpiocdev::line::EdgeDetection;
+use gpiocdev::line::{EdgeDetection, EdgeEvent};
 use gpiocdev::request::Request;
 use gpiocdev::AbiVersion;
 use gpiosim::{Level, Simpleton};
@@ -19,6 +19,8 @@ fn v1_benchmarks(c: &mut Criterion) {
     c.bench_function("uapi_v1 edge latency", |b| edge_latency(b, V1));
     c.bench_function("uapi_v1 ten edge events", |b| ten_edge_events(b, V1));
     c.bench_function("uapi_v1 edge event object", |b| edge_event_object(b, V1));
+    c.bench_function("uapi_v1 edge event into", |b| edge_event_into(b, V1));
+    c.bench_function("uapi_v1 edge event decode", edge_event_decode_v1);
 }
 #[cfg(not(feature = "uapi_v1"))]
 fn v1_benchmarks(_c: &mut Criterion) {}
@@ -29,6 +31,8 @@ fn v2_benchmarks(c: &mut Criterion) {
     c.bench_function("uapi_v2 edge latency", |b| edge_latency(b, V2));
     c.bench_function("uapi_v2 ten edge events", |b| ten_edge_events(b, V2));
     c.bench_function("uapi_v2 edge event object", |b| edge_event_object(b, V2));
+    c.bench_function("uapi_v2 edge event into", |b| edge_event_into(b, V2));
+    c.bench_function("uapi_v2 edge event decode", edge_event_decode_v2);
 }
 #[cfg(not(feature = "uapi_v2"))]
 fn v2_benchmarks(_c: &mut Criterion) {}
@@ -126,3 +130,73 @@ fn edge_event_object(b: &mut Bencher, abiv: AbiVersion) {
         let _ = req.read_edge_event().unwrap();
     });
 }
+
+// determine the interrupt latency when reading into an existing event
+// overheads are toggle time and edge latency.
+#[allow(unused_variables)]
+fn edge_event_into(b: &mut Bencher, abiv: AbiVersion) {
+    let s = Simpleton::new(10);
+    let offset = 1;
+
+    let mut builder = Request::builder();
+    builder.on_chip(s.dev_path());
+    #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+    builder.using_abi_version(abiv);
+    let req = builder
+        .with_line(offset)
+        .with_edge_detection(EdgeDetection::BothEdges)
+        .request()
+        .unwrap();
+
+    let mut pull = Level::High;
+    s.set_pull(offset, pull).unwrap();
+    let mut event = req.read_edge_event().unwrap();
+
+    b.iter(|| {
+        pull = match pull {
+            Level::High => Level::Low,
+            Level::Low => Level::High,
+        };
+        s.set_pull(offset, pull).unwrap();
+        // overwriting the event
+        req.read_edge_event_into(&mut event).unwrap();
+    });
+}
+
+// pack two u32 fields into a u64, as laid out by the kernel.
+fn pack(first: u32, second: u32) -> u64 {
+    let mut bytes = [0; 8];
+    bytes[..4].copy_from_slice(&first.to_ne_bytes());
+    bytes[4..].copy_from_slice(&second.to_ne_bytes());
+    u64::from_ne_bytes(bytes)
+}
+
+// determine the time taken to decode an event read from the kernel.
+// no overheads - the event is decoded from a buffer, so no chip is required.
+#[cfg(feature = "uapi_v1")]
+fn edge_event_decode_v1(b: &mut Bencher) {
+    use gpiocdev_uapi::v1;
+
+    // timestamp_ns, kind
+    let buf = [1234567890_u64, pack(1, 0)];
+
+    b.iter(|| {
+        let lee = v1::LineEdgeEvent::from_slice(black_box(&buf)).unwrap();
+        black_box(EdgeEvent::from(lee));
+    });
+}
+
+// determine the time taken to decode an event read from the kernel.
+// no overheads - the event is decoded from a buffer, so no chip is required.
+#[cfg(feature = "uapi_v2")]
+fn edge_event_decode_v2(b: &mut Bencher) {
+    use gpiocdev_uapi::v2;
+
+    // timestamp_ns, kind and offset, seqno and line_seqno, padding
+    let buf = [1234567890_u64, pack(1, 3), pack(42, 7), 0, 0, 0];
+
+    b.iter(|| {
+        let lee = v2::LineEdgeEvent::from_slice(black_box(&buf)).unwrap();
+        black_box(EdgeEvent::from(lee));
+    });
+}
@@ -32,6 +32,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let value = match event?.kind {
             EdgeKind::Rising => Value::Active,
             EdgeKind::Falling => Value::Inactive,
+            _ => continue,
         };
         output.set_lone_value(value)?;
     }
@@ -9,3 +9,117 @@ pub mod tokio;
 /// Asynchronous wrappers for the async-io reactor.
 #[cfg(feature = "async_io")]
 pub mod async_io;
+
+// The sink shared by the async request wrappers, re-exported by each.
+mod values_sink;
+pub use values_sink::ValuesSink;
+
+use crate::request::Request;
+use std::ops::Deref;
+use std::os::unix::prelude::{AsFd, AsRawFd, BorrowedFd, RawFd};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+// A request shared by an async request wrapper with the blocking threads used by
+// values() and set_values().
+//
+// Each blocking call holds a Lease on the request, and the request is recovered by
+// waiting for any outstanding leases to be released.
+#[derive(Debug)]
+struct SharedRequest {
+    req: Arc<Request>,
+    leases: Arc<Leases>,
+}
+
+impl SharedRequest {
+    fn new(req: Request) -> Self {
+        SharedRequest {
+            req: Arc::new(req),
+            leases: Default::default(),
+        }
+    }
+
+    // Lease the request to a blocking call.
+    fn lease(&self) -> Lease {
+        *self.leases.lock() += 1;
+        Lease {
+            req: Some(Arc::clone(&self.req)),
+            leases: Arc::clone(&self.leases),
+        }
+    }
+
+    // Recover the request.
+    //
+    // Blocking calls for values() or set_values() futures that were dropped may still
+    // hold leases, so wait for those calls to complete.
+    fn into_request(self) -> Request {
+        let mut active = self.leases.lock();
+        while *active != 0 {
+            active = self
+                .leases
+                .released
+                .wait(active)
+                .expect("failed to acquire lock on request leases");
+        }
+        drop(active);
+        Arc::try_unwrap(self.req).expect("request leased after all leases were released")
+    }
+}
+
+impl AsFd for SharedRequest {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.req.as_fd()
+    }
+}
+
+impl AsRawFd for SharedRequest {
+    fn as_raw_fd(&self) -> RawFd {
+        self.req.as_raw_fd()
+    }
+}
+
+impl Deref for SharedRequest {
+    type Target = Request;
+
+    fn deref(&self) -> &Request {
+        &self.req
+    }
+}
+
+// The number of leases on a shared request.
+#[derive(Debug, Default)]
+struct Leases {
+    active: Mutex<usize>,
+    released: Condvar,
+}
+
+impl Leases {
+    fn lock(&self) -> MutexGuard<'_, usize> {
+        self.active
+            .lock()
+            .expect("failed to acquire lock on request leases")
+    }
+}
+
+// A reference to a shared request held by a blocking call.
+struct Lease {
+    // Only None while the lease is being dropped.
+    req: Option<Arc<Request>>,
+    leases: Arc<Leases>,
+}
+
+impl Deref for Lease {
+    type Target = Request;
+
+    fn deref(&self) -> &Request {
+        self.req.as_ref().expect("lease has been released")
+    }
+}
+
+impl Drop for Lease {
+    fn drop(&mut self) {
+        // release the reference before the lease, so the request can be unwrapped.
+        self.req = None;
+        *self.leases.lock() -= 1;
+        self.leases.released.notify_all();
+    }
+}
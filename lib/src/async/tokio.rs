@@ -2,16 +2,21 @@
 //
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use crate::chip::Chip;
-use crate::line::{EdgeEvent, InfoChangeEvent};
-use crate::request::{EdgeEventBuffer, Request};
-use crate::Result;
+use super::SharedRequest;
+use crate::chip::{Chip, InfoChangeFilter};
+use crate::line::{EdgeEvent, InfoChangeEvent, InfoChangeKind, Offset, Value, Values};
+use crate::request::{CoalescedEdgeEvent, Coalescer, Debouncer, EdgeEventBuffer, Levels, Request};
+use crate::{ChipEvent, ChipWatcher, Result};
 use futures::ready;
 use futures::task::{Context, Poll};
+use std::future::Future;
 use std::pin::Pin;
+use std::time::{Duration, Instant};
 use tokio::io::unix::AsyncFd;
 use tokio_stream::Stream;
 
+pub use super::ValuesSink;
+
 /// Async wrapper around [`Chip`] for the tokio reactor.
 ///
 /// # Example
@@ -82,9 +87,39 @@ impl AsyncChip {
     /// # Ok(())
     /// # }
     /// ```
-    pub fn info_change_events(&self) -> InfoChangeStream {
+    pub fn info_change_events(&self) -> InfoChangeStream<'_> {
         InfoChangeStream { chip: self }
     }
+
+    /// Async form of [`Chip::info_change_events_filtered`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use gpiocdev::{Chip, Result};
+    /// use gpiocdev::tokio::AsyncChip;
+    /// use gpiocdev::line::InfoChangeKind;
+    /// use tokio_stream::StreamExt;
+    ///
+    /// # async fn docfn() -> Result<()> {
+    /// let chip = Chip::from_path("/dev/gpiochip0")?;
+    /// let achip = AsyncChip::new(chip);
+    /// let mut events = achip.info_change_events_filtered(&[InfoChangeKind::Reconfigured], &[]);
+    /// while let Ok(evt) = events.next().await.unwrap() {
+    ///     // process event...
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn info_change_events_filtered(
+        &self,
+        kinds: &[InfoChangeKind],
+        offsets: &[Offset],
+    ) -> FilteredInfoChangeStream<'_> {
+        FilteredInfoChangeStream {
+            chip: self,
+            filter: InfoChangeFilter::new(kinds, offsets),
+        }
+    }
 }
 
 impl AsRef<Chip> for AsyncChip {
@@ -127,6 +162,144 @@ impl Stream for InfoChangeStream<'_> {
     }
 }
 
+/// Async form of [`FilteredInfoChangeIterator`].
+///
+/// Created by [`AsyncChip::info_change_events_filtered`].
+///
+/// [`FilteredInfoChangeIterator`]: crate::chip::FilteredInfoChangeIterator
+pub struct FilteredInfoChangeStream<'a> {
+    chip: &'a AsyncChip,
+
+    filter: InfoChangeFilter,
+}
+
+impl Stream for FilteredInfoChangeStream<'_> {
+    type Item = Result<InfoChangeEvent>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        loop {
+            let mut guard = ready!(self.chip.0.poll_read_ready(cx))?;
+            let chip = self.chip.as_ref();
+            let res = chip.read_line_info_change_event();
+            if !chip.has_line_info_change_event()? {
+                guard.clear_ready();
+            }
+            match res {
+                Ok(evt) if !self.filter.matches(&evt) => {}
+                res => return Poll::Ready(Some(res)),
+            }
+        }
+    }
+}
+
+/// Async wrapper around [`ChipWatcher`] for the tokio reactor.
+///
+/// # Example
+/// ```no_run
+/// # use gpiocdev::Result;
+/// use gpiocdev::tokio::AsyncChipWatcher;
+///
+/// # async fn docfn() -> Result<()> {
+/// let watcher = AsyncChipWatcher::new(gpiocdev::chips_watch()?);
+/// let evt = watcher.read_event().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct AsyncChipWatcher(AsyncFd<ChipWatcher>);
+
+impl AsyncChipWatcher {
+    /// Create a Tokio wrapper for a ChipWatcher.
+    pub fn new(watcher: ChipWatcher) -> Self {
+        AsyncChipWatcher(AsyncFd::new(watcher).unwrap())
+    }
+
+    /// Async form of [`ChipWatcher::read_event`].
+    pub async fn read_event(&self) -> Result<ChipEvent> {
+        loop {
+            let mut guard = self.0.readable().await?;
+            let watcher = self.0.get_ref();
+            if watcher.has_event()? {
+                let res = watcher.read_event();
+                if !watcher.has_event()? {
+                    guard.clear_ready();
+                }
+                return res;
+            }
+            // only events for chips that are not yet available
+            guard.clear_ready();
+        }
+    }
+
+    /// Async form of [`ChipWatcher::events`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use gpiocdev::Result;
+    /// use gpiocdev::tokio::AsyncChipWatcher;
+    /// use tokio_stream::StreamExt;
+    ///
+    /// # async fn docfn() -> Result<()> {
+    /// let watcher = AsyncChipWatcher::new(gpiocdev::chips_watch()?);
+    /// let mut events = watcher.events();
+    /// while let Ok(evt) = events.next().await.unwrap() {
+    ///     // process event...
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn events(&self) -> ChipEventStream<'_> {
+        ChipEventStream { watcher: self }
+    }
+}
+
+impl AsRef<ChipWatcher> for AsyncChipWatcher {
+    fn as_ref(&self) -> &ChipWatcher {
+        self.0.get_ref()
+    }
+}
+
+impl From<AsyncChipWatcher> for ChipWatcher {
+    fn from(w: AsyncChipWatcher) -> ChipWatcher {
+        w.0.into_inner()
+    }
+}
+
+impl From<ChipWatcher> for AsyncChipWatcher {
+    fn from(w: ChipWatcher) -> AsyncChipWatcher {
+        AsyncChipWatcher::new(w)
+    }
+}
+
+/// Async form of [`ChipEventIterator`].
+///
+/// Created by [`AsyncChipWatcher::events`].
+///
+/// [`ChipEventIterator`]: crate::ChipEventIterator
+pub struct ChipEventStream<'a> {
+    watcher: &'a AsyncChipWatcher,
+}
+
+impl Stream for ChipEventStream<'_> {
+    type Item = Result<ChipEvent>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        loop {
+            let mut guard = ready!(self.watcher.0.poll_read_ready(cx))?;
+            let watcher = self.watcher.as_ref();
+            if watcher.has_event()? {
+                let res = Poll::Ready(Some(watcher.read_event()));
+                if !watcher.has_event()? {
+                    guard.clear_ready();
+                }
+                return res;
+            }
+            // only events for chips that are not yet available
+            guard.clear_ready();
+        }
+    }
+}
+
 /// Async wrapper around [`Request`] for the tokio reactor.
 ///
 /// # Example
@@ -148,12 +321,12 @@ impl Stream for InfoChangeStream<'_> {
 /// # }
 /// ```
 #[derive(Debug)]
-pub struct AsyncRequest(AsyncFd<Request>);
+pub struct AsyncRequest(AsyncFd<SharedRequest>);
 
 impl AsyncRequest {
     /// Create a Tokio wrapper for a Request.
     pub fn new(req: Request) -> Self {
-        AsyncRequest(AsyncFd::new(req).unwrap())
+        AsyncRequest(AsyncFd::new(SharedRequest::new(req)).unwrap())
     }
 
     /// Async form of [`Request::read_edge_event`].
@@ -229,7 +402,11 @@ impl AsyncRequest {
 
     /// Async form of [`Request::new_edge_event_buffer`].
     ///
+    /// Reads up to `capacity` events from the kernel at once, so can be used in place
+    /// of [`edge_events`](#method.edge_events) to read events in bulk.
+    ///
     /// * `capacity` - The number of events that can be buffered in user space.
+    ///   A zero capacity is treated as 1.
     ///
     /// # Example
     /// ```no_run
@@ -253,7 +430,7 @@ impl AsyncRequest {
     /// # Ok(())
     /// # }
     /// ```
-    pub fn new_edge_event_stream(&self, capacity: usize) -> EdgeEventStream {
+    pub fn new_edge_event_stream(&self, capacity: usize) -> EdgeEventStream<'_> {
         EdgeEventStream {
             req: self,
             events: self.0.get_ref().new_edge_event_buffer(capacity),
@@ -283,12 +460,196 @@ impl AsyncRequest {
     /// # Ok(())
     /// # }
     /// ```
-    pub fn edge_events(&self) -> EdgeEventStream {
+    pub fn edge_events(&self) -> EdgeEventStream<'_> {
         EdgeEventStream {
             req: self,
             events: self.0.get_ref().edge_events(),
         }
     }
+
+    /// Async form of [`Request::debounced_edge_events`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use gpiocdev::Result;
+    /// use gpiocdev::Request;
+    /// use gpiocdev::tokio::AsyncRequest;
+    /// use std::time::Duration;
+    /// use tokio_stream::StreamExt;
+    ///
+    /// # async fn docfn() -> Result<()> {
+    /// let req = Request::builder()
+    ///    .on_chip("/dev/gpiochip0")
+    ///    .with_line(42)
+    ///    .as_input()
+    ///    .with_edge_detection(gpiocdev::line::EdgeDetection::BothEdges)
+    ///    .request()?;
+    /// let areq = AsyncRequest::new(req);
+    /// let mut events = areq.debounced_edge_events(Duration::from_millis(10));
+    /// while let Ok(evt) = events.next().await.unwrap() {
+    ///     // process event...
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn debounced_edge_events(&self, period: Duration) -> DebouncedEdgeEventStream<'_> {
+        DebouncedEdgeEventStream {
+            req: self,
+            events: self.0.get_ref().edge_events(),
+            debouncer: Debouncer::new(period),
+            sleep: None,
+        }
+    }
+
+    /// Async form of [`Request::coalesced_edge_events`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use gpiocdev::Result;
+    /// use gpiocdev::Request;
+    /// use gpiocdev::tokio::AsyncRequest;
+    /// use std::time::Duration;
+    /// use tokio_stream::StreamExt;
+    ///
+    /// # async fn docfn() -> Result<()> {
+    /// let req = Request::builder()
+    ///    .on_chip("/dev/gpiochip0")
+    ///    .with_line(42)
+    ///    .as_input()
+    ///    .with_edge_detection(gpiocdev::line::EdgeDetection::BothEdges)
+    ///    .request()?;
+    /// let areq = AsyncRequest::new(req);
+    /// let mut events = areq.coalesced_edge_events(Duration::from_millis(100));
+    /// while let Ok(ce) = events.next().await.unwrap() {
+    ///     // process event...
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn coalesced_edge_events(&self, max_rate: Duration) -> CoalescedEdgeEventStream<'_> {
+        CoalescedEdgeEventStream {
+            req: self,
+            events: self.0.get_ref().edge_events(),
+            coalescer: Coalescer::new(max_rate),
+            sleep: None,
+        }
+    }
+
+    /// Async form of [`Request::level_changes`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use gpiocdev::Result;
+    /// use gpiocdev::Request;
+    /// use gpiocdev::tokio::AsyncRequest;
+    /// use tokio_stream::StreamExt;
+    ///
+    /// # async fn docfn() -> Result<()> {
+    /// let req = Request::builder()
+    ///    .on_chip("/dev/gpiochip0")
+    ///    .with_line(42)
+    ///    .as_input()
+    ///    .with_edge_detection(gpiocdev::line::EdgeDetection::BothEdges)
+    ///    .request()?;
+    /// let areq = AsyncRequest::new(req);
+    /// let mut levels = areq.level_changes();
+    /// while let Ok((offset, value)) = levels.next().await.unwrap() {
+    ///     // process level...
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn level_changes(&self) -> LevelChangeStream<'_> {
+        LevelChangeStream {
+            req: self,
+            events: self.0.get_ref().edge_events(),
+            levels: Levels::new(),
+        }
+    }
+
+    /// Create a sink that sets the values of the requested lines.
+    ///
+    /// Each [`Values`] sent to the sink is applied using [`Request::set_values`],
+    /// and each `(Offset, Value)` using [`Request::set_value`], in the order sent.
+    ///
+    /// The values are set directly, not on a blocking thread, so the sink is always ready.
+    /// Refer to [`ValuesSink`] for details.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use gpiocdev::Result;
+    /// use futures::{stream, StreamExt};
+    /// use gpiocdev::line::{Value, Values};
+    /// use gpiocdev::Request;
+    /// use gpiocdev::tokio::AsyncRequest;
+    ///
+    /// # async fn docfn() -> Result<()> {
+    /// let req = Request::builder()
+    ///    .on_chip("/dev/gpiochip0")
+    ///    .with_lines(&[17, 22])
+    ///    .as_output(Value::Inactive)
+    ///    .request()?;
+    /// let areq = AsyncRequest::new(req);
+    /// let waveform = [
+    ///     Values::from_iter([(17, Value::Active), (22, Value::Inactive)]),
+    ///     Values::from_iter([(17, Value::Inactive), (22, Value::Active)]),
+    /// ];
+    /// stream::iter(waveform).map(Ok).forward(areq.values_sink()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn values_sink(&self) -> ValuesSink<'_> {
+        ValuesSink::new(self.as_ref())
+    }
+
+    /// Async form of [`Request::values`].
+    ///
+    /// The values are read on a blocking thread, so the runtime is not blocked while
+    /// the kernel reads the lines, which may be slow for some hardware, such as
+    /// GPIO expanders on I2C or SPI buses.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use gpiocdev::Result;
+    /// use gpiocdev::line::Values;
+    /// use gpiocdev::Request;
+    /// use gpiocdev::tokio::AsyncRequest;
+    ///
+    /// # async fn docfn() -> Result<()> {
+    /// let offsets = &[17, 22];
+    /// let req = Request::builder()
+    ///    .on_chip("/dev/gpiochip0")
+    ///    .with_lines(offsets)
+    ///    .request()?;
+    /// let areq = AsyncRequest::new(req);
+    /// let mut values = Values::from_offsets(offsets);
+    /// areq.values(&mut values).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn values(&self, values: &mut Values) -> Result<()> {
+        let req = self.0.get_ref().lease();
+        // the values are cloned, rather than moved, so they are unaltered if cancelled.
+        let mut vv = values.clone();
+        let (vv, res) = unblock(move || {
+            let res = req.values(&mut vv);
+            (vv, res)
+        })
+        .await;
+        *values = vv;
+        res
+    }
+
+    /// Async form of [`Request::set_values`].
+    ///
+    /// The values are set on a blocking thread, so the runtime is not blocked while
+    /// the kernel sets the lines, which may be slow for some hardware, such as
+    /// GPIO expanders on I2C or SPI buses.
+    pub async fn set_values(&self, values: &Values) -> Result<()> {
+        let req = self.0.get_ref().lease();
+        let vv = values.clone();
+        unblock(move || req.set_values(&vv)).await
+    }
 }
 
 impl AsRef<Request> for AsyncRequest {
@@ -299,7 +660,7 @@ impl AsRef<Request> for AsyncRequest {
 
 impl From<AsyncRequest> for Request {
     fn from(r: AsyncRequest) -> Request {
-        r.0.into_inner()
+        r.0.into_inner().into_request()
     }
 }
 
@@ -334,3 +695,186 @@ impl Stream for EdgeEventStream<'_> {
         res
     }
 }
+
+/// Async form of [`DebouncedEdgeEvents`](crate::request::DebouncedEdgeEvents).
+///
+/// Created by [`AsyncRequest::debounced_edge_events`].
+pub struct DebouncedEdgeEventStream<'a> {
+    req: &'a AsyncRequest,
+    events: EdgeEventBuffer<'a>,
+    debouncer: Debouncer,
+
+    /// The timer for the next line to settle.
+    ///
+    /// Created on first use, as that must be within the runtime.
+    sleep: Option<Pin<Box<tokio::time::Sleep>>>,
+}
+
+impl<'a> DebouncedEdgeEventStream<'a> {
+    /// Limit the rate of the debounced events.
+    ///
+    /// Async form of [`DebouncedEdgeEvents::coalesced`](crate::request::DebouncedEdgeEvents::coalesced).
+    pub fn coalesced(self, max_rate: Duration) -> CoalescedEdgeEventStream<'a> {
+        CoalescedEdgeEventStream {
+            req: self.req,
+            events: self.events,
+            coalescer: Coalescer::new(max_rate).with_debouncer(self.debouncer),
+            sleep: None,
+        }
+    }
+
+    /// Set the debounce period for all lines.
+    ///
+    /// Replaces any periods previously set for individual lines.
+    ///
+    /// Takes effect from the next edge on each line.
+    pub fn set_period(&mut self, period: Duration) {
+        self.debouncer.set_period(period);
+    }
+
+    /// Set the debounce period for an individual line.
+    ///
+    /// Takes effect from the next edge on the line.
+    pub fn set_line_period(&mut self, offset: Offset, period: Duration) {
+        self.debouncer.set_line_period(offset, period);
+    }
+}
+
+impl Stream for DebouncedEdgeEventStream<'_> {
+    type Item = Result<EdgeEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            if let Some(evt) = this.debouncer.pop(Instant::now()) {
+                return Poll::Ready(Some(Ok(evt)));
+            }
+            // feed any available events to the debouncer...
+            if !this.events.is_empty() {
+                let evt = this.events.read_event()?;
+                this.debouncer.push(evt, Instant::now());
+                continue;
+            }
+            if let Poll::Ready(guard) = this.req.0.poll_read_ready(cx) {
+                let mut guard = guard?;
+                if this.req.0.get_ref().has_edge_event()? {
+                    let evt = this.events.read_event()?;
+                    this.debouncer.push(evt, Instant::now());
+                } else {
+                    guard.clear_ready();
+                }
+                continue;
+            }
+            // ... else wait for the next line to settle.
+            let deadline = match this.debouncer.next_deadline() {
+                Some(deadline) => tokio::time::Instant::from_std(deadline),
+                None => return Poll::Pending,
+            };
+            let sleep = this
+                .sleep
+                .get_or_insert_with(|| Box::pin(tokio::time::sleep_until(deadline)));
+            sleep.as_mut().reset(deadline);
+            ready!(sleep.as_mut().poll(cx));
+        }
+    }
+}
+
+/// Async form of [`CoalescedEdgeEvents`](crate::request::CoalescedEdgeEvents).
+///
+/// Created by [`AsyncRequest::coalesced_edge_events`] or
+/// [`DebouncedEdgeEventStream::coalesced`].
+pub struct CoalescedEdgeEventStream<'a> {
+    req: &'a AsyncRequest,
+    events: EdgeEventBuffer<'a>,
+    coalescer: Coalescer,
+
+    /// The timer for the next event to be ready.
+    ///
+    /// Created on first use, as that must be within the runtime.
+    sleep: Option<Pin<Box<tokio::time::Sleep>>>,
+}
+
+impl Stream for CoalescedEdgeEventStream<'_> {
+    type Item = Result<CoalescedEdgeEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            if let Some(evt) = this.coalescer.pop(Instant::now()) {
+                return Poll::Ready(Some(Ok(evt)));
+            }
+            // feed any available events to the coalescer...
+            if !this.events.is_empty() {
+                let evt = this.events.read_event()?;
+                this.coalescer.push(evt, Instant::now());
+                continue;
+            }
+            if let Poll::Ready(guard) = this.req.0.poll_read_ready(cx) {
+                let mut guard = guard?;
+                if this.req.0.get_ref().has_edge_event()? {
+                    let evt = this.events.read_event()?;
+                    this.coalescer.push(evt, Instant::now());
+                } else {
+                    guard.clear_ready();
+                }
+                continue;
+            }
+            // ... else wait for the next event to be ready.
+            let deadline = match this.coalescer.next_deadline() {
+                Some(deadline) => tokio::time::Instant::from_std(deadline),
+                None => return Poll::Pending,
+            };
+            let sleep = this
+                .sleep
+                .get_or_insert_with(|| Box::pin(tokio::time::sleep_until(deadline)));
+            sleep.as_mut().reset(deadline);
+            ready!(sleep.as_mut().poll(cx));
+        }
+    }
+}
+
+/// Async form of [`LevelChanges`](crate::request::LevelChanges).
+///
+/// Created by [`AsyncRequest::level_changes`].
+pub struct LevelChangeStream<'a> {
+    req: &'a AsyncRequest,
+    events: EdgeEventBuffer<'a>,
+    levels: Levels,
+}
+
+impl Stream for LevelChangeStream<'_> {
+    type Item = Result<(Offset, Value)>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        this.levels.snapshot(&mut this.events)?;
+        loop {
+            if let Some(level) = this.levels.pop_initial() {
+                return Poll::Ready(Some(Ok(level)));
+            }
+            if this.events.is_empty() {
+                let mut guard = ready!(this.req.0.poll_read_ready(cx))?;
+                if !this.req.0.get_ref().has_edge_event()? {
+                    guard.clear_ready();
+                    continue;
+                }
+            }
+            let evt = this.events.read_event()?;
+            if let Some(level) = this.levels.update(&evt) {
+                return Poll::Ready(Some(Ok(level)));
+            }
+        }
+    }
+}
+
+// Run a blocking operation on the blocking thread pool, propagating any panic.
+async fn unblock<F, T>(f: F) -> T
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(res) => res,
+        Err(e) => std::panic::resume_unwind(e.into_panic()),
+    }
+}
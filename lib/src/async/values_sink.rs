@@ -0,0 +1,83 @@
+// SPDX-FileCopyrightText: 2024 Kent Gibson <warthog618@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::line::{Offset, Value, Values};
+use crate::request::Request;
+use crate::{Error, Result};
+use futures::task::{Context, Poll};
+use futures::Sink;
+use std::pin::Pin;
+
+/// A sink that sets the values of the lines in an async request.
+///
+/// Created by the `values_sink()` method of the async request wrappers.
+///
+/// # Readiness
+///
+/// The sink does not buffer values.
+/// Each is applied to the lines by [`start_send`], which sets the values directly,
+/// rather than on a blocking thread.
+/// So the sink is always ready, [`poll_ready`] never returns `Pending`, and
+/// flushing and closing the sink have nothing to do.
+///
+/// Errors setting the values are returned by [`start_send`], and so by the send
+/// of the value that caused them.
+///
+/// Setting values is quick for lines on SoC GPIO controllers, but may be slow for
+/// lines on expanders on I2C or SPI buses, and would block the executor while the
+/// values are set.
+/// For those, the async `set_values()` of the request wrappers, which sets the
+/// values on a blocking thread, is more appropriate.
+///
+/// [`start_send`]: Sink::start_send
+/// [`poll_ready`]: Sink::poll_ready
+pub struct ValuesSink<'a> {
+    req: &'a Request,
+}
+
+impl<'a> ValuesSink<'a> {
+    pub(super) fn new(req: &'a Request) -> Self {
+        ValuesSink { req }
+    }
+}
+
+impl Sink<Values> for ValuesSink<'_> {
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, values: Values) -> Result<()> {
+        self.req.set_values(&values)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Sink<(Offset, Value)> for ValuesSink<'_> {
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, (offset, value): (Offset, Value)) -> Result<()> {
+        self.req.set_value(offset, value)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
@@ -3,9 +3,8 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use super::line::Offset;
-use crate::{
-    line, line::InfoChangeEvent, AbiSupportKind, AbiVersion, AbiVersion::*, Error, Result, UapiCall,
-};
+use crate::line::{self, decode_error, InfoChangeEvent, InfoChangeKind};
+use crate::{AbiSupport, AbiSupportKind, AbiVersion, AbiVersion::*, Error, Result, UapiCall};
 #[cfg(all(feature = "uapi_v1", not(feature = "uapi_v2")))]
 use gpiocdev_uapi::v1 as uapi;
 #[cfg(any(feature = "uapi_v2", not(feature = "uapi_v1")))]
@@ -14,8 +13,9 @@ use gpiocdev_uapi::v2 as uapi;
 use gpiocdev_uapi::{v1, v2};
 #[cfg(feature = "serde")]
 use serde_derive::{Deserialize, Serialize};
-#[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
-use std::cell::Cell;
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::ffi::CStr;
 use std::fmt;
 use std::fs;
 use std::mem;
@@ -24,8 +24,9 @@ use std::ops::Range;
 use std::os::linux::fs::MetadataExt;
 #[cfg(target_os = "android")]
 use std::os::android::fs::MetadataExt;
-use std::os::unix::prelude::{AsFd, AsRawFd, BorrowedFd, OsStrExt};
+use std::os::unix::prelude::{AsFd, AsRawFd, BorrowedFd, OsStrExt, OwnedFd};
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use std::time::Duration;
 
 const CHARDEV_MODE: u32 = 0x2000;
@@ -35,73 +36,201 @@ const CHARDEV_MODE: u32 = 0x2000;
 /// Returns the resolved path to the character device.
 pub fn is_chip<P: AsRef<Path>>(path: P) -> Result<PathBuf> {
     let pb = fs::canonicalize(&path)?;
-    // if canonical path is of form /dev/gpiochipXX assume we are good
-    if let Some(pbstr) = pb.to_str() {
-        if let Some(num) = pbstr.strip_prefix("/dev/gpiochip") {
-            if num.chars().all(|c| char::is_digit(c, 10)) {
-                return Ok(pb);
-            }
-        }
-    }
-
-    // else take a more detailed look...
     let m = fs::metadata(&pb)?;
     if m.st_mode() & CHARDEV_MODE == 0 {
         return Err(Error::GpioChip(pb, ErrorKind::NotCharacterDevice));
     }
-    let mut sysfs_dev = PathBuf::from("/sys/bus/gpio/devices");
-    sysfs_dev.push(pb.file_name().unwrap());
-    sysfs_dev.push("dev");
-    if let Ok(rdev) = fs::read_to_string(sysfs_dev) {
-        let st_rdev = m.st_rdev();
-        let dev_str = format!("{}:{}", (st_rdev as u16 >> 8) as u8, st_rdev as u8);
-        if rdev.trim_end() == dev_str {
-            return Ok(pb);
+    if !is_gpio_rdev(m.st_rdev()) {
+        return Err(Error::GpioChip(pb, ErrorKind::NotGpioDevice));
+    }
+    Ok(pb)
+}
+
+// Check if a character device number corresponds to a GPIO device.
+fn is_gpio_rdev(rdev: u64) -> bool {
+    // SAFETY: major() and minor() only decode the device number.
+    let (major, minor) = unsafe { (libc::major(rdev), libc::minor(rdev)) };
+    let subsystem = format!("/sys/dev/char/{}:{}/subsystem", major, minor);
+    match fs::canonicalize(subsystem) {
+        Ok(pb) => pb.file_name().map_or(false, |n| n == "gpio"),
+        Err(_) => false,
+    }
+}
+
+// Determine the path of an open file.
+//
+// This is the path the file was opened with, if it still refers to the same file,
+// else the path of the file descriptor in /proc.
+fn fd_path(f: &fs::File, m: &fs::Metadata) -> PathBuf {
+    let fd_path = PathBuf::from(format!("/proc/self/fd/{}", f.as_raw_fd()));
+    if let Ok(path) = fs::read_link(&fd_path) {
+        if let Ok(pm) = fs::metadata(&path) {
+            if pm.st_dev() == m.st_dev() && pm.st_ino() == m.st_ino() {
+                return path;
+            }
         }
     }
-    Err(Error::GpioChip(pb, ErrorKind::NotGpioDevice))
+    fd_path
+}
+
+// Open the chip, identifying the owner and mode of the chip if access is denied.
+//
+// The owner names are resolved here, once, rather than whenever the error is displayed.
+fn open_chip(path: &Path) -> Result<fs::File> {
+    fs::File::open(path).map_err(|e| match e.raw_os_error() {
+        Some(libc::EACCES) | Some(libc::EPERM) => match fs::metadata(path) {
+            Ok(m) => Error::GpioChip(
+                path.to_path_buf(),
+                ErrorKind::PermissionDenied {
+                    uid: m.st_uid(),
+                    user: user_name(m.st_uid()),
+                    gid: m.st_gid(),
+                    group: group_name(m.st_gid()),
+                    mode: m.st_mode() & 0o7777,
+                },
+            ),
+            Err(_) => Error::from(e),
+        },
+        _ => Error::from(e),
+    })
 }
 
 /// Compare two chip paths.
 ///
-// Sorts paths naturally, assuming any chip numbering is at the end of the path - as it is for gpiochips.
-pub fn path_compare(a: &Path, b: &Path) -> std::cmp::Ordering {
+/// Sorts paths naturally, with runs of digits compared by numeric value, so chips are
+/// sorted by chip number, e.g. "/dev/gpiochip2" before "/dev/gpiochip10".
+///
+/// Paths that are otherwise equal, such as those differing only in leading zeros,
+/// are compared lexicographically, so the ordering is total and deterministic.
+pub fn path_compare(a: &Path, b: &Path) -> Ordering {
     let a = a.as_os_str().as_bytes();
     let b = b.as_os_str().as_bytes();
+    natural_compare(a, b).then_with(|| a.cmp(b))
+}
 
-    if a.len() == b.len() {
-        // if equal length then just compare lexicographically
-        return a.cmp(b);
-    }
-    for it in a.iter().zip(b.iter()) {
-        let (ai, bi) = it;
-        if *ai != *bi {
-            if !ai.is_ascii_digit() || !bi.is_ascii_digit() {
-                // if either is not a digit then this character is definitive
-                return (*ai).cmp(bi);
+// Compare byte strings with runs of digits compared by numeric value.
+fn natural_compare(mut a: &[u8], mut b: &[u8]) -> Ordering {
+    loop {
+        match (a.first(), b.first()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(ac), Some(bc)) if ac.is_ascii_digit() && bc.is_ascii_digit() => {
+                let (an, ar) = split_digits(a);
+                let (bn, br) = split_digits(b);
+                let ord = number_compare(an, bn);
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+                a = ar;
+                b = br;
+            }
+            (Some(ac), Some(bc)) => {
+                if ac != bc {
+                    return ac.cmp(bc);
+                }
+                a = &a[1..];
+                b = &b[1..];
             }
-            // else drop thru to length comparison
-            break;
         }
     }
-    // equal up to to the length of the shortest - or to digits and shorter numbers are smaller
-    a.len().cmp(&b.len())
+}
+
+// Split the leading run of digits from a byte string.
+fn split_digits(s: &[u8]) -> (&[u8], &[u8]) {
+    let n = s
+        .iter()
+        .position(|c| !c.is_ascii_digit())
+        .unwrap_or(s.len());
+    s.split_at(n)
+}
+
+// Compare two runs of digits by numeric value, without overflow for long runs.
+fn number_compare(a: &[u8], b: &[u8]) -> Ordering {
+    let strip = |s: &[u8]| -> usize { s.iter().position(|c| *c != b'0').unwrap_or(s.len()) };
+    let a = &a[strip(a)..];
+    let b = &b[strip(b)..];
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
 }
 
 /// Returns the paths of all the GPIO character devices on the system.
 ///
-/// The returned paths are sorted in name order and are confirmed to be GPIO character devices,
-/// so there is no need to check them with [`is_chip`].
+/// The returned paths are sorted in chip number order, as per [`path_compare`], and are
+/// confirmed to be GPIO character devices, so there is no need to check them with [`is_chip`].
+///
+/// Each device is only returned once, even if reachable via several paths.
+/// Symlinks are resolved to the device they point to, and device nodes that duplicate
+/// another node, such as hard links, are dropped in favour of the first in chip number
+/// order. Paths that cannot be resolved, such as dangling symlinks, are skipped.
 pub fn chips() -> Result<Vec<PathBuf>> {
     let mut chips = std::fs::read_dir("/dev")?
         .filter_map(|x| x.ok())
         .flat_map(|de| is_chip(de.path()))
         .collect::<Vec<PathBuf>>();
     chips.sort_unstable_by(|a, b| path_compare(a, b));
-    chips.dedup();
+    let mut devs = HashSet::new();
+    chips.retain(|p| fs::metadata(p).map_or(false, |m| devs.insert(m.st_rdev())));
     Ok(chips)
 }
 
+/// Returns the GPIO chips on the system with info matching the predicate.
+///
+/// Each chip is opened to read its info, which is returned along with the path,
+/// so the caller need not open the chip again to inspect it.
+///
+/// The chips are returned in chip number order, as per [`chips`].
+/// Chips that are not available to the caller are skipped.
+///
+/// # Examples
+/// ```no_run
+/// # fn example() -> gpiocdev::Result<()> {
+/// let pinctrl = gpiocdev::chip::chips_matching(|info| info.label.contains("pinctrl-bcm2711"))?;
+/// for (path, info) in pinctrl {
+///     println!("{} [{}] ({} lines)", path.display(), info.label, info.num_lines);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn chips_matching<F>(pred: F) -> Result<Vec<(PathBuf, Info)>>
+where
+    F: Fn(&Info) -> bool,
+{
+    Ok(chips()?
+        .into_iter()
+        .filter_map(|p| {
+            let info = Chip::from_path(&p).and_then(|c| c.info()).ok()?;
+            if pred(&info) {
+                Some((p, info))
+            } else {
+                None
+            }
+        })
+        .collect())
+}
+
+/// Find the GPIO chip with the given label.
+///
+/// Returns None if there is no chip with the label, or an error if the label
+/// is shared by multiple chips.
+///
+/// Chips that are not available to the caller are skipped, as per [`chips_matching`].
+pub fn find_chip_by_label(label: &str) -> Result<Option<(PathBuf, Info)>> {
+    let mut found = chips_matching(|info| info.label == label)?;
+    if found.len() > 1 {
+        return Err(Error::NonuniqueChipLabel(label.to_string()));
+    }
+    Ok(found.pop())
+}
+
+// Convert a uAPI line name, with unnamed lines being None.
+fn line_name(name: &gpiocdev_uapi::Name) -> Option<String> {
+    if name.is_empty() {
+        None
+    } else {
+        Some(String::from(name))
+    }
+}
+
 /// An iterator that returns the info for each line on the [`Chip`].
 pub struct LineInfoIterator<'a> {
     chip: &'a Chip,
@@ -116,17 +245,42 @@ impl Iterator for LineInfoIterator<'_> {
             .next()
             .map(|offset| self.chip.line_info(offset))
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.offsets.size_hint()
+    }
 }
 
+impl ExactSizeIterator for LineInfoIterator<'_> {}
+
 /// A GPIO character device.
+///
+/// # Polling
+///
+/// The chip implements [`AsFd`] and [`AsRawFd`], so the chip can be added to an
+/// external poller, such as `epoll` or `mio`, alongside other file descriptors,
+/// such as those of requests.
+/// The file descriptor is readable when info change events are available to be read
+/// using [`read_line_info_change_event`].
+///
+/// Info change events are only generated for lines being watched, so the file descriptor
+/// never becomes readable until at least one line is watched using [`watch_line_info`].
+///
+/// [`read_line_info_change_event`]: #method.read_line_info_change_event
+/// [`watch_line_info`]: #method.watch_line_info
 #[derive(Debug)]
 pub struct Chip {
     /// The resolved path of the GPIO character device.
     path: PathBuf,
     /// The open GPIO character device file.
     pub(crate) f: fs::File,
+    /// The ABI version used for operations on the chip, once set or detected.
     #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
-    abiv: Cell<Option<AbiVersion>>,
+    abiv: Mutex<Option<AbiVersion>>,
+    /// The lines currently watched via this chip.
+    watched: Mutex<BTreeSet<Offset>>,
+    /// The chip info, cached as it cannot change while the chip is open.
+    info: Mutex<Option<Info>>,
 }
 
 impl Chip {
@@ -143,18 +297,40 @@ impl Chip {
     ///```
     pub fn from_path<P: AsRef<Path>>(p: P) -> Result<Chip> {
         let path = is_chip(p.as_ref())?;
-        let f = fs::File::open(&path)?;
+        let f = open_chip(&path)?;
         Ok(Chip {
             path,
             f,
             #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
             abiv: Default::default(),
+            watched: Default::default(),
+            info: Default::default(),
         })
     }
 
+    /// Constructs a Chip using the given chip number.
+    ///
+    /// The number identifies the chip by its standard path, e.g. 3 is `/dev/gpiochip3`,
+    /// which must resolve to a valid GPIO character device.
+    ///
+    /// # Examples
+    ///```no_run
+    /// # fn example() -> gpiocdev::Result<gpiocdev::Chip>{
+    /// let chip = gpiocdev::Chip::from_number(0)?;
+    /// # Ok(chip)
+    /// # }
+    ///```
+    pub fn from_number(n: u32) -> Result<Chip> {
+        Chip::from_path(format!("/dev/gpiochip{}", n))
+    }
+
     /// Constructs a Chip using the given name.
     ///
-    /// The name must resolve to a valid GPIO character device.
+    /// The name identifies the chip by its path in `/dev`, e.g. `gpiochip3` is
+    /// `/dev/gpiochip3`, which must resolve to a valid GPIO character device.
+    /// Other devices matching the name are rejected, as per [`from_path`].
+    ///
+    /// [`from_path`]: #method.from_path
     ///
     /// # Examples
     ///```no_run
@@ -164,21 +340,124 @@ impl Chip {
     /// # }
     ///```
     pub fn from_name(n: &str) -> Result<Chip> {
-        let path = is_chip(format!("/dev/{}", n))?;
-        let f = fs::File::open(&path)?;
+        Chip::from_path(format!("/dev/{}", n))
+    }
+
+    /// Constructs a Chip from an open file descriptor.
+    ///
+    /// The file descriptor must refer to a valid GPIO character device.
+    ///
+    /// This is useful where the file descriptor is opened elsewhere, such as by a
+    /// privileged process and passed over a Unix socket.
+    ///
+    /// The [`path`] of the chip is the path the file descriptor was opened with,
+    /// if that is accessible to this process, else the path of the file descriptor
+    /// in `/proc/self/fd`.
+    ///
+    /// # Examples
+    ///```no_run
+    /// # fn example() -> gpiocdev::Result<gpiocdev::Chip>{
+    /// # use std::os::unix::prelude::OwnedFd;
+    /// let fd = OwnedFd::from(std::fs::File::open("/dev/gpiochip0")?);
+    /// let chip = gpiocdev::Chip::from_fd(fd)?;
+    /// # Ok(chip)
+    /// # }
+    ///```
+    ///
+    /// [`path`]: #method.path
+    pub fn from_fd(fd: OwnedFd) -> Result<Chip> {
+        let f = fs::File::from(fd);
+        let m = f.metadata()?;
+        let path = fd_path(&f, &m);
+        if m.st_mode() & CHARDEV_MODE == 0 {
+            return Err(Error::GpioChip(path, ErrorKind::NotCharacterDevice));
+        }
+        if !is_gpio_rdev(m.st_rdev()) {
+            return Err(Error::GpioChip(path, ErrorKind::NotGpioDevice));
+        }
         Ok(Chip {
             path,
             f,
             #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
             abiv: Default::default(),
+            watched: Default::default(),
+            info: Default::default(),
         })
     }
 
     /// Get the information for the chip.
     pub fn info(&self) -> Result<Info> {
-        Ok(Info::from(
+        let info = Info::from(
             uapi::get_chip_info(&self.f).map_err(|e| Error::Uapi(UapiCall::GetChipInfo, e))?,
-        ))
+        );
+        *self.lock_info() = Some(info.clone());
+        Ok(info)
+    }
+
+    /// Get the number of lines provided by the chip.
+    ///
+    /// The chip info is read on first use and cached for the lifetime of the chip handle,
+    /// as it cannot change while the chip is open.
+    pub fn num_lines(&self) -> Result<u32> {
+        self.cached_info().map(|info| info.num_lines)
+    }
+
+    /// Get the label of the chip.
+    ///
+    /// The chip info is read on first use and cached for the lifetime of the chip handle,
+    /// as it cannot change while the chip is open.
+    pub fn label(&self) -> Result<String> {
+        self.cached_info().map(|info| info.label)
+    }
+
+    // The chip info, read from the kernel only if not already cached.
+    fn cached_info(&self) -> Result<Info> {
+        if let Some(info) = &*self.lock_info() {
+            return Ok(info.clone());
+        }
+        self.info()
+    }
+
+    #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+    fn lock_abiv(&self) -> std::sync::MutexGuard<'_, Option<AbiVersion>> {
+        self.abiv
+            .lock()
+            .expect("failed to acquire lock on chip ABI version")
+    }
+
+    fn lock_info(&self) -> std::sync::MutexGuard<'_, Option<Info>> {
+        self.info
+            .lock()
+            .expect("failed to acquire lock on chip info")
+    }
+
+    /// Return a stable identity for the chip.
+    ///
+    /// Unlike the path, the identity is independent of how the chip was opened,
+    /// so it can be used to identify chips opened via different paths, such as
+    /// symlinks.
+    ///
+    /// The identity is based on the [`Info`] reported by the kernel, so this
+    /// involves a system call.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # fn example() -> gpiocdev::Result<()> {
+    /// use gpiocdev::Chip;
+    /// use std::collections::HashSet;
+    ///
+    /// let mut ids = HashSet::new();
+    /// for path in ["/dev/gpiochip0", "/dev/my_gpio_symlink"] {
+    ///     let chip = Chip::from_path(path)?;
+    ///     if !ids.insert(chip.uid()?) {
+    ///         println!("{} is a duplicate", path);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn uid(&self) -> Result<ChipId> {
+        self.info().map(ChipId::from)
     }
 
     /// Return the name of the chip.
@@ -197,18 +476,102 @@ impl Chip {
         self.path.as_ref()
     }
 
-    // determine the actual abi version to use for subsequent uAPI operations.
+    /// Reopen the GPIO character device at the path of the chip.
+    ///
+    /// This allows a long lived chip to recover from the device being removed and
+    /// subsequently re-added, such as a hotplugged GPIO expander, without having to be
+    /// reconstructed.
+    ///
+    /// The open file descriptor is replaced, so any line info watches are lost
+    /// and the cached chip info is discarded.
+    ///
+    /// If the device cannot be opened, such as if it is currently absent, the error
+    /// is returned and the chip is left unchanged, still holding the old file descriptor,
+    /// so the reopen may be retried later.
+    ///
+    /// For chips constructed using [`from_fd`], the path may be that of the file
+    /// descriptor in `/proc/self/fd`, which cannot be reopened once the device is removed.
+    ///
+    /// # Examples
+    ///```no_run
+    /// # fn example() -> gpiocdev::Result<()>{
+    /// let mut chip = gpiocdev::Chip::from_path("/dev/gpiochip0")?;
+    /// // ... the device is removed and re-added ...
+    /// chip.reopen()?;
+    /// # Ok(())
+    /// # }
+    ///```
+    ///
+    /// [`from_fd`]: #method.from_fd
+    pub fn reopen(&mut self) -> Result<()> {
+        let path = is_chip(&self.path)?;
+        self.f = open_chip(&path)?;
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        {
+            *self
+                .abiv
+                .get_mut()
+                .expect("failed to acquire lock on chip ABI version") = None;
+        }
+        self.watched
+            .get_mut()
+            .expect("failed to acquire lock on chip watched lines")
+            .clear();
+        *self
+            .info
+            .get_mut()
+            .expect("failed to acquire lock on chip info") = None;
+        Ok(())
+    }
+
+    /// Create a new Chip that shares the open GPIO character device with this one.
+    ///
+    /// The file descriptor is duplicated rather than the device being reopened.
+    pub(crate) fn try_clone(&self) -> Result<Chip> {
+        Ok(Chip {
+            path: self.path.clone(),
+            f: self.f.try_clone()?,
+            #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+            abiv: Mutex::new(*self.lock_abiv()),
+            watched: Default::default(),
+            info: Mutex::new(self.lock_info().clone()),
+        })
+    }
+
+    /// Return the uAPI ABI version used for operations on the chip.
+    ///
+    /// This is the version set by [`using_abi_version`], if any, else the version
+    /// returned by [`detect_abi_version`], which is only detected on the first call and
+    /// cached for the lifetime of the chip handle.
+    ///
+    /// For builds that only support one ABI version, that version is returned without
+    /// checking that it is supported by the kernel.
+    ///
+    /// [`using_abi_version`]: #method.using_abi_version
+    /// [`detect_abi_version`]: #method.detect_abi_version
     #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
-    fn actual_abi_version(&self) -> Result<AbiVersion> {
-        Ok(match self.abiv.get() {
+    pub fn cached_abi_version(&self) -> Result<AbiVersion> {
+        let cached = *self.lock_abiv();
+        Ok(match cached {
             Some(abiv) => abiv,
             None => {
                 let abiv = self.detect_abi_version()?;
-                self.abiv.set(Some(abiv));
+                *self.lock_abiv() = Some(abiv);
                 abiv
             }
         })
     }
+    /// Return the uAPI ABI version used for operations on the chip.
+    ///
+    /// This build only supports one ABI version, so that version is returned
+    /// without checking that it is supported by the kernel.
+    #[cfg(not(all(feature = "uapi_v1", feature = "uapi_v2")))]
+    pub fn cached_abi_version(&self) -> Result<AbiVersion> {
+        #[cfg(not(feature = "uapi_v2"))]
+        return Ok(V1);
+        #[cfg(feature = "uapi_v2")]
+        return Ok(V2);
+    }
 
     /// Find the info for the named line.
     ///
@@ -219,13 +582,129 @@ impl Chip {
             .and_then(|iter| iter.filter_map(|x| x.ok()).find(|li| li.name == name))
     }
 
+    /// Find the offset of the named line.
+    ///
+    /// Returns None if no line on the chip has the name.
+    ///
+    /// Line names need not be unique, so if several lines share the name then the
+    /// offset of the first, i.e. the lowest offset, is returned.
+    ///
+    /// The info for each line is read from the kernel in turn, until the line is found.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # fn example() -> Result<(), gpiocdev::Error> {
+    /// let chip = gpiocdev::Chip::from_name("gpiochip0")?;
+    /// if let Some(offset) = chip.line_offset_by_name("GPIO22")? {
+    ///     println!("GPIO22 is line {}", offset);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn line_offset_by_name(&self, name: &str) -> Result<Option<Offset>> {
+        for offset in 0..self.num_lines()? {
+            if self.name_of(offset)?.as_deref() == Some(name) {
+                return Ok(Some(offset));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Find the named line on the chip.
+    ///
+    /// Returns the info for the first matching line, or None if no line on the chip
+    /// has the name.
+    ///
+    /// Unlike [`find_line_info`], errors reading the line info are returned rather
+    /// than being treated as the line not being found.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # fn example() -> Result<(), gpiocdev::Error> {
+    /// let chip = gpiocdev::Chip::from_name("gpiochip0")?;
+    /// if let Some(li) = chip.find_line("GPIO22")? {
+    ///     println!("GPIO22 is line {}", li.offset);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`find_line_info`]: #method.find_line_info
+    pub fn find_line(&self, name: &str) -> Result<Option<line::Info>> {
+        for li in self.line_info_iter()? {
+            let li = li?;
+            if li.name == name {
+                return Ok(Some(li));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Find a collection of named lines on the chip.
+    ///
+    ///  - `names`: the names of the lines to find, as any collection of `&str` or `String`
+    ///  - `strict`: if true then the names are checked to be unique within the chip
+    ///
+    /// As per [`find_named_lines`], but only the lines on this chip are scanned,
+    /// so only the line info is returned - the chip is known.
+    ///
+    /// For each name, returns the info for the first matching line, if one can be found.
+    /// If it cannot be found then there will be no matching entry in the returned map.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # fn example() -> Result<(), gpiocdev::Error> {
+    /// use gpiocdev::line::Offset;
+    ///
+    /// let chip = gpiocdev::Chip::from_name("gpiochip0")?;
+    /// let leds = chip.find_lines(["LED0", "LED1"], true)?;
+    /// let offsets: Vec<Offset> = leds.values().map(|li| li.offset).collect();
+    /// let req = gpiocdev::Request::builder()
+    ///     .on_chip(chip.path())
+    ///     .with_lines(&offsets)
+    ///     .as_output(gpiocdev::line::Value::Inactive)
+    ///     .request()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`find_named_lines`]: crate::find_named_lines
+    pub fn find_lines<I, S>(&self, names: I, strict: bool) -> Result<HashMap<String, line::Info>>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let names: Vec<S> = names.into_iter().collect();
+        let wanted: HashSet<&str> = names.iter().map(|n| n.as_ref()).collect();
+        let mut found = HashMap::new();
+        for offset in 0..self.num_lines()? {
+            let info = self.line_info(offset)?;
+            if !wanted.contains(info.name.as_str()) {
+                continue;
+            }
+            if found.contains_key(&info.name) {
+                if strict {
+                    return Err(Error::NonuniqueLineName(info.name));
+                }
+                continue;
+            }
+            found.insert(info.name.clone(), info);
+            if !strict && found.len() == wanted.len() {
+                break;
+            }
+        }
+        Ok(found)
+    }
+
     /// Get the information for a line on the chip.
     pub fn line_info(&self, offset: Offset) -> Result<line::Info> {
+        #[cfg(feature = "test_abi_override")]
+        self.check_abi_override(UapiCall::GetLineInfo)?;
         self.do_line_info(offset)
     }
     #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
     fn do_line_info(&self, offset: Offset) -> Result<line::Info> {
-        match self.actual_abi_version()? {
+        match self.cached_abi_version()? {
             V1 => v1::get_line_info(&self.f, offset).map(|li| line::Info::from(&li)),
             V2 => v2::get_line_info(&self.f, offset).map(|li| line::Info::from(&li)),
         }
@@ -238,27 +717,134 @@ impl Chip {
             .map_err(|e| Error::Uapi(UapiCall::GetLineInfo, e))
     }
 
+    /// Get the name of a line on the chip.
+    ///
+    /// Returns None if the line is unnamed.
+    ///
+    /// The full line info is read from the kernel, so this is no cheaper than
+    /// [`line_info`], but only the name is decoded.
+    ///
+    /// Names that are not valid UTF-8 are converted lossily.
+    ///
+    /// [`line_info`]: #method.line_info
+    pub fn name_of(&self, offset: Offset) -> Result<Option<String>> {
+        #[cfg(feature = "test_abi_override")]
+        self.check_abi_override(UapiCall::GetLineInfo)?;
+        self.do_name_of(offset)
+    }
+    #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+    fn do_name_of(&self, offset: Offset) -> Result<Option<String>> {
+        match self.cached_abi_version()? {
+            V1 => v1::get_line_info(&self.f, offset).map(|li| line_name(&li.name)),
+            V2 => v2::get_line_info(&self.f, offset).map(|li| line_name(&li.name)),
+        }
+        .map_err(|e| Error::Uapi(UapiCall::GetLineInfo, e))
+    }
+    #[cfg(not(all(feature = "uapi_v1", feature = "uapi_v2")))]
+    fn do_name_of(&self, offset: Offset) -> Result<Option<String>> {
+        uapi::get_line_info(&self.f, offset)
+            .map(|li| line_name(&li.name))
+            .map_err(|e| Error::Uapi(UapiCall::GetLineInfo, e))
+    }
+
+    /// Get the names of all the lines on the chip.
+    ///
+    /// The names are indexed by offset, with None for unnamed lines.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # fn example() -> Result<(), gpiocdev::Error> {
+    /// let chip = gpiocdev::Chip::from_name("gpiochip0")?;
+    /// for (offset, name) in chip.line_names()?.iter().enumerate() {
+    ///     println!("{}: {}", offset, name.as_deref().unwrap_or("unnamed"));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn line_names(&self) -> Result<Vec<Option<String>>> {
+        (0..self.num_lines()?)
+            .map(|offset| self.name_of(offset))
+            .collect()
+    }
+
     /// An iterator that returns the info for each line on the chip.
-    pub fn line_info_iter(&self) -> Result<LineInfoIterator> {
-        let cinfo = self.info()?;
+    ///
+    /// The lines are returned in offset order, with the iterator yielding exactly
+    /// [`num_lines`] entries.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # fn example() -> Result<(), gpiocdev::Error> {
+    /// let chip = gpiocdev::Chip::from_name("gpiochip0")?;
+    /// for li in chip.line_info_iter()? {
+    ///     let li = li?;
+    ///     println!("{}: {}", li.offset, li.name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`num_lines`]: Info::num_lines
+    pub fn line_info_iter(&self) -> Result<LineInfoIterator<'_>> {
         Ok(LineInfoIterator {
             chip: self,
             offsets: Range {
                 start: 0,
-                end: cinfo.num_lines,
+                end: self.num_lines()?,
             },
         })
     }
 
+    /// Get the information for all the lines on the chip.
+    ///
+    /// The info is in offset order.
+    ///
+    /// Returns the first error encountered, if any line cannot be read.
+    pub fn line_infos(&self) -> Result<Vec<line::Info>> {
+        self.line_info_iter()?.collect()
+    }
+
+    /// Get a snapshot of the chip info and the info for all the lines on the chip.
+    ///
+    /// Returns the first error encountered, if any line cannot be read.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # fn example() -> Result<(), gpiocdev::Error> {
+    /// let chip = gpiocdev::Chip::from_name("gpiochip0")?;
+    /// let t = chip.topology()?;
+    /// println!("{} [{}] ({} lines)", t.info.name, t.info.label, t.info.num_lines);
+    /// for li in &t.lines {
+    ///     println!("\t{}: {}", li.offset, li.name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn topology(&self) -> Result<Topology> {
+        let info = self.info()?;
+        let lines = (0..info.num_lines)
+            .map(|offset| self.line_info(offset))
+            .collect::<Result<Vec<line::Info>>>()?;
+        Ok(Topology { info, lines })
+    }
+
     /// Add a watch for changes to the publicly available information on a line.
     ///
-    /// This is a null operation if there is already a watch on the line.
+    /// Returns an error if the line is already being watched via this chip.
+    ///
+    /// The line is tracked in [`watched_lines`] until the watch is removed.
+    ///
+    /// [`watched_lines`]: #method.watched_lines
     pub fn watch_line_info(&self, offset: Offset) -> Result<line::Info> {
-        self.do_watch_line_info(offset)
+        #[cfg(feature = "test_abi_override")]
+        self.check_abi_override(UapiCall::WatchLineInfo)?;
+        let info = self.do_watch_line_info(offset)?;
+        self.lock_watched().insert(offset);
+        Ok(info)
     }
     #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
     fn do_watch_line_info(&self, offset: Offset) -> Result<line::Info> {
-        match self.actual_abi_version()? {
+        match self.cached_abi_version()? {
             V1 => v1::watch_line_info(&self.f, offset).map(|li| line::Info::from(&li)),
             V2 => v2::watch_line_info(&self.f, offset).map(|li| line::Info::from(&li)),
         }
@@ -273,19 +859,109 @@ impl Chip {
 
     /// Remove a watch for changes to the publicly available information on a line.
     ///
-    /// This is a null operation if there is no existing watch on the line.
+    /// Returns an error if the line is not being watched via this chip.
     pub fn unwatch_line_info(&self, offset: Offset) -> Result<()> {
+        // the line is no longer watched, even if the unwatch fails.
+        self.lock_watched().remove(&offset);
+        #[cfg(feature = "test_abi_override")]
+        self.check_abi_override(UapiCall::UnwatchLineInfo)?;
         uapi::unwatch_line_info(&self.f, offset)
             .map_err(|e| Error::Uapi(UapiCall::UnwatchLineInfo, e))
     }
 
-    /// Check if the request has at least one info change event available to read.
+    /// Remove the watches from all the lines watched via this chip.
+    ///
+    /// Attempts to remove the watch from every line, returning the first error encountered.
+    pub fn unwatch_all(&self) -> Result<()> {
+        self.unwatch_lines_info(&self.watched_lines())
+    }
+
+    /// The offsets of the lines currently watched via this chip, in ascending order.
+    ///
+    /// Only watches added via this chip are tracked, so watches added by other means,
+    /// such as via another handle to the same file descriptor, are not included.
+    pub fn watched_lines(&self) -> Vec<Offset> {
+        self.lock_watched().iter().copied().collect()
+    }
+
+    fn lock_watched(&self) -> std::sync::MutexGuard<'_, BTreeSet<Offset>> {
+        self.watched
+            .lock()
+            .expect("failed to acquire lock on chip watched lines")
+    }
+
+    /// Add watches for changes to the publicly available information on a set of lines.
+    ///
+    /// Returns the info for each line, in the same order as the offsets.
+    ///
+    /// If any watch fails then the watches added by this call are removed before
+    /// returning the error, so no lines are left watched.
+    pub fn watch_lines_info(&self, offsets: &[Offset]) -> Result<Vec<line::Info>> {
+        let mut infos = Vec::with_capacity(offsets.len());
+        for offset in offsets {
+            match self.watch_line_info(*offset) {
+                Ok(info) => infos.push(info),
+                Err(e) => {
+                    for info in &infos {
+                        _ = self.unwatch_line_info(info.offset);
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Ok(infos)
+    }
+
+    /// Remove watches for changes to the publicly available information on a set of lines.
+    ///
+    /// Attempts to remove the watch from every line, returning the first error encountered.
+    pub fn unwatch_lines_info(&self, offsets: &[Offset]) -> Result<()> {
+        let mut res = Ok(());
+        for offset in offsets {
+            if let Err(e) = self.unwatch_line_info(*offset) {
+                if res.is_ok() {
+                    res = Err(e);
+                }
+            }
+        }
+        res
+    }
+
+    /// Check if the chip has at least one info change event available to read.
     pub fn has_line_info_change_event(&self) -> Result<bool> {
+        self.has_info_change_event()
+    }
+
+    /// Check if the chip has at least one info change event available to read.
+    ///
+    /// Returns true if [`read_line_info_change_event`] will return an event without blocking.
+    ///
+    /// This does not block, and the event is not consumed.
+    ///
+    /// Events are only generated for watched lines, so this always returns false if
+    /// no lines are being watched.
+    ///
+    /// [`read_line_info_change_event`]: #method.read_line_info_change_event
+    pub fn has_info_change_event(&self) -> Result<bool> {
         gpiocdev_uapi::has_event(&self.f).map_err(|e| Error::Uapi(UapiCall::HasEvent, e))
     }
 
     /// Wait for an info change event to be available.
     pub fn wait_line_info_change_event(&self, timeout: Duration) -> Result<bool> {
+        self.wait_info_change_event(timeout)
+    }
+
+    /// Wait for an info change event to be available.
+    ///
+    /// Returns true if [`read_line_info_change_event`] will return an event without blocking,
+    /// or false if the timeout expires first.
+    ///
+    /// The event is not consumed.
+    ///
+    /// * `timeout` - The maximum time to wait for an event.
+    ///
+    /// [`read_line_info_change_event`]: #method.read_line_info_change_event
+    pub fn wait_info_change_event(&self, timeout: Duration) -> Result<bool> {
         gpiocdev_uapi::wait_event(&self.f, timeout).map_err(|e| Error::Uapi(UapiCall::WaitEvent, e))
     }
 
@@ -297,7 +973,7 @@ impl Chip {
     }
     #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
     fn do_read_line_info_change_event(&self) -> Result<InfoChangeEvent> {
-        self.actual_abi_version()?;
+        self.cached_abi_version()?;
         // bbuf is statically sized to the greater of the v1/v2 size so it can be placed on the stack.
         debug_assert!(
             mem::size_of::<v2::LineInfoChangeEvent>() >= mem::size_of::<v1::LineInfoChangeEvent>()
@@ -318,14 +994,66 @@ impl Chip {
         self.line_info_change_event_from_slice(&buf[0..n])
     }
 
+    /// Read a single line info change event from the chip, waiting up to the timeout
+    /// for one to be available.
+    ///
+    /// Returns None if the timeout expires before an event is available.
+    ///
+    /// * `timeout` - The maximum time to wait for an event.
+    pub fn read_info_change_event_timeout(
+        &self,
+        timeout: Duration,
+    ) -> Result<Option<InfoChangeEvent>> {
+        if !self.wait_info_change_event(timeout)? {
+            return Ok(None);
+        }
+        self.read_line_info_change_event().map(Some)
+    }
+
     /// An iterator for info change events from the chip.
-    pub fn info_change_events(&self) -> InfoChangeIterator {
+    pub fn info_change_events(&self) -> InfoChangeIterator<'_> {
         InfoChangeIterator {
             chip: self,
             buf: vec![0_u64; self.line_info_change_event_u64_size()],
         }
     }
 
+    /// An iterator for info change events from the chip, returning only those events
+    /// matching the given kinds and offsets.
+    ///
+    /// * `kinds` - The kinds of event to return. If empty then all kinds are returned.
+    /// * `offsets` - The lines to return events for. If empty then events for all watched
+    ///   lines are returned.
+    ///
+    /// Events that do not match are read and discarded.
+    /// Errors are always returned.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # fn example() -> gpiocdev::Result<()> {
+    /// use gpiocdev::line::InfoChangeKind;
+    ///
+    /// let chip = gpiocdev::Chip::from_name("gpiochip0")?;
+    /// for offset in 0..10 {
+    ///     chip.watch_line_info(offset)?;
+    /// }
+    /// for evt in chip.info_change_events_filtered(&[InfoChangeKind::Reconfigured], &[]) {
+    ///     println!("{:?}", evt?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn info_change_events_filtered(
+        &self,
+        kinds: &[InfoChangeKind],
+        offsets: &[Offset],
+    ) -> FilteredInfoChangeIterator<'_> {
+        FilteredInfoChangeIterator {
+            events: self.info_change_events(),
+            filter: InfoChangeFilter::new(kinds, offsets),
+        }
+    }
+
     /// Detect the most recent uAPI ABI supported by the library for the chip.
     pub fn detect_abi_version(&self) -> Result<AbiVersion> {
         // check in preferred order
@@ -337,10 +1065,38 @@ impl Chip {
         Err(Error::NoAbiSupport())
     }
 
+    /// Detect which uAPI ABI versions are supported by the library and kernel for the chip.
+    ///
+    /// Unlike [`detect_abi_support`](crate::detect_abi_support), the result is not cached.
+    pub fn abi_support(&self) -> AbiSupport {
+        #[cfg(feature = "test_abi_override")]
+        return self.probe_abi_support().with_overrides();
+        #[cfg(not(feature = "test_abi_override"))]
+        return self.probe_abi_support();
+    }
+
+    // Probe the ABI versions supported, ignoring any test overrides.
+    pub(crate) fn probe_abi_support(&self) -> AbiSupport {
+        AbiSupport {
+            v1: self.do_supports_abi_version(V1).is_ok(),
+            v2: self.do_supports_abi_version(V2).is_ok(),
+        }
+    }
+
     /// Check if the platform and library support a specific ABI version.
     pub fn supports_abi_version(&self, abiv: AbiVersion) -> Result<()> {
+        #[cfg(feature = "test_abi_override")]
+        if crate::test::is_abi_unsupported(abiv) {
+            return Err(Error::UnsupportedAbi(abiv, AbiSupportKind::Kernel));
+        }
         self.do_supports_abi_version(abiv)
     }
+    // Fail a uAPI call if the ABI version in use has been forced unsupported.
+    #[cfg(feature = "test_abi_override")]
+    fn check_abi_override(&self, call: UapiCall) -> Result<()> {
+        crate::test::check_uapi_call(self.cached_abi_version()?, call)
+    }
+
     #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
     fn do_supports_abi_version(&self, abiv: AbiVersion) -> Result<()> {
         let res = match abiv {
@@ -371,28 +1127,35 @@ impl Chip {
     /// Set the ABI version to use for subsequent operations.
     #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
     pub fn using_abi_version(&mut self, abiv: AbiVersion) -> &mut Self {
-        self.abiv.set(Some(abiv));
+        *self
+            .abiv
+            .get_mut()
+            .expect("failed to acquire lock on chip ABI version") = Some(abiv);
         self
     }
 
     #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
     fn line_info_change_event_from_slice(&self, d: &[u64]) -> Result<InfoChangeEvent> {
-        Ok(match self.actual_abi_version()? {
+        #[cfg(feature = "test_abi_override")]
+        self.check_abi_override(UapiCall::LICEFromBuf)?;
+        Ok(match self.cached_abi_version()? {
             V1 => InfoChangeEvent::from(
                 v1::LineInfoChangeEvent::from_slice(d)
-                    .map_err(|e| Error::Uapi(UapiCall::LICEFromBuf, e))?,
+                    .map_err(|e| decode_error::<InfoChangeKind>(UapiCall::LICEFromBuf, e))?,
             ),
             V2 => InfoChangeEvent::from(
                 v2::LineInfoChangeEvent::from_slice(d)
-                    .map_err(|e| Error::Uapi(UapiCall::LICEFromBuf, e))?,
+                    .map_err(|e| decode_error::<InfoChangeKind>(UapiCall::LICEFromBuf, e))?,
             ),
         })
     }
     #[cfg(not(all(feature = "uapi_v1", feature = "uapi_v2")))]
     fn line_info_change_event_from_slice(&self, d: &[u64]) -> Result<InfoChangeEvent> {
+        #[cfg(feature = "test_abi_override")]
+        self.check_abi_override(UapiCall::LICEFromBuf)?;
         Ok(InfoChangeEvent::from(
             uapi::LineInfoChangeEvent::from_slice(d)
-                .map_err(|e| Error::Uapi(UapiCall::LICEFromBuf, e))?,
+                .map_err(|e| decode_error::<InfoChangeKind>(UapiCall::LICEFromBuf, e))?,
         ))
     }
 
@@ -404,7 +1167,7 @@ impl Chip {
     fn line_info_change_event_size(&self) -> usize {
         // may not be initialised if called via info_change_events()
         // in which case return the larger of the two
-        match self.abiv.get().unwrap_or(V2) {
+        match self.lock_abiv().unwrap_or(V2) {
             V1 => mem::size_of::<v1::LineInfoChangeEvent>(),
             V2 => mem::size_of::<v2::LineInfoChangeEvent>(),
         }
@@ -466,6 +1229,67 @@ impl From<uapi::ChipInfo> for Info {
     }
 }
 
+/// A snapshot of the chip info and the info for all the lines on the chip.
+///
+/// Returned by [`Chip::topology`].
+///
+/// With the `serde` feature, the topology can be serialized to describe the chip for
+/// documentation and external tooling, e.g. as JSON using `serde_json`.
+/// The chip info fields are flattened into the topology.
+///
+/// Line names that are not valid UTF-8 are serialized lossily.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct Topology {
+    /// The info for the chip.
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub info: Info,
+
+    /// The info for each line on the chip, in offset order.
+    pub lines: Vec<line::Info>,
+}
+
+/// A stable identity for a GPIO chip.
+///
+/// The kernel guarantees the chip name is unique within the system, so distinct
+/// chips have distinct identities, while the same chip opened via different paths
+/// has the one identity.
+///
+/// Returned by [`Chip::uid`].
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ChipId {
+    /// The system name for the chip, such as "*gpiochip0*".
+    pub name: String,
+
+    /// The number of lines provided by the chip.
+    pub num_lines: u32,
+}
+
+impl From<Info> for ChipId {
+    fn from(info: Info) -> Self {
+        ChipId {
+            name: info.name,
+            num_lines: info.num_lines,
+        }
+    }
+}
+
+impl From<ChipId> for (String, u32) {
+    fn from(id: ChipId) -> Self {
+        (id.name, id.num_lines)
+    }
+}
+
+impl fmt::Display for ChipId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({} lines)", self.name, self.num_lines)
+    }
+}
+
 /// An iterator for reading info change events from a [`Chip`].
 ///
 /// Blocks until events are available.
@@ -492,29 +1316,297 @@ impl Iterator for InfoChangeIterator<'_> {
     }
 }
 
+/// An iterator for reading info change events from a [`Chip`], returning only those
+/// events matching a filter.
+///
+/// Created by [`Chip::info_change_events_filtered`].
+///
+/// Blocks until matching events are available.
+pub struct FilteredInfoChangeIterator<'a> {
+    events: InfoChangeIterator<'a>,
+
+    filter: InfoChangeFilter,
+}
+
+impl Iterator for FilteredInfoChangeIterator<'_> {
+    type Item = Result<InfoChangeEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.events.read_event() {
+                Ok(evt) if !self.filter.matches(&evt) => {}
+                res => return Some(res),
+            }
+        }
+    }
+}
+
+/// A filter for info change events, by kind and line offset.
+///
+/// Empty sets match everything.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct InfoChangeFilter {
+    kinds: Vec<InfoChangeKind>,
+
+    offsets: Vec<Offset>,
+}
+
+impl InfoChangeFilter {
+    pub(crate) fn new(kinds: &[InfoChangeKind], offsets: &[Offset]) -> InfoChangeFilter {
+        InfoChangeFilter {
+            kinds: kinds.to_vec(),
+            offsets: offsets.to_vec(),
+        }
+    }
+
+    pub(crate) fn matches(&self, evt: &InfoChangeEvent) -> bool {
+        (self.kinds.is_empty() || self.kinds.contains(&evt.kind))
+            && (self.offsets.is_empty() || self.offsets.contains(&evt.info.offset))
+    }
+}
+
+/// A cache of the info for all the lines on a chip, kept fresh using info change events.
+///
+/// The cache watches all the lines on the chip, and updates the cached info from the
+/// info change events when they are processed by [`process_events`].
+///
+/// The cached info is exact as of the last processed event, so it becomes stale
+/// when the info changes, until the events reporting the changes are processed.
+/// The chip file descriptor is readable when events are available, so the cache can be
+/// refreshed on demand, or when signalled by a poller.
+///
+/// # Examples
+/// ```no_run
+/// # fn example() -> Result<(), gpiocdev::Error> {
+/// use gpiocdev::chip::{Chip, LineInfoCache};
+///
+/// let mut cache = LineInfoCache::new(Chip::from_name("gpiochip0")?)?;
+/// // ...
+/// cache.process_events()?;
+/// for li in cache.all() {
+///     println!("{}: {} {}", li.offset, li.name, li.consumer);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`process_events`]: #method.process_events
+#[derive(Debug)]
+pub struct LineInfoCache {
+    chip: Chip,
+
+    /// The info for each line, indexed by offset.
+    infos: Vec<line::Info>,
+}
+
+impl LineInfoCache {
+    /// Create a cache of the info for all the lines on the chip.
+    ///
+    /// Watches all the lines on the chip, other than those already watched via the
+    /// chip, which are assumed to still be watched.
+    ///
+    /// If any watch fails then the watches added are removed before returning the error.
+    pub fn new(chip: Chip) -> Result<LineInfoCache> {
+        let num_lines = chip.num_lines()?;
+        let watched = chip.watched_lines();
+        let mut infos = watched
+            .iter()
+            .filter(|offset| **offset < num_lines)
+            .map(|offset| chip.line_info(*offset))
+            .collect::<Result<Vec<line::Info>>>()?;
+        // watched last, as watch_lines_info() removes its watches on failure.
+        let unwatched: Vec<Offset> = (0..num_lines)
+            .filter(|offset| !watched.contains(offset))
+            .collect();
+        infos.extend(chip.watch_lines_info(&unwatched)?);
+        infos.sort_unstable_by_key(|info| info.offset);
+        Ok(LineInfoCache { chip, infos })
+    }
+
+    /// Get the cached info for a line.
+    ///
+    /// Returns None if the offset is not a line on the chip.
+    pub fn get(&self, offset: Offset) -> Option<&line::Info> {
+        self.infos.get(offset as usize)
+    }
+
+    /// Get the cached info for all the lines on the chip, in offset order.
+    pub fn all(&self) -> &[line::Info] {
+        &self.infos
+    }
+
+    /// Update the cache from any available info change events.
+    ///
+    /// Does not block, so only the events available when called are processed.
+    ///
+    /// Returns the number of events processed.
+    pub fn process_events(&mut self) -> Result<usize> {
+        let mut count = 0;
+        while self.chip.has_info_change_event()? {
+            let evt = self.chip.read_line_info_change_event()?;
+            if let Some(info) = self.infos.get_mut(evt.info.offset as usize) {
+                *info = evt.info;
+            }
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// The chip containing the lines.
+    ///
+    /// Reading info change events directly from the chip will bypass the cache,
+    /// leaving it stale.
+    pub fn chip(&self) -> &Chip {
+        &self.chip
+    }
+
+    /// Release the chip from the cache.
+    ///
+    /// The lines remain watched.
+    pub fn into_chip(self) -> Chip {
+        self.chip
+    }
+}
+
+impl AsFd for LineInfoCache {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.chip.as_fd()
+    }
+}
+
 /// Reasons a file cannot be opened as a GPIO character device.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
 pub enum ErrorKind {
     /// File is not a character device.
     NotCharacterDevice,
 
     /// File is not a GPIO character device.
     NotGpioDevice,
+
+    /// The user does not have permission to open the file.
+    ///
+    /// Identifies the owner and permissions of the file, to help identify the remedy,
+    /// which is typically to add the user to the group owning the file.
+    PermissionDenied {
+        /// The user id of the owner of the file.
+        uid: u32,
+        /// The name of the owner of the file, if the user has a name.
+        user: Option<String>,
+        /// The group id of the owner of the file.
+        gid: u32,
+        /// The name of the group owning the file, if the group has a name.
+        group: Option<String>,
+        /// The permission bits of the file.
+        mode: u32,
+    },
 }
 
 impl fmt::Display for ErrorKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let msg = match self {
-            ErrorKind::NotCharacterDevice => "is not a character device",
-            ErrorKind::NotGpioDevice => "is not a GPIO character device",
-        };
-        write!(f, "{}", msg)
+        match self {
+            ErrorKind::NotCharacterDevice => write!(f, "is not a character device"),
+            ErrorKind::NotGpioDevice => write!(f, "is not a GPIO character device"),
+            ErrorKind::PermissionDenied {
+                uid,
+                user,
+                gid,
+                group,
+                mode,
+            } => {
+                let user = user.clone().unwrap_or_else(|| uid.to_string());
+                let group = group.clone().unwrap_or_else(|| gid.to_string());
+                write!(f, "permission denied ({}:{} {:04o}) - ", user, group, mode)?;
+                if *gid != 0 && mode & 0o060 != 0 {
+                    write!(f, "add the user to the '{}' group", group)
+                } else if *uid == 0 {
+                    write!(f, "requires root access")
+                } else {
+                    write!(f, "requires access as '{}'", user)
+                }
+            }
+        }
+    }
+}
+
+// The name of the user with the given id, if the user has a name.
+fn user_name(uid: u32) -> Option<String> {
+    let mut buf = [0 as libc::c_char; 1024];
+    // SAFETY: passwd is plain old data, so zeroed is a valid value.
+    let mut pwd = unsafe { mem::zeroed::<libc::passwd>() };
+    let mut res = std::ptr::null_mut();
+    // SAFETY: pwd and buf are valid for getpwuid_r to populate.
+    let rc = unsafe { libc::getpwuid_r(uid, &mut pwd, buf.as_mut_ptr(), buf.len(), &mut res) };
+    if rc != 0 || res.is_null() {
+        return None;
+    }
+    // SAFETY: getpwuid_r null terminates the name, which is stored in buf.
+    Some(
+        unsafe { CStr::from_ptr(pwd.pw_name) }
+            .to_string_lossy()
+            .into_owned(),
+    )
+}
+
+// The name of the group with the given id, if the group has a name.
+fn group_name(gid: u32) -> Option<String> {
+    let mut buf = [0 as libc::c_char; 1024];
+    // SAFETY: group is plain old data, so zeroed is a valid value.
+    let mut grp = unsafe { mem::zeroed::<libc::group>() };
+    let mut res = std::ptr::null_mut();
+    // SAFETY: grp and buf are valid for getgrgid_r to populate.
+    let rc = unsafe { libc::getgrgid_r(gid, &mut grp, buf.as_mut_ptr(), buf.len(), &mut res) };
+    if rc != 0 || res.is_null() {
+        return None;
     }
+    // SAFETY: getgrgid_r null terminates the name, which is stored in buf.
+    Some(
+        unsafe { CStr::from_ptr(grp.gr_name) }
+            .to_string_lossy()
+            .into_owned(),
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    #[test]
+    fn info_change_filter() {
+        let evt = |kind, offset| InfoChangeEvent {
+            info: line::Info {
+                offset,
+                ..Default::default()
+            },
+            timestamp_ns: 0,
+            kind,
+        };
+        let requested = evt(InfoChangeKind::Requested, 3);
+        let reconfigured = evt(InfoChangeKind::Reconfigured, 3);
+        let released = evt(InfoChangeKind::Released, 5);
+
+        // empty matches everything
+        let f = InfoChangeFilter::default();
+        assert!(f.matches(&requested));
+        assert!(f.matches(&reconfigured));
+        assert!(f.matches(&released));
+
+        let f = InfoChangeFilter::new(&[InfoChangeKind::Reconfigured], &[]);
+        assert!(!f.matches(&requested));
+        assert!(f.matches(&reconfigured));
+        assert!(!f.matches(&released));
+
+        let f = InfoChangeFilter::new(&[], &[5, 7]);
+        assert!(!f.matches(&requested));
+        assert!(!f.matches(&reconfigured));
+        assert!(f.matches(&released));
+
+        let f = InfoChangeFilter::new(&[InfoChangeKind::Requested, InfoChangeKind::Released], &[3]);
+        assert!(f.matches(&requested));
+        assert!(!f.matches(&reconfigured));
+        assert!(!f.matches(&released));
+    }
     #[cfg(all(feature = "uapi_v1", not(feature = "uapi_v2")))]
     use gpiocdev_uapi::v1 as uapi;
     #[cfg(any(feature = "uapi_v2", not(feature = "uapi_v1")))]
@@ -540,10 +1632,121 @@ mod tests {
         }
     }
 
+    mod chip_id {
+        use super::{ChipId, Info};
+        use std::collections::HashSet;
+
+        #[test]
+        fn from_info() {
+            let info = Info {
+                name: "gpiochip3".into(),
+                label: "peel".into(),
+                num_lines: 42,
+            };
+            let id = ChipId::from(info);
+            assert_eq!(id.name.as_str(), "gpiochip3");
+            assert_eq!(id.num_lines, 42);
+            assert_eq!(id.to_string(), "gpiochip3 (42 lines)");
+            assert_eq!(<(String, u32)>::from(id), ("gpiochip3".to_string(), 42));
+        }
+
+        #[test]
+        fn hash() {
+            let id = |name: &str, num_lines| ChipId {
+                name: name.into(),
+                num_lines,
+            };
+            let mut ids = HashSet::new();
+            assert!(ids.insert(id("gpiochip0", 8)));
+            assert!(!ids.insert(id("gpiochip0", 8)));
+            assert!(ids.insert(id("gpiochip1", 8)));
+            assert_eq!(ids.len(), 2);
+        }
+    }
+
+    #[test]
+    fn error_kind_display() {
+        assert_eq!(
+            ErrorKind::NotCharacterDevice.to_string(),
+            "is not a character device"
+        );
+        assert_eq!(
+            ErrorKind::NotGpioDevice.to_string(),
+            "is not a GPIO character device"
+        );
+        let root_only = ErrorKind::PermissionDenied {
+            uid: 0,
+            user: Some("root".into()),
+            gid: 0,
+            group: Some("root".into()),
+            mode: 0o660,
+        };
+        assert_eq!(
+            root_only.to_string(),
+            "permission denied (root:root 0660) - requires root access"
+        );
+        let group_access = ErrorKind::PermissionDenied {
+            uid: 0,
+            user: Some("root".into()),
+            gid: 20,
+            group: Some("dialout".into()),
+            mode: 0o660,
+        };
+        assert_eq!(
+            group_access.to_string(),
+            "permission denied (root:dialout 0660) - add the user to the 'dialout' group"
+        );
+        // the group cannot access the file, so joining it is no help
+        let owner_only = ErrorKind::PermissionDenied {
+            uid: 1000,
+            user: Some("pi".into()),
+            gid: 20,
+            group: Some("dialout".into()),
+            mode: 0o600,
+        };
+        assert_eq!(
+            owner_only.to_string(),
+            "permission denied (pi:dialout 0600) - requires access as 'pi'"
+        );
+        let owner_only = ErrorKind::PermissionDenied {
+            uid: 0,
+            user: Some("root".into()),
+            gid: 20,
+            group: Some("dialout".into()),
+            mode: 0o600,
+        };
+        assert_eq!(
+            owner_only.to_string(),
+            "permission denied (root:dialout 0600) - requires root access"
+        );
+        // ids without names are displayed as numbers
+        let unnamed = ErrorKind::PermissionDenied {
+            uid: 4_000_000_001,
+            user: None,
+            gid: 4_000_000_002,
+            group: None,
+            mode: 0o660,
+        };
+        assert_eq!(
+            unnamed.to_string(),
+            "permission denied (4000000001:4000000002 0660) - add the user to the '4000000002' group"
+        );
+        assert_eq!(
+            Error::GpioChip(PathBuf::from("/dev/gpiochip0"), unnamed).to_string(),
+            "\"/dev/gpiochip0\" permission denied (4000000001:4000000002 0660) - add the user to the '4000000002' group."
+        );
+    }
+
+    #[test]
+    fn owner_names() {
+        // ids without names
+        assert_eq!(user_name(4_000_000_001), None);
+        assert_eq!(group_name(4_000_000_002), None);
+    }
+
     #[test]
     fn path_compare() {
         use super::path_compare;
-        use std::cmp::Ordering;
 
         assert_eq!(
             path_compare(Path::new("/dev/gpiochip0"), Path::new("/dev/gpiochip0")),
@@ -573,5 +1776,53 @@ mod tests {
             path_compare(Path::new("/dev/gpiochip0"), Path::new("/dev/gpiochip1")),
             Ordering::Less
         );
+        assert_eq!(
+            path_compare(Path::new("/dev/gpiochip2"), Path::new("/dev/gpiochip02")),
+            Ordering::Greater
+        );
+        assert_eq!(
+            path_compare(Path::new("/dev/gpiochip9"), Path::new("/dev/gpiochipa")),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn path_sort() {
+        use super::path_compare;
+
+        let sorted: Vec<PathBuf> = (0..=12)
+            .map(|n| PathBuf::from(format!("/dev/gpiochip{}", n)))
+            .collect();
+        let mut paths = sorted.clone();
+        paths.reverse();
+        paths.swap(2, 9);
+        paths.sort_by(|a, b| path_compare(a, b));
+        assert_eq!(paths, sorted);
+
+        // non-standard names
+        let sorted: Vec<PathBuf> = [
+            "/dev/gpio-sim/chip3",
+            "/dev/gpio-sim/chip11",
+            "/dev/gpiochip",
+            "/dev/gpiochip1",
+            "/dev/gpiochip01a",
+            "/dev/gpiochip1a",
+            "/dev/gpiochip2",
+            "/dev/gpiochip10",
+            "/dev/gpiochip10_alias",
+            "/dev/gpiochip99999999999999999999",
+            "/dev/gpiochip100000000000000000000",
+            "/dev/gpiochipx",
+        ]
+        .iter()
+        .map(PathBuf::from)
+        .collect();
+        for rotation in 0..sorted.len() {
+            let mut paths = sorted.clone();
+            paths.rotate_left(rotation);
+            paths.reverse();
+            paths.sort_by(|a, b| path_compare(a, b));
+            assert_eq!(paths, sorted);
+        }
     }
 }
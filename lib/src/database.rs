@@ -0,0 +1,165 @@
+// SPDX-FileCopyrightText: 2024 Kent Gibson <warthog618@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::line::{self, Offset};
+use crate::{Error, FoundLine, LineIterator, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// An index of the lines in the system, for repeated lookups by name.
+///
+/// The lines are scanned once when the database is created, and again on each call
+/// to [`refresh`].
+///
+/// The database is a snapshot - it does not track chips being added or removed,
+/// nor changes to line names or info. Where that is required, use [`refresh`] in
+/// response to changes reported by [`Chip::watch_line_info`] and the info change
+/// event API.
+///
+/// The database is cheap to clone, with clones sharing the one snapshot.
+/// A refresh only applies to the database being refreshed - any clones retain the
+/// previous snapshot.
+///
+/// # Examples
+/// ```no_run
+/// # fn example() -> Result<(), gpiocdev::Error> {
+/// # use gpiocdev::line::Value;
+/// let db = gpiocdev::LineDatabase::new()?;
+/// let led0 = db.get_unique("LED0")?.unwrap();
+/// let req = gpiocdev::Request::builder()
+///     .with_found_line(led0)
+///     .as_output(Value::Active)
+///     .request()?;
+/// for btn in db.get("BUTTON") {
+///     println!("BUTTON found on {} line {}", btn.chip.display(), btn.info.offset);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`refresh`]: #method.refresh
+/// [`Chip::watch_line_info`]: crate::chip::Chip::watch_line_info
+#[derive(Clone, Debug, Default)]
+pub struct LineDatabase {
+    /// The chips to scan, or None for all chips in the system.
+    chips: Option<Arc<[PathBuf]>>,
+
+    /// The most recent scan.
+    snapshot: Arc<Snapshot>,
+}
+
+#[derive(Debug, Default)]
+struct Snapshot {
+    /// The lines with each name, in scan order.
+    names: HashMap<String, Vec<FoundLine>>,
+
+    /// The info for each line, keyed by chip path and offset.
+    lines: HashMap<(PathBuf, Offset), line::Info>,
+}
+
+impl Snapshot {
+    fn scan(lines: LineIterator) -> Snapshot {
+        let mut s = Snapshot::default();
+        for l in lines {
+            s.lines
+                .insert((l.chip.clone(), l.info.offset), l.info.clone());
+            if !l.info.name.is_empty() {
+                s.names.entry(l.info.name.clone()).or_default().push(l);
+            }
+        }
+        s
+    }
+}
+
+impl LineDatabase {
+    /// Create a database of all the lines in the system available to the caller.
+    ///
+    /// Returns an error if there are no GPIO chips available.
+    pub fn new() -> Result<LineDatabase> {
+        Ok(LineDatabase {
+            chips: None,
+            snapshot: Arc::new(Snapshot::scan(LineIterator::new()?)),
+        })
+    }
+
+    /// Create a database of the lines on a set of chips.
+    ///
+    /// Chips that are not available to the caller are skipped, as per
+    /// [`LineIterator::on_chips`].
+    pub fn on_chips<P: AsRef<Path>>(paths: &[P]) -> LineDatabase {
+        let chips: Arc<[PathBuf]> = paths.iter().map(|p| p.as_ref().to_path_buf()).collect();
+        LineDatabase {
+            snapshot: Arc::new(Snapshot::scan(LineIterator::on_chips(&chips[..]))),
+            chips: Some(chips),
+        }
+    }
+
+    /// Rescan the lines, replacing the current snapshot.
+    ///
+    /// Rescans the same set of chips used to create the database, or all chips
+    /// in the system if the database was created by [`new`], in which case
+    /// chips added since the previous scan are included.
+    ///
+    /// On error the current snapshot is retained.
+    ///
+    /// [`new`]: #method.new
+    pub fn refresh(&mut self) -> Result<()> {
+        let lines = match &self.chips {
+            Some(chips) => LineIterator::on_chips(&chips[..]),
+            None => LineIterator::new()?,
+        };
+        self.snapshot = Arc::new(Snapshot::scan(lines));
+        Ok(())
+    }
+
+    /// Return all the lines with the given name.
+    ///
+    /// The lines are in the order they were scanned, as per [`LineIterator`].
+    ///
+    /// Returns an empty slice if there are no lines with the name.
+    pub fn get(&self, name: &str) -> &[FoundLine] {
+        self.snapshot
+            .names
+            .get(name)
+            .map(|lines| lines.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Return the line with the given name, checking that the name is unique.
+    ///
+    /// Returns an error if multiple lines have the name, as per [`find_named_lines`]
+    /// in strict mode, or None if there are no lines with the name.
+    ///
+    /// [`find_named_lines`]: crate::find_named_lines
+    pub fn get_unique(&self, name: &str) -> Result<Option<&FoundLine>> {
+        match self.get(name) {
+            [] => Ok(None),
+            [line] => Ok(Some(line)),
+            _ => Err(Error::NonuniqueLineName(name.to_string())),
+        }
+    }
+
+    /// Return the info for the line at the offset on the chip.
+    ///
+    /// The chip must be identified by the same path used in the scan, such as
+    /// the paths returned by [`chip::chips`].
+    ///
+    /// [`chip::chips`]: crate::chip::chips
+    pub fn info<P: AsRef<Path>>(&self, chip: P, offset: Offset) -> Option<&line::Info> {
+        self.snapshot
+            .lines
+            .get(&(chip.as_ref().to_path_buf(), offset))
+    }
+
+    /// Return the number of lines in the database.
+    pub fn len(&self) -> usize {
+        self.snapshot.lines.len()
+    }
+
+    /// Return true if the database contains no lines.
+    pub fn is_empty(&self) -> bool {
+        self.snapshot.lines.is_empty()
+    }
+}
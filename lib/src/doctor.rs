@@ -0,0 +1,448 @@
+// SPDX-FileCopyrightText: 2024 Kent Gibson <warthog618@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::chip::{self, Chip};
+use crate::line::{self, Offset};
+use crate::request::Request;
+use crate::AbiVersion;
+#[cfg(feature = "serde")]
+use serde_derive::Serialize;
+use std::fmt;
+
+/// The consumer label applied to lines requested while probing.
+pub const PROBE_CONSUMER: &str = "gpiocdev-doctor";
+
+/// Check a line for common misconfigurations prior to requesting it.
+///
+/// The `intended` config is the config the line is expected to be requested with.
+///
+/// The capabilities of the platform are detected from the `chip`.
+///
+/// If the line is not in use, and `intended` sets a bias, then the line is briefly
+/// requested as an input with the intended bias to check that the bias is applied by
+/// the driver.
+/// The probe is only performed if `intended` is an input, or does not set a direction
+/// and the line is currently an input, so probing never reconfigures an output.
+/// Otherwise the bias is reported as not probed.
+///
+/// Returns the findings, in order of decreasing severity.
+pub fn check_line(chip: &Chip, offset: Offset, intended: &line::Config) -> Vec<Finding> {
+    check_line_with_capabilities(chip, offset, intended, &Capabilities::detect(chip))
+}
+
+/// Check a line for common misconfigurations, assuming the provided platform capabilities.
+///
+/// As per [`check_line`], but the platform capabilities are provided rather than detected.
+pub fn check_line_with_capabilities(
+    chip: &Chip,
+    offset: Offset,
+    intended: &line::Config,
+    caps: &Capabilities,
+) -> Vec<Finding> {
+    let ci = match chip.info() {
+        Ok(ci) => ci,
+        Err(e) => return vec![Finding::new(Code::InfoUnavailable, e.to_string())],
+    };
+    let li = match chip.line_info(offset) {
+        Ok(li) => li,
+        Err(e) => return vec![Finding::new(Code::InfoUnavailable, e.to_string())],
+    };
+    let mut findings = check_info(&ci, &li, intended, caps);
+    if !li.used && intended.bias.is_some() {
+        match probe_config(intended, &li) {
+            Some(cfg) => findings.extend(probe_bias(chip, offset, &cfg)),
+            None => findings.push(Finding::new(
+                Code::BiasNotProbed,
+                "bias support is unknown - the line is only probed as an input".to_string(),
+            )),
+        }
+    }
+    findings.sort_by_key(|f| std::cmp::Reverse(f.severity));
+    findings
+}
+
+// The checks that can be performed purely from the info.
+fn check_info(
+    ci: &chip::Info,
+    li: &line::Info,
+    intended: &line::Config,
+    caps: &Capabilities,
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    if li.used {
+        let msg = if li.consumer.is_empty() {
+            "line is already in use by an unnamed consumer".to_string()
+        } else {
+            format!("line is already in use by \"{}\"", li.consumer)
+        };
+        findings.push(Finding::new(Code::LineInUse, msg));
+    }
+    if li.name.is_empty() {
+        findings.push(Finding::new(
+            Code::LineUnnamed,
+            "line has no name - check the offset is correct".to_string(),
+        ));
+    }
+    if ci.label.contains("pinctrl") {
+        findings.push(Finding::new(
+            Code::PinctrlChip,
+            format!(
+                "chip \"{}\" is a pin controller - the pin may need to be muxed to GPIO",
+                ci.label
+            ),
+        ));
+    }
+    if intended.debounce_period.is_some() && !caps.debounce {
+        findings.push(Finding::new(
+            Code::DebounceUnsupported,
+            "debounce requires uAPI v2 (Linux 5.10 or later)".to_string(),
+        ));
+    }
+    findings
+}
+
+// The config used to probe the bias of the line, if the line can be probed.
+//
+// The probe is always an input, so requesting the line can never drive it.
+// Lines intended to be outputs are not probed, nor are lines that are currently
+// outputs unless intended to be inputs, as the probe would reconfigure them.
+fn probe_config(intended: &line::Config, li: &line::Info) -> Option<line::Config> {
+    let direction = intended.direction.unwrap_or(li.direction);
+    if direction != line::Direction::Input {
+        return None;
+    }
+    let bias = intended.bias?;
+    let mut cfg = line::Config::default();
+    cfg.as_input();
+    cfg.bias = Some(bias);
+    Some(cfg)
+}
+
+// Request the line with the probe config and check the bias is reflected in the info.
+fn probe_bias(chip: &Chip, offset: Offset, probe: &line::Config) -> Option<Finding> {
+    let req = match Request::builder()
+        .on_chip(chip.path())
+        .with_consumer(PROBE_CONSUMER)
+        .with_line(offset)
+        .from_line_config(probe)
+        .request()
+    {
+        Ok(req) => req,
+        Err(e) => {
+            return Some(Finding::new(
+                Code::RequestFailed,
+                format!("unable to request line with intended bias: {}", e),
+            ))
+        }
+    };
+    let li = chip.line_info(offset).ok()?;
+    drop(req);
+    if li.bias != probe.bias {
+        return Some(Finding::new(
+            Code::BiasNotApplied,
+            format!(
+                "requested bias {:?} is reported as {:?} - the driver may not support bias",
+                probe.bias.unwrap(),
+                li.bias
+            ),
+        ));
+    }
+    None
+}
+
+/// The platform capabilities relevant to the checks.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Capabilities {
+    /// The platform supports debounce.
+    ///
+    /// Requires uAPI v2, so Linux 5.10 or later.
+    pub debounce: bool,
+}
+
+impl Capabilities {
+    /// Detect the capabilities of the platform hosting the chip.
+    pub fn detect(chip: &Chip) -> Capabilities {
+        Capabilities {
+            debounce: chip.supports_abi_version(AbiVersion::V2).is_ok(),
+        }
+    }
+}
+
+/// A potential problem identified by [`check_line`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Finding {
+    /// The stable identifier for the type of finding.
+    pub code: Code,
+
+    /// How serious the finding is.
+    pub severity: Severity,
+
+    /// A human readable description of the finding.
+    pub message: String,
+}
+
+impl Finding {
+    fn new(code: Code, message: String) -> Finding {
+        Finding {
+            code,
+            severity: code.severity(),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}[{}]: {}", self.severity, self.code, self.message)
+    }
+}
+
+/// The type of a [`Finding`].
+///
+/// The string form returned by [`Code::as_str`] is stable and suitable for scripting.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+#[non_exhaustive]
+pub enum Code {
+    /// The chip or line info could not be read.
+    InfoUnavailable,
+
+    /// The line is already requested by another consumer.
+    LineInUse,
+
+    /// The line has no name, which may indicate the wrong offset.
+    LineUnnamed,
+
+    /// The chip is a pin controller, so the pin may require muxing.
+    PinctrlChip,
+
+    /// Debounce is requested but not supported by the platform.
+    DebounceUnsupported,
+
+    /// The line could not be requested with the intended config.
+    RequestFailed,
+
+    /// The requested bias is not reflected by the driver.
+    BiasNotApplied,
+
+    /// The bias was not probed, as that would reconfigure the line.
+    BiasNotProbed,
+}
+
+impl Code {
+    /// The stable string form of the code.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Code::InfoUnavailable => "info-unavailable",
+            Code::LineInUse => "line-in-use",
+            Code::LineUnnamed => "line-unnamed",
+            Code::PinctrlChip => "pinctrl-chip",
+            Code::DebounceUnsupported => "debounce-unsupported",
+            Code::RequestFailed => "request-failed",
+            Code::BiasNotApplied => "bias-not-applied",
+            Code::BiasNotProbed => "bias-not-probed",
+        }
+    }
+
+    /// The severity of findings of this type.
+    pub fn severity(&self) -> Severity {
+        match self {
+            Code::InfoUnavailable => Severity::Error,
+            Code::LineInUse => Severity::Error,
+            Code::LineUnnamed => Severity::Info,
+            Code::PinctrlChip => Severity::Info,
+            Code::DebounceUnsupported => Severity::Error,
+            Code::RequestFailed => Severity::Error,
+            Code::BiasNotApplied => Severity::Warning,
+            Code::BiasNotProbed => Severity::Info,
+        }
+    }
+}
+
+impl fmt::Display for Code {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// The severity of a [`Finding`].
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[non_exhaustive]
+pub enum Severity {
+    /// Worth knowing, but probably not a problem.
+    Info,
+
+    /// Likely to cause unexpected behaviour.
+    Warning,
+
+    /// Will prevent the line being used as intended.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn chip_info(label: &str) -> chip::Info {
+        chip::Info {
+            name: "gpiochip0".into(),
+            label: label.into(),
+            num_lines: 8,
+        }
+    }
+
+    fn line_info(name: &str) -> line::Info {
+        line::Info {
+            offset: 3,
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    fn codes(findings: &[Finding]) -> Vec<Code> {
+        findings.iter().map(|f| f.code).collect()
+    }
+
+    const CAPS: Capabilities = Capabilities { debounce: true };
+
+    #[test]
+    fn check_info_clean() {
+        let f = check_info(
+            &chip_info("sim"),
+            &line_info("LED0"),
+            &line::Config::default(),
+            &CAPS,
+        );
+        assert!(f.is_empty());
+    }
+
+    #[test]
+    fn check_info_line_in_use() {
+        let mut li = line_info("LED0");
+        li.used = true;
+        li.consumer = "foo".into();
+        let f = check_info(&chip_info("sim"), &li, &line::Config::default(), &CAPS);
+        assert_eq!(codes(&f), [Code::LineInUse]);
+        assert_eq!(f[0].severity, Severity::Error);
+        assert!(f[0].message.contains("\"foo\""));
+
+        li.consumer.clear();
+        let f = check_info(&chip_info("sim"), &li, &line::Config::default(), &CAPS);
+        assert_eq!(codes(&f), [Code::LineInUse]);
+        assert!(f[0].message.contains("unnamed consumer"));
+    }
+
+    #[test]
+    fn check_info_line_unnamed() {
+        let f = check_info(
+            &chip_info("sim"),
+            &line_info(""),
+            &line::Config::default(),
+            &CAPS,
+        );
+        assert_eq!(codes(&f), [Code::LineUnnamed]);
+        assert_eq!(f[0].severity, Severity::Info);
+    }
+
+    #[test]
+    fn check_info_pinctrl_chip() {
+        let f = check_info(
+            &chip_info("pinctrl-bcm2711"),
+            &line_info("LED0"),
+            &line::Config::default(),
+            &CAPS,
+        );
+        assert_eq!(codes(&f), [Code::PinctrlChip]);
+    }
+
+    #[test]
+    fn check_info_debounce_unsupported() {
+        let mut cfg = line::Config::default();
+        cfg.with_debounce_period(Duration::from_millis(5));
+        let f = check_info(&chip_info("sim"), &line_info("BTN"), &cfg, &CAPS);
+        assert!(f.is_empty());
+
+        let caps = Capabilities { debounce: false };
+        let f = check_info(&chip_info("sim"), &line_info("BTN"), &cfg, &caps);
+        assert_eq!(codes(&f), [Code::DebounceUnsupported]);
+        assert_eq!(f[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn probe_config() {
+        use line::{Bias, Direction, Drive, Value};
+
+        let input = line_info("BTN");
+        let output = line::Info {
+            direction: Direction::Output,
+            ..line_info("LED")
+        };
+
+        // nothing to probe
+        assert_eq!(super::probe_config(&line::Config::default(), &input), None);
+
+        let mut intended = line::Config {
+            bias: Some(Bias::PullUp),
+            ..Default::default()
+        };
+        let probe = super::probe_config(&intended, &input).unwrap();
+        assert_eq!(probe.direction, Some(Direction::Input));
+        assert_eq!(probe.bias, Some(Bias::PullUp));
+
+        // lines currently outputs are only probed if intended as inputs
+        assert_eq!(super::probe_config(&intended, &output), None);
+
+        intended
+            .as_input()
+            .with_debounce_period(Duration::from_millis(5));
+        intended.bias = Some(Bias::PullDown);
+        for li in [&input, &output] {
+            let probe = super::probe_config(&intended, li).unwrap();
+            assert_eq!(probe.direction, Some(Direction::Input));
+            assert_eq!(probe.bias, Some(Bias::PullDown));
+            assert_eq!(probe.debounce_period, None);
+        }
+
+        // outputs are never probed
+        intended.as_output(Value::Active);
+        intended.drive = Some(Drive::OpenDrain);
+        intended.bias = Some(Bias::PullUp);
+        assert_eq!(super::probe_config(&intended, &input), None);
+        assert_eq!(super::probe_config(&intended, &output), None);
+    }
+
+    #[test]
+    fn code_as_str() {
+        assert_eq!(Code::LineInUse.as_str(), "line-in-use");
+        assert_eq!(Code::BiasNotApplied.to_string(), "bias-not-applied");
+    }
+
+    #[test]
+    fn finding_display() {
+        let f = Finding::new(Code::LineUnnamed, "no name".into());
+        assert_eq!(f.to_string(), "info[line-unnamed]: no name");
+    }
+
+    #[test]
+    fn severity_order() {
+        assert!(Severity::Info < Severity::Warning);
+        assert!(Severity::Warning < Severity::Error);
+    }
+}
@@ -0,0 +1,400 @@
+// SPDX-FileCopyrightText: 2024 Kent Gibson <warthog618@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::chip::{self, Chip};
+use crate::{Error, Result, UapiCall};
+use std::collections::{HashSet, VecDeque};
+use std::ffi::{CString, OsStr};
+use std::fs::File;
+use std::io::Read;
+use std::os::unix::prelude::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OsStrExt};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const DEV_DIR: &str = "/dev";
+
+// The size of the fixed portion of struct inotify_event.
+const INOTIFY_EVENT_SIZE: usize = 16;
+
+// Large enough for at least one event with a maximal name.
+const INOTIFY_BUF_SIZE: usize = 4096;
+
+/// Watch for GPIO chips being added to or removed from the system.
+///
+/// Chips already present when the watcher is created are not reported.
+/// Use [`chip::chips`] to find those.
+///
+/// # Examples
+/// ```no_run
+/// # fn example() -> Result<(), gpiocdev::Error> {
+/// let watcher = gpiocdev::chips_watch()?;
+/// for event in watcher.events() {
+///     match event? {
+///         gpiocdev::ChipEvent::Added(p) => println!("added {}", p.display()),
+///         gpiocdev::ChipEvent::Removed(p) => println!("removed {}", p.display()),
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn chips_watch() -> Result<ChipWatcher> {
+    ChipWatcher::new()
+}
+
+/// A change to the set of GPIO chips in the system.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ChipEvent {
+    /// A chip has been added, and is available to be opened by the caller.
+    Added(PathBuf),
+
+    /// A chip has been removed.
+    Removed(PathBuf),
+}
+
+/// A watcher for GPIO chips being added to or removed from the system.
+///
+/// Created by [`chips_watch`].
+///
+/// Uses inotify to detect changes to the GPIO chip device nodes in `/dev`.
+///
+/// Events are de-duplicated, so a chip is reported as added once, and
+/// as removed once, no matter how many changes are made to the device node.
+///
+/// A chip is only reported as added once its device node can be opened as a GPIO chip
+/// by the caller, so not while the node is still having its permissions set by udev.
+/// Device nodes that never become accessible to the caller are never reported.
+#[derive(Debug)]
+pub struct ChipWatcher {
+    /// The inotify instance.
+    f: File,
+
+    /// The state of the watched chips.
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// The chips reported as present.
+    known: HashSet<PathBuf>,
+
+    /// The device nodes that have been created but cannot yet be opened as chips.
+    unready: HashSet<PathBuf>,
+
+    /// Events detected but not yet read.
+    pending: VecDeque<ChipEvent>,
+}
+
+impl State {
+    fn node_added(&mut self, path: PathBuf) {
+        if self.known.contains(&path) {
+            return;
+        }
+        if is_openable_chip(&path) {
+            self.unready.remove(&path);
+            self.known.insert(path.clone());
+            self.pending.push_back(ChipEvent::Added(path));
+        } else {
+            self.unready.insert(path);
+        }
+    }
+
+    fn node_removed(&mut self, path: PathBuf) {
+        self.unready.remove(&path);
+        if self.known.remove(&path) {
+            self.pending.push_back(ChipEvent::Removed(path));
+        }
+    }
+
+    // Reconcile with the chips present, as events may have been lost.
+    fn resync(&mut self) {
+        let present: HashSet<PathBuf> = match std::fs::read_dir(DEV_DIR) {
+            Ok(rd) => rd
+                .filter_map(|de| de.ok())
+                .map(|de| de.path())
+                .filter(|p| is_chip_node_name(p.file_name().unwrap_or_default()))
+                .collect(),
+            Err(_) => return,
+        };
+        let mut removed: Vec<PathBuf> = self.known.difference(&present).cloned().collect();
+        removed.sort_unstable_by(|a, b| chip::path_compare(a, b));
+        for path in removed {
+            self.node_removed(path);
+        }
+        self.unready.retain(|p| present.contains(p));
+        let mut added: Vec<PathBuf> = present.difference(&self.known).cloned().collect();
+        added.sort_unstable_by(|a, b| chip::path_compare(a, b));
+        for path in added {
+            self.node_added(path);
+        }
+    }
+
+    // Retry any device nodes that were not openable when created.
+    fn retry_unready(&mut self) {
+        let mut unready: Vec<PathBuf> = self.unready.iter().cloned().collect();
+        unready.sort_unstable_by(|a, b| chip::path_compare(a, b));
+        for path in unready {
+            self.node_added(path);
+        }
+    }
+}
+
+fn is_chip_node_name(name: &OsStr) -> bool {
+    name.as_bytes().starts_with(b"gpiochip")
+}
+
+fn is_openable_chip(path: &Path) -> bool {
+    chip::is_chip(path).is_ok() && Chip::from_path(path).is_ok()
+}
+
+impl ChipWatcher {
+    fn new() -> Result<ChipWatcher> {
+        // SAFETY: inotify_init1 returns a new fd that is owned by the File.
+        let f = unsafe {
+            let fd = libc::inotify_init1(libc::IN_CLOEXEC);
+            if fd < 0 {
+                return Err(std::io::Error::last_os_error().into());
+            }
+            File::from_raw_fd(fd)
+        };
+        let dir = CString::new(DEV_DIR).unwrap();
+        let mask = libc::IN_CREATE
+            | libc::IN_DELETE
+            | libc::IN_ATTRIB
+            | libc::IN_MOVED_TO
+            | libc::IN_MOVED_FROM;
+        // SAFETY: the fd and path are valid for the duration of the call.
+        if unsafe { libc::inotify_add_watch(f.as_raw_fd(), dir.as_ptr(), mask) } < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        let known = match chip::chips() {
+            Ok(chips) => chips.into_iter().collect(),
+            Err(_) => HashSet::new(),
+        };
+        Ok(ChipWatcher {
+            f,
+            state: Mutex::new(State {
+                known,
+                ..Default::default()
+            }),
+        })
+    }
+
+    /// Check if the watcher has an event available to read.
+    pub fn has_event(&self) -> Result<bool> {
+        let mut state = self.lock_state();
+        self.process_available(&mut state)?;
+        Ok(!state.pending.is_empty())
+    }
+
+    /// Wait for the watcher to have an event available to read.
+    ///
+    /// Returns true if an event is available, or false if the timeout expired.
+    pub fn wait_event(&self, timeout: Duration) -> Result<bool> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if self.has_event()? {
+                return Ok(true);
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero()
+                || !gpiocdev_uapi::wait_event(&self.f, remaining)
+                    .map_err(|e| Error::Uapi(UapiCall::WaitEvent, e))?
+            {
+                return Ok(false);
+            }
+        }
+    }
+
+    /// Read a single event from the watcher.
+    ///
+    /// Will block until an event is available.
+    pub fn read_event(&self) -> Result<ChipEvent> {
+        loop {
+            let mut state = self.lock_state();
+            self.process_available(&mut state)?;
+            if let Some(evt) = state.pending.pop_front() {
+                return Ok(evt);
+            }
+            drop(state);
+            self.process_blocking()?;
+        }
+    }
+
+    /// An iterator for events from the watcher.
+    ///
+    /// Blocks until events are available.
+    pub fn events(&self) -> ChipEventIterator<'_> {
+        ChipEventIterator { watcher: self }
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .expect("failed to acquire lock on chip watcher state")
+    }
+
+    // Process any inotify events that can be read without blocking.
+    fn process_available(&self, state: &mut State) -> Result<()> {
+        while gpiocdev_uapi::has_event(&self.f).map_err(|e| Error::Uapi(UapiCall::HasEvent, e))? {
+            self.read_inotify(state)?;
+        }
+        state.retry_unready();
+        Ok(())
+    }
+
+    // Block until inotify events are available, then process them.
+    fn process_blocking(&self) -> Result<()> {
+        let mut buf = [0_u8; INOTIFY_BUF_SIZE];
+        let n = self.read_buf(&mut buf)?;
+        let mut state = self.lock_state();
+        self.decode(&buf[..n], &mut state);
+        Ok(())
+    }
+
+    fn read_inotify(&self, state: &mut State) -> Result<()> {
+        let mut buf = [0_u8; INOTIFY_BUF_SIZE];
+        let n = self.read_buf(&mut buf)?;
+        self.decode(&buf[..n], state);
+        Ok(())
+    }
+
+    // Read from the inotify instance, retrying if interrupted by a signal.
+    fn read_buf(&self, buf: &mut [u8]) -> Result<usize> {
+        loop {
+            match (&self.f).read(buf) {
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                res => return Ok(res?),
+            }
+        }
+    }
+
+    fn decode(&self, mut buf: &[u8], state: &mut State) {
+        let u32_at = |b: &[u8], idx: usize| u32::from_ne_bytes(b[idx..idx + 4].try_into().unwrap());
+        while buf.len() >= INOTIFY_EVENT_SIZE {
+            let mask = u32_at(buf, 4);
+            let len = u32_at(buf, 12) as usize;
+            let end = (INOTIFY_EVENT_SIZE + len).min(buf.len());
+            let name = &buf[INOTIFY_EVENT_SIZE..end];
+            buf = &buf[end..];
+            if mask & libc::IN_Q_OVERFLOW != 0 {
+                state.resync();
+                continue;
+            }
+            // the name is nul padded
+            let name = OsStr::from_bytes(name.split(|&b| b == 0).next().unwrap_or_default());
+            if !is_chip_node_name(name) {
+                continue;
+            }
+            let path = Path::new(DEV_DIR).join(name);
+            if mask & (libc::IN_DELETE | libc::IN_MOVED_FROM) != 0 {
+                state.node_removed(path);
+            } else {
+                state.node_added(path);
+            }
+        }
+    }
+}
+
+impl AsFd for ChipWatcher {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.f.as_fd()
+    }
+}
+
+impl AsRawFd for ChipWatcher {
+    #[inline]
+    fn as_raw_fd(&self) -> i32 {
+        self.f.as_raw_fd()
+    }
+}
+
+/// An iterator for reading events from a [`ChipWatcher`].
+///
+/// Blocks until events are available.
+pub struct ChipEventIterator<'a> {
+    watcher: &'a ChipWatcher,
+}
+
+impl Iterator for ChipEventIterator<'_> {
+    type Item = Result<ChipEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.watcher.read_event())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Build a raw inotify event.
+    fn event(mask: u32, name: &str) -> Vec<u8> {
+        let mut buf = Vec::new();
+        let len = (name.len() + 1 + 3) & !3;
+        buf.extend_from_slice(&1_i32.to_ne_bytes());
+        buf.extend_from_slice(&mask.to_ne_bytes());
+        buf.extend_from_slice(&0_u32.to_ne_bytes());
+        buf.extend_from_slice(&(len as u32).to_ne_bytes());
+        let mut n = name.as_bytes().to_vec();
+        n.resize(len, 0);
+        buf.extend_from_slice(&n);
+        buf
+    }
+
+    fn watcher() -> ChipWatcher {
+        ChipWatcher::new().unwrap()
+    }
+
+    #[test]
+    fn decode_ignores_other_nodes() {
+        let w = watcher();
+        let mut state = State::default();
+        let mut buf = event(libc::IN_CREATE, "ttyS0");
+        buf.extend(event(libc::IN_DELETE, "gpiochip_not_known"));
+        w.decode(&buf, &mut state);
+        assert!(state.pending.is_empty());
+        assert!(state.unready.is_empty());
+    }
+
+    #[test]
+    fn decode_unopenable_node_is_unready() {
+        let w = watcher();
+        let mut state = State::default();
+        w.decode(&event(libc::IN_CREATE, "gpiochip_nonexistent"), &mut state);
+        assert!(state.pending.is_empty());
+        assert!(state
+            .unready
+            .contains(Path::new("/dev/gpiochip_nonexistent")));
+
+        // removed before it became openable, so never reported
+        w.decode(&event(libc::IN_DELETE, "gpiochip_nonexistent"), &mut state);
+        assert!(state.pending.is_empty());
+        assert!(state.unready.is_empty());
+    }
+
+    #[test]
+    fn decode_removed_known_chip() {
+        let w = watcher();
+        let mut state = State::default();
+        let path = PathBuf::from("/dev/gpiochip_nonexistent");
+        state.known.insert(path.clone());
+        let mut buf = event(libc::IN_DELETE, "gpiochip_nonexistent");
+        // de-duplicated
+        buf.extend(event(libc::IN_MOVED_FROM, "gpiochip_nonexistent"));
+        w.decode(&buf, &mut state);
+        assert_eq!(state.pending, [ChipEvent::Removed(path)]);
+        assert!(state.known.is_empty());
+    }
+
+    #[test]
+    fn resync_removes_missing_chips() {
+        let mut state = State::default();
+        let path = PathBuf::from("/dev/gpiochip_nonexistent");
+        state.known.insert(path.clone());
+        state.resync();
+        assert_eq!(state.pending.front(), Some(&ChipEvent::Removed(path)));
+    }
+}
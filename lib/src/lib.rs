@@ -58,18 +58,35 @@ compile_error!("Either feature \"uapi_v1\" or \"uapi_v2\" must be enabled for th
 use gpiocdev_uapi as uapi;
 #[cfg(feature = "serde")]
 use serde_derive::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::ffi::OsStr;
 use std::fmt;
 use std::ops::Range;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 /// Types and functions specific to chips.
 pub mod chip;
 pub use chip::Chip;
 
+/// An index of the lines in the system.
+mod database;
+pub use database::LineDatabase;
+
+/// Checks for common line misconfigurations.
+pub mod doctor;
+
+/// Monitoring of chips being added to and removed from the system.
+mod hotplug;
+pub use hotplug::{chips_watch, ChipEvent, ChipEventIterator, ChipWatcher};
+
 /// Types specific to lines.
 pub mod line;
 
+/// A summary of the GPIO capabilities of the platform.
+pub mod platform;
+
 /// Wrappers for various async reactors.
 #[cfg(any(feature = "async_tokio", feature = "async_io"))]
 mod r#async;
@@ -79,7 +96,16 @@ pub use r#async::async_io;
 #[cfg(feature = "async_tokio")]
 pub use r#async::tokio;
 
+/// Support for testing behaviour on kernels lacking particular uAPI ABI versions.
+///
+/// Intended for testing only, so requires the `test_abi_override` feature.
+#[cfg(feature = "test_abi_override")]
+pub mod test;
+
 /// An iterator over all the GPIO lines visible to the caller.
+///
+/// The chips are visited in the order returned by [`chip::chips`], i.e. in chip number order,
+/// and the lines in offset order within each chip.
 pub fn lines() -> Result<LineIterator> {
     LineIterator::new()
 }
@@ -87,6 +113,8 @@ pub fn lines() -> Result<LineIterator> {
 /// Find the chip hosting a named line, and the line offset on that chip.
 ///
 /// Stops at the first matching line, if one can be found.
+/// The chips are searched in the order returned by [`chip::chips`], so if multiple lines
+/// have the name then the one on the lowest numbered chip is returned.
 ///
 /// Returns the path of the chip containing the line, and the offset of the line on that chip.
 ///
@@ -95,6 +123,13 @@ pub fn lines() -> Result<LineIterator> {
 ///
 /// If multiple lines are required then [`find_named_lines`] is more performant.
 ///
+/// The name must match exactly.  Use [`find_named_line_with`] for case insensitive
+/// or whitespace tolerant matching.
+///
+/// Chips that cannot be read are skipped, and None is returned both if there are no
+/// chips available and if the name cannot be found.
+/// Use [`try_find_named_line`] to distinguish those cases.
+///
 /// # Examples
 /// The found line can be used to request the line:
 /// ```no_run
@@ -123,28 +158,91 @@ pub fn lines() -> Result<LineIterator> {
 /// # }
 /// ```
 pub fn find_named_line(name: &str) -> Option<FoundLine> {
-    if let Ok(mut liter) = LineIterator::new() {
-        return liter.find(|l| l.info.name == name);
-    }
-    None
+    LineIterator::new().ok()?.find_next(|li| li.name == name)
+}
+
+/// Find the chip hosting a named line, reporting any problem reading the chips.
+///
+/// As per [`find_named_line`], but returns an error if there are no chips available,
+/// or if any chip, or line on a chip, cannot be read, such as when the caller lacks
+/// permission to access the chip.
+///
+/// So `Ok(None)` means that all the lines in the system were scanned and none have the name.
+///
+/// # Examples
+/// ```no_run
+/// # fn example() -> Result<(), gpiocdev::Error> {
+/// match gpiocdev::try_find_named_line("LED0")? {
+///     Some(led0) => println!("LED0 is {}:{}", led0.chip.display(), led0.info.offset),
+///     None => println!("LED0 not found"),
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn try_find_named_line(name: &str) -> Result<Option<FoundLine>> {
+    LineIterator::all_chips(true)?.try_find_next(true, |li| li.name == name)
+}
+
+/// Find the chip hosting a named line, matching the name exactly as provided by the kernel.
+///
+/// As per [`find_named_line`], but the name is compared byte for byte with the
+/// [`name_os`] of each line, so lines with names that are not valid UTF-8 can be found.
+///
+/// # Examples
+/// ```no_run
+/// # use std::ffi::OsStr;
+/// # use std::os::unix::ffi::OsStrExt;
+/// let led0 = gpiocdev::find_named_line_os(OsStr::from_bytes(b"LED\xff")).unwrap();
+/// ```
+///
+/// [`name_os`]: line::Info::name_os
+pub fn find_named_line_os(name: &OsStr) -> Option<FoundLine> {
+    LineIterator::new().ok()?.find_next(|li| li.name_os == name)
+}
+
+/// Find the chip hosting a named line, using the provided options to match the name.
+///
+/// As per [`find_named_line`], other than the matching of names.
+///
+/// # Examples
+/// ```no_run
+/// # fn example() -> Result<(), gpiocdev::Error> {
+/// let opts = gpiocdev::MatchOptions::default().with_case_insensitive(true);
+/// // matches "LED0", "led0", "Led0" etc.
+/// let led0 = gpiocdev::find_named_line_with("led0", opts).unwrap();
+/// # Ok(())
+/// # }
+/// ```
+pub fn find_named_line_with(name: &str, opts: MatchOptions) -> Option<FoundLine> {
+    let name = opts.normalize(name);
+    LineIterator::new()
+        .ok()?
+        .find_next(|li| opts.matches(&name, &li.name))
 }
 
 /// Find a collection of named lines.
 ///
+///  - `names`: the names of the lines to find, as any collection of `&str` or `String`
 ///  - `strict`: if true then the names are checked to be unique within the available lines
 ///
 /// For each name, returns the first matching line, if one can be found.
 /// If it cannot be found then there will be no matching entry in the returned map.
 ///
+/// Chips that cannot be read are skipped, unless `strict`, in which case an error is
+/// returned, as the uniqueness of the names cannot be confirmed.
+///
 /// Returns the path of the chip containing the line, the offset of the line on that chip,
 /// and the info for the line.
 ///
+/// The names must match exactly.  Use [`find_named_lines_with`] for case insensitive
+/// or whitespace tolerant matching.
+///
 /// # Examples
 /// Adding the found lines to the request directly:
 /// ```no_run
 /// # fn example() -> Result<(), gpiocdev::Error> {
 /// # use gpiocdev::line::Value;
-/// let sensors = gpiocdev::find_named_lines(&["SENSOR0", "SENSOR1"], true)?;
+/// let sensors = gpiocdev::find_named_lines(["SENSOR0", "SENSOR1"], true)?;
 /// let req = gpiocdev::Request::builder()
 ///     .with_found_lines(&sensors)
 ///     .as_input()
@@ -160,7 +258,7 @@ pub fn find_named_line(name: &str) -> Option<FoundLine> {
 /// ```no_run
 /// # fn example() -> Result<(), gpiocdev::Error> {
 /// # use gpiocdev::line::Value;
-/// let lines = gpiocdev::find_named_lines(&["SENSOR0", "LED0"], true)?;
+/// let lines = gpiocdev::find_named_lines(["SENSOR0", "LED0"], true)?;
 /// let sensor0 = lines.get("SENSOR0").unwrap();
 /// let led0 = lines.get("LED0").unwrap();
 /// let req = gpiocdev::Request::builder()
@@ -174,18 +272,97 @@ pub fn find_named_line(name: &str) -> Option<FoundLine> {
 /// # }
 /// ```
 ///
-pub fn find_named_lines<'a>(
-    names: &'a [&'a str],
+/// Using names read at runtime:
+/// ```no_run
+/// # fn example() -> Result<(), gpiocdev::Error> {
+/// let names: Vec<String> = std::env::args().skip(1).collect();
+/// let lines = gpiocdev::find_named_lines(&names, true)?;
+/// for name in &names {
+///     if !lines.contains_key(name) {
+///         println!("{} not found", name);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn find_named_lines<I, S>(names: I, strict: bool) -> Result<HashMap<String, FoundLine>>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    find_named_lines_with(names, strict, MatchOptions::default())
+}
+
+/// Find a collection of named lines, using the provided options to match the names.
+///
+/// As per [`find_named_lines`], other than the matching of names.
+///
+/// The keys of the returned map are the names as provided, not as normalised for matching.
+///
+/// When `strict`, lines with names that only differ in ways ignored by the options,
+/// such as "LED0" and "led0" when case insensitive, are considered to have the same
+/// name, and so are reported as not unique.
+pub fn find_named_lines_with<I, S>(
+    names: I,
     strict: bool,
-) -> Result<HashMap<&'a str, FoundLine>> {
+    opts: MatchOptions,
+) -> Result<HashMap<String, FoundLine>>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    find_named_lines_in(&mut LineIterator::all_chips(strict)?, names, strict, opts)
+}
+
+/// Find a collection of named lines, scanning the lines provided by an existing iterator.
+///
+/// As per [`find_named_lines_with`], but the lines are scanned using `lines`, which
+/// is rewound before the scan.
+///
+/// When `strict`, chips that cannot be read during the scan return an error, but chips
+/// already skipped by `lines` are not revisited.
+///
+/// This allows the one iterator, and the chips it has opened, to be reused for
+/// multiple lookups rather than reopening every chip for each lookup.
+///
+/// # Examples
+/// ```no_run
+/// # fn example() -> Result<(), gpiocdev::Error> {
+/// let mut lines = gpiocdev::LineIterator::new()?;
+/// let opts = gpiocdev::MatchOptions::default();
+/// let sensors = gpiocdev::find_named_lines_in(&mut lines, ["SENSOR0", "SENSOR1"], true, opts)?;
+/// let leds = gpiocdev::find_named_lines_in(&mut lines, ["LED0", "LED1"], true, opts)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn find_named_lines_in<I, S>(
+    lines: &mut LineIterator,
+    names: I,
+    strict: bool,
+    opts: MatchOptions,
+) -> Result<HashMap<String, FoundLine>>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let names: Vec<S> = names.into_iter().collect();
+    let normalized: Vec<Cow<str>> = names
+        .iter()
+        .map(|name| opts.normalize(name.as_ref()))
+        .collect();
     let mut found = HashMap::new();
-    for l in LineIterator::new()? {
-        for name in names {
-            if *name != l.info.name.as_str() {
+    lines.cidx = 0;
+    lines.try_load_chip(strict)?;
+    while let Some(l) = lines.try_find_next(strict, |li| {
+        normalized.iter().any(|n| opts.matches(n, &li.name))
+    })? {
+        for (name, nname) in names.iter().zip(normalized.iter()) {
+            if !opts.matches(nname, &l.info.name) {
                 continue;
             }
-            if !found.contains_key(*name) {
-                found.insert(name.to_owned(), l.clone());
+            let name = name.as_ref();
+            if !found.contains_key(name) {
+                found.insert(name.to_string(), l.clone());
                 if !strict && found.len() == names.len() {
                     return Ok(found);
                 }
@@ -198,6 +375,263 @@ pub fn find_named_lines<'a>(
     Ok(found)
 }
 
+/// Find all the lines with the given names.
+///
+/// Unlike [`find_named_lines`], all chips are scanned and every line matching a name is
+/// returned, rather than only the first.
+/// This is useful for diagnosing line names that are not unique.
+///
+/// The lines for each name are returned in chip order, and in offset order within each chip.
+///
+/// Names which are not found are not included in the returned map.
+///
+/// # Examples
+/// ```no_run
+/// # fn example() -> Result<(), gpiocdev::Error> {
+/// let lines = gpiocdev::find_all_named_lines(&["LED0"])?;
+/// for l in lines.get("LED0").into_iter().flatten() {
+///     println!("LED0 found on {} line {}", l.chip.display(), l.info.offset);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn find_all_named_lines(names: &[&str]) -> Result<HashMap<String, Vec<FoundLine>>> {
+    let mut found: HashMap<String, Vec<FoundLine>> = HashMap::new();
+    let mut lines = LineIterator::new()?;
+    while let Some(l) = lines.find_next(|li| names.contains(&li.name.as_str())) {
+        found.entry(l.info.name.clone()).or_default().push(l);
+    }
+    Ok(found)
+}
+
+/// Options controlling how line names are matched.
+///
+/// The default is exact matching, with all options disabled.
+///
+/// Options are enabled using the `with_` setters, as more options may be added in the future:
+/// ```
+/// let opts = gpiocdev::MatchOptions::default()
+///     .with_case_insensitive(true)
+///     .with_trim_whitespace(true);
+/// assert!(opts.case_insensitive);
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct MatchOptions {
+    /// Ignore differences in case.
+    pub case_insensitive: bool,
+
+    /// Ignore leading and trailing whitespace.
+    pub trim_whitespace: bool,
+}
+
+impl MatchOptions {
+    /// Set whether differences in case are ignored.
+    pub fn with_case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    /// Set whether leading and trailing whitespace is ignored.
+    pub fn with_trim_whitespace(mut self, trim_whitespace: bool) -> Self {
+        self.trim_whitespace = trim_whitespace;
+        self
+    }
+
+    // Normalise a name for comparison.
+    fn normalize<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        let name = if self.trim_whitespace {
+            name.trim()
+        } else {
+            name
+        };
+        if self.case_insensitive {
+            Cow::Owned(name.chars().flat_map(char::to_lowercase).collect())
+        } else {
+            Cow::Borrowed(name)
+        }
+    }
+
+    // Check if a line name matches a name previously normalised by `normalize`.
+    //
+    // Does not allocate, so is suitable for comparing against every line in the system.
+    fn matches(&self, normalized: &str, line_name: &str) -> bool {
+        let line_name = if self.trim_whitespace {
+            line_name.trim()
+        } else {
+            line_name
+        };
+        if self.case_insensitive {
+            normalized
+                .chars()
+                .eq(line_name.chars().flat_map(char::to_lowercase))
+        } else {
+            normalized == line_name
+        }
+    }
+}
+
+/// Find all the lines that match a predicate.
+///
+/// The predicate is applied to the info of each line in the system available to the caller.
+///
+/// The matching lines are grouped per chip, so a request can be built for each group.
+/// The groups are in chip number order, as per [`chip::chips`], and the lines within each
+/// group are in offset order.  Chips with no matching lines are omitted, so every group
+/// contains at least one line.
+///
+/// # Examples
+/// Requesting the matching lines, one request per chip:
+/// ```no_run
+/// # fn example() -> Result<(), gpiocdev::Error> {
+/// let mut reqs = Vec::new();
+/// for lines in gpiocdev::find_lines_matching(|li| li.name.starts_with("SENSOR"))? {
+///     let offsets: Vec<_> = lines.iter().map(|l| l.info.offset).collect();
+///     reqs.push(
+///         gpiocdev::Request::builder()
+///             .on_chip(&lines[0].chip)
+///             .with_lines(&offsets)
+///             .as_input()
+///             .request()?,
+///     );
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn find_lines_matching<P: Fn(&line::Info) -> bool>(pred: P) -> Result<Vec<Vec<FoundLine>>> {
+    let mut lines = LineIterator::new()?;
+    let mut found: Vec<Vec<FoundLine>> = Vec::new();
+    while let Some(l) = lines.find_next(&pred) {
+        match found.last_mut() {
+            Some(group) if group[0].chip == l.chip => group.push(l),
+            _ => found.push(vec![l]),
+        }
+    }
+    Ok(found)
+}
+
+/// Find all the lines with names that match a glob pattern.
+///
+/// In the pattern, `*` matches any sequence of characters, including an empty sequence,
+/// and `?` matches any single character.
+/// All other characters must match exactly.
+///
+/// Lines are grouped per chip, as per [`find_lines_matching`].
+///
+/// # Examples
+/// ```no_run
+/// # fn example() -> Result<(), gpiocdev::Error> {
+/// let power_enables = gpiocdev::find_lines_glob("PWR_EN_*")?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn find_lines_glob(pattern: &str) -> Result<Vec<Vec<FoundLine>>> {
+    let pattern: Vec<char> = pattern.chars().collect();
+    find_lines_matching(|li| glob_match(&pattern, &li.name))
+}
+
+// Match a name against a glob pattern supporting `*` and `?` wildcards.
+fn glob_match(pattern: &[char], name: &str) -> bool {
+    let name: Vec<char> = name.chars().collect();
+    let (mut pidx, mut nidx) = (0, 0);
+    // the position of the most recent `*` in the pattern, and of the name it is matched from.
+    let mut star: Option<(usize, usize)> = None;
+    while nidx < name.len() {
+        match pattern.get(pidx) {
+            Some('*') => {
+                star = Some((pidx, nidx));
+                pidx += 1;
+            }
+            Some(&c) if c == '?' || c == name[nidx] => {
+                pidx += 1;
+                nidx += 1;
+            }
+            _ => match star {
+                // backtrack, extending the `*` match by one character.
+                Some((spidx, snidx)) => {
+                    star = Some((spidx, snidx + 1));
+                    pidx = spidx + 1;
+                    nidx = snidx + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[pidx..].iter().all(|&c| c == '*')
+}
+
+/// Request a single line as an input.
+///
+/// A shorthand for the common case of reading one line, equivalent to:
+/// ```no_run
+/// # fn example() -> Result<(), gpiocdev::Error> {
+/// let req = gpiocdev::Request::builder()
+///     .on_chip("/dev/gpiochip0")
+///     .with_line(17)
+///     .as_input()
+///     .request()?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Use the [`Request::builder`] for anything more involved, such as setting the
+/// bias or consumer, or requesting multiple lines.
+///
+/// # Examples
+/// ```no_run
+/// # fn example() -> Result<(), gpiocdev::Error> {
+/// let req = gpiocdev::request_input("/dev/gpiochip0", 17)?;
+/// let value = req.value(17)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn request_input<P: Into<PathBuf>>(chip: P, offset: line::Offset) -> Result<Request> {
+    Request::builder()
+        .on_chip(chip)
+        .with_line(offset)
+        .as_input()
+        .request()
+}
+
+/// Request a single line as an output, set to the given value.
+///
+/// A shorthand for the common case of driving one line, equivalent to:
+/// ```no_run
+/// # fn example() -> Result<(), gpiocdev::Error> {
+/// # use gpiocdev::line::Value;
+/// let req = gpiocdev::Request::builder()
+///     .on_chip("/dev/gpiochip0")
+///     .with_line(22)
+///     .as_output(Value::Active)
+///     .request()?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Use the [`Request::builder`] for anything more involved, such as setting the
+/// drive or consumer, or requesting multiple lines.
+///
+/// # Examples
+/// ```no_run
+/// # fn example() -> Result<(), gpiocdev::Error> {
+/// # use gpiocdev::line::Value;
+/// let req = gpiocdev::request_output("/dev/gpiochip0", 22, Value::Active)?;
+/// req.set_value(22, Value::Inactive)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn request_output<P: Into<PathBuf>>(
+    chip: P,
+    offset: line::Offset,
+    value: line::Value,
+) -> Result<Request> {
+    Request::builder()
+        .on_chip(chip)
+        .with_line(offset)
+        .as_output(value)
+        .request()
+}
+
 /// The info for a line discovered in the system.
 ///
 /// Identifies the chip hosting the line, and the line info.
@@ -217,6 +651,10 @@ pub fn find_named_lines<'a>(
 pub struct FoundLine {
     /// The path to the chip containing the line.
     pub chip: PathBuf,
+    /// The info of the chip containing the line.
+    ///
+    /// Provides the chip name and label without having to reopen the chip.
+    pub chip_info: chip::Info,
     /// The info of the line.
     pub info: line::Info,
 }
@@ -235,6 +673,9 @@ impl From<line::Offset> for FoundLine {
 /// Can be used to discover and filter lines based on by particular criteria.
 ///
 /// Used by [`find_named_line`] and [`find_named_lines`] to find lines based on line name.
+///
+/// Unless the chips are explicitly provided, they are visited in the order returned by
+/// [`chip::chips`], i.e. in chip number order, and the lines in offset order within each chip.
 /// ```no_run
 /// # fn example() -> Result<(), gpiocdev::Error> {
 /// # use gpiocdev::line::Value;
@@ -248,56 +689,246 @@ impl From<line::Offset> for FoundLine {
 /// # }
 /// ```
 pub struct LineIterator {
-    chips: Vec<PathBuf>,
-    citer: Range<usize>,
-    chip: chip::Chip,
+    /// The chips opened so far, and their info.
+    chips: Vec<(chip::Chip, chip::Info)>,
+    /// The paths of the chips yet to be opened.
+    paths: std::vec::IntoIter<PathBuf>,
+    /// The index of the current chip in `chips`.
+    cidx: usize,
     liter: Range<u32>,
 }
 
-fn next_chip(chips: &[PathBuf], citer: &mut Range<usize>) -> Option<(chip::Chip, Range<u32>)> {
-    for cidx in citer {
-        if let Ok(chip) = chip::Chip::from_path(&chips[cidx]) {
-            if let Ok(info) = chip.info() {
-                return Some((
-                    chip,
-                    Range {
-                        start: 0,
-                        end: info.num_lines,
-                    },
-                ));
-            }
-        }
-    }
-    None
-}
-
 impl LineIterator {
     /// Creates an iterator over all the GPIO lines in the system that are available to the caller.
     pub fn new() -> Result<Self> {
-        let chips = chip::chips()?;
-        let mut citer = Range {
-            start: 0,
-            end: chips.len(),
+        Self::all_chips(false)
+    }
+
+    /// Creates an iterator over the GPIO lines in the system, starting from a particular chip.
+    ///
+    /// The `chip_index` is the index of the chip in the list returned by [`chip::chips`],
+    /// so iteration can be resumed from a known chip on systems with many chips.
+    ///
+    /// The iterator is empty if there are no available chips at or after the index.
+    ///
+    /// ```no_run
+    /// # fn example() -> Result<(), gpiocdev::Error> {
+    /// // skip the lines on the first chip
+    /// for l in gpiocdev::LineIterator::starting_from(1)? {
+    ///     println!("{} {}", l.chip_info.name, l.info.offset);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn starting_from(chip_index: usize) -> Result<Self> {
+        let mut chips = chip::chips()?;
+        chips.drain(..chip_index.min(chips.len()));
+        Ok(Self::from_paths(chips))
+    }
+
+    /// Creates an iterator over the GPIO lines on a single chip.
+    ///
+    /// Returns an error if the path does not identify an available GPIO chip.
+    pub fn for_chip<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let chip = chip::Chip::from_path(path)?;
+        let info = chip.info()?;
+        Ok(Self::from_open_chips(vec![(chip, info)]))
+    }
+
+    /// Creates an iterator over the GPIO lines on a single chip.
+    ///
+    /// The single chip form of [`on_chips`], but returns an error if the path does not
+    /// identify an available GPIO chip, rather than skipping it.
+    ///
+    /// Equivalent to [`for_chip`].
+    ///
+    /// ```no_run
+    /// # fn example() -> Result<(), gpiocdev::Error> {
+    /// let led0 = gpiocdev::LineIterator::on_chip("/dev/gpiochip0")?
+    ///     .find(|l| l.info.name == "LED0");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`on_chips`]: #method.on_chips
+    /// [`for_chip`]: #method.for_chip
+    pub fn on_chip<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::for_chip(path)
+    }
+
+    /// Creates an iterator over the GPIO lines on a set of chips.
+    ///
+    /// The chips are iterated in the order provided, and lines on other chips are not visited.
+    ///
+    /// Chips that are not available to the caller are skipped.
+    /// Use [`LineIterator::for_chip`] to iterate over a single chip.
+    ///
+    /// ```no_run
+    /// let led0 = gpiocdev::LineIterator::on_chips(&["/dev/gpiochip1", "/dev/gpiochip0"])
+    ///     .find(|l| l.info.name == "LED0");
+    /// ```
+    pub fn on_chips<P: AsRef<Path>>(paths: &[P]) -> Self {
+        Self::from_paths(paths.iter().map(|p| p.as_ref().to_path_buf()).collect())
+    }
+
+    /// Creates an iterator over the GPIO lines on a set of already open chips.
+    ///
+    /// The chips are iterated in the order provided.
+    /// Chips for which the info cannot be read are skipped.
+    ///
+    /// Combined with [`rewind`], this allows multiple scans of the lines without
+    /// reopening the chips.
+    ///
+    /// ```no_run
+    /// # fn example() -> Result<(), gpiocdev::Error> {
+    /// let chips = gpiocdev::chip::chips()?
+    ///     .iter()
+    ///     .filter_map(|p| gpiocdev::Chip::from_path(p).ok())
+    ///     .collect();
+    /// let mut lines = gpiocdev::LineIterator::with_chips(chips);
+    /// let opts = gpiocdev::MatchOptions::default();
+    /// let leds = gpiocdev::find_named_lines_in(&mut lines, ["LED0", "LED1"], true, opts)?;
+    /// let buttons = gpiocdev::find_named_lines_in(&mut lines, ["BTN0"], true, opts)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`rewind`]: #method.rewind
+    pub fn with_chips(chips: Vec<chip::Chip>) -> Self {
+        Self::from_open_chips(
+            chips
+                .into_iter()
+                .filter_map(|chip| chip.info().ok().map(|info| (chip, info)))
+                .collect(),
+        )
+    }
+
+    /// Restart the iteration from the first line of the first chip.
+    ///
+    /// Chips already opened by the iterator are reused rather than reopened.
+    pub fn rewind(&mut self) {
+        self.cidx = 0;
+        self.load_chip();
+    }
+
+    // Create an iterator over all the chips in the system.
+    //
+    // Chips that cannot be read are skipped, unless `strict`, in which case the error is returned.
+    fn all_chips(strict: bool) -> Result<Self> {
+        let mut iter = Self::unloaded(chip::chips()?);
+        iter.try_load_chip(strict)?;
+        if iter.chips.is_empty() {
+            return Err(Error::NoGpioChips());
+        }
+        Ok(iter)
+    }
+
+    fn from_paths(paths: Vec<PathBuf>) -> Self {
+        let mut iter = Self::unloaded(paths);
+        iter.load_chip();
+        iter
+    }
+
+    fn unloaded(paths: Vec<PathBuf>) -> Self {
+        LineIterator {
+            chips: Vec::new(),
+            paths: paths.into_iter(),
+            cidx: 0,
+            liter: Range { start: 0, end: 0 },
+        }
+    }
+
+    fn from_open_chips(chips: Vec<(chip::Chip, chip::Info)>) -> Self {
+        let mut iter = LineIterator {
+            chips,
+            paths: Vec::new().into_iter(),
+            cidx: 0,
+            liter: Range { start: 0, end: 0 },
         };
-        if let Some((chip, liter)) = next_chip(&chips, &mut citer) {
-            Ok(LineIterator {
-                chips,
-                citer,
-                chip,
-                liter,
-            })
-        } else {
-            Err(Error::NoGpioChips())
+        iter.load_chip();
+        iter
+    }
+
+    // Prepare to iterate over the lines of the current chip, opening it if necessary.
+    //
+    // Chips that cannot be read are skipped.
+    //
+    // Returns false if there are no more chips.
+    fn load_chip(&mut self) -> bool {
+        matches!(self.try_load_chip(false), Ok(true))
+    }
+
+    // Prepare to iterate over the lines of the current chip, opening it if necessary.
+    //
+    // Chips that cannot be read are skipped, unless `strict`, in which case the error
+    // is returned and the chip is not revisited.
+    //
+    // Returns false if there are no more chips.
+    fn try_load_chip(&mut self, strict: bool) -> Result<bool> {
+        while self.chips.len() <= self.cidx {
+            let path = match self.paths.next() {
+                Some(path) => path,
+                None => {
+                    self.liter = Range { start: 0, end: 0 };
+                    return Ok(false);
+                }
+            };
+            match chip::Chip::from_path(&path).and_then(|chip| Ok((chip.info()?, chip))) {
+                Ok((info, chip)) => self.chips.push((chip, info)),
+                Err(e) if strict => return Err(chip_error(&path, e)),
+                Err(_) => {}
+            }
         }
+        self.liter = Range {
+            start: 0,
+            end: self.chips[self.cidx].1.num_lines,
+        };
+        Ok(true)
     }
 
-    fn next_line_info(&mut self) -> Option<line::Info> {
-        for offset in &mut self.liter {
-            if let Ok(linfo) = self.chip.line_info(offset) {
-                return Some(linfo);
+    // Find the next line with info matching the predicate.
+    //
+    // The FoundLine is only constructed for a matching line.
+    fn find_next<F: FnMut(&line::Info) -> bool>(&mut self, pred: F) -> Option<FoundLine> {
+        self.try_find_next(false, pred).ok().flatten()
+    }
+
+    // Find the next line with info matching the predicate.
+    //
+    // Chips and lines that cannot be read are skipped, unless `strict`, in which case
+    // the error is returned and the iteration can be continued past the failed chip or line.
+    fn try_find_next<F: FnMut(&line::Info) -> bool>(
+        &mut self,
+        strict: bool,
+        mut pred: F,
+    ) -> Result<Option<FoundLine>> {
+        loop {
+            if let Some((chip, chip_info)) = self.chips.get(self.cidx) {
+                for offset in &mut self.liter {
+                    match chip.line_info(offset) {
+                        Ok(info) => {
+                            if pred(&info) {
+                                return Ok(Some(FoundLine {
+                                    chip: chip.path().to_path_buf(),
+                                    chip_info: chip_info.clone(),
+                                    info,
+                                }));
+                            }
+                        }
+                        Err(e) if strict => return Err(chip_error(chip.path(), e)),
+                        Err(_) => {}
+                    }
+                }
+            }
+            // skip to the next chip with lines, unless the current chip failed to load
+            if self.cidx < self.chips.len() {
+                self.cidx += 1;
+            }
+            if !self.try_load_chip(strict)? {
+                self.cidx = self.chips.len();
+                return Ok(None);
             }
         }
-        None
     }
 }
 
@@ -305,23 +936,67 @@ impl Iterator for LineIterator {
     type Item = FoundLine;
 
     fn next(&mut self) -> Option<FoundLine> {
-        if let Some(linfo) = self.next_line_info() {
-            return Some(FoundLine {
-                chip: self.chip.path().to_path_buf(),
-                info: linfo,
-            });
-        }
-        if let Some((chip, liter)) = next_chip(&self.chips, &mut self.citer) {
-            self.chip = chip;
-            self.liter = liter;
-            if let Some(linfo) = self.next_line_info() {
-                return Some(FoundLine {
-                    chip: self.chip.path().to_path_buf(),
-                    info: linfo,
-                });
-            }
+        self.find_next(|_| true)
+    }
+}
+
+// Identify the chip associated with an error, if the error does not already do so.
+fn chip_error(path: &Path, e: Error) -> Error {
+    match e {
+        Error::GpioChip(..) => e,
+        _ => Error::ChipAccess(path.to_path_buf(), Box::new(e)),
+    }
+}
+
+/// An iterator over GPIO lines that reports the chips and lines that cannot be read.
+///
+/// Unlike the [`LineIterator`], which skips chips and lines that cannot be read, such as
+/// when the caller lacks permission to access a chip, errors are returned as items,
+/// so applications can fail loudly rather than quietly missing lines.
+///
+/// The errors identify the chip, either as an [`Error::GpioChip`], or as an
+/// [`Error::ChipAccess`] wrapping the underlying error.
+///
+/// Iteration may be continued after an error, skipping the failed chip or line.
+///
+/// ```no_run
+/// # fn example() -> Result<(), gpiocdev::Error> {
+/// for l in gpiocdev::StrictLineIterator::new()? {
+///     let l = l?;
+///     println!("{} {} {}", l.chip.display(), l.info.offset, l.info.name);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct StrictLineIterator(LineIterator);
+
+impl StrictLineIterator {
+    /// Creates an iterator over all the GPIO lines in the system.
+    ///
+    /// Returns an error if there are no GPIO chips in the system.
+    pub fn new() -> Result<Self> {
+        let paths = chip::chips()?;
+        if paths.is_empty() {
+            return Err(Error::NoGpioChips());
         }
-        None
+        Ok(StrictLineIterator(LineIterator::unloaded(paths)))
+    }
+
+    /// Creates an iterator over the GPIO lines on a set of chips.
+    ///
+    /// The chips are iterated in the order provided, and lines on other chips are not visited.
+    pub fn on_chips<P: AsRef<Path>>(paths: &[P]) -> Self {
+        StrictLineIterator(LineIterator::unloaded(
+            paths.iter().map(|p| p.as_ref().to_path_buf()).collect(),
+        ))
+    }
+}
+
+impl Iterator for StrictLineIterator {
+    type Item = Result<FoundLine>;
+
+    fn next(&mut self) -> Option<Result<FoundLine>> {
+        self.0.try_find_next(true, |_| true).transpose()
     }
 }
 
@@ -398,6 +1073,12 @@ pub enum Error {
     #[error("{0} {1}.")]
     AbiLimitation(AbiVersion, String),
 
+    /// A problem accessing a particular GPIO chip, or the lines on that chip.
+    ///
+    /// Identifies the chip, and the underlying error.
+    #[error("\"{0}\": {1}")]
+    ChipAccess(PathBuf, #[source] Box<Error>),
+
     /// Problem accessing GPIO chip character devices
     #[error("\"{0}\" {1}.")]
     GpioChip(PathBuf, chip::ErrorKind),
@@ -406,10 +1087,20 @@ pub enum Error {
     #[error("{0}")]
     InvalidArgument(String),
 
+    /// A request includes lines from more than one chip.
+    ///
+    /// Identifies the chips involved, and the offsets of the lines requested on each.
+    #[error("Multiple chips requested: {}.", format_chip_lines(.0))]
+    MultipleChips(Vec<(PathBuf, Vec<line::Offset>)>),
+
     /// No gpiochips are available to the user.
     #[error("No GPIO chips are available")]
     NoGpioChips(),
 
+    /// Returned when [`chip::find_chip_by_label`] finds multiple chips with the same label.
+    #[error("Chip label '{0}' is not unique")]
+    NonuniqueChipLabel(String),
+
     /// Returned when the strict mode of [`find_named_lines`] finds multiple lines with the same name.
     #[error("Line name '{0}' is not unique")]
     NonuniqueLineName(String),
@@ -435,6 +1126,21 @@ pub enum Error {
     NoAbiSupport(),
 }
 
+// Format the chips and lines from a multiple chip request for display.
+fn format_chip_lines(chips: &[(PathBuf, Vec<line::Offset>)]) -> String {
+    chips
+        .iter()
+        .map(|(chip, offsets)| {
+            if offsets.is_empty() {
+                format!("\"{}\"", chip.display())
+            } else {
+                format!("\"{}\" lines {:?}", chip.display(), offsets)
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
 impl From<std::io::Error> for Error {
     fn from(e: std::io::Error) -> Self {
         Error::Os(uapi::Errno::from(&e))
@@ -512,26 +1218,129 @@ impl fmt::Display for AbiSupportKind {
 /// [`gpiocdev`]: crate
 pub type Result<T> = std::result::Result<T, Error>;
 
-/// Detect the most recent uAPI ABI supported by the platform.
-pub fn detect_abi_version() -> Result<AbiVersion> {
-    for p in chip::chips()? {
-        if let Ok(c) = chip::Chip::from_path(p) {
-            return c.detect_abi_version();
+/// The uAPI ABI versions supported by the library and kernel.
+///
+/// Returned by [`detect_abi_support`] and [`Chip::abi_support`].
+///
+/// [`Chip::abi_support`]: chip::Chip::abi_support
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AbiSupport {
+    /// uAPI ABI v1 is supported.
+    pub v1: bool,
+
+    /// uAPI ABI v2 is supported.
+    pub v2: bool,
+}
+
+impl AbiSupport {
+    /// Check if a specific ABI version is supported.
+    ///
+    /// Returns an [`Error::UnsupportedAbi`] identifying whether the library or the kernel
+    /// lacks support if the version is not supported.
+    pub fn supports(&self, abiv: AbiVersion) -> Result<()> {
+        let (supported, built) = match abiv {
+            AbiVersion::V1 => (self.v1, cfg!(feature = "uapi_v1")),
+            AbiVersion::V2 => (self.v2, cfg!(feature = "uapi_v2")),
+        };
+        if supported {
+            return Ok(());
+        }
+        let kind = if built {
+            AbiSupportKind::Kernel
+        } else {
+            AbiSupportKind::Build
+        };
+        Err(Error::UnsupportedAbi(abiv, kind))
+    }
+
+    /// Return the most recent ABI version supported.
+    pub fn latest(&self) -> Result<AbiVersion> {
+        if self.v2 {
+            Ok(AbiVersion::V2)
+        } else if self.v1 {
+            Ok(AbiVersion::V1)
+        } else {
+            Err(Error::NoAbiSupport())
+        }
+    }
+
+    // Apply any ABI versions forced unsupported for testing.
+    #[cfg(feature = "test_abi_override")]
+    pub(crate) fn with_overrides(self) -> AbiSupport {
+        AbiSupport {
+            v1: self.v1 && !test::is_abi_unsupported(AbiVersion::V1),
+            v2: self.v2 && !test::is_abi_unsupported(AbiVersion::V2),
         }
     }
-    Err(Error::NoGpioChips())
 }
 
-/// Check if the platform and library support a specific ABI version.
-pub fn supports_abi_version(abiv: AbiVersion) -> Result<()> {
+// The ABI support detected for the platform, cached as it cannot change.
+static ABI_SUPPORT: Mutex<Option<AbiSupport>> = Mutex::new(None);
+
+/// Detect the uAPI ABI versions supported by the platform.
+///
+/// The platform is probed on the first call and the result cached for subsequent calls.
+/// Use [`detect_abi_support_uncached`] to probe the platform on every call.
+pub fn detect_abi_support() -> Result<AbiSupport> {
+    let mut cache = ABI_SUPPORT
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let support = match *cache {
+        Some(support) => support,
+        None => {
+            let support = probe_abi_support()?;
+            *cache = Some(support);
+            support
+        }
+    };
+    #[cfg(feature = "test_abi_override")]
+    let support = support.with_overrides();
+    Ok(support)
+}
+
+/// Detect the uAPI ABI versions supported by the platform, without caching.
+///
+/// As per [`detect_abi_support`], but the platform is probed on every call.
+pub fn detect_abi_support_uncached() -> Result<AbiSupport> {
+    let support = probe_abi_support()?;
+    #[cfg(feature = "test_abi_override")]
+    let support = support.with_overrides();
+    Ok(support)
+}
+
+// Probe the ABI support using the first available chip.
+fn probe_abi_support() -> Result<AbiSupport> {
     for p in chip::chips()? {
         if let Ok(c) = chip::Chip::from_path(p) {
-            return c.supports_abi_version(abiv);
+            return Ok(c.probe_abi_support());
         }
     }
     Err(Error::NoGpioChips())
 }
 
+/// Detect the most recent uAPI ABI supported by the platform.
+///
+/// The platform is probed on the first call and the result cached for subsequent calls,
+/// as per [`detect_abi_support`].
+pub fn detect_abi_version() -> Result<AbiVersion> {
+    detect_abi_support()?.latest()
+}
+
+/// Detect the most recent uAPI ABI supported by the platform, without caching.
+///
+/// As per [`detect_abi_version`], but the platform is probed on every call.
+pub fn detect_abi_version_uncached() -> Result<AbiVersion> {
+    detect_abi_support_uncached()?.latest()
+}
+
+/// Check if the platform and library support a specific ABI version.
+///
+/// The platform support is cached, as per [`detect_abi_support`].
+pub fn supports_abi_version(abiv: AbiVersion) -> Result<()> {
+    detect_abi_support()?.supports(abiv)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -574,6 +1383,122 @@ mod tests {
         }
     }
 
+    mod error {
+        use super::{chip, chip_error, uapi, Error};
+        use std::path::PathBuf;
+
+        #[test]
+        fn chip_access_display() {
+            let e = Error::ChipAccess(
+                PathBuf::from("/dev/gpiochip0"),
+                Box::new(Error::Os(uapi::Errno(13))),
+            );
+            assert_eq!(
+                e.to_string(),
+                "\"/dev/gpiochip0\": Permission denied (os error 13)"
+            );
+        }
+
+        #[test]
+        fn chip_error_identifies_chip() {
+            let path = PathBuf::from("/dev/gpiochip0");
+            let e = chip_error(&path, Error::Os(uapi::Errno(13)));
+            assert_eq!(
+                e,
+                Error::ChipAccess(path.clone(), Box::new(Error::Os(uapi::Errno(13))))
+            );
+
+            // errors already identifying the chip are returned as is
+            let e = Error::GpioChip(path.clone(), chip::ErrorKind::NotGpioDevice);
+            assert_eq!(chip_error(&path, e.clone()), e);
+        }
+
+        #[test]
+        fn multiple_chips_display() {
+            let e = Error::MultipleChips(vec![
+                (PathBuf::from("/dev/gpiochip0"), vec![2, 5]),
+                (PathBuf::from("/dev/gpiochip1"), vec![3]),
+                (PathBuf::from("/dev/gpiochip2"), vec![]),
+            ]);
+            assert_eq!(
+                e.to_string(),
+                "Multiple chips requested: \"/dev/gpiochip0\" lines [2, 5], \
+                \"/dev/gpiochip1\" lines [3], \"/dev/gpiochip2\"."
+            );
+        }
+    }
+
+    #[test]
+    fn match_options_normalize() {
+        let exact = MatchOptions::default();
+        assert_eq!(exact.normalize(" Led0 "), " Led0 ");
+
+        let opts = MatchOptions::default().with_case_insensitive(true);
+        assert_eq!(opts.normalize(" Led0 "), " led0 ");
+        assert_eq!(opts.normalize("PIÑATA"), "piñata");
+
+        let opts = MatchOptions::default().with_trim_whitespace(true);
+        assert_eq!(opts.normalize(" Led0 \t"), "Led0");
+        assert_eq!(opts.normalize("Led 0"), "Led 0");
+
+        let opts = MatchOptions::default()
+            .with_case_insensitive(true)
+            .with_trim_whitespace(true);
+        assert_eq!(opts.normalize(" Led0\n"), "led0");
+    }
+
+    #[test]
+    fn match_options_matches() {
+        let exact = MatchOptions::default();
+        assert!(exact.matches("Led0", "Led0"));
+        assert!(!exact.matches("Led0", "led0"));
+        assert!(!exact.matches("Led0", " Led0"));
+
+        let opts = MatchOptions::default().with_case_insensitive(true);
+        let name = opts.normalize("LED0");
+        assert!(opts.matches(&name, "Led0"));
+        assert!(opts.matches(&opts.normalize("piñata"), "PIÑATA"));
+        assert!(!opts.matches(&name, " Led0"));
+        assert!(!opts.matches(&name, "Led01"));
+
+        let opts = MatchOptions::default()
+            .with_case_insensitive(true)
+            .with_trim_whitespace(true);
+        let name = opts.normalize(" Led0\t");
+        assert!(opts.matches(&name, "LED0 "));
+        assert!(!opts.matches(&name, "LED 0"));
+    }
+
+    #[test]
+    fn glob_match() {
+        use super::glob_match;
+
+        let m =
+            |pattern: &str, name: &str| glob_match(&pattern.chars().collect::<Vec<char>>(), name);
+        assert!(m("", ""));
+        assert!(!m("", "a"));
+        assert!(m("*", ""));
+        assert!(m("*", "PWR_EN_1"));
+        assert!(m("PWR_EN_*", "PWR_EN_1"));
+        assert!(m("PWR_EN_*", "PWR_EN_"));
+        assert!(m("PWR_EN_*", "PWR_EN_12"));
+        assert!(!m("PWR_EN_*", "PWR_EN"));
+        assert!(!m("PWR_EN_*", "XPWR_EN_1"));
+        assert!(m("PWR_EN_?", "PWR_EN_1"));
+        assert!(!m("PWR_EN_?", "PWR_EN_12"));
+        assert!(!m("PWR_EN_?", "PWR_EN_"));
+        assert!(m("*_EN_*", "PWR_EN_1"));
+        assert!(m("*EN*", "PWR_EN_1"));
+        assert!(m("P*R*1", "PWR_EN_1"));
+        assert!(!m("P*R*2", "PWR_EN_1"));
+        assert!(m("a*b*c", "abbbcbc"));
+        assert!(m("**a", "bba"));
+        assert!(m("pi?ata", "piñata"));
+        assert!(m("LED0", "LED0"));
+        assert!(!m("LED0", "LED1"));
+        assert!(!m("LED0", "led0"));
+    }
+
     mod abi_support_kind {
 
         #[test]
@@ -585,4 +1510,56 @@ mod tests {
             assert_eq!(format!("{}", ask), "kernel");
         }
     }
+
+    mod abi_support {
+        use crate::{AbiSupport, AbiSupportKind, AbiVersion, Error};
+
+        #[test]
+        fn supports() {
+            let s = AbiSupport { v1: true, v2: true };
+            assert_eq!(s.supports(AbiVersion::V1), Ok(()));
+            assert_eq!(s.supports(AbiVersion::V2), Ok(()));
+
+            let s = AbiSupport::default();
+            let kind = |built| {
+                if built {
+                    AbiSupportKind::Kernel
+                } else {
+                    AbiSupportKind::Build
+                }
+            };
+            assert_eq!(
+                s.supports(AbiVersion::V1),
+                Err(Error::UnsupportedAbi(
+                    AbiVersion::V1,
+                    kind(cfg!(feature = "uapi_v1"))
+                ))
+            );
+            assert_eq!(
+                s.supports(AbiVersion::V2),
+                Err(Error::UnsupportedAbi(
+                    AbiVersion::V2,
+                    kind(cfg!(feature = "uapi_v2"))
+                ))
+            );
+        }
+
+        #[test]
+        fn latest() {
+            let s = AbiSupport { v1: true, v2: true };
+            assert_eq!(s.latest(), Ok(AbiVersion::V2));
+            let s = AbiSupport {
+                v1: true,
+                v2: false,
+            };
+            assert_eq!(s.latest(), Ok(AbiVersion::V1));
+            let s = AbiSupport {
+                v1: false,
+                v2: true,
+            };
+            assert_eq!(s.latest(), Ok(AbiVersion::V2));
+            let s = AbiSupport::default();
+            assert_eq!(s.latest(), Err(Error::NoAbiSupport()));
+        }
+    }
 }
@@ -3,13 +3,14 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 mod config;
-pub use self::config::Config;
+pub use self::config::{Config, ConfigChange};
 
 mod event;
+pub(crate) use self::event::decode_error;
 pub use self::event::{EdgeEvent, EdgeKind, InfoChangeEvent, InfoChangeKind};
 
 mod info;
-pub use self::info::Info;
+pub use self::info::{ConsumerKind, Info, InfoFieldSet};
 
 mod value;
 pub use self::value::{Value, Values};
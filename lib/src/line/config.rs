@@ -2,7 +2,7 @@
 //
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use super::{Bias, Direction, Drive, EdgeDetection, EventClock, Value};
+use super::{Bias, Direction, Drive, EdgeDetection, EventClock, Info, Value};
 
 #[cfg(feature = "uapi_v1")]
 use gpiocdev_uapi::v1;
@@ -10,6 +10,7 @@ use gpiocdev_uapi::v1;
 use gpiocdev_uapi::v2;
 #[cfg(feature = "serde")]
 use serde_derive::{Deserialize, Serialize};
+use std::fmt;
 use std::time::Duration;
 
 /// The configuration settings for a single line.
@@ -65,6 +66,10 @@ impl Config {
     }
 
     /// Do not set the direction of the line.
+    ///
+    /// The kernel leaves the line in its current direction.
+    ///
+    /// Removes any input or output specific settings.
     pub fn as_is(&mut self) -> &mut Self {
         self.direction = None;
         self.sanitize_input();
@@ -131,6 +136,59 @@ impl Config {
         false
     }
 
+    /// Return the changes required to convert this config into the `other` config.
+    ///
+    /// The changes are returned in field order, and are empty if the configs are equal.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpiocdev::line::{Bias, Config, ConfigChange};
+    ///
+    /// let mut old = Config::default();
+    /// old.as_input();
+    /// let mut new = old.clone();
+    /// new.bias = Some(Bias::PullUp);
+    /// assert_eq!(old.diff(&new), [ConfigChange::Bias(None, Some(Bias::PullUp))]);
+    /// assert_eq!(old.diff(&new)[0].to_string(), "bias: none -> PullUp");
+    /// ```
+    pub fn diff(&self, other: &Config) -> Vec<ConfigChange> {
+        let mut changes = Vec::new();
+        if self.direction != other.direction {
+            changes.push(ConfigChange::Direction(self.direction, other.direction));
+        }
+        if self.active_low != other.active_low {
+            changes.push(ConfigChange::ActiveLow(self.active_low, other.active_low));
+        }
+        if self.bias != other.bias {
+            changes.push(ConfigChange::Bias(self.bias, other.bias));
+        }
+        if self.drive != other.drive {
+            changes.push(ConfigChange::Drive(self.drive, other.drive));
+        }
+        if self.edge_detection != other.edge_detection {
+            changes.push(ConfigChange::EdgeDetection(
+                self.edge_detection,
+                other.edge_detection,
+            ));
+        }
+        if self.event_clock != other.event_clock {
+            changes.push(ConfigChange::EventClock(
+                self.event_clock,
+                other.event_clock,
+            ));
+        }
+        if self.debounce_period != other.debounce_period {
+            changes.push(ConfigChange::DebouncePeriod(
+                self.debounce_period,
+                other.debounce_period,
+            ));
+        }
+        if self.value != other.value {
+            changes.push(ConfigChange::Value(self.value, other.value));
+        }
+        changes
+    }
+
     /// return the effective value specified by the config
     pub(crate) fn value(&self) -> Value {
         match self.value {
@@ -152,6 +210,79 @@ impl Config {
     }
 }
 
+/// A change to a single field of a line [`Config`].
+///
+/// Each variant contains the old and new values of the field.
+///
+/// Returned by [`Config::diff`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ConfigChange {
+    /// The direction changed.
+    Direction(Option<Direction>, Option<Direction>),
+
+    /// The active low setting changed.
+    ActiveLow(bool, bool),
+
+    /// The bias changed.
+    Bias(Option<Bias>, Option<Bias>),
+
+    /// The drive changed.
+    Drive(Option<Drive>, Option<Drive>),
+
+    /// The edge detection changed.
+    EdgeDetection(Option<EdgeDetection>, Option<EdgeDetection>),
+
+    /// The event clock changed.
+    EventClock(Option<EventClock>, Option<EventClock>),
+
+    /// The debounce period changed.
+    DebouncePeriod(Option<Duration>, Option<Duration>),
+
+    /// The output value changed.
+    Value(Option<Value>, Option<Value>),
+}
+
+impl fmt::Display for ConfigChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn opt<T: fmt::Debug>(v: &Option<T>) -> String {
+            match v {
+                Some(v) => format!("{:?}", v),
+                None => "none".to_string(),
+            }
+        }
+        let (field, from, to) = match self {
+            ConfigChange::Direction(from, to) => ("direction", opt(from), opt(to)),
+            ConfigChange::ActiveLow(from, to) => ("active-low", from.to_string(), to.to_string()),
+            ConfigChange::Bias(from, to) => ("bias", opt(from), opt(to)),
+            ConfigChange::Drive(from, to) => ("drive", opt(from), opt(to)),
+            ConfigChange::EdgeDetection(from, to) => ("edge-detection", opt(from), opt(to)),
+            ConfigChange::EventClock(from, to) => ("event-clock", opt(from), opt(to)),
+            ConfigChange::DebouncePeriod(from, to) => ("debounce-period", opt(from), opt(to)),
+            ConfigChange::Value(from, to) => ("value", opt(from), opt(to)),
+        };
+        write!(f, "{}: {} -> {}", field, from, to)
+    }
+}
+
+impl From<&Info> for Config {
+    /// Create a config matching the current configuration of a line.
+    ///
+    /// The value is left unset, as it is not part of the line info.
+    fn from(info: &Info) -> Self {
+        Config {
+            direction: Some(info.direction),
+            active_low: info.active_low,
+            bias: info.bias,
+            drive: info.drive,
+            edge_detection: info.edge_detection,
+            event_clock: info.event_clock,
+            debounce_period: info.debounce_period.filter(|period| !period.is_zero()),
+            value: None,
+        }
+    }
+}
+
 #[cfg(any(feature = "uapi_v2", not(feature = "uapi_v1")))]
 impl From<&Config> for v2::LineFlags {
     fn from(cfg: &Config) -> v2::LineFlags {
@@ -408,6 +539,53 @@ mod tests {
         assert!(lcfg.equivalent(&rcfg));
     }
 
+    #[test]
+    fn from_info() {
+        let info = Info {
+            offset: 3,
+            name: "banana".into(),
+            name_os: "banana".into(),
+            consumer: "jam".into(),
+            used: true,
+            active_low: true,
+            direction: Input,
+            bias: Some(PullUp),
+            drive: None,
+            edge_detection: Some(BothEdges),
+            event_clock: Some(EventClock::Realtime),
+            debounce_period: Some(Duration::from_millis(3)),
+        };
+        assert_eq!(
+            Config::from(&info),
+            Config {
+                direction: Some(Input),
+                active_low: true,
+                bias: Some(PullUp),
+                drive: None,
+                edge_detection: Some(BothEdges),
+                event_clock: Some(EventClock::Realtime),
+                debounce_period: Some(Duration::from_millis(3)),
+                value: None,
+            }
+        );
+
+        let info = Info {
+            offset: 5,
+            direction: Output,
+            drive: Some(OpenDrain),
+            debounce_period: Some(Duration::ZERO),
+            ..Default::default()
+        };
+        assert_eq!(
+            Config::from(&info),
+            Config {
+                direction: Some(Output),
+                drive: Some(OpenDrain),
+                ..Default::default()
+            }
+        );
+    }
+
     #[test]
     fn value() {
         let mut cfg: Config = Default::default();
@@ -420,6 +598,64 @@ mod tests {
         assert_eq!(cfg.value(), Value::Inactive);
     }
 
+    #[test]
+    fn diff() {
+        let mut old = Config::default();
+        old.as_input().with_edge_detection(Some(RisingEdge));
+        assert!(old.diff(&old).is_empty());
+
+        let mut new = old.clone();
+        new.active_low = true;
+        new.bias = Some(PullDown);
+        new.with_debounce_period(Duration::from_millis(5));
+        assert_eq!(
+            old.diff(&new),
+            [
+                ConfigChange::ActiveLow(false, true),
+                ConfigChange::Bias(None, Some(PullDown)),
+                ConfigChange::DebouncePeriod(None, Some(Duration::from_millis(5)))
+            ]
+        );
+
+        let mut new = old.clone();
+        new.as_output(Active).with_drive(OpenDrain);
+        assert_eq!(
+            old.diff(&new),
+            [
+                ConfigChange::Direction(Some(Input), Some(Output)),
+                ConfigChange::Drive(None, Some(OpenDrain)),
+                ConfigChange::EdgeDetection(Some(RisingEdge), None),
+                ConfigChange::Value(None, Some(Active))
+            ]
+        );
+        // and back again
+        assert_eq!(
+            new.diff(&old),
+            [
+                ConfigChange::Direction(Some(Output), Some(Input)),
+                ConfigChange::Drive(Some(OpenDrain), None),
+                ConfigChange::EdgeDetection(None, Some(RisingEdge)),
+                ConfigChange::Value(Some(Active), None)
+            ]
+        );
+    }
+
+    #[test]
+    fn config_change_display() {
+        assert_eq!(
+            ConfigChange::Direction(Some(Input), Some(Output)).to_string(),
+            "direction: Input -> Output"
+        );
+        assert_eq!(
+            ConfigChange::ActiveLow(false, true).to_string(),
+            "active-low: false -> true"
+        );
+        assert_eq!(
+            ConfigChange::DebouncePeriod(Some(Duration::from_millis(5)), None).to_string(),
+            "debounce-period: 5ms -> none"
+        );
+    }
+
     #[test]
     #[cfg(any(feature = "uapi_v2", not(feature = "uapi_v1")))]
     fn v2_line_flags_from_config() {
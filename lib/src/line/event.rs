@@ -2,7 +2,8 @@
 //
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use super::{Info, Offset};
+use super::{EventClock, Info, Offset};
+use crate::{Error, UapiCall};
 #[cfg(all(feature = "uapi_v1", not(feature = "uapi_v2")))]
 use gpiocdev_uapi::v1 as uapi;
 #[cfg(feature = "uapi_v1")]
@@ -11,12 +12,29 @@ use gpiocdev_uapi::v1;
 use gpiocdev_uapi::{v2, v2 as uapi};
 #[cfg(feature = "serde")]
 use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+use std::time::{Duration, SystemTime};
 
 /// The details of an edge detected on an input line.
 ///
 /// ABI v1 does not provide the seqno nor line_seqno fields.
-#[derive(Clone, Debug, Eq, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+///
+/// The event is a plain value, so it is cheap to copy and decoding it does not allocate.
+///
+/// With the `serde` feature, the event is serialized with camelCase field names -
+/// `timestampNs`, `kind`, `offset`, `seqno` and `lineSeqno`, with the timestamp in
+/// raw nanoseconds.  The `seqno` and `lineSeqno` are omitted when zero, as for uAPI v1.
+///
+/// The event is displayed as space separated fields, such as
+/// `offset=18 rising seqno=5 line_seqno=3 ts=12345678ns`, with the timestamp in
+/// raw nanoseconds.  As for serde, the `seqno` and `line_seqno` are omitted when zero.
+/// The format is stable, so is suitable for parsing from logs.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "camelCase")
+)]
 pub struct EdgeEvent {
     /// The best estimate of time of event occurrence, in nanoseconds.
     ///
@@ -28,6 +46,7 @@ pub struct EdgeEvent {
     ///
     /// **CLOCK_MONOTONIC** is intended for comparing times between events and
     /// should be converted to [`Duration`](std::time::Duration).
+    #[cfg_attr(feature = "serde", serde(alias = "timestamp_ns"))]
     pub timestamp_ns: u64,
 
     /// The event trigger identifier.
@@ -38,17 +57,70 @@ pub struct EdgeEvent {
 
     /// The sequence number for this event in the sequence of events for all
     /// the lines in this line request.
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_zero"))]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "is_zero"))]
     pub seqno: u32,
 
     /// The sequence number for this event in the sequence of events on this
     /// particular line.
-    #[cfg_attr(
-        feature = "serde",
-        serde(rename = "lineSeqno", skip_serializing_if = "is_zero")
-    )]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "is_zero"))]
     pub line_seqno: u32,
 }
+
+impl EdgeEvent {
+    /// The timestamp of the event as a [`SystemTime`].
+    ///
+    /// Only meaningful for events from lines using the **CLOCK_REALTIME**, so returns
+    /// None for other clocks.
+    ///
+    /// * `clock` - The event clock configured for the line.
+    ///
+    /// For uAPI v1 the event clock cannot be configured and depends on the kernel -
+    /// **CLOCK_REALTIME** prior to Linux 5.7 and **CLOCK_MONOTONIC** since.
+    ///
+    /// [`Request::edge_event_systemtime`](crate::Request::edge_event_systemtime)
+    /// determines the clock from the request configuration.
+    pub fn systemtime(&self, clock: EventClock) -> Option<SystemTime> {
+        match clock {
+            EventClock::Realtime => {
+                Some(SystemTime::UNIX_EPOCH + Duration::from_nanos(self.timestamp_ns))
+            }
+            _ => None,
+        }
+    }
+
+    /// The timestamp of the event as a [`Duration`] since an arbitrary point,
+    /// typically system boot.
+    ///
+    /// Only meaningful for events from lines using the **CLOCK_MONOTONIC**, so returns
+    /// None for other clocks.
+    ///
+    /// * `clock` - The event clock configured for the line.
+    ///
+    /// The durations are intended for comparing times between events, or with other
+    /// **CLOCK_MONOTONIC** times, not for conversion to wall clock time.
+    ///
+    /// The uAPI v1 caveats for [`systemtime`](#method.systemtime) also apply.
+    pub fn monotonic(&self, clock: EventClock) -> Option<Duration> {
+        match clock {
+            EventClock::Monotonic => Some(Duration::from_nanos(self.timestamp_ns)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for EdgeEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "offset={} {}", self.offset, self.kind)?;
+        if self.seqno != 0 {
+            write!(f, " seqno={}", self.seqno)?;
+        }
+        if self.line_seqno != 0 {
+            write!(f, " line_seqno={}", self.line_seqno)?;
+        }
+        write!(f, " ts={}ns", self.timestamp_ns)
+    }
+}
+
 #[cfg(feature = "uapi_v1")]
 impl From<&v1::LineEdgeEvent> for EdgeEvent {
     fn from(le: &v1::LineEdgeEvent) -> Self {
@@ -81,8 +153,17 @@ fn is_zero(u: &u32) -> bool {
 }
 
 /// The cause of an [`EdgeEvent`].
+///
+/// The discriminants match the event ids used by the kernel.
+///
+/// Future kernels may add new kinds of edge event, so matches on `EdgeKind`
+/// must include a wildcard arm.  Events with an id unknown to this library are
+/// reported as an [`Error::UnexpectedResponse`] when read.
+///
+/// The kind is displayed in lower case, e.g. `rising`.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
 pub enum EdgeKind {
     /// Indicates the line transitioned from inactive to active.
     Rising = 1,
@@ -91,6 +172,15 @@ pub enum EdgeKind {
     Falling = 2,
 }
 
+impl fmt::Display for EdgeKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(match self {
+            EdgeKind::Rising => "rising",
+            EdgeKind::Falling => "falling",
+        })
+    }
+}
+
 impl From<uapi::LineEdgeEventKind> for EdgeKind {
     fn from(kind: uapi::LineEdgeEventKind) -> Self {
         match kind {
@@ -100,9 +190,34 @@ impl From<uapi::LineEdgeEventKind> for EdgeKind {
     }
 }
 
+impl TryFrom<u32> for EdgeKind {
+    type Error = Error;
+
+    fn try_from(id: u32) -> Result<Self, Self::Error> {
+        match id {
+            1 => Ok(EdgeKind::Rising),
+            2 => Ok(EdgeKind::Falling),
+            _ => Err(Error::UnexpectedResponse(format!(
+                "unknown edge event id {id}"
+            ))),
+        }
+    }
+}
+
 /// The details of a change to the [`Info`] for a line.
+///
+/// With the `serde` feature, the event is serialized as the fields of the [`Info`],
+/// plus `timestampNs`, in raw nanoseconds, and `kind`.
+///
+/// The event is displayed as space separated fields, such as
+/// `offset=18 requested ts=12345678ns`, with the timestamp in raw nanoseconds.
+/// The format is stable, so is suitable for parsing from logs.
 #[derive(Clone, Debug, Eq, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "camelCase")
+)]
 pub struct InfoChangeEvent {
     /// The updated line info.
     #[cfg_attr(feature = "serde", serde(flatten))]
@@ -111,11 +226,23 @@ pub struct InfoChangeEvent {
     /// The best estimate of time of event occurrence.
     ///
     /// The **CLOCK_MONOTONIC** is used as the source for info change timestamps.
+    #[cfg_attr(feature = "serde", serde(alias = "timestamp_ns"))]
     pub timestamp_ns: u64,
 
     /// The trigger for the change.
     pub kind: InfoChangeKind,
 }
+
+impl fmt::Display for InfoChangeEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "offset={} {} ts={}ns",
+            self.info.offset, self.kind, self.timestamp_ns
+        )
+    }
+}
+
 #[cfg(feature = "uapi_v1")]
 impl From<&v1::LineInfoChangeEvent> for InfoChangeEvent {
     fn from(ice: &v1::LineInfoChangeEvent) -> Self {
@@ -138,8 +265,17 @@ impl From<&v2::LineInfoChangeEvent> for InfoChangeEvent {
 }
 
 /// The cause of a [`InfoChangeEvent`]
+///
+/// The discriminants match the event ids used by the kernel.
+///
+/// Future kernels may add new kinds of info change event, so matches on `InfoChangeKind`
+/// must include a wildcard arm.  Events with an id unknown to this library are
+/// reported as an [`Error::UnexpectedResponse`] when read.
+///
+/// The kind is displayed in lower case, e.g. `requested`.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
 pub enum InfoChangeKind {
     /// Line has been requested.
     Requested = 1,
@@ -151,6 +287,16 @@ pub enum InfoChangeKind {
     Reconfigured = 3,
 }
 
+impl fmt::Display for InfoChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(match self {
+            InfoChangeKind::Requested => "requested",
+            InfoChangeKind::Released => "released",
+            InfoChangeKind::Reconfigured => "reconfigured",
+        })
+    }
+}
+
 impl From<uapi::LineInfoChangeKind> for InfoChangeKind {
     fn from(kind: uapi::LineInfoChangeKind) -> Self {
         match kind {
@@ -161,14 +307,151 @@ impl From<uapi::LineInfoChangeKind> for InfoChangeKind {
     }
 }
 
+impl TryFrom<u32> for InfoChangeKind {
+    type Error = Error;
+
+    fn try_from(id: u32) -> Result<Self, Self::Error> {
+        match id {
+            1 => Ok(InfoChangeKind::Requested),
+            2 => Ok(InfoChangeKind::Released),
+            3 => Ok(InfoChangeKind::Reconfigured),
+            _ => Err(Error::UnexpectedResponse(format!(
+                "unknown info change event id {id}"
+            ))),
+        }
+    }
+}
+
+// Map an error decoding a raw uAPI event.
+//
+// An unknown event kind is reported by the uAPI as a validation failure carrying the raw id,
+// which is translated to the error from K.
+// Other errors are passed through.
+pub(crate) fn decode_error<K: TryFrom<u32, Error = Error>>(
+    call: UapiCall,
+    e: gpiocdev_uapi::Error,
+) -> Error {
+    if let gpiocdev_uapi::Error::Validation(ve) = &e {
+        if let Some(Err(ke)) = ve.value.map(K::try_from) {
+            return ke;
+        }
+    }
+    Error::Uapi(call, e)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::line::Drive;
 
+    // A u64 word containing the event kind followed by the u32 that follows it in the event.
+    fn kind_word(kind: u32, next: u32) -> u64 {
+        let mut b = [0_u8; 8];
+        b[..4].copy_from_slice(&kind.to_ne_bytes());
+        b[4..].copy_from_slice(&next.to_ne_bytes());
+        u64::from_ne_bytes(b)
+    }
+
     mod edge_event {
         use super::*;
 
+        #[test]
+        #[cfg(feature = "serde")]
+        fn serde() {
+            let ee = EdgeEvent {
+                timestamp_ns: 1_700_000_000_123_456_789,
+                kind: EdgeKind::Rising,
+                offset: 3,
+                seqno: 7,
+                line_seqno: 2,
+            };
+            let json = serde_json::to_string(&ee).unwrap();
+            assert_eq!(
+                json,
+                r#"{"timestampNs":1700000000123456789,"kind":"Rising","offset":3,"seqno":7,"lineSeqno":2}"#
+            );
+            assert_eq!(serde_json::from_str::<EdgeEvent>(&json).unwrap(), ee);
+
+            // v1 events have no seqnos
+            let ee = EdgeEvent {
+                timestamp_ns: u64::MAX,
+                kind: EdgeKind::Falling,
+                offset: 5,
+                seqno: 0,
+                line_seqno: 0,
+            };
+            let json = serde_json::to_string(&ee).unwrap();
+            assert_eq!(
+                json,
+                r#"{"timestampNs":18446744073709551615,"kind":"Falling","offset":5}"#
+            );
+            assert_eq!(serde_json::from_str::<EdgeEvent>(&json).unwrap(), ee);
+
+            // snake case timestamp accepted
+            let ee: EdgeEvent =
+                serde_json::from_str(r#"{"timestamp_ns":42,"kind":"Falling","offset":5}"#).unwrap();
+            assert_eq!(ee.timestamp_ns, 42);
+        }
+
+        #[test]
+        fn display() {
+            let ee = EdgeEvent {
+                timestamp_ns: 12_345_678,
+                kind: EdgeKind::Rising,
+                offset: 18,
+                seqno: 5,
+                line_seqno: 3,
+            };
+            assert_eq!(
+                ee.to_string(),
+                "offset=18 rising seqno=5 line_seqno=3 ts=12345678ns"
+            );
+
+            // v1 events have no seqnos
+            let ee = EdgeEvent {
+                timestamp_ns: u64::MAX,
+                kind: EdgeKind::Falling,
+                offset: 5,
+                seqno: 0,
+                line_seqno: 0,
+            };
+            assert_eq!(ee.to_string(), "offset=5 falling ts=18446744073709551615ns");
+        }
+
+        #[test]
+        fn systemtime() {
+            let ee = EdgeEvent {
+                timestamp_ns: 1_700_000_000_123_456_789,
+                kind: EdgeKind::Rising,
+                offset: 3,
+                seqno: 1,
+                line_seqno: 1,
+            };
+            assert_eq!(
+                ee.systemtime(EventClock::Realtime),
+                Some(SystemTime::UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789))
+            );
+            assert_eq!(ee.systemtime(EventClock::Monotonic), None);
+            assert_eq!(ee.systemtime(EventClock::Hte), None);
+        }
+
+        #[test]
+        fn monotonic() {
+            let ee = EdgeEvent {
+                timestamp_ns: 42_000_000_007,
+                kind: EdgeKind::Falling,
+                offset: 3,
+                seqno: 1,
+                line_seqno: 1,
+            };
+            assert_eq!(
+                ee.monotonic(EventClock::Monotonic),
+                Some(Duration::new(42, 7))
+            );
+            assert_eq!(ee.monotonic(EventClock::Realtime), None);
+            assert_eq!(ee.monotonic(EventClock::Hte), None);
+        }
+
         #[test]
         #[cfg(feature = "uapi_v1")]
         fn from_v1() {
@@ -204,9 +487,155 @@ mod tests {
         }
     }
 
+    mod edge_kind {
+        use super::*;
+
+        #[test]
+        fn display() {
+            assert_eq!(EdgeKind::Rising.to_string(), "rising");
+            assert_eq!(EdgeKind::Falling.to_string(), "falling");
+            // padded
+            assert_eq!(format!("{:9}|", EdgeKind::Rising), "rising   |");
+        }
+
+        #[test]
+        fn try_from_u32() {
+            assert_eq!(EdgeKind::try_from(1), Ok(EdgeKind::Rising));
+            assert_eq!(EdgeKind::try_from(2), Ok(EdgeKind::Falling));
+            assert_eq!(
+                EdgeKind::try_from(0),
+                Err(Error::UnexpectedResponse("unknown edge event id 0".into()))
+            );
+            assert_eq!(
+                EdgeKind::try_from(3),
+                Err(Error::UnexpectedResponse("unknown edge event id 3".into()))
+            );
+        }
+
+        #[test]
+        #[cfg(feature = "uapi_v1")]
+        fn decode_unknown_v1() {
+            let mut buf = [0_u64; std::mem::size_of::<v1::LineEdgeEvent>() / 8];
+            buf[1] = kind_word(3, 0);
+            let e = v1::LineEdgeEvent::from_slice(&buf).unwrap_err();
+            assert_eq!(
+                decode_error::<EdgeKind>(UapiCall::LEEFromBuf, e),
+                Error::UnexpectedResponse("unknown edge event id 3".into())
+            );
+            buf[1] = kind_word(2, 0);
+            let ee = EdgeEvent::from(v1::LineEdgeEvent::from_slice(&buf).unwrap());
+            assert_eq!(ee.kind, EdgeKind::Falling);
+        }
+
+        #[test]
+        #[cfg(any(feature = "uapi_v2", not(feature = "uapi_v1")))]
+        fn decode_unknown_v2() {
+            let mut buf = [0_u64; std::mem::size_of::<v2::LineEdgeEvent>() / 8];
+            buf[1] = kind_word(42, 5);
+            let e = v2::LineEdgeEvent::from_slice(&buf).unwrap_err();
+            assert_eq!(
+                decode_error::<EdgeKind>(UapiCall::LEEFromBuf, e),
+                Error::UnexpectedResponse("unknown edge event id 42".into())
+            );
+            buf[1] = kind_word(1, 5);
+            let ee = EdgeEvent::from(v2::LineEdgeEvent::from_slice(&buf).unwrap());
+            assert_eq!(ee.kind, EdgeKind::Rising);
+            assert_eq!(ee.offset, 5);
+        }
+
+        #[test]
+        fn decode_passthrough() {
+            let e = gpiocdev_uapi::Error::Os(gpiocdev_uapi::Errno(22));
+            assert_eq!(
+                decode_error::<EdgeKind>(UapiCall::LEEFromBuf, e.clone()),
+                Error::Uapi(UapiCall::LEEFromBuf, e)
+            );
+        }
+    }
+
+    mod info_change_kind {
+        use super::*;
+
+        #[test]
+        fn display() {
+            assert_eq!(InfoChangeKind::Requested.to_string(), "requested");
+            assert_eq!(InfoChangeKind::Released.to_string(), "released");
+            assert_eq!(InfoChangeKind::Reconfigured.to_string(), "reconfigured");
+        }
+
+        #[test]
+        fn try_from_u32() {
+            assert_eq!(InfoChangeKind::try_from(1), Ok(InfoChangeKind::Requested));
+            assert_eq!(InfoChangeKind::try_from(2), Ok(InfoChangeKind::Released));
+            assert_eq!(
+                InfoChangeKind::try_from(3),
+                Ok(InfoChangeKind::Reconfigured)
+            );
+            assert_eq!(
+                InfoChangeKind::try_from(0),
+                Err(Error::UnexpectedResponse(
+                    "unknown info change event id 0".into()
+                ))
+            );
+            assert_eq!(
+                InfoChangeKind::try_from(4),
+                Err(Error::UnexpectedResponse(
+                    "unknown info change event id 4".into()
+                ))
+            );
+        }
+
+        #[test]
+        #[cfg(feature = "uapi_v1")]
+        fn decode_unknown_v1() {
+            let kind_idx = std::mem::size_of::<v1::LineInfo>() / 8 + 1;
+            let mut buf = [0_u64; std::mem::size_of::<v1::LineInfoChangeEvent>() / 8];
+            buf[kind_idx] = kind_word(7, 0);
+            let e = v1::LineInfoChangeEvent::from_slice(&buf).unwrap_err();
+            assert_eq!(
+                decode_error::<InfoChangeKind>(UapiCall::LICEFromBuf, e),
+                Error::UnexpectedResponse("unknown info change event id 7".into())
+            );
+            buf[kind_idx] = kind_word(2, 0);
+            let ice = InfoChangeEvent::from(v1::LineInfoChangeEvent::from_slice(&buf).unwrap());
+            assert_eq!(ice.kind, InfoChangeKind::Released);
+        }
+
+        #[test]
+        #[cfg(any(feature = "uapi_v2", not(feature = "uapi_v1")))]
+        fn decode_unknown_v2() {
+            let kind_idx = std::mem::size_of::<v2::LineInfo>() / 8 + 1;
+            let mut buf = [0_u64; std::mem::size_of::<v2::LineInfoChangeEvent>() / 8];
+            buf[kind_idx] = kind_word(4, 0);
+            let e = v2::LineInfoChangeEvent::from_slice(&buf).unwrap_err();
+            assert_eq!(
+                decode_error::<InfoChangeKind>(UapiCall::LICEFromBuf, e),
+                Error::UnexpectedResponse("unknown info change event id 4".into())
+            );
+            buf[kind_idx] = kind_word(3, 0);
+            let ice = InfoChangeEvent::from(v2::LineInfoChangeEvent::from_slice(&buf).unwrap());
+            assert_eq!(ice.kind, InfoChangeKind::Reconfigured);
+        }
+    }
+
     mod info_change_event {
         use super::*;
 
+        #[test]
+        fn display() {
+            let ice = InfoChangeEvent {
+                info: Info {
+                    offset: 18,
+                    name: "banana".into(),
+                    used: true,
+                    ..Default::default()
+                },
+                timestamp_ns: 12_345_678,
+                kind: InfoChangeKind::Requested,
+            };
+            assert_eq!(ice.to_string(), "offset=18 requested ts=12345678ns");
+        }
+
         #[test]
         #[cfg(feature = "uapi_v1")]
         fn from_v1() {
@@ -251,5 +680,47 @@ mod tests {
             assert_eq!(ee.info.offset, 32);
             assert_eq!(ee.info.drive, Some(Drive::OpenDrain));
         }
+
+        #[test]
+        #[cfg(feature = "serde")]
+        fn serde() {
+            use crate::line::{Direction, EdgeDetection};
+
+            let ice = InfoChangeEvent {
+                info: Info {
+                    offset: 32,
+                    name: "banana".into(),
+                    consumer: "mybutton".into(),
+                    used: true,
+                    direction: Direction::Input,
+                    edge_detection: Some(EdgeDetection::BothEdges),
+                    ..Default::default()
+                },
+                timestamp_ns: 1_234_567_890_123,
+                kind: InfoChangeKind::Requested,
+            };
+            let json = serde_json::to_string(&ice).unwrap();
+            assert_eq!(
+                json,
+                r#"{"offset":32,"name":"banana","consumer":"mybutton","used":true,"direction":"Input","edgeDetection":"BothEdges","timestampNs":1234567890123,"kind":"Requested"}"#
+            );
+            assert_eq!(serde_json::from_str::<InfoChangeEvent>(&json).unwrap(), ice);
+
+            // empty and default info fields are omitted
+            let ice = InfoChangeEvent {
+                info: Info {
+                    offset: 3,
+                    ..Default::default()
+                },
+                timestamp_ns: 42,
+                kind: InfoChangeKind::Released,
+            };
+            let json = serde_json::to_string(&ice).unwrap();
+            assert_eq!(
+                json,
+                r#"{"offset":3,"direction":"Input","timestampNs":42,"kind":"Released"}"#
+            );
+            assert_eq!(serde_json::from_str::<InfoChangeEvent>(&json).unwrap(), ice);
+        }
     }
 }
@@ -9,6 +9,9 @@ use gpiocdev_uapi::v1;
 use gpiocdev_uapi::v2;
 #[cfg(feature = "serde")]
 use serde_derive::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::fmt;
+use std::ops::{BitOr, BitOrAssign};
 use std::time::Duration;
 
 /// The publicly available information for a line.
@@ -16,7 +19,7 @@ use std::time::Duration;
 #[cfg_attr(
     feature = "serde",
     derive(Serialize, Deserialize),
-    serde(rename_all = "camelCase")
+    serde(default, rename_all = "camelCase")
 )]
 pub struct Info {
     /// The line offset on the GPIO chip.
@@ -27,9 +30,21 @@ pub struct Info {
     /// GPIO chip.
     ///
     /// May be empty.
+    ///
+    /// The name is converted to UTF-8, with any invalid sequences replaced by
+    /// U+FFFD, so use [`name_os`](#structfield.name_os) where the name may not be
+    /// valid UTF-8.
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "String::is_empty"))]
     pub name: String,
 
+    /// The name of this GPIO line, exactly as specified by the GPIO chip.
+    ///
+    /// May be empty.
+    ///
+    /// This is not serialized, so is empty if the info is deserialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub name_os: OsString,
+
     /// A functional name for the consumer of this GPIO line as set
     /// by whatever is using it.
     ///
@@ -79,6 +94,303 @@ pub struct Info {
     pub debounce_period: Option<Duration>,
 }
 
+impl Info {
+    /// Return a compact, human readable, summary of the state of the line.
+    ///
+    /// The summary is a comma separated list of the direction, bias, drive,
+    /// edge detection, event clock, debounce period, active low and used
+    /// states, such as:
+    ///
+    /// `input, pull-up, rising-edge, active-low, used by "mybutton"`
+    ///
+    /// Fields that are not set, or are set to their default value, such as
+    /// push-pull drive and the monotonic event clock, are omitted.
+    ///
+    /// The name and offset of the line are not included.
+    pub fn summary(&self) -> String {
+        let mut attrs: Vec<String> = Vec::new();
+        attrs.push(
+            match self.direction {
+                Direction::Input => "input",
+                Direction::Output => "output",
+            }
+            .into(),
+        );
+        match self.bias {
+            None => {}
+            Some(Bias::PullUp) => attrs.push("pull-up".into()),
+            Some(Bias::PullDown) => attrs.push("pull-down".into()),
+            Some(Bias::Disabled) => attrs.push("bias-disabled".into()),
+        }
+        match self.drive {
+            None | Some(Drive::PushPull) => {}
+            Some(Drive::OpenDrain) => attrs.push("open-drain".into()),
+            Some(Drive::OpenSource) => attrs.push("open-source".into()),
+        }
+        match self.edge_detection {
+            None => {}
+            Some(EdgeDetection::RisingEdge) => attrs.push("rising-edge".into()),
+            Some(EdgeDetection::FallingEdge) => attrs.push("falling-edge".into()),
+            Some(EdgeDetection::BothEdges) => attrs.push("both-edges".into()),
+        }
+        match self.event_clock {
+            None | Some(EventClock::Monotonic) => {}
+            Some(EventClock::Realtime) => attrs.push("realtime-clock".into()),
+            Some(EventClock::Hte) => attrs.push("hte-clock".into()),
+        }
+        if let Some(period) = self.debounce_period.filter(|p| !p.is_zero()) {
+            attrs.push(format!("debounced {:?}", period));
+        }
+        if self.active_low {
+            attrs.push("active-low".into());
+        }
+        if self.used {
+            if self.consumer.is_empty() {
+                attrs.push("used".into());
+            } else {
+                attrs.push(format!("used by \"{}\"", self.consumer));
+            }
+        }
+        attrs.join(", ")
+    }
+
+    /// Classify the consumer of the line.
+    ///
+    /// The classification is based on the `used` flag and the [`consumer`] label, as
+    /// that is all the uAPI provides, so only consumers with labels set by the kernel
+    /// itself can be identified.
+    /// In particular, lines hogged by the kernel, such as those specified by `gpio-hog`
+    /// nodes in device tree, are labelled with the name of the hog, so cannot be
+    /// distinguished from lines requested by drivers or user space.
+    ///
+    /// [`consumer`]: #structfield.consumer
+    pub fn consumer_kind(&self) -> ConsumerKind {
+        if !self.used {
+            return ConsumerKind::Unused;
+        }
+        match self.consumer.as_str() {
+            "" | "?" => ConsumerKind::Unlabelled,
+            "interrupt" => ConsumerKind::Interrupt,
+            "sysfs" => ConsumerKind::Sysfs,
+            _ => ConsumerKind::Labelled,
+        }
+    }
+
+    /// Identify the fields that differ between this info and `other`.
+    ///
+    /// Typically used to determine what changed between the info before and after an
+    /// [`InfoChangeEvent`](super::InfoChangeEvent).
+    ///
+    /// The [`offset`] and [`name`] identify the line, so a change to either is unusual
+    /// and suggests the infos are for different lines.  Those changes can be detected
+    /// using [`InfoFieldSet::IDENTITY`].
+    ///
+    /// # Examples
+    /// ```
+    /// # use gpiocdev::line::{EdgeDetection, Info, InfoFieldSet};
+    /// let old = Info::default();
+    /// let new = Info {
+    ///     edge_detection: Some(EdgeDetection::RisingEdge),
+    ///     ..Default::default()
+    /// };
+    /// let changed = old.changed_fields(&new);
+    /// assert_eq!(changed, InfoFieldSet::EDGE_DETECTION);
+    /// assert!(!changed.intersects(InfoFieldSet::IDENTITY));
+    /// assert_eq!(changed.to_string(), "edge_detection");
+    /// ```
+    ///
+    /// [`offset`]: #structfield.offset
+    /// [`name`]: #structfield.name
+    pub fn changed_fields(&self, other: &Info) -> InfoFieldSet {
+        let mut changed = InfoFieldSet::empty();
+        let mut check = |differs: bool, field: InfoFieldSet| {
+            if differs {
+                changed |= field;
+            }
+        };
+        check(self.offset != other.offset, InfoFieldSet::OFFSET);
+        check(
+            self.name != other.name || self.name_os != other.name_os,
+            InfoFieldSet::NAME,
+        );
+        check(self.consumer != other.consumer, InfoFieldSet::CONSUMER);
+        check(self.used != other.used, InfoFieldSet::USED);
+        check(
+            self.active_low != other.active_low,
+            InfoFieldSet::ACTIVE_LOW,
+        );
+        check(self.direction != other.direction, InfoFieldSet::DIRECTION);
+        check(self.bias != other.bias, InfoFieldSet::BIAS);
+        check(self.drive != other.drive, InfoFieldSet::DRIVE);
+        check(
+            self.edge_detection != other.edge_detection,
+            InfoFieldSet::EDGE_DETECTION,
+        );
+        check(
+            self.event_clock != other.event_clock,
+            InfoFieldSet::EVENT_CLOCK,
+        );
+        check(
+            self.debounce_period != other.debounce_period,
+            InfoFieldSet::DEBOUNCE_PERIOD,
+        );
+        changed
+    }
+}
+
+/// A set of [`Info`] fields.
+///
+/// Returned by [`Info::changed_fields`].
+///
+/// Sets can be combined using `|`, and are displayed as a comma separated list
+/// of the names of the fields, in the order they are declared in [`Info`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct InfoFieldSet(u16);
+
+impl InfoFieldSet {
+    /// The [`Info::offset`] field.
+    pub const OFFSET: InfoFieldSet = InfoFieldSet(1 << 0);
+
+    /// The [`Info::name`] and [`Info::name_os`] fields.
+    pub const NAME: InfoFieldSet = InfoFieldSet(1 << 1);
+
+    /// The [`Info::consumer`] field.
+    pub const CONSUMER: InfoFieldSet = InfoFieldSet(1 << 2);
+
+    /// The [`Info::used`] field.
+    pub const USED: InfoFieldSet = InfoFieldSet(1 << 3);
+
+    /// The [`Info::active_low`] field.
+    pub const ACTIVE_LOW: InfoFieldSet = InfoFieldSet(1 << 4);
+
+    /// The [`Info::direction`] field.
+    pub const DIRECTION: InfoFieldSet = InfoFieldSet(1 << 5);
+
+    /// The [`Info::bias`] field.
+    pub const BIAS: InfoFieldSet = InfoFieldSet(1 << 6);
+
+    /// The [`Info::drive`] field.
+    pub const DRIVE: InfoFieldSet = InfoFieldSet(1 << 7);
+
+    /// The [`Info::edge_detection`] field.
+    pub const EDGE_DETECTION: InfoFieldSet = InfoFieldSet(1 << 8);
+
+    /// The [`Info::event_clock`] field.
+    pub const EVENT_CLOCK: InfoFieldSet = InfoFieldSet(1 << 9);
+
+    /// The [`Info::debounce_period`] field.
+    pub const DEBOUNCE_PERIOD: InfoFieldSet = InfoFieldSet(1 << 10);
+
+    /// The fields that identify the line - the offset and name.
+    pub const IDENTITY: InfoFieldSet = InfoFieldSet(Self::OFFSET.0 | Self::NAME.0);
+
+    // The names of the fields, indexed by bit.
+    const NAMES: [&'static str; 11] = [
+        "offset",
+        "name",
+        "consumer",
+        "used",
+        "active_low",
+        "direction",
+        "bias",
+        "drive",
+        "edge_detection",
+        "event_clock",
+        "debounce_period",
+    ];
+
+    /// The set containing no fields.
+    pub const fn empty() -> InfoFieldSet {
+        InfoFieldSet(0)
+    }
+
+    /// Returns true if the set contains no fields.
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns true if the set contains all the fields in `other`.
+    pub const fn contains(&self, other: InfoFieldSet) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns true if the set contains any of the fields in `other`.
+    pub const fn intersects(&self, other: InfoFieldSet) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// Add the fields in `other` to the set.
+    pub fn insert(&mut self, other: InfoFieldSet) {
+        self.0 |= other.0;
+    }
+
+    /// Remove the fields in `other` from the set.
+    pub fn remove(&mut self, other: InfoFieldSet) {
+        self.0 &= !other.0;
+    }
+
+    /// The names of the fields in the set, in the order they are declared in [`Info`].
+    pub fn names(&self) -> Vec<&'static str> {
+        Self::NAMES
+            .iter()
+            .enumerate()
+            .filter(|(bit, _)| self.0 & (1 << bit) != 0)
+            .map(|(_, name)| *name)
+            .collect()
+    }
+}
+
+impl BitOr for InfoFieldSet {
+    type Output = InfoFieldSet;
+
+    fn bitor(self, rhs: InfoFieldSet) -> InfoFieldSet {
+        InfoFieldSet(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for InfoFieldSet {
+    fn bitor_assign(&mut self, rhs: InfoFieldSet) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl fmt::Display for InfoFieldSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.names().join(", "))
+    }
+}
+
+/// The classification of the consumer of a line.
+///
+/// Returned by [`Info::consumer_kind`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum ConsumerKind {
+    /// The line is not in use.
+    Unused,
+
+    /// The line is in use, but the consumer did not provide a label.
+    ///
+    /// This includes lines requested from user space with an empty consumer,
+    /// which the kernel labels as "?", and lines claimed by pin control.
+    Unlabelled,
+
+    /// The line is in use as an interrupt by the kernel.
+    Interrupt,
+
+    /// The line has been exported to user space via the deprecated sysfs interface.
+    Sysfs,
+
+    /// The line is in use by a consumer that provided a label.
+    ///
+    /// The consumer may be a kernel driver, a kernel hog, or user space - the [`consumer`]
+    /// label is the only indication of which.
+    ///
+    /// [`consumer`]: Info::consumer
+    Labelled,
+}
+
 #[cfg(feature = "serde")]
 fn is_false(b: &bool) -> bool {
     !b
@@ -90,6 +402,7 @@ impl From<&v1::LineInfo> for Info {
         Info {
             offset: li.offset,
             name: String::from(&li.name),
+            name_os: li.name.as_os_str().to_os_string(),
             consumer: String::from(&li.consumer),
             used: li.flags.contains(v1::LineInfoFlags::USED),
             active_low: li.flags.contains(v1::LineInfoFlags::ACTIVE_LOW),
@@ -119,6 +432,7 @@ impl From<&v2::LineInfo> for Info {
         Info {
             offset: li.offset,
             name: String::from(&li.name),
+            name_os: li.name.as_os_str().to_os_string(),
             consumer: String::from(&li.consumer),
             used: li.flags.contains(v2::LineFlags::USED),
             active_low: li.flags.contains(v2::LineFlags::ACTIVE_LOW),
@@ -136,6 +450,158 @@ impl From<&v2::LineInfo> for Info {
 mod tests {
     use super::*;
 
+    #[test]
+    fn summary() {
+        let mut info = Info::default();
+        assert_eq!(info.summary(), "input");
+
+        info.bias = Some(Bias::PullUp);
+        info.edge_detection = Some(EdgeDetection::RisingEdge);
+        info.event_clock = Some(EventClock::Monotonic);
+        info.active_low = true;
+        info.used = true;
+        info.consumer = "mybutton".into();
+        assert_eq!(
+            info.summary(),
+            "input, pull-up, rising-edge, active-low, used by \"mybutton\""
+        );
+
+        info.edge_detection = Some(EdgeDetection::BothEdges);
+        info.event_clock = Some(EventClock::Realtime);
+        info.debounce_period = Some(Duration::from_millis(5));
+        info.consumer.clear();
+        assert_eq!(
+            info.summary(),
+            "input, pull-up, both-edges, realtime-clock, debounced 5ms, active-low, used"
+        );
+
+        let info = Info {
+            direction: Direction::Output,
+            bias: Some(Bias::Disabled),
+            drive: Some(Drive::OpenDrain),
+            debounce_period: Some(Duration::ZERO),
+            ..Default::default()
+        };
+        assert_eq!(info.summary(), "output, bias-disabled, open-drain");
+
+        let info = Info {
+            direction: Direction::Output,
+            drive: Some(Drive::PushPull),
+            ..Default::default()
+        };
+        assert_eq!(info.summary(), "output");
+    }
+
+    #[test]
+    fn changed_fields() {
+        let info = Info {
+            offset: 3,
+            name: "banana".into(),
+            name_os: "banana".into(),
+            ..Default::default()
+        };
+        assert!(info.changed_fields(&info.clone()).is_empty());
+
+        let mut other = info.clone();
+        other.used = true;
+        other.consumer = "mybutton".into();
+        other.edge_detection = Some(EdgeDetection::BothEdges);
+        other.event_clock = Some(EventClock::Monotonic);
+        let changed = info.changed_fields(&other);
+        assert_eq!(
+            changed,
+            InfoFieldSet::CONSUMER
+                | InfoFieldSet::USED
+                | InfoFieldSet::EDGE_DETECTION
+                | InfoFieldSet::EVENT_CLOCK
+        );
+        assert_eq!(other.changed_fields(&info), changed);
+        assert!(!changed.intersects(InfoFieldSet::IDENTITY));
+        assert_eq!(
+            changed.to_string(),
+            "consumer, used, edge_detection, event_clock"
+        );
+
+        let other = Info {
+            direction: Direction::Output,
+            active_low: true,
+            bias: Some(Bias::PullUp),
+            drive: Some(Drive::OpenDrain),
+            debounce_period: Some(Duration::from_millis(5)),
+            ..info.clone()
+        };
+        assert_eq!(
+            info.changed_fields(&other).names(),
+            [
+                "active_low",
+                "direction",
+                "bias",
+                "drive",
+                "debounce_period"
+            ]
+        );
+
+        // identity changes are flagged distinctly
+        let other = Info {
+            offset: 4,
+            ..info.clone()
+        };
+        assert_eq!(info.changed_fields(&other), InfoFieldSet::OFFSET);
+        let other = Info {
+            name_os: "apple".into(),
+            ..info.clone()
+        };
+        let changed = info.changed_fields(&other);
+        assert_eq!(changed, InfoFieldSet::NAME);
+        assert!(changed.intersects(InfoFieldSet::IDENTITY));
+        assert!(!changed.contains(InfoFieldSet::IDENTITY));
+    }
+
+    #[test]
+    fn info_field_set() {
+        let mut set = InfoFieldSet::empty();
+        assert!(set.is_empty());
+        assert_eq!(set.to_string(), "");
+
+        set.insert(InfoFieldSet::BIAS | InfoFieldSet::OFFSET);
+        assert!(!set.is_empty());
+        assert!(set.contains(InfoFieldSet::BIAS));
+        assert!(set.contains(InfoFieldSet::OFFSET | InfoFieldSet::BIAS));
+        assert!(!set.contains(InfoFieldSet::IDENTITY));
+        assert!(set.intersects(InfoFieldSet::IDENTITY));
+        assert_eq!(set.to_string(), "offset, bias");
+
+        set.remove(InfoFieldSet::OFFSET);
+        assert_eq!(set, InfoFieldSet::BIAS);
+        assert!(!set.intersects(InfoFieldSet::IDENTITY));
+        set |= InfoFieldSet::IDENTITY;
+        assert!(set.contains(InfoFieldSet::IDENTITY));
+        assert_eq!(set.names(), ["offset", "name", "bias"]);
+    }
+
+    #[test]
+    fn consumer_kind() {
+        let mut info = Info::default();
+        assert_eq!(info.consumer_kind(), ConsumerKind::Unused);
+
+        // consumer is irrelevant if unused
+        info.consumer = "interrupt".into();
+        assert_eq!(info.consumer_kind(), ConsumerKind::Unused);
+
+        info.used = true;
+        assert_eq!(info.consumer_kind(), ConsumerKind::Interrupt);
+        info.consumer = "sysfs".into();
+        assert_eq!(info.consumer_kind(), ConsumerKind::Sysfs);
+        info.consumer = "?".into();
+        assert_eq!(info.consumer_kind(), ConsumerKind::Unlabelled);
+        info.consumer.clear();
+        assert_eq!(info.consumer_kind(), ConsumerKind::Unlabelled);
+        info.consumer = "reset-hog".into();
+        assert_eq!(info.consumer_kind(), ConsumerKind::Labelled);
+        info.consumer = "Interrupt".into();
+        assert_eq!(info.consumer_kind(), ConsumerKind::Labelled);
+    }
+
     #[test]
     #[cfg(feature = "uapi_v1")]
     fn info_from_v1_line_info() {
@@ -165,6 +631,7 @@ mod tests {
         let info = Info::from(&v1info);
         assert_eq!(info.offset, 32);
         assert_eq!(info.name, "banana");
+        assert_eq!(info.name_os, "banana");
         assert_eq!(info.consumer, "jam");
         assert!(info.used);
         assert!(info.active_low);
@@ -197,6 +664,34 @@ mod tests {
         assert!(info.event_clock.is_none());
         assert!(info.debounce_period.is_none());
     }
+    #[test]
+    #[cfg(feature = "uapi_v1")]
+    fn info_from_v1_line_info_non_utf8_name() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let v1info = v1::LineInfo {
+            name: v1::Name::from_bytes(b"ban\xffana"),
+            ..Default::default()
+        };
+        let info = Info::from(&v1info);
+        assert_eq!(info.name, "ban\u{fffd}ana");
+        assert_eq!(info.name_os.as_bytes(), b"ban\xffana");
+    }
+
+    #[test]
+    #[cfg(any(feature = "uapi_v2", not(feature = "uapi_v1")))]
+    fn info_from_v2_line_info_non_utf8_name() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let v2info = v2::LineInfo {
+            name: v2::Name::from_bytes(b"ban\xffana"),
+            ..Default::default()
+        };
+        let info = Info::from(&v2info);
+        assert_eq!(info.name, "ban\u{fffd}ana");
+        assert_eq!(info.name_os.as_bytes(), b"ban\xffana");
+    }
+
     #[test]
     #[cfg(any(feature = "uapi_v2", not(feature = "uapi_v1")))]
     fn info_from_v2_line_info() {
@@ -229,6 +724,7 @@ mod tests {
         let info = Info::from(&v2info);
         assert_eq!(info.offset, 32);
         assert_eq!(info.name, "banana");
+        assert_eq!(info.name_os, "banana");
         assert_eq!(info.consumer, "jam");
         assert!(info.used);
         assert!(info.active_low);
@@ -3,6 +3,8 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use super::Offset;
+use crate::request::Config;
+use crate::{Error, Result};
 #[cfg(feature = "uapi_v1")]
 use gpiocdev_uapi::v1;
 #[cfg(feature = "uapi_v2")]
@@ -39,6 +41,27 @@ impl Value {
             Value::Inactive => Value::Active,
         }
     }
+
+    /// The value as an integer, 0 for inactive and 1 for active.
+    pub fn as_u8(&self) -> u8 {
+        u8::from(*self)
+    }
+
+    /// Convert an integer to a value, accepting only 0 and 1.
+    ///
+    /// Unlike the `From<u8>` conversion, which treats any non-zero value as active,
+    /// this rejects values other than 0 and 1, so malformed data is caught rather
+    /// than clamped.
+    pub fn try_from_u8(i: u8) -> Result<Value> {
+        match i {
+            0 => Ok(Value::Inactive),
+            1 => Ok(Value::Active),
+            _ => Err(Error::InvalidArgument(format!(
+                "Value {} is not a valid line value.",
+                i
+            ))),
+        }
+    }
 }
 
 impl std::fmt::Display for Value {
@@ -100,7 +123,7 @@ pub struct LineValue {
 /// A  collection of line values.
 ///
 /// Lines are identified by their offset.
-#[derive(Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Values(Vec<LineValue>);
 impl Values {
@@ -288,6 +311,16 @@ impl Values {
         self
     }
 
+    /// Set the value of all lines.
+    ///
+    /// Only lines already contained in the set are affected.
+    pub fn set_all(&mut self, value: Value) -> &mut Self {
+        for lv in self.0.iter_mut() {
+            lv.value = value;
+        }
+        self
+    }
+
     /// Toggle all values.
     pub fn not(&mut self) -> &mut Self {
         for lv in self.0.iter_mut() {
@@ -296,6 +329,34 @@ impl Values {
         self
     }
 
+    /// Convert logical values to physical levels, given the configuration of the lines.
+    ///
+    /// In the returned values, [`Value::Active`] indicates a physically high line and
+    /// [`Value::Inactive`] a physically low line.
+    ///
+    /// Values for active-low lines are inverted.
+    /// Lines not contained in `cfg` are assumed to be active-high, so are unchanged.
+    pub fn to_physical(&self, cfg: &Config) -> Values {
+        self.invert_active_low(cfg)
+    }
+
+    /// Convert physical levels to logical values, given the configuration of the lines.
+    ///
+    /// The inverse of [`to_physical`](#method.to_physical).
+    pub fn from_physical(levels: &Values, cfg: &Config) -> Values {
+        levels.invert_active_low(cfg)
+    }
+
+    fn invert_active_low(&self, cfg: &Config) -> Values {
+        let mut vv = self.clone();
+        for lv in vv.0.iter_mut() {
+            if cfg.line_config(lv.offset).map_or(false, |lc| lc.active_low) {
+                lv.value = lv.value.not();
+            }
+        }
+        vv
+    }
+
     /// Toggle the value of a line.
     ///
     /// If not already set then sets the line active.
@@ -346,6 +407,11 @@ impl Values {
         offsets.iter().collect()
     }
 
+    /// The values of all lines, in offset order.
+    pub fn values(&self) -> Vec<Value> {
+        self.0.iter().map(|lv| lv.value).collect()
+    }
+
     /// An iterator to visit all values.
     pub fn iter(&self) -> std::slice::Iter<'_, LineValue> {
         self.0.iter()
@@ -389,6 +455,29 @@ impl<'a> FromIterator<&'a Offset> for Values {
     }
 }
 
+/// Iterate over the (offset, value) pairs in offset order.
+///
+/// Only lines with values set in the collection are returned.
+///
+/// # Examples
+/// ```
+/// # use gpiocdev::line::{Value, Values};
+/// let mut values = Values::from_offsets(&[5, 3]);
+/// values.set(5, Value::Active);
+/// for (offset, value) in &values {
+///     println!("{}: {}", offset, value);
+/// }
+/// ```
+impl<'a> IntoIterator for &'a Values {
+    type Item = (Offset, Value);
+    type IntoIter =
+        std::iter::Map<std::slice::Iter<'a, LineValue>, fn(&LineValue) -> (Offset, Value)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter().map(|lv| (lv.offset, lv.value))
+    }
+}
+
 impl FromIterator<(Offset, Value)> for Values {
     fn from_iter<I: IntoIterator<Item = (Offset, Value)>>(iter: I) -> Self {
         let mut values = Values::default();
@@ -437,6 +526,25 @@ mod tests {
         assert_eq!(Value::from(0), Value::Inactive);
     }
 
+    #[test]
+    fn try_from_u8() {
+        assert_eq!(Value::try_from_u8(0), Ok(Value::Inactive));
+        assert_eq!(Value::try_from_u8(1), Ok(Value::Active));
+        assert_eq!(
+            Value::try_from_u8(2),
+            Err(Error::InvalidArgument(
+                "Value 2 is not a valid line value.".into()
+            ))
+        );
+        assert!(Value::try_from_u8(255).is_err());
+    }
+
+    #[test]
+    fn as_u8() {
+        assert_eq!(Value::Active.as_u8(), 1);
+        assert_eq!(Value::Inactive.as_u8(), 0);
+    }
+
     #[test]
     fn into_u8() {
         let u: u8 = Value::Active.into();
@@ -614,6 +722,28 @@ mod tests {
             assert_eq!(vv.get(3), Some(Value::Inactive));
         }
 
+        #[test]
+        fn set_all() {
+            let mut vv = Values::from_offsets(&[1, 2, 3]);
+            vv.set_all(Value::Active);
+            assert_eq!(vv.get(0), None);
+            assert_eq!(vv.get(1), Some(Value::Active));
+            assert_eq!(vv.get(2), Some(Value::Active));
+            assert_eq!(vv.get(3), Some(Value::Active));
+            assert_eq!(vv.get(4), None);
+            assert_eq!(vv.len(), 3);
+
+            vv.set(2, Value::Inactive).set_all(Value::Inactive);
+            assert_eq!(vv.get(1), Some(Value::Inactive));
+            assert_eq!(vv.get(2), Some(Value::Inactive));
+            assert_eq!(vv.get(3), Some(Value::Inactive));
+            assert_eq!(vv.len(), 3);
+
+            let mut vv = Values::default();
+            vv.set_all(Value::Active);
+            assert!(vv.is_empty());
+        }
+
         #[test]
         fn unset() {
             let mut vv = Values::from_offsets(&[1, 2, 3]);
@@ -668,6 +798,29 @@ mod tests {
             assert_eq!(vv.get(3), Some(Value::Active));
         }
 
+        #[test]
+        fn to_physical() {
+            let mut cfg = Config::default();
+            cfg.with_lines(&[1, 2]).as_active_low().with_line(3);
+            let vv: Values = [
+                (1, Value::Active),
+                (2, Value::Inactive),
+                (3, Value::Active),
+                (4, Value::Inactive),
+            ]
+            .into_iter()
+            .collect();
+            let pv = vv.to_physical(&cfg);
+            assert_eq!(pv.get(1), Some(Value::Inactive));
+            assert_eq!(pv.get(2), Some(Value::Active));
+            assert_eq!(pv.get(3), Some(Value::Active));
+            // not in the config, so assumed active-high
+            assert_eq!(pv.get(4), Some(Value::Inactive));
+            assert_eq!(pv.len(), 4);
+
+            assert_eq!(Values::from_physical(&pv, &cfg), vv);
+        }
+
         #[test]
         fn from_offset_iterator() {
             let vv: Values = [1, 2, 3].iter().collect();
@@ -686,6 +839,18 @@ mod tests {
             assert_eq!(vv.get(3), Some(Value::Active));
         }
 
+        #[test]
+        fn values() {
+            assert!(Values::default().values().is_empty());
+            let vv: Values = [(3, Value::Active), (1, Value::Inactive), (2, Value::Active)]
+                .into_iter()
+                .collect();
+            assert_eq!(
+                vv.values(),
+                vec![Value::Inactive, Value::Active, Value::Active]
+            );
+        }
+
         #[test]
         fn len() {
             assert_eq!(Values::default().len(), 0);
@@ -728,6 +893,25 @@ mod tests {
             assert_eq!(i.next(), None);
         }
 
+        #[test]
+        fn into_iter() {
+            let mut vv = Values::from_offsets(&[3, 1, 2]);
+            vv.set(2, Value::Active);
+            vv.unset(3);
+            let pairs: Vec<(Offset, Value)> = (&vv).into_iter().collect();
+            assert_eq!(pairs, vec![(1, Value::Inactive), (2, Value::Active)]);
+
+            let mut count = 0;
+            for (offset, value) in &vv {
+                assert_eq!(vv.get(offset), Some(value));
+                count += 1;
+            }
+            assert_eq!(count, 2);
+
+            // round trip
+            assert_eq!((&vv).into_iter().collect::<Values>(), vv);
+        }
+
         #[test]
         fn iter_mut() {
             let mut vv = Values::from_offsets(&[1, 2, 3]);
@@ -0,0 +1,315 @@
+// SPDX-FileCopyrightText: 2024 Kent Gibson <warthog618@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::chip::{self, Chip};
+use crate::{detect_abi_support, AbiSupport, Error, Result};
+#[cfg(feature = "serde")]
+use serde_derive::{Deserialize, Serialize};
+use std::ffi::CStr;
+use std::fmt;
+use std::path::PathBuf;
+
+/// Summarise the GPIO capabilities of the platform.
+///
+/// Reports the kernel version, the uAPI ABI versions supported by the platform and by
+/// each chip, and the kernel features expected to be available given the kernel version.
+///
+/// Chips that are not available to the caller are skipped.
+///
+/// # Examples
+/// ```no_run
+/// # fn example() -> Result<(), gpiocdev::Error> {
+/// let s = gpiocdev::platform::summary()?;
+/// println!("Kernel {}", s.kernel.release);
+/// for c in &s.chips {
+///     println!("{} [{}] ({} lines)", c.info.name, c.info.label, c.info.num_lines);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn summary() -> Result<PlatformSummary> {
+    let kernel = Kernel::detect()?;
+    let features = kernel.version.map(Features::for_kernel);
+    let mut chips = Vec::new();
+    for p in chip::chips()? {
+        if let Ok(c) = Chip::from_path(&p) {
+            if let Ok(info) = c.info() {
+                chips.push(ChipSummary {
+                    path: p,
+                    info,
+                    abi_support: c.abi_support(),
+                });
+            }
+        }
+    }
+    Ok(PlatformSummary {
+        kernel,
+        abi_support: detect_abi_support().ok(),
+        features,
+        chips,
+    })
+}
+
+/// A summary of the GPIO capabilities of the platform.
+///
+/// Returned by [`summary`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct PlatformSummary {
+    /// The running kernel.
+    pub kernel: Kernel,
+
+    /// The uAPI ABI versions supported by the platform.
+    ///
+    /// None if there are no GPIO chips available to probe.
+    pub abi_support: Option<AbiSupport>,
+
+    /// The kernel features expected to be available, based on the kernel version.
+    ///
+    /// None if the kernel version could not be determined.
+    pub features: Option<Features>,
+
+    /// The chips available to the caller, in name order.
+    pub chips: Vec<ChipSummary>,
+}
+
+impl PlatformSummary {
+    /// Returns true if any GPIO chips are available to the caller.
+    ///
+    /// Chips that exist but cannot be opened, such as due to insufficient permissions,
+    /// are not included.
+    pub fn has_chips(&self) -> bool {
+        !self.chips.is_empty()
+    }
+}
+
+/// The running kernel.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Kernel {
+    /// The kernel release, as reported by `uname -r`.
+    pub release: String,
+
+    /// The kernel version parsed from the release.
+    ///
+    /// None if the release is not in the expected `major.minor` form.
+    pub version: Option<KernelVersion>,
+}
+
+impl Kernel {
+    /// Identify the running kernel.
+    pub fn detect() -> Result<Kernel> {
+        // SAFETY: utsname is plain old data, so zeroed is a valid value.
+        let mut uts = unsafe { std::mem::zeroed::<libc::utsname>() };
+        // SAFETY: uts is a valid utsname for uname to populate.
+        if unsafe { libc::uname(&mut uts) } != 0 {
+            return Err(Error::from(std::io::Error::last_os_error()));
+        }
+        // SAFETY: uname null terminates the release.
+        let release = unsafe { CStr::from_ptr(uts.release.as_ptr()) }
+            .to_string_lossy()
+            .into_owned();
+        Ok(Kernel::from_release(release))
+    }
+
+    /// Construct a kernel from its release string.
+    pub fn from_release<S: Into<String>>(release: S) -> Kernel {
+        let release = release.into();
+        Kernel {
+            version: KernelVersion::parse(&release),
+            release,
+        }
+    }
+}
+
+/// The major and minor version of a kernel.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct KernelVersion {
+    /// The major version.
+    pub major: u32,
+
+    /// The minor version.
+    pub minor: u32,
+}
+
+impl KernelVersion {
+    /// Construct a version from its major and minor components.
+    pub const fn new(major: u32, minor: u32) -> KernelVersion {
+        KernelVersion { major, minor }
+    }
+
+    /// Parse the version from a kernel release string, such as "6.1.0-rpi7-rpi-v8".
+    ///
+    /// Returns None if the release does not start with `major.minor`.
+    pub fn parse(release: &str) -> Option<KernelVersion> {
+        let mut f = release.split('.');
+        let major = f.next()?.parse().ok()?;
+        let minor = f
+            .next()?
+            .split(|c: char| !c.is_ascii_digit())
+            .next()?
+            .parse()
+            .ok()?;
+        Some(KernelVersion { major, minor })
+    }
+}
+
+impl fmt::Display for KernelVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// The kernel features expected to be available, based on the kernel version.
+///
+/// Features may also have been backported to older kernels, so these are only indicative.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct Features {
+    /// Bias and reconfigure are supported.
+    ///
+    /// Added in Linux 5.5.
+    pub bias: bool,
+
+    /// The realtime event clock is supported.
+    ///
+    /// Added in Linux 5.11.
+    pub realtime_clock: bool,
+
+    /// The HTE event clock is supported.
+    ///
+    /// Added in Linux 5.19.
+    pub hte: bool,
+}
+
+impl Features {
+    /// The kernel version that added bias and reconfigure.
+    pub const BIAS_VERSION: KernelVersion = KernelVersion::new(5, 5);
+
+    /// The kernel version that added the realtime event clock.
+    pub const REALTIME_CLOCK_VERSION: KernelVersion = KernelVersion::new(5, 11);
+
+    /// The kernel version that added the HTE event clock.
+    pub const HTE_VERSION: KernelVersion = KernelVersion::new(5, 19);
+
+    /// The features expected to be available in the given kernel version.
+    pub fn for_kernel(v: KernelVersion) -> Features {
+        Features {
+            bias: v >= Self::BIAS_VERSION,
+            realtime_clock: v >= Self::REALTIME_CLOCK_VERSION,
+            hte: v >= Self::HTE_VERSION,
+        }
+    }
+}
+
+/// A summary of a chip within the [`PlatformSummary`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct ChipSummary {
+    /// The path to the chip.
+    pub path: PathBuf,
+
+    /// The name, label and number of lines of the chip.
+    pub info: chip::Info,
+
+    /// The uAPI ABI versions supported by the chip.
+    pub abi_support: AbiSupport,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kernel_version_parse() {
+        assert_eq!(
+            KernelVersion::parse("6.1.0-rpi7-rpi-v8"),
+            Some(KernelVersion::new(6, 1))
+        );
+        assert_eq!(
+            KernelVersion::parse("5.10"),
+            Some(KernelVersion::new(5, 10))
+        );
+        assert_eq!(
+            KernelVersion::parse("6.9-rc3"),
+            Some(KernelVersion::new(6, 9))
+        );
+        assert_eq!(
+            KernelVersion::parse("4.19.0+"),
+            Some(KernelVersion::new(4, 19))
+        );
+        assert_eq!(KernelVersion::parse("6"), None);
+        assert_eq!(KernelVersion::parse("unknown"), None);
+        assert_eq!(KernelVersion::parse(""), None);
+    }
+
+    #[test]
+    fn kernel_version_order() {
+        assert!(KernelVersion::new(5, 10) < KernelVersion::new(5, 11));
+        assert!(KernelVersion::new(5, 19) < KernelVersion::new(6, 0));
+        assert_eq!(KernelVersion::new(5, 10).to_string(), "5.10");
+    }
+
+    #[test]
+    fn kernel_from_release() {
+        let k = Kernel::from_release("6.6.31+rpt-rpi-v8");
+        assert_eq!(k.release, "6.6.31+rpt-rpi-v8");
+        assert_eq!(k.version, Some(KernelVersion::new(6, 6)));
+
+        let k = Kernel::from_release("garbage");
+        assert_eq!(k.version, None);
+    }
+
+    #[test]
+    fn has_chips() {
+        let mut s = PlatformSummary::default();
+        assert!(!s.has_chips());
+        s.chips.push(ChipSummary::default());
+        assert!(s.has_chips());
+    }
+
+    #[test]
+    fn kernel_detect() {
+        let k = Kernel::detect().unwrap();
+        assert!(!k.release.is_empty());
+    }
+
+    #[test]
+    fn features_for_kernel() {
+        let none = Features::default();
+        assert_eq!(Features::for_kernel(KernelVersion::new(4, 19)), none);
+        assert_eq!(Features::for_kernel(KernelVersion::new(5, 4)), none);
+        let bias = Features {
+            bias: true,
+            ..Default::default()
+        };
+        assert_eq!(Features::for_kernel(KernelVersion::new(5, 5)), bias);
+        assert_eq!(Features::for_kernel(KernelVersion::new(5, 10)), bias);
+        let realtime = Features {
+            realtime_clock: true,
+            ..bias
+        };
+        assert_eq!(Features::for_kernel(KernelVersion::new(5, 11)), realtime);
+        assert_eq!(Features::for_kernel(KernelVersion::new(5, 18)), realtime);
+        let all = Features {
+            hte: true,
+            ..realtime
+        };
+        assert_eq!(Features::for_kernel(KernelVersion::new(5, 19)), all);
+        assert_eq!(Features::for_kernel(KernelVersion::new(6, 1)), all);
+    }
+}
@@ -6,26 +6,38 @@ mod builder;
 pub use self::builder::Builder;
 
 mod config;
-pub use self::config::Config;
+pub use self::config::{Config, LineConfigDiff};
 
 mod edge_event_buffer;
-pub use self::edge_event_buffer::EdgeEventBuffer;
+pub use self::edge_event_buffer::{
+    CoalescedEdgeEvent, CoalescedEdgeEvents, DebouncedEdgeEvents, EdgeEventBuffer, EdgeEventDeltas,
+    EdgeEventIter, EdgeEventTimer, EdgeEventTryIter, LevelChanges,
+};
+#[cfg(any(feature = "async_io", feature = "async_tokio"))]
+pub(crate) use self::edge_event_buffer::{Coalescer, Debouncer, Levels};
 
-use crate::line::{self, EdgeEvent, Offset, Value, Values};
-#[cfg(feature = "uapi_v1")]
-use crate::AbiVersion;
-use crate::{Error, Result, UapiCall};
+mod seqno_tracker;
+use self::seqno_tracker::SeqnoTracker;
+
+mod value_cache;
+use self::value_cache::ValueCache;
+
+use crate::chip::Chip;
+use crate::line::{self, decode_error, EdgeEvent, EdgeKind, Offset, Value, Values};
+use crate::{AbiVersion, Error, Result, UapiCall};
 #[cfg(not(feature = "uapi_v2"))]
 use gpiocdev_uapi::v1 as uapi;
 #[cfg(feature = "uapi_v1")]
 use gpiocdev_uapi::v1;
 #[cfg(feature = "uapi_v2")]
 use gpiocdev_uapi::{v2, v2 as uapi};
+use std::any::Any;
+use std::collections::HashMap;
 use std::fs::File;
 use std::mem;
 use std::os::unix::prelude::{AsFd, AsRawFd, BorrowedFd};
 use std::sync::{Arc, RwLock};
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 /// An active request of a set of lines.
 ///
@@ -62,6 +74,15 @@ use std::time::Duration;
 /// from the kernel.  The [`edge_event_size`] method provides the size required to store a
 /// single event to allow sizing of custom slices.
 ///
+/// # Polling
+///
+/// The request implements [`AsFd`] and [`AsRawFd`], so the request can be added to an
+/// external poller, such as `epoll` or `mio`, alongside other file descriptors.
+/// The file descriptor is readable when edge events are available to be read.
+///
+/// For requests without edge detection enabled the file descriptor is still valid, but
+/// it never becomes readable.
+///
 /// # Reading Output Values
 ///
 /// Note that reading back output values using [`value`] or [`values`] is dependent on driver
@@ -90,6 +111,18 @@ pub struct Request {
     /// The size of the user buffer created for the `edge_events` iterator.
     user_event_buffer_size: usize,
 
+    /// The consumer label applied to the requested lines.
+    consumer: String,
+
+    /// The values most recently read by `cached_values`.
+    value_cache: ValueCache,
+
+    /// The seqnos of decoded edge events, shared with clones, to detect missed events.
+    seqnos: Arc<SeqnoTracker>,
+
+    /// Application data associated with the requested lines.
+    line_data: HashMap<Offset, Box<dyn Any + Send + Sync>>,
+
     /// The ABI version used to create the request, and so determines how to decode events.
     #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
     abiv: AbiVersion,
@@ -136,6 +169,55 @@ impl Request {
         Builder::from_config(config)
     }
 
+    /// The largest kernel event buffer size supported by the kernel.
+    ///
+    /// Larger sizes passed to [`with_kernel_event_buffer_size`] are reduced to this size
+    /// by the kernel.
+    ///
+    /// [`with_kernel_event_buffer_size`]: Builder::with_kernel_event_buffer_size
+    pub const KERNEL_EVENT_BUFFER_SIZE_MAX: u32 = 16 * gpiocdev_uapi::NUM_LINES_MAX as u32;
+
+    /// Recommend a kernel event buffer size for a given event rate and polling interval.
+    ///
+    /// * `expected_rate_hz` - The peak rate of edge events expected across all the requested lines.
+    /// * `poll_interval` - The maximum time between the events being drained from the request.
+    ///
+    /// The size is twice the number of events expected in a poll interval, to allow
+    /// for jitter in servicing the request, and is intended for use with
+    /// [`with_kernel_event_buffer_size`].
+    ///
+    /// The size is never less than 16, the kernel default for a single line, nor more
+    /// than [`KERNEL_EVENT_BUFFER_SIZE_MAX`].
+    /// If the recommended size is the maximum then events may be lost if the rate
+    /// is sustained, and the poll interval should be reduced.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # fn example() -> Result<(), gpiocdev::Error> {
+    /// # use gpiocdev::Request;
+    /// # use std::time::Duration;
+    /// let req = Request::builder()
+    ///     .on_chip("/dev/gpiochip0")
+    ///     .with_line(5)
+    ///     .with_edge_detection(gpiocdev::line::EdgeDetection::BothEdges)
+    ///     .with_kernel_event_buffer_size(Request::recommend_event_buffer_size(
+    ///         2000,
+    ///         Duration::from_millis(50),
+    ///     ))
+    ///     .request()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`with_kernel_event_buffer_size`]: Builder::with_kernel_event_buffer_size
+    /// [`KERNEL_EVENT_BUFFER_SIZE_MAX`]: #associatedconstant.KERNEL_EVENT_BUFFER_SIZE_MAX
+    pub fn recommend_event_buffer_size(expected_rate_hz: u32, poll_interval: Duration) -> u32 {
+        // u128 is more than sufficient for any u32 rate and Duration, so no overflow.
+        let nanos = u128::from(expected_rate_hz) * poll_interval.as_nanos();
+        let events = (nanos + 999_999_999) / 1_000_000_000;
+        (events * 2).clamp(16, u128::from(Self::KERNEL_EVENT_BUFFER_SIZE_MAX)) as u32
+    }
+
     /// Get the values for a subset of the requested lines.
     ///
     /// The keys indicate the lines to get.
@@ -179,6 +261,8 @@ impl Request {
     }
     #[cfg(feature = "uapi_v1")]
     fn do_values_v1(&self, values: &mut Values) -> Result<()> {
+        #[cfg(feature = "test_abi_override")]
+        crate::test::check_uapi_call(AbiVersion::V1, UapiCall::GetLineValues)?;
         let mut vals = v1::LineValues::default();
         v1::get_line_values(&self.f, &mut vals)
             .map(|_| values.update_from_v1(&self.offsets, &vals))
@@ -186,12 +270,61 @@ impl Request {
     }
     #[cfg(feature = "uapi_v2")]
     fn do_values_v2(&self, values: &mut Values) -> Result<()> {
+        #[cfg(feature = "test_abi_override")]
+        crate::test::check_uapi_call(AbiVersion::V2, UapiCall::GetLineValues)?;
         let mut vals = values.to_v2(&self.offsets);
         v2::get_line_values(&self.f, &mut vals)
             .map(|_| values.update_from_v2(&self.offsets, &vals))
             .map_err(|e| Error::Uapi(UapiCall::GetLineValues, e))
     }
 
+    /// Get the values for all lines in the request, using cached values where possible.
+    ///
+    /// If the values were read by a previous call within the `ttl` then those values
+    /// are returned, otherwise the values are read from the kernel and cached.
+    ///
+    /// Intended to reduce the load on slow buses, such as I2C, when values are polled
+    /// more frequently than they are expected to change.
+    ///
+    /// The cache is invalidated by any change to the request applied through this `Request`,
+    /// i.e. setting values or reconfiguring, and by reading edge events.
+    /// The cache cannot detect changes to input lines without edge detection, so those
+    /// may be up to `ttl` stale.
+    ///
+    /// The cache is only used by this method - [`values`] always reads from the kernel.
+    ///
+    /// * `ttl` - The maximum age of cached values that may be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn example() -> Result<(), gpiocdev::Error> {
+    /// # use std::time::Duration;
+    /// let req = gpiocdev::Request::builder()
+    ///     .on_chip("/dev/gpiochip0")
+    ///     .with_lines(&[3,5,6,8])
+    ///     .request()?;
+    /// // only reads from the kernel if the cached values are more than 100ms old
+    /// let values = req.cached_values(Duration::from_millis(100))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`values`]: #method.values
+    pub fn cached_values(&self, ttl: Duration) -> Result<Values> {
+        self.value_cache
+            .get_or_read(ttl, |values| self.do_values(values))
+    }
+
+    /// Discard any values cached by [`cached_values`].
+    ///
+    /// The next call to [`cached_values`] will read the values from the kernel.
+    ///
+    /// [`cached_values`]: #method.cached_values
+    pub fn invalidate_value_cache(&self) {
+        self.value_cache.invalidate();
+    }
+
     /// Get the value for one line in the request.
     ///
     /// # Examples
@@ -231,6 +364,8 @@ impl Request {
     }
     #[cfg(feature = "uapi_v1")]
     fn do_value_v1(&self, idx: usize) -> Result<Value> {
+        #[cfg(feature = "test_abi_override")]
+        crate::test::check_uapi_call(AbiVersion::V1, UapiCall::GetLineValues)?;
         let mut vals = v1::LineValues::default();
         v1::get_line_values(&self.f, &mut vals)
             .map_err(|e| Error::Uapi(UapiCall::GetLineValues, e))?;
@@ -238,6 +373,8 @@ impl Request {
     }
     #[cfg(feature = "uapi_v2")]
     fn do_value_v2(&self, idx: usize) -> Result<Value> {
+        #[cfg(feature = "test_abi_override")]
+        crate::test::check_uapi_call(AbiVersion::V2, UapiCall::GetLineValues)?;
         let mut vals = v2::LineValues {
             mask: 0x01 << idx,
             ..Default::default()
@@ -290,7 +427,9 @@ impl Request {
     /// # Ok(())
     /// # }
     pub fn set_values(&self, values: &Values) -> Result<()> {
-        self.do_set_values(values)
+        let res = self.do_set_values(values);
+        self.value_cache.invalidate();
+        res
     }
     #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
     fn do_set_values(&self, values: &Values) -> Result<()> {
@@ -309,6 +448,8 @@ impl Request {
     }
     #[cfg(feature = "uapi_v1")]
     fn do_set_values_v1(&self, values: &Values) -> Result<()> {
+        #[cfg(feature = "test_abi_override")]
+        crate::test::check_uapi_call(AbiVersion::V1, UapiCall::SetLineValues)?;
         if !values.contains_keys(&self.offsets) {
             return Err(Error::AbiLimitation(
                 AbiVersion::V1,
@@ -320,6 +461,8 @@ impl Request {
     }
     #[cfg(feature = "uapi_v2")]
     fn do_set_values_v2(&self, values: &Values) -> Result<()> {
+        #[cfg(feature = "test_abi_override")]
+        crate::test::check_uapi_call(AbiVersion::V2, UapiCall::SetLineValues)?;
         let lv = &values.to_v2(&self.offsets);
         if lv.mask == 0 {
             return Err(Error::InvalidArgument(
@@ -329,6 +472,35 @@ impl Request {
         v2::set_line_values(&self.f, lv).map_err(|e| Error::Uapi(UapiCall::SetLineValues, e))
     }
 
+    /// Set the values for a subset of the requested lines, provided as offset and value pairs.
+    ///
+    /// A convenience wrapper around [`set_values`] that removes the need to construct
+    /// the [`Values`] for simple cases.
+    ///
+    /// If an offset is repeated then the last value for that offset is used.
+    ///
+    /// Offsets that are not in the request are ignored, though, as per [`set_values`],
+    /// uAPI v1 requires values for all the requested lines.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # fn example() -> Result<(), gpiocdev::Error> {
+    /// # use gpiocdev::line::Value::{Active, Inactive};
+    /// let req = gpiocdev::Request::builder()
+    ///     .on_chip("/dev/gpiochip0")
+    ///     .with_lines(&[3,4,5])
+    ///     .as_output(Active)
+    ///     .request()?;
+    /// req.set_values_from(&[(4, Inactive), (5, Inactive)])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`set_values`]: #method.set_values
+    pub fn set_values_from(&self, pairs: &[(Offset, Value)]) -> Result<()> {
+        self.set_values(&pairs.iter().copied().collect())
+    }
+
     /// Set the value for one line in the request.
     ///
     /// # Examples
@@ -349,7 +521,9 @@ impl Request {
             .iter()
             .position(|v| v == &offset)
             .ok_or_else(|| Error::InvalidArgument("offset is not a requested line.".into()))?;
-        self.do_set_value(idx, value)
+        let res = self.do_set_value(idx, value);
+        self.value_cache.invalidate();
+        res
     }
     #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
     fn do_set_value(&self, idx: usize, value: Value) -> Result<()> {
@@ -368,6 +542,8 @@ impl Request {
     }
     #[cfg(feature = "uapi_v1")]
     fn do_set_value_v1(&self, idx: usize, value: Value) -> Result<()> {
+        #[cfg(feature = "test_abi_override")]
+        crate::test::check_uapi_call(AbiVersion::V1, UapiCall::SetLineValues)?;
         if self.offsets.len() > 1 {
             return Err(Error::AbiLimitation(
                 AbiVersion::V1,
@@ -380,6 +556,8 @@ impl Request {
     }
     #[cfg(feature = "uapi_v2")]
     fn do_set_value_v2(&self, idx: usize, value: Value) -> Result<()> {
+        #[cfg(feature = "test_abi_override")]
+        crate::test::check_uapi_call(AbiVersion::V2, UapiCall::SetLineValues)?;
         let mut vals = v2::LineValues::default();
         let mask = 0x01 << idx;
         if value == Value::Active {
@@ -414,7 +592,53 @@ impl Request {
         if self.offsets.len() != 1 {
             return Err(Error::InvalidArgument("request contains multiple lines.".into()))?;
         }
-        self.do_set_value(0, value)
+        let res = self.do_set_value(0, value);
+        self.value_cache.invalidate();
+        res
+    }
+
+    /// Pulse an output line a number of times.
+    ///
+    /// Each pulse sets the line active for the `high` period, then inactive for the
+    /// `low` period, so the line is left inactive when this returns.
+    /// The values are logical, so active-low lines are pulsed physically low.
+    ///
+    /// Blocks until all the pulses are complete.
+    /// The periods are timed by sleeping, so are subject to the scheduling latency of
+    /// the system, and are a minimum rather than exact.
+    ///
+    /// Returns an error if the line is not requested as an output.
+    /// A `count` of zero is a no-op.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # fn example() -> Result<(), gpiocdev::Error> {
+    /// # use gpiocdev::line::Value;
+    /// # use std::time::Duration;
+    /// let req = gpiocdev::Request::builder()
+    ///     .on_chip("/dev/gpiochip0")
+    ///     .with_line(5)
+    ///     .as_output(Value::Inactive)
+    ///     .request()?;
+    /// // three 10ms pulses, 20ms apart
+    /// req.pulse(5, 3, Duration::from_millis(10), Duration::from_millis(10))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pulse(&self, offset: Offset, count: u32, high: Duration, low: Duration) -> Result<()> {
+        let lcfg = self
+            .line_config(offset)
+            .ok_or_else(|| Error::InvalidArgument("offset is not a requested line.".into()))?;
+        if lcfg.direction != Some(line::Direction::Output) {
+            return Err(Error::InvalidArgument("line is not an output.".into()));
+        }
+        for _ in 0..count {
+            self.set_value(offset, Value::Active)?;
+            std::thread::sleep(high);
+            self.set_value(offset, Value::Inactive)?;
+            std::thread::sleep(low);
+        }
+        Ok(())
     }
 
     /// Return the path of the chip for this request.
@@ -426,6 +650,35 @@ impl Request {
             .clone()
     }
 
+    /// Return the uAPI ABI version used by the request.
+    ///
+    /// This is the version used to request the lines, and so determines the operations
+    /// available, such as reconfiguration and the selection of the event clock.
+    #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+    pub fn abi_version(&self) -> AbiVersion {
+        self.abiv
+    }
+    /// Return the uAPI ABI version used by the request.
+    ///
+    /// This build only supports one ABI version, so that version is returned.
+    #[cfg(not(all(feature = "uapi_v1", feature = "uapi_v2")))]
+    pub fn abi_version(&self) -> AbiVersion {
+        #[cfg(not(feature = "uapi_v2"))]
+        return AbiVersion::V1;
+        #[cfg(feature = "uapi_v2")]
+        return AbiVersion::V2;
+    }
+
+    /// Return the consumer label applied to the requested lines.
+    ///
+    /// If no consumer was provided to the [`Builder`] this is the default
+    /// consumer, of the form `gpiocdev-pNNN`, that was sent to the kernel.
+    ///
+    /// [`Builder`]: struct.Builder.html
+    pub fn consumer(&self) -> &str {
+        &self.consumer
+    }
+
     /// Get a snapshot of the requested configuration.
     ///
     /// This is the configuration currently applied to the hardware.
@@ -447,6 +700,208 @@ impl Request {
             .cloned()
     }
 
+    /// Get the direction of a particular line.
+    ///
+    /// Returns None if the line is not in the request, or if the direction was not set
+    /// when the line was requested, i.e. the line was requested as-is.
+    ///
+    /// The direction is taken from the configuration snapshot, as per [`line_config`],
+    /// not read from the kernel, so may be stale if the line has been reconfigured by
+    /// other means. Use [`refresh_config`] to update the snapshot, or
+    /// [`Chip::line_info`] to read the direction from the kernel.
+    ///
+    /// [`line_config`]: #method.line_config
+    /// [`refresh_config`]: #method.refresh_config
+    pub fn direction(&self, offset: Offset) -> Option<line::Direction> {
+        self.cfg
+            .read()
+            .expect("failed to acquire read lock on config")
+            .line_config(offset)
+            .and_then(|lc| lc.direction)
+    }
+
+    /// Returns true if the line is in the request and is an output.
+    ///
+    /// As per [`direction`], this reflects the configuration snapshot.
+    ///
+    /// [`direction`]: #method.direction
+    pub fn is_output(&self, offset: Offset) -> bool {
+        self.direction(offset) == Some(line::Direction::Output)
+    }
+
+    /// Returns true if the line is in the request and is an input.
+    ///
+    /// As per [`direction`], this reflects the configuration snapshot.
+    ///
+    /// [`direction`]: #method.direction
+    pub fn is_input(&self, offset: Offset) -> bool {
+        self.direction(offset) == Some(line::Direction::Input)
+    }
+
+    /// Associate application data with a requested line.
+    ///
+    /// Allows application metadata, such as a label or role, to be kept with the
+    /// request rather than in a separate table keyed by offset.
+    ///
+    /// Replaces any data previously set for the line.
+    ///
+    /// Returns an error if the offset is not a requested line.
+    ///
+    /// Line data is local to this handle - it is not shared with handles created by
+    /// [`try_clone`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # fn example() -> Result<(), gpiocdev::Error> {
+    /// let mut req = gpiocdev::Request::builder()
+    ///     .on_chip("/dev/gpiochip0")
+    ///     .with_lines(&[3,5])
+    ///     .as_input()
+    ///     .request()?;
+    /// req.set_line_data(3, "door sensor")?;
+    /// req.set_line_data(5, "window sensor")?;
+    /// for event in req.edge_events() {
+    ///     let event = event?;
+    ///     let role = req.line_data::<&str>(event.offset).unwrap();
+    ///     println!("{}: {:?}", role, event.kind);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`try_clone`]: #method.try_clone
+    pub fn set_line_data<T: Any + Send + Sync>(&mut self, offset: Offset, data: T) -> Result<()> {
+        if !self.offsets.contains(&offset) {
+            return Err(Error::InvalidArgument(
+                "offset is not a requested line.".into(),
+            ));
+        }
+        self.line_data.insert(offset, Box::new(data));
+        Ok(())
+    }
+
+    /// Get the application data associated with a requested line.
+    ///
+    /// Returns None if no data has been set for the line, or if the data is not of type `T`.
+    pub fn line_data<T: Any>(&self, offset: Offset) -> Option<&T> {
+        self.line_data.get(&offset)?.downcast_ref()
+    }
+
+    /// Get a mutable reference to the application data associated with a requested line.
+    ///
+    /// Returns None if no data has been set for the line, or if the data is not of type `T`.
+    pub fn line_data_mut<T: Any>(&mut self, offset: Offset) -> Option<&mut T> {
+        self.line_data.get_mut(&offset)?.downcast_mut()
+    }
+
+    /// Create a new handle to the request by duplicating the request file descriptor.
+    ///
+    /// Both handles refer to the same underlying kernel request, so one task may
+    /// read edge events while another sets values, without wrapping the request
+    /// in a mutex.
+    ///
+    /// The configuration is shared, so reconfiguring via either handle is
+    /// reflected in both.
+    ///
+    /// The lines are not released until all handles are dropped.
+    ///
+    /// Edge events are drained from the one kernel buffer, so concurrent readers
+    /// will each receive a subset of the events, interleaved arbitrarily.
+    ///
+    /// The new handle has its own value cache, so values cached by
+    /// [`cached_values`] on one handle are not invalidated by setting values
+    /// via the other.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn example() -> Result<(), gpiocdev::Error> {
+    /// # use gpiocdev::line::{EdgeDetection, Value};
+    /// let req = gpiocdev::Request::builder()
+    ///     .on_chip("/dev/gpiochip0")
+    ///     .with_line(3)
+    ///     .with_edge_detection(EdgeDetection::BothEdges)
+    ///     .with_line(5)
+    ///     .as_output(Value::Inactive)
+    ///     .request()?;
+    /// let reader = req.try_clone()?;
+    /// std::thread::spawn(move || {
+    ///     for event in reader.edge_events() {
+    ///         println!("{:?}", event);
+    ///     }
+    /// });
+    /// req.set_value(5, Value::Active)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`cached_values`]: #method.cached_values
+    pub fn try_clone(&self) -> Result<Request> {
+        Ok(Request {
+            f: self.f.try_clone()?,
+            offsets: self.offsets.clone(),
+            cfg: self.cfg.clone(),
+            user_event_buffer_size: self.user_event_buffer_size,
+            consumer: self.consumer.clone(),
+            value_cache: ValueCache::default(),
+            seqnos: self.seqnos.clone(),
+            line_data: HashMap::new(),
+            #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+            abiv: self.abiv,
+        })
+    }
+
+    /// Update the configuration snapshot to match the configuration applied by the kernel.
+    ///
+    /// The snapshot returned by [`config`] is only updated by [`reconfigure`] on this
+    /// request, or its clones, so it becomes stale if the lines are reconfigured by other
+    /// means, such as by another process holding the request file descriptor.
+    /// This reads the info for each requested line and updates the snapshot to match.
+    ///
+    /// The line info does not include all the configuration, so:
+    ///  - output values are retained from the snapshot, or cleared for lines that are
+    ///    no longer outputs.
+    ///  - the debounce period is the period reported by the kernel, which may differ from
+    ///    the period requested due to hardware limitations.
+    ///  - for uAPI v1, which does not report edge detection, the edge detection, event
+    ///    clock and debounce settings are retained from the snapshot.
+    ///
+    /// [`config`]: #method.config
+    /// [`reconfigure`]: #method.reconfigure
+    pub fn refresh_config(&self) -> Result<()> {
+        let chip = self.open_chip()?;
+        let mut cfg = self.config();
+        for offset in &self.offsets {
+            let info = chip.line_info(*offset)?;
+            let lcfg = cfg.lcfg.entry(*offset).or_default();
+            *lcfg = reconcile_line_config(lcfg, &info, self.reports_edge_detection());
+        }
+        self.cfg
+            .write()
+            .expect("failed to acquire write lock on config")
+            .update(cfg);
+        self.value_cache.invalidate();
+        Ok(())
+    }
+    #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+    fn open_chip(&self) -> Result<Chip> {
+        let mut chip = Chip::from_path(self.chip_path())?;
+        chip.using_abi_version(self.abiv);
+        Ok(chip)
+    }
+    #[cfg(not(all(feature = "uapi_v1", feature = "uapi_v2")))]
+    fn open_chip(&self) -> Result<Chip> {
+        Chip::from_path(self.chip_path())
+    }
+    #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+    fn reports_edge_detection(&self) -> bool {
+        self.abiv == AbiVersion::V2
+    }
+    #[cfg(not(all(feature = "uapi_v1", feature = "uapi_v2")))]
+    fn reports_edge_detection(&self) -> bool {
+        cfg!(feature = "uapi_v2")
+    }
+
     /// Reconfigure the request with the an updated configuration.
     ///
     /// Note that lines cannot be added or removed from the request.
@@ -458,7 +913,11 @@ impl Request {
             .read()
             .expect("failed to acquire read lock on config")
             .overlay(new_cfg);
-        self.do_reconfigure(&cfg)?;
+        #[cfg(feature = "test_abi_override")]
+        crate::test::check_uapi_call(self.abi_version(), UapiCall::SetLineConfig)?;
+        let res = self.do_reconfigure(&cfg);
+        self.value_cache.invalidate();
+        res?;
         // only update request config if reconfigure succeeds.
         self.cfg
             .write()
@@ -532,6 +991,8 @@ impl Request {
     ///
     /// The iterator can be backed by a user space buffer using the
     /// [`Builder.with_user_event_buffer_size`] option in the builder.
+    /// Alternatively, [`new_edge_event_buffer`] creates an iterator backed by a buffer of
+    /// a given capacity, so up to that many events are read from the kernel at once.
     ///
     /// # Examples
     /// ```no_run
@@ -553,10 +1014,165 @@ impl Request {
     /// ```
     ///
     /// [`Builder.with_user_event_buffer_size`]: struct.Builder.html#method.with_user_event_buffer_size
-    pub fn edge_events(&self) -> EdgeEventBuffer {
+    /// [`new_edge_event_buffer`]: #method.new_edge_event_buffer
+    pub fn edge_events(&self) -> EdgeEventBuffer<'_> {
         self.new_edge_event_buffer(self.user_event_buffer_size)
     }
 
+    /// An iterator for events from the request, paired with the time since the previous
+    /// event on the same line.
+    ///
+    /// The time is calculated from the event timestamps, so is subject to the event clock
+    /// of the line.
+    /// It is None for the first event on each line, and if the timestamp goes backwards,
+    /// as can occur with the realtime clock.
+    ///
+    /// Use an [`EdgeEventTimer`] directly to determine the deltas for events from other
+    /// sources, such as the async streams.
+    ///
+    /// Otherwise behaves as per [`edge_events`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # fn example() -> Result<(), gpiocdev::Error> {
+    /// # use gpiocdev::Request;
+    /// # use gpiocdev::line::EdgeDetection;
+    /// let req = Request::builder()
+    ///     .on_chip("/dev/gpiochip0")
+    ///     .with_line(23)
+    ///     .with_edge_detection(EdgeDetection::RisingEdge)
+    ///     .request()?;
+    ///
+    /// for res in req.edge_events_with_delta() {
+    ///     if let (event, Some(period)) = res? {
+    ///         println!("line {} period {:?}", event.offset, period);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`edge_events`]: #method.edge_events
+    pub fn edge_events_with_delta(&self) -> EdgeEventDeltas<'_> {
+        EdgeEventDeltas::new(self.edge_events())
+    }
+
+    /// An iterator for events from the request, debounced in user space.
+    ///
+    /// After an edge on a line, further edges on that line are suppressed until the line
+    /// has been stable for the `period`. A single event is then returned reflecting the
+    /// state the line settled into - the kind of the most recent edge on the line, with
+    /// the timestamp and seqnos of the first edge, when the line started to change.
+    /// If the line settles back into the state it was in before the first edge then no
+    /// event is returned.
+    /// Lines that keep bouncing are held until they settle, however long that takes.
+    ///
+    /// This provides debouncing where it is not supported by the kernel, such as with
+    /// uAPI v1. Where supported, debouncing in the kernel using
+    /// [`Builder.with_debounce_period`] is preferable.
+    ///
+    /// The events are returned in the order the lines settled, so are delayed by at
+    /// least the `period`.
+    ///
+    /// Otherwise behaves as per [`edge_events`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # fn example() -> Result<(), gpiocdev::Error> {
+    /// # use gpiocdev::Request;
+    /// # use gpiocdev::line::EdgeDetection;
+    /// # use std::time::Duration;
+    /// let req = Request::builder()
+    ///     .on_chip("/dev/gpiochip0")
+    ///     .with_line(23)
+    ///     .with_edge_detection(EdgeDetection::BothEdges)
+    ///     .request()?;
+    ///
+    /// for event in req.debounced_edge_events(Duration::from_millis(10)) {
+    ///     println!("{:?}", event?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`edge_events`]: #method.edge_events
+    /// [`Builder.with_debounce_period`]: struct.Builder.html#method.with_debounce_period
+    pub fn debounced_edge_events(&self, period: Duration) -> DebouncedEdgeEvents<'_> {
+        DebouncedEdgeEvents::new(self.edge_events(), period)
+    }
+
+    /// An iterator over edge events that are rate limited in user space.
+    ///
+    /// At most one event is delivered per line per `max_rate`, along with the number
+    /// of events on the line discarded since the previous delivered event.
+    ///
+    /// The first event on a line is delivered immediately.
+    /// Subsequent events on the line within the `max_rate` are coalesced, and the most
+    /// recent is delivered when the `max_rate` expires, so the final state of the line
+    /// is always delivered once the line is quiescent.
+    ///
+    /// Use [`DebouncedEdgeEvents::coalesced`] to rate limit debounced events.
+    ///
+    /// Otherwise behaves as per [`edge_events`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # fn example() -> Result<(), gpiocdev::Error> {
+    /// # use gpiocdev::Request;
+    /// # use gpiocdev::line::EdgeDetection;
+    /// # use std::time::Duration;
+    /// let req = Request::builder()
+    ///     .on_chip("/dev/gpiochip0")
+    ///     .with_line(23)
+    ///     .with_edge_detection(EdgeDetection::BothEdges)
+    ///     .request()?;
+    ///
+    /// for ce in req.coalesced_edge_events(Duration::from_millis(100)) {
+    ///     let ce = ce?;
+    ///     println!("{:?} ({} suppressed)", ce.event, ce.suppressed);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`edge_events`]: #method.edge_events
+    pub fn coalesced_edge_events(&self, max_rate: Duration) -> CoalescedEdgeEvents<'_> {
+        CoalescedEdgeEvents::new(self.edge_events(), max_rate)
+    }
+
+    /// An iterator over the levels of the lines detecting edges.
+    ///
+    /// First returns the current value of each line with edge detection enabled,
+    /// then the value of a line after each subsequent edge, as `(offset, value)` pairs.
+    ///
+    /// Any edge events waiting to be read when the iterator is first advanced
+    /// precede the initial values, so are discarded.
+    /// Edges that occur while the initial values are being read are filtered out,
+    /// for lines detecting both edges, so only actual changes in level are returned.
+    /// For lines detecting only one kind of edge, every edge is returned.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # fn example() -> Result<(), gpiocdev::Error> {
+    /// # use gpiocdev::Request;
+    /// # use gpiocdev::line::EdgeDetection;
+    /// let req = Request::builder()
+    ///     .on_chip("/dev/gpiochip0")
+    ///     .with_lines(&[23, 24])
+    ///     .with_edge_detection(EdgeDetection::BothEdges)
+    ///     .request()?;
+    ///
+    /// for level in req.level_changes() {
+    ///     let (offset, value) = level?;
+    ///     println!("line {} is {:?}", offset, value);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn level_changes(&self) -> LevelChanges<'_> {
+        LevelChanges::new(self.edge_events())
+    }
+
     /// Returns true when the request has edge events available to read using [`read_edge_event`].
     ///
     /// [`read_edge_event`]: #method.read_edge_event
@@ -586,6 +1202,38 @@ impl Request {
     pub fn read_edge_event(&self) -> Result<EdgeEvent> {
         self.do_read_edge_event()
     }
+
+    /// Read a single edge event from the request into an existing event.
+    ///
+    /// Will block until an edge event is available.
+    ///
+    /// An in-place variant of [`read_edge_event`], which overwrites `event` rather than
+    /// returning a new event.
+    ///
+    /// `event` is unchanged if an error is returned.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # fn example() -> Result<(), gpiocdev::Error> {
+    /// # use gpiocdev::line::EdgeDetection;
+    /// let req = gpiocdev::Request::builder()
+    ///     .on_chip("/dev/gpiochip0")
+    ///     .with_line(23)
+    ///     .with_edge_detection(EdgeDetection::BothEdges)
+    ///     .request()?;
+    /// let mut evt = req.read_edge_event()?;
+    /// loop {
+    ///     req.read_edge_event_into(&mut evt)?;
+    ///     println!("{:?}", evt);
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// [`read_edge_event`]: #method.read_edge_event
+    pub fn read_edge_event_into(&self, event: &mut EdgeEvent) -> Result<()> {
+        *event = self.do_read_edge_event()?;
+        Ok(())
+    }
     #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
     fn do_read_edge_event(&self) -> Result<EdgeEvent> {
         // bbuf is statically sized to the greater of the v1/v2 size so it can be placed on the stack.
@@ -593,19 +1241,149 @@ impl Request {
         // and dynamically sliced down to the required size, if necessary
         let buf = &mut bbuf[0..self.edge_event_u64_size()];
         let n = self.read_edge_events_into_slice(buf)?;
-        self.do_edge_event_from_slice(&buf[0..n])
+        self.edge_event_from_slice(&buf[0..n])
     }
     #[cfg(not(all(feature = "uapi_v1", feature = "uapi_v2")))]
     fn do_read_edge_event(&self) -> Result<EdgeEvent> {
-        let mut buf = [0; mem::size_of::<uapi::LineEdgeEvent>()];
+        let mut buf = [0; mem::size_of::<uapi::LineEdgeEvent>() / 8];
         let n = self.read_edge_events_into_slice(&mut buf)?;
-        self.do_edge_event_from_slice(&buf[0..n])
+        self.edge_event_from_slice(&buf[0..n])
+    }
+
+    /// The number of edge events dropped by the kernel since the previous call.
+    ///
+    /// The kernel discards the oldest events when its event buffer overflows, such as
+    /// when events are not read as quickly as they are generated.
+    /// Drops are detected from gaps in the [`seqno`] of the events decoded from the
+    /// request, or its clones, so are only reported once subsequent events have been read.
+    /// Events read into a slice using [`read_edge_events_into_slice`] are only tracked
+    /// once decoded using [`edge_event_from_slice`].
+    ///
+    /// Increasing the kernel event buffer size, using [`with_kernel_event_buffer_size`],
+    /// reduces the likelihood of overflow.
+    ///
+    /// uAPI v1 does not provide the seqno, so dropped events cannot be detected and
+    /// this always returns 0.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # fn example() -> Result<(), gpiocdev::Error> {
+    /// # use gpiocdev::line::EdgeDetection;
+    /// let req = gpiocdev::Request::builder()
+    ///     .on_chip("/dev/gpiochip0")
+    ///     .with_line(23)
+    ///     .with_edge_detection(EdgeDetection::BothEdges)
+    ///     .request()?;
+    /// let evt = req.read_edge_event()?;
+    /// let missed = req.take_overflow_count();
+    /// if missed != 0 {
+    ///     println!("missed {} events before {:?}", missed, evt);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`seqno`]: crate::line::EdgeEvent::seqno
+    /// [`read_edge_events_into_slice`]: #method.read_edge_events_into_slice
+    /// [`edge_event_from_slice`]: #method.edge_event_from_slice
+    /// [`with_kernel_event_buffer_size`]: struct.Builder.html#method.with_kernel_event_buffer_size
+    pub fn take_overflow_count(&self) -> u64 {
+        self.seqnos.take_missed()
+    }
+
+    /// The timestamp of an edge event read from the request as a [`SystemTime`].
+    ///
+    /// Returns None unless the line that triggered the event is configured to use
+    /// the **CLOCK_REALTIME** event clock, so the caller need not track how the
+    /// lines were configured.
+    ///
+    /// The event clock cannot be configured with uAPI v1, and the clock used depends
+    /// on the kernel - **CLOCK_REALTIME** prior to Linux 5.7 and **CLOCK_MONOTONIC** since.
+    /// As the clock cannot be determined, this always returns None for uAPI v1.
+    /// If the kernel version is known then use [`EdgeEvent::systemtime`] directly.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # fn example() -> Result<(), gpiocdev::Error> {
+    /// # use gpiocdev::line::{EdgeDetection, EventClock};
+    /// let req = gpiocdev::Request::builder()
+    ///     .on_chip("/dev/gpiochip0")
+    ///     .with_line(23)
+    ///     .with_edge_detection(EdgeDetection::BothEdges)
+    ///     .with_event_clock(EventClock::Realtime)
+    ///     .request()?;
+    /// let evt = req.read_edge_event()?;
+    /// println!("{:?} at {:?}", evt.kind, req.edge_event_systemtime(&evt));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn edge_event_systemtime(&self, event: &EdgeEvent) -> Option<SystemTime> {
+        let clock = self.line_config(event.offset)?.event_clock?;
+        event.systemtime(clock)
+    }
+
+    /// Discard all the edge events currently available from the request.
+    ///
+    /// Useful for discarding stale events, such as after reconfiguring the edge detection
+    /// of the lines.
+    ///
+    /// Reads until the kernel has no events available, so events that arrive while
+    /// draining are also discarded.
+    ///
+    /// Does not block if no events are available.
+    ///
+    /// Returns the number of events discarded.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # fn example() -> Result<(), gpiocdev::Error> {
+    /// # use gpiocdev::line::EdgeDetection;
+    /// let req = gpiocdev::Request::builder()
+    ///     .on_chip("/dev/gpiochip0")
+    ///     .with_line(23)
+    ///     .with_edge_detection(EdgeDetection::RisingEdge)
+    ///     .request()?;
+    /// // ...
+    /// let mut cfg = req.config();
+    /// cfg.with_edge_detection(EdgeDetection::FallingEdge);
+    /// req.reconfigure(&cfg)?;
+    /// // discard any rising edges
+    /// req.drain_edge_events()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn drain_edge_events(&self) -> Result<usize> {
+        self.new_edge_event_buffer(self.user_event_buffer_size)
+            .drain_kernel()
     }
 
     /// Create an edge event buffer.
     ///
+    /// The buffer reads up to `capacity` events from the kernel at once, and can be used
+    /// as an iterator in place of [`edge_events`] to read events in bulk.
+    ///
     /// * `capacity` - The number of events that can be buffered.
-    pub fn new_edge_event_buffer(&self, capacity: usize) -> EdgeEventBuffer {
+    ///   A zero capacity is treated as 1.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # fn example() -> Result<(), gpiocdev::Error> {
+    /// # use gpiocdev::line::EdgeDetection;
+    /// let req = gpiocdev::Request::builder()
+    ///     .on_chip("/dev/gpiochip0")
+    ///     .with_line(23)
+    ///     .with_edge_detection(EdgeDetection::BothEdges)
+    ///     .request()?;
+    ///
+    /// for event in req.new_edge_event_buffer(256) {
+    ///     println!("{:?}", event?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`edge_events`]: #method.edge_events
+    pub fn new_edge_event_buffer(&self, capacity: usize) -> EdgeEventBuffer<'_> {
         EdgeEventBuffer::new(self, self.edge_event_size(), capacity)
     }
 
@@ -630,7 +1408,11 @@ impl Request {
     ///
     /// [`edge_event_size`]: #method.edge_event_size
     pub fn read_edge_events_into_slice(&self, buf: &mut [u64]) -> Result<usize> {
-        gpiocdev_uapi::read_event(&self.f, buf).map_err(|e| Error::Uapi(UapiCall::ReadEvent, e))
+        let n = gpiocdev_uapi::read_event(&self.f, buf)
+            .map_err(|e| Error::Uapi(UapiCall::ReadEvent, e))?;
+        // an edge implies the cached values are stale
+        self.value_cache.invalidate();
+        Ok(n)
     }
 
     /// Read an edge event from a `[u64]` slice.
@@ -642,11 +1424,22 @@ impl Request {
     /// Assumes the buffer has been previously populated by a call to
     /// [`read_edge_events_into_slice`].
     ///
+    /// The event is decoded from the start of the slice.
+    /// Taking a `[u64]` slice ensures the raw event is suitably aligned, so there is no
+    /// requirement on the caller to align the buffer.
+    /// Returns an error, rather than reading beyond the end of the slice, if the slice
+    /// is shorter than [`edge_event_u64_size`].
+    ///
     /// * `buf` - The slice containing the raw event.
     ///
     /// [`read_edge_events_into_slice`]: #method.read_edge_events_into_slice
+    /// [`edge_event_u64_size`]: #method.edge_event_u64_size
     pub fn edge_event_from_slice(&self, buf: &[u64]) -> Result<EdgeEvent> {
-        self.do_edge_event_from_slice(buf)
+        #[cfg(feature = "test_abi_override")]
+        crate::test::check_uapi_call(self.abi_version(), UapiCall::LEEFromBuf)?;
+        let ee = self.do_edge_event_from_slice(buf)?;
+        self.seqnos.observe(ee.seqno);
+        Ok(ee)
     }
     #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
     fn do_edge_event_from_slice(&self, buf: &[u64]) -> Result<EdgeEvent> {
@@ -654,7 +1447,7 @@ impl Request {
             AbiVersion::V1 => {
                 let mut ee = EdgeEvent::from(
                     v1::LineEdgeEvent::from_slice(buf)
-                        .map_err(|e| Error::Uapi(UapiCall::LEEFromBuf, e))?,
+                        .map_err(|e| decode_error::<EdgeKind>(UapiCall::LEEFromBuf, e))?,
                 );
                 // populate offset for v1
                 ee.offset = self.offsets[0];
@@ -662,14 +1455,15 @@ impl Request {
             }
             AbiVersion::V2 => EdgeEvent::from(
                 uapi::LineEdgeEvent::from_slice(buf)
-                    .map_err(|e| Error::Uapi(UapiCall::LEEFromBuf, e))?,
+                    .map_err(|e| decode_error::<EdgeKind>(UapiCall::LEEFromBuf, e))?,
             ),
         })
     }
     #[cfg(not(feature = "uapi_v2"))]
     fn do_edge_event_from_slice(&self, buf: &[u64]) -> Result<EdgeEvent> {
         let mut ee = EdgeEvent::from(
-            v1::LineEdgeEvent::from_slice(buf).map_err(|e| Error::Uapi(UapiCall::LEEFromBuf, e))?,
+            v1::LineEdgeEvent::from_slice(buf)
+                .map_err(|e| decode_error::<EdgeKind>(UapiCall::LEEFromBuf, e))?,
         );
         // populate offset for v1
         ee.offset = self.offsets[0]; // there can be only one
@@ -678,7 +1472,8 @@ impl Request {
     #[cfg(not(feature = "uapi_v1"))]
     fn do_edge_event_from_slice(&self, buf: &[u64]) -> Result<EdgeEvent> {
         Ok(EdgeEvent::from(
-            v2::LineEdgeEvent::from_slice(buf).map_err(|e| Error::Uapi(UapiCall::LEEFromBuf, e))?,
+            v2::LineEdgeEvent::from_slice(buf)
+                .map_err(|e| decode_error::<EdgeKind>(UapiCall::LEEFromBuf, e))?,
         ))
     }
 
@@ -712,6 +1507,23 @@ impl Request {
     }
 }
 
+// Determine the config of a line from its info, filling in the gaps from the old config.
+//
+// If `edges` is false then the info does not report the edge detection, event clock and
+// debounce settings, so those are retained from the old config.
+fn reconcile_line_config(old: &line::Config, info: &line::Info, edges: bool) -> line::Config {
+    let mut cfg = line::Config::from(info);
+    if cfg.direction == Some(line::Direction::Output) {
+        cfg.value = old.value;
+    }
+    if !edges {
+        cfg.edge_detection = old.edge_detection;
+        cfg.event_clock = old.event_clock;
+        cfg.debounce_period = old.debounce_period;
+    }
+    cfg
+}
+
 impl AsFd for Request {
     #[inline]
     fn as_fd(&self) -> BorrowedFd<'_> {
@@ -768,4 +1580,101 @@ mod tests {
         #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
         assert_eq!(b.abiv, None);
     }
+
+    #[test]
+    fn reconcile_line_config() {
+        use super::line::{self, Bias, Direction, EdgeDetection, EventClock, Value};
+        use super::reconcile_line_config;
+        use std::time::Duration;
+
+        let info = line::Info {
+            offset: 3,
+            direction: Direction::Output,
+            bias: Some(Bias::PullUp),
+            ..Default::default()
+        };
+        let old = line::Config {
+            direction: Some(Direction::Input),
+            edge_detection: Some(EdgeDetection::BothEdges),
+            debounce_period: Some(Duration::from_millis(5)),
+            value: Some(Value::Active),
+            ..Default::default()
+        };
+        let cfg = reconcile_line_config(&old, &info, true);
+        assert_eq!(cfg.direction, Some(Direction::Output));
+        assert_eq!(cfg.bias, Some(Bias::PullUp));
+        assert_eq!(cfg.edge_detection, None);
+        assert_eq!(cfg.debounce_period, None);
+        // value retained for outputs
+        assert_eq!(cfg.value, Some(Value::Active));
+
+        // edge settings retained if not reported
+        let cfg = reconcile_line_config(&old, &info, false);
+        assert_eq!(cfg.edge_detection, Some(EdgeDetection::BothEdges));
+        assert_eq!(cfg.debounce_period, Some(Duration::from_millis(5)));
+        assert_eq!(cfg.event_clock, None);
+
+        // value cleared for inputs
+        let info = line::Info {
+            edge_detection: Some(EdgeDetection::RisingEdge),
+            event_clock: Some(EventClock::Realtime),
+            debounce_period: Some(Duration::from_micros(1500)),
+            ..Default::default()
+        };
+        let cfg = reconcile_line_config(&old, &info, true);
+        assert_eq!(cfg.direction, Some(Direction::Input));
+        assert_eq!(cfg.edge_detection, Some(EdgeDetection::RisingEdge));
+        assert_eq!(cfg.event_clock, Some(EventClock::Realtime));
+        assert_eq!(cfg.debounce_period, Some(Duration::from_micros(1500)));
+        assert_eq!(cfg.value, None);
+    }
+
+    #[test]
+    fn recommend_event_buffer_size() {
+        use std::time::Duration;
+
+        let max = Request::KERNEL_EVENT_BUFFER_SIZE_MAX;
+        assert_eq!(max, 1024);
+        // minimum
+        assert_eq!(Request::recommend_event_buffer_size(0, Duration::ZERO), 16);
+        assert_eq!(
+            Request::recommend_event_buffer_size(0, Duration::from_secs(1)),
+            16
+        );
+        assert_eq!(
+            Request::recommend_event_buffer_size(1000, Duration::ZERO),
+            16
+        );
+        assert_eq!(
+            Request::recommend_event_buffer_size(100, Duration::from_millis(10)),
+            16
+        );
+        // twice the events per interval
+        assert_eq!(
+            Request::recommend_event_buffer_size(1000, Duration::from_millis(10)),
+            20
+        );
+        assert_eq!(
+            Request::recommend_event_buffer_size(2000, Duration::from_millis(50)),
+            200
+        );
+        // partial events round up
+        assert_eq!(
+            Request::recommend_event_buffer_size(1001, Duration::from_millis(10)),
+            22
+        );
+        // maximum
+        assert_eq!(
+            Request::recommend_event_buffer_size(512, Duration::from_secs(1)),
+            max
+        );
+        assert_eq!(
+            Request::recommend_event_buffer_size(513, Duration::from_secs(1)),
+            max
+        );
+        assert_eq!(
+            Request::recommend_event_buffer_size(u32::MAX, Duration::MAX),
+            max
+        );
+    }
 }
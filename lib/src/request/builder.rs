@@ -11,7 +11,7 @@ use crate::{Error, Result, UapiCall};
 use gpiocdev_uapi::v1;
 #[cfg(feature = "uapi_v2")]
 use gpiocdev_uapi::v2;
-use gpiocdev_uapi::NUM_LINES_MAX;
+use gpiocdev_uapi::NAME_LEN_MAX;
 use std::cmp::max;
 use std::collections::HashMap;
 use std::fs::File;
@@ -107,6 +107,10 @@ pub struct Builder {
     pub(super) kernel_event_buffer_size: u32,
     pub(super) user_event_buffer_size: usize,
     err: Option<Error>,
+    /// The chip provided by [`on_chip_handle`], used instead of reopening the chip.
+    ///
+    /// [`on_chip_handle`]: #method.on_chip_handle
+    chip: Option<ChipHandle>,
     /// The ABI version used to create the request, and so determines how to decode events.
     #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
     pub(super) abiv: Option<AbiVersion>,
@@ -133,16 +137,51 @@ impl Builder {
         if self.cfg.chip.as_os_str().is_empty() {
             return Err(Error::InvalidArgument("No chip specified.".into()));
         }
-        let chip = Chip::from_path(&self.cfg.chip)?;
         self.cfg.offsets.sort_unstable();
-        self.do_request(&chip).map(|f| self.to_request(f))
+        let f = match self.chip_handle() {
+            Some(h) => self.do_request(&h.0),
+            None => {
+                let chip = Chip::from_path(&self.cfg.chip)?;
+                self.do_request(&chip)
+            }
+        }?;
+        Ok(self.to_request(f))
+    }
+
+    /// Check the request is valid, without requesting the lines.
+    ///
+    /// Performs the same validation as [`request`], but without accessing the chip,
+    /// so problems with the configuration, such as exceeding the limits of the uAPI,
+    /// can be identified before making the request.
+    ///
+    /// The configuration is checked against the ABI version set by [`using_abi_version`],
+    /// or uAPI v2 if none is set and both versions are supported.
+    ///
+    /// [`request`]: #method.request
+    /// [`using_abi_version`]: #method.using_abi_version
+    pub fn check(&self) -> Result<()> {
+        if let Some(e) = &self.err {
+            return Err(e.clone());
+        }
+        self.to_uapi().map(|_| ())
+    }
+
+    // The chip handle, if one was provided for the chip in the config.
+    fn chip_handle(&self) -> Option<ChipHandle> {
+        self.chip
+            .as_ref()
+            .filter(|h| h.0.path() == self.cfg.chip)
+            .cloned()
     }
     #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
     fn do_request(&mut self, chip: &Chip) -> Result<File> {
         if self.abiv.is_none() {
-            self.abiv = Some(chip.detect_abi_version()?);
+            self.abiv = Some(chip.cached_abi_version()?);
         }
-        match self.to_uapi()? {
+        let ur = self.to_uapi()?;
+        #[cfg(feature = "test_abi_override")]
+        ur.check_abi_override()?;
+        match ur {
             UapiRequest::Handle(hr) => v1::get_line_handle(&chip.f, hr)
                 .map_err(|e| Error::Uapi(UapiCall::GetLineHandle, e)),
             UapiRequest::Event(er) => {
@@ -155,7 +194,10 @@ impl Builder {
     }
     #[cfg(not(feature = "uapi_v2"))]
     fn do_request(&self, chip: &Chip) -> Result<File> {
-        match self.to_uapi()? {
+        let ur = self.to_uapi()?;
+        #[cfg(feature = "test_abi_override")]
+        ur.check_abi_override()?;
+        match ur {
             UapiRequest::Handle(hr) => v1::get_line_handle(&chip.f, hr)
                 .map_err(|e| Error::Uapi(UapiCall::GetLineHandle, e)),
             UapiRequest::Event(er) => {
@@ -165,7 +207,10 @@ impl Builder {
     }
     #[cfg(not(feature = "uapi_v1"))]
     fn do_request(&self, chip: &Chip) -> Result<File> {
-        match self.to_uapi()? {
+        let ur = self.to_uapi()?;
+        #[cfg(feature = "test_abi_override")]
+        ur.check_abi_override()?;
+        match ur {
             UapiRequest::Line(lr) => {
                 v2::get_line(&chip.f, lr).map_err(|e| Error::Uapi(UapiCall::GetLine, e))
             }
@@ -178,11 +223,24 @@ impl Builder {
             offsets: self.cfg.offsets.clone(),
             cfg: Arc::new(RwLock::new(self.cfg.clone())),
             user_event_buffer_size: max(self.user_event_buffer_size, 1),
+            consumer: self.resolved_consumer(),
+            value_cache: Default::default(),
+            seqnos: Default::default(),
+            line_data: Default::default(),
             #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
             abiv: self.abiv.unwrap(),
         }
     }
 
+    // The consumer label applied to the request, including the default if none is set.
+    fn resolved_consumer(&self) -> String {
+        if self.consumer.is_empty() {
+            default_consumer()
+        } else {
+            self.consumer.clone()
+        }
+    }
+
     /// Replace the request configuration with the new one provided.
     pub fn with_config(&mut self, cfg: Config) -> &mut Self {
         self.cfg = cfg;
@@ -207,6 +265,11 @@ impl Builder {
     /// If not specified, a label *"gpiocdev-p**PID**"* is applied by [`request`],
     /// where **PID** is the process id of the application.
     ///
+    /// The kernel limits the consumer to 31 bytes, so [`request`] returns an error
+    /// for longer consumers rather than have the kernel truncate them.
+    /// Note that the limit is in bytes, not characters, so multi-byte UTF-8
+    /// characters count more than once.
+    ///
     /// # Examples
     /// ```no_run
     /// # use gpiocdev::{Request, Result};
@@ -298,8 +361,55 @@ impl Builder {
     pub fn on_chip<P: Into<PathBuf>>(&mut self, path: P) -> &mut Self {
         if self.cfg.chip.as_os_str().is_empty() {
             self.cfg.on_chip(path);
-        } else if self.cfg.chip != path.into() {
-            self.err = Some(Error::InvalidArgument("Multiple chips requested.".into()))
+        } else {
+            let path = path.into();
+            if self.cfg.chip != path {
+                self.err = Some(Error::MultipleChips(vec![
+                    (self.cfg.chip.clone(), self.cfg.offsets.clone()),
+                    (path, Vec::new()),
+                ]))
+            }
+        }
+        self
+    }
+
+    /// Set the chip from which to request lines, using an already open chip.
+    ///
+    /// This is equivalent to [`on_chip`] with the path of the chip, except that
+    /// the lines are requested using the open chip rather than by reopening the chip.
+    /// This avoids reopening and revalidating the chip, and allows lines to be
+    /// requested from a chip provided as a file descriptor using [`Chip::from_fd`].
+    ///
+    /// The `Builder` holds a duplicate of the chip file descriptor, so the chip
+    /// may be dropped before [`request`] is called.
+    ///
+    /// As with [`on_chip`], the `Builder` locks to the first chip provided to it.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # fn example() -> Result<(), gpiocdev::Error> {
+    /// let chip = gpiocdev::Chip::from_path("/dev/gpiochip0")?;
+    /// let offset = chip.find_line_info("GPIO17").unwrap().offset;
+    /// let req = gpiocdev::Request::builder()
+    ///     .on_chip_handle(&chip)
+    ///     .with_line(offset)
+    ///     .request()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`on_chip`]: #method.on_chip
+    /// [`request`]: #method.request
+    /// [`Chip::from_fd`]: crate::chip::Chip::from_fd
+    pub fn on_chip_handle(&mut self, chip: &Chip) -> &mut Self {
+        match chip.try_clone() {
+            Ok(c) => {
+                self.on_chip(c.path());
+                if self.cfg.chip == c.path() {
+                    self.chip = Some(ChipHandle(Arc::new(c)));
+                }
+            }
+            Err(e) => self.err = Some(e),
         }
         self
     }
@@ -330,7 +440,32 @@ impl Builder {
         self
     }
 
+    /// Set the selected lines to output with the given value and bias.
+    ///
+    /// This is a short form of [`as_output(value)`] and [`with_bias(bias)`] that allows
+    /// the direction, value and bias to be set in the one call.
+    ///
+    /// As with any configuration, these are all applied by the kernel together when the
+    /// lines are requested, so there is no window where the output is driven without
+    /// the bias being applied.
+    ///
+    /// [`as_output(value)`]: #method.as_output
+    /// [`with_bias(bias)`]: #method.with_bias
+    pub fn as_output_with_bias(&mut self, value: Value, bias: Bias) -> &mut Self {
+        self.cfg.as_output_with_bias(value, bias);
+        self
+    }
+
     /// Do not set the direction of the selected lines.
+    ///
+    /// The kernel leaves the lines in their current direction, so lines can be
+    /// requested without disturbing whether they are inputs or outputs.
+    /// This is supported by both uAPI versions.
+    ///
+    /// As the kernel only applies bias to lines with a direction set, the request
+    /// will fail if a bias is also set for the selected lines.
+    ///
+    /// Any input or output specific settings are removed from the selected lines.
     pub fn as_is(&mut self) -> &mut Self {
         self.cfg.as_is();
         self
@@ -437,7 +572,7 @@ impl Builder {
     /// ```no_run
     /// # use gpiocdev::line::EdgeDetection;
     /// # fn example() -> Result<(), gpiocdev::Error> {
-    /// let buttons = gpiocdev::find_named_lines(&["BUTTON0","BUTTON1"], true)?;
+    /// let buttons = gpiocdev::find_named_lines(["BUTTON0","BUTTON1"], true)?;
     /// let req = gpiocdev::Request::builder()
     ///     .with_found_lines(&buttons)
     ///     .with_edge_detection(EdgeDetection::BothEdges)
@@ -445,7 +580,7 @@ impl Builder {
     /// # Ok(())
     /// # }
     /// ```
-    pub fn with_found_lines(&mut self, lines: &HashMap<&str, crate::FoundLine>) -> &mut Self {
+    pub fn with_found_lines<K>(&mut self, lines: &HashMap<K, crate::FoundLine>) -> &mut Self {
         if let Err(e) = self.cfg.with_found_lines(lines) {
             self.err = Some(e);
         }
@@ -461,6 +596,21 @@ impl Builder {
         self
     }
 
+    /// Add a line to the request, configured to match its current info.
+    ///
+    /// Useful for taking over a line in its current state.
+    /// The value of output lines is not part of the info, so should be set
+    /// with [`with_value`] if the line is an output.
+    ///
+    /// Note that all configuration mutators applied subsequently only
+    /// apply to this line.
+    ///
+    /// [`with_value`]: #method.with_value
+    pub fn with_line_as_info(&mut self, info: &line::Info) -> &mut Self {
+        self.cfg.with_line_as_info(info);
+        self
+    }
+
     /// Remove a line from the request.
     pub fn without_line(&mut self, offset: Offset) -> &mut Self {
         self.cfg.without_line(offset);
@@ -508,18 +658,29 @@ impl Builder {
         if self.cfg.num_lines() == 0 {
             return Err(Error::InvalidArgument("No lines specified.".into()));
         }
-        if self.cfg.offsets.len() > NUM_LINES_MAX {
+        self.cfg.check_num_lines()?;
+        for offset in self.cfg.offsets.iter() {
+            let lcfg = self.cfg.lcfg.get(offset).unwrap();
+            if lcfg.direction.is_none() && lcfg.bias.is_some() {
+                return Err(Error::InvalidArgument(format!(
+                    "Line {} has bias set without direction.",
+                    offset
+                )));
+            }
+        }
+        // allowing for the null terminator
+        if self.consumer.len() >= NAME_LEN_MAX {
             return Err(Error::InvalidArgument(format!(
-                "Requested {} lines is greater than the maximum of {}.",
-                self.cfg.offsets.len(),
-                NUM_LINES_MAX,
+                "Consumer is {} bytes which is greater than the maximum of {}.",
+                self.consumer.len(),
+                NAME_LEN_MAX - 1
             )));
         }
         self.do_to_uapi()
     }
     #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
     fn do_to_uapi(&self) -> Result<UapiRequest> {
-        match self.abiv.unwrap() {
+        match self.abiv.unwrap_or(AbiVersion::V2) {
             AbiVersion::V1 => self.to_v1(),
             AbiVersion::V2 => self.to_v2(),
         }
@@ -554,11 +715,7 @@ impl Builder {
                 "does not support selecting the event clock source".into(),
             ));
         }
-        let consumer = if self.consumer.is_empty() {
-            default_consumer().as_str().into()
-        } else {
-            self.consumer.as_str().into()
-        };
+        let consumer = self.resolved_consumer().as_str().into();
         if lcfg.edge_detection.is_some() {
             if self.cfg.offsets.len() != 1 {
                 return Err(Error::AbiLimitation(
@@ -587,11 +744,7 @@ impl Builder {
 
     #[cfg(any(feature = "uapi_v2", not(feature = "uapi_v1")))]
     fn to_v2(&self) -> Result<UapiRequest> {
-        let consumer = if self.consumer.is_empty() {
-            default_consumer().as_str().into()
-        } else {
-            self.consumer.as_str().into()
-        };
+        let consumer = self.resolved_consumer().as_str().into();
         Ok(UapiRequest::Line(v2::LineRequest {
             offsets: v2::Offsets::from_slice(&self.cfg.offsets),
             consumer,
@@ -603,6 +756,20 @@ impl Builder {
     }
 }
 
+// An open chip shared by clones of the Builder.
+//
+// Handles are equal if they share the same chip, so cloned Builders compare equal.
+#[derive(Clone, Debug)]
+struct ChipHandle(Arc<Chip>);
+
+impl PartialEq for ChipHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for ChipHandle {}
+
 #[cfg_attr(test, derive(Debug))]
 #[allow(clippy::large_enum_variant)]
 enum UapiRequest {
@@ -616,6 +783,27 @@ enum UapiRequest {
     Line(v2::LineRequest),
 }
 
+#[cfg(feature = "test_abi_override")]
+impl UapiRequest {
+    // Fail the request if its ABI version has been forced unsupported.
+    fn check_abi_override(&self) -> Result<()> {
+        match self {
+            #[cfg(feature = "uapi_v1")]
+            UapiRequest::Handle(_) => {
+                crate::test::check_uapi_call(crate::AbiVersion::V1, UapiCall::GetLineHandle)
+            }
+            #[cfg(feature = "uapi_v1")]
+            UapiRequest::Event(_) => {
+                crate::test::check_uapi_call(crate::AbiVersion::V1, UapiCall::GetLineEvent)
+            }
+            #[cfg(any(feature = "uapi_v2", not(feature = "uapi_v1")))]
+            UapiRequest::Line(_) => {
+                crate::test::check_uapi_call(crate::AbiVersion::V2, UapiCall::GetLine)
+            }
+        }
+    }
+}
+
 fn default_consumer() -> String {
     format!("gpiocdev-p{}", std::process::id())
 }
@@ -666,8 +854,13 @@ mod tests {
             .on_chip("/dev/gpiochip1")
             .with_line(3)
             .request();
-        assert!(res.is_err());
-        assert_eq!(res.err().unwrap().to_string(), "Multiple chips requested.");
+        assert_eq!(
+            res.unwrap_err(),
+            Error::MultipleChips(vec![
+                (PathBuf::from("/dev/gpiochip0"), vec![2]),
+                (PathBuf::from("/dev/gpiochip1"), vec![])
+            ])
+        );
     }
 
     #[test]
@@ -689,6 +882,19 @@ mod tests {
         assert_eq!(cfg.offsets, &[1, 7, 4]);
     }
 
+    #[test]
+    fn resolved_consumer() {
+        let mut b = Builder::default();
+        assert_eq!(b.resolved_consumer(), default_consumer());
+        assert!(b.resolved_consumer().starts_with("gpiocdev-p"));
+
+        b.with_consumer("builder test");
+        assert_eq!(b.resolved_consumer(), "builder test");
+
+        b.with_consumer("");
+        assert_eq!(b.resolved_consumer(), default_consumer());
+    }
+
     #[test]
     fn with_consumer() {
         let mut b = Builder::default();
@@ -760,6 +966,18 @@ mod tests {
         assert_eq!(b.cfg.base.direction, Some(Output));
     }
 
+    #[test]
+    fn as_output_with_bias() {
+        let mut b = Builder::default();
+        b.as_input();
+        assert_eq!(b.cfg.base.direction, Some(Input));
+
+        b.as_output_with_bias(Active, PullDown);
+        assert_eq!(b.cfg.base.direction, Some(Output));
+        assert_eq!(b.cfg.base.value, Some(Active));
+        assert_eq!(b.cfg.base.bias, Some(PullDown));
+    }
+
     #[test]
     fn as_active_low() {
         let mut b = Builder::default();
@@ -906,6 +1124,27 @@ mod tests {
         assert_eq!(b.cfg.num_lines(), 2);
     }
 
+    #[test]
+    fn with_line_as_info() {
+        let mut b = Builder::default();
+        let info = line::Info {
+            offset: 3,
+            active_low: true,
+            direction: Output,
+            bias: Some(PullDown),
+            drive: Some(OpenSource),
+            ..Default::default()
+        };
+        b.with_line(1).as_input().with_line_as_info(&info);
+        assert_eq!(b.cfg.offsets, &[1, 3]);
+        assert_eq!(b.cfg.line_config(3), Some(&line::Config::from(&info)));
+
+        // subsequent mutators apply to the line
+        b.with_value(Active);
+        assert_eq!(b.cfg.line_config(3).unwrap().value, Some(Active));
+        assert_eq!(b.cfg.line_config(1).unwrap().value, None);
+    }
+
     #[test]
     fn without_line() {
         let mut b = Builder::default();
@@ -1028,6 +1267,86 @@ mod tests {
         let res = Builder::default().to_uapi();
         assert!(res.is_err());
         assert_eq!(res.err().unwrap().to_string(), "No lines specified.");
+
+        let mut b = Builder::default();
+        b.with_lines(&[1, 3])
+            .as_input()
+            .with_bias(Bias::PullUp)
+            .with_line(3)
+            .as_is();
+        assert_eq!(
+            b.to_uapi().err().unwrap(),
+            Error::InvalidArgument("Line 3 has bias set without direction.".into())
+        );
+
+        let offsets: Vec<Offset> = (0..65).collect();
+        let mut b = Builder::default();
+        b.with_lines(&offsets).as_input();
+        assert_eq!(
+            b.to_uapi().err().unwrap(),
+            Error::InvalidArgument("Requested 65 lines is greater than the maximum of 64.".into())
+        );
+
+        let mut b = Builder::default();
+        b.with_line(3).as_input().with_consumer("a".repeat(32));
+        assert_eq!(
+            b.to_uapi().err().unwrap(),
+            Error::InvalidArgument(
+                "Consumer is 32 bytes which is greater than the maximum of 31.".into()
+            )
+        );
+
+        // measured in bytes, not chars
+        b.with_consumer("🦀".repeat(8));
+        assert_eq!(
+            b.to_uapi().err().unwrap(),
+            Error::InvalidArgument(
+                "Consumer is 32 bytes which is greater than the maximum of 31.".into()
+            )
+        );
+    }
+
+    #[test]
+    fn check() {
+        use crate::AbiVersion;
+
+        let mut b = Builder::default();
+        b.with_lines(&[1, 3]).as_input();
+        assert!(b.check().is_ok());
+
+        b.with_line(3).as_output(Active);
+        #[cfg(feature = "uapi_v2")]
+        assert!(b.check().is_ok());
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        b.using_abi_version(AbiVersion::V1);
+        #[cfg(feature = "uapi_v1")]
+        assert_eq!(
+            b.check().err().unwrap(),
+            Error::AbiLimitation(
+                AbiVersion::V1,
+                "requires all lines to share the same configuration".into()
+            )
+        );
+
+        let mut b = Builder::default();
+        for offset in 0..12 {
+            b.with_line(offset)
+                .as_input()
+                .with_debounce_period(Duration::from_millis(offset as u64 + 1));
+        }
+        #[cfg(feature = "uapi_v2")]
+        assert_eq!(
+            b.check().err().unwrap(),
+            Error::AbiLimitation(
+                AbiVersion::V2,
+                "supports 10 attrs, configuration requires 12".into()
+            )
+        );
+
+        let offsets: Vec<Offset> = (0..65).collect();
+        let mut b = Builder::default();
+        b.with_lines(&offsets).as_input();
+        assert!(b.check().is_err());
     }
 
     #[test]
@@ -1091,6 +1410,20 @@ mod tests {
             panic!("not a handle request");
         }
 
+        let mut b = Builder::default();
+        b.with_lines(&[2, 6])
+            .as_output_with_bias(Value::Active, PullUp);
+        if let UapiRequest::Handle(hr) = b.to_v1().unwrap() {
+            assert_eq!(hr.num_lines, 2);
+            assert!(hr
+                .flags
+                .contains(v1::HandleRequestFlags::OUTPUT | v1::HandleRequestFlags::BIAS_PULL_UP));
+            assert_eq!(hr.values.get(0), 1);
+            assert_eq!(hr.values.get(1), 1);
+        } else {
+            panic!("not a handle request");
+        }
+
         // ABI limitations
 
         let mut b = Builder::default();
@@ -2,6 +2,7 @@
 //
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+use crate::chip::path_compare;
 use crate::line::{
     self, Bias, Direction, Drive, EdgeDetection, EventClock, Offset, OffsetMap, Offsets, Value,
     Values,
@@ -11,7 +12,9 @@ use crate::{AbiVersion, Error, Result};
 use gpiocdev_uapi::v1;
 #[cfg(feature = "uapi_v2")]
 use gpiocdev_uapi::v2;
+use gpiocdev_uapi::NUM_LINES_MAX;
 use std::collections::HashMap;
+use std::fmt;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
@@ -108,6 +111,10 @@ impl Config {
     }
 
     /// Do not set the direction of the selected lines.
+    ///
+    /// The kernel leaves the lines in their current direction.
+    ///
+    /// Any input or output specific settings are removed from the selected lines.
     pub fn as_is(&mut self) -> &mut Self {
         for cfg in self.selected_iter() {
             cfg.as_is();
@@ -128,6 +135,21 @@ impl Config {
         self
     }
 
+    /// Set the selected lines to output with the given value and bias.
+    ///
+    /// This is a short form of [`as_output(value)`] and [`with_bias(bias)`] that allows
+    /// the direction, value and bias to be set in the one call.
+    ///
+    /// As with any configuration, these are all applied by the kernel together when the
+    /// lines are requested, so there is no window where the output is driven without
+    /// the bias being applied.
+    ///
+    /// [`as_output(value)`]: #method.as_output
+    /// [`with_bias(bias)`]: #method.with_bias
+    pub fn as_output_with_bias(&mut self, value: Value, bias: Bias) -> &mut Self {
+        self.as_output(value).with_bias(bias)
+    }
+
     /// Set the selected lines to active low.
     pub fn as_active_low(&mut self) -> &mut Self {
         for cfg in self.selected_iter() {
@@ -253,7 +275,7 @@ impl Config {
             self.with_line(line.info.offset);
             Ok(self)
         } else {
-            Err(Error::InvalidArgument("Multiple chips requested.".into()))
+            Err(self.multiple_chips(std::iter::once(line)))
         }
     }
 
@@ -268,17 +290,27 @@ impl Config {
     /// # use gpiocdev::request::Config;
     /// # use gpiocdev::line::EdgeDetection;
     /// # fn example() -> Result<(), gpiocdev::Error> {
-    /// let buttons = gpiocdev::find_named_lines(&["BUTTON0","BUTTON1"], true)?;
+    /// let buttons = gpiocdev::find_named_lines(["BUTTON0","BUTTON1"], true)?;
     /// let mut cfg = Config::default();
     /// cfg.with_found_lines(&buttons)?
     ///    .with_edge_detection(EdgeDetection::BothEdges);
     /// # Ok(())
     /// # }
     /// ```
-    pub fn with_found_lines(
+    pub fn with_found_lines<K>(
         &mut self,
-        lines: &HashMap<&str, crate::FoundLine>,
+        lines: &HashMap<K, crate::FoundLine>,
     ) -> Result<&mut Self> {
+        let chip = if self.chip.as_os_str().is_empty() {
+            lines.values().next().map(|l| l.chip.as_path())
+        } else {
+            Some(self.chip.as_path())
+        };
+        if let Some(chip) = chip {
+            if lines.values().any(|l| l.chip != chip) {
+                return Err(self.multiple_chips(lines.values()));
+            }
+        }
         for line in lines.values() {
             self.with_found_line(line)?;
         }
@@ -288,6 +320,33 @@ impl Config {
         Ok(self)
     }
 
+    // Build the error for found lines that span multiple chips.
+    //
+    // The chip already in the config, if any, is listed first, followed by the
+    // chips of the found lines in path order.
+    fn multiple_chips<'a, I: Iterator<Item = &'a crate::FoundLine>>(&self, lines: I) -> Error {
+        let mut chips: Vec<(PathBuf, Vec<Offset>)> = Vec::new();
+        for line in lines {
+            match chips.iter_mut().find(|(chip, _)| *chip == line.chip) {
+                Some((_, offsets)) => offsets.push(line.info.offset),
+                None => chips.push((line.chip.clone(), vec![line.info.offset])),
+            }
+        }
+        chips.sort_by(|a, b| path_compare(&a.0, &b.0));
+        if !self.chip.as_os_str().is_empty() {
+            let mut offsets = self.offsets.clone();
+            if let Some(idx) = chips.iter().position(|(chip, _)| *chip == self.chip) {
+                offsets.extend(chips.remove(idx).1);
+            }
+            chips.insert(0, (self.chip.clone(), offsets));
+        }
+        for (_, offsets) in chips.iter_mut() {
+            offsets.sort_unstable();
+            offsets.dedup();
+        }
+        Error::MultipleChips(chips)
+    }
+
     /// Add a line to the config.
     ///
     /// Note that all configuration mutators applied subsequently only apply to this line.
@@ -297,6 +356,13 @@ impl Config {
         self
     }
 
+    /// Add a line to the config, configured to match its current info.
+    ///
+    /// Note that all configuration mutators applied subsequently only apply to this line.
+    pub fn with_line_as_info(&mut self, info: &line::Info) -> &mut Self {
+        self.with_line(info.offset).from_line_config(&info.into())
+    }
+
     /// Remove a line from the config.
     pub fn without_line(&mut self, offset: Offset) -> &mut Self {
         self.remove_line(&offset);
@@ -359,7 +425,7 @@ impl Config {
         self
     }
 
-    fn selected_iter(&mut self) -> SelectedIterator {
+    fn selected_iter(&mut self) -> SelectedIterator<'_> {
         SelectedIterator {
             cfg: self,
             index: 0,
@@ -373,6 +439,60 @@ impl Config {
         self.lcfg.get(&offset)
     }
 
+    /// Return the per-line differences required to convert this config into the `other` config.
+    ///
+    /// Lines present in both configs, but with differing configuration, are reported as
+    /// [`LineConfigDiff::Changed`], while lines only present in one config are reported as
+    /// [`LineConfigDiff::Added`] or [`LineConfigDiff::Removed`].
+    /// Lines with identical configuration are not reported.
+    ///
+    /// The lines are returned in the order of this config, followed by any lines added in `other`,
+    /// in the order of `other`.
+    ///
+    /// The chip and base config are not compared.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpiocdev::line::{Bias, ConfigChange};
+    /// use gpiocdev::request::{Config, LineConfigDiff};
+    ///
+    /// let mut old = Config::default();
+    /// old.with_lines(&[3, 5]).as_input();
+    /// let mut new = old.clone();
+    /// new.with_line(5).with_bias(Bias::PullUp);
+    /// assert_eq!(
+    ///     old.diff(&new),
+    ///     [(5, LineConfigDiff::Changed(vec![ConfigChange::Bias(None, Some(Bias::PullUp))]))]
+    /// );
+    /// ```
+    pub fn diff(&self, other: &Config) -> Vec<(Offset, LineConfigDiff)> {
+        let mut diffs = Vec::new();
+        for offset in &self.offsets {
+            let lc = match self.lcfg.get(offset) {
+                Some(lc) => lc,
+                None => continue,
+            };
+            match other.lcfg.get(offset) {
+                Some(olc) => {
+                    let changes = lc.diff(olc);
+                    if !changes.is_empty() {
+                        diffs.push((*offset, LineConfigDiff::Changed(changes)));
+                    }
+                }
+                None => diffs.push((*offset, LineConfigDiff::Removed(lc.clone()))),
+            }
+        }
+        for offset in &other.offsets {
+            if self.lcfg.contains_key(offset) {
+                continue;
+            }
+            if let Some(olc) = other.lcfg.get(offset) {
+                diffs.push((*offset, LineConfigDiff::Added(olc.clone())));
+            }
+        }
+        diffs
+    }
+
     /// Returns the set of lines described by the Config.
     ///
     /// Lines are in the order first added by calls to [`with_line`] or [`with_lines`].
@@ -388,6 +508,21 @@ impl Config {
         self.lcfg.len()
     }
 
+    // Check the number of lines does not exceed the maximum supported by the kernel.
+    //
+    // Lines are identified by their index in the uAPI bitmaps, so more lines than
+    // that would be silently mis-grouped.
+    pub(crate) fn check_num_lines(&self) -> Result<()> {
+        if self.offsets.len() > NUM_LINES_MAX {
+            return Err(Error::InvalidArgument(format!(
+                "Requested {} lines is greater than the maximum of {}.",
+                self.offsets.len(),
+                NUM_LINES_MAX,
+            )));
+        }
+        Ok(())
+    }
+
     /// Returns the config that applies to all lines, or an error if the lines have
     /// distinct configurations.
     #[cfg(feature = "uapi_v1")]
@@ -467,6 +602,7 @@ impl Config {
 
     #[cfg(feature = "uapi_v1")]
     pub(crate) fn to_v1_values(&self) -> Result<v1::LineValues> {
+        self.check_num_lines()?;
         let mut values = v1::LineValues::default();
         for (idx, offset) in self.offsets.iter().enumerate() {
             // unwrap is safe here as offsets match lcfg keys
@@ -478,6 +614,7 @@ impl Config {
     // v2
     #[cfg(any(feature = "uapi_v2", not(feature = "uapi_v1")))]
     pub(crate) fn to_v2(&self) -> Result<v2::LineConfig> {
+        self.check_num_lines()?;
         // debounced and flags provide maps from attr values to bitmap of lines using those values.
         let mut debounced  = OffsetMap::default();
         let mut flags = HashMap::new();
@@ -557,6 +694,9 @@ impl Config {
     }
 }
 
+// The uAPI bitmaps identifying lines are u64, so cannot identify more lines than that.
+const _: () = assert!(NUM_LINES_MAX <= u64::BITS as usize);
+
 /// An iterator over the currently selected lines in a Config.
 // This is strictly internal as external usage could invalidate the safety contract.
 struct SelectedIterator<'a> {
@@ -599,6 +739,34 @@ impl<'a> Iterator for SelectedIterator<'a> {
     }
 }
 
+/// The difference in the configuration of a line between two [`Config`]s.
+///
+/// Returned by [`Config::diff`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LineConfigDiff {
+    /// The line is only present in the other config, with the contained config.
+    Added(line::Config),
+
+    /// The line is only present in this config, with the contained config.
+    Removed(line::Config),
+
+    /// The line is present in both configs, with the contained changes.
+    Changed(Vec<line::ConfigChange>),
+}
+
+impl fmt::Display for LineConfigDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LineConfigDiff::Added(_) => write!(f, "added"),
+            LineConfigDiff::Removed(_) => write!(f, "removed"),
+            LineConfigDiff::Changed(changes) => {
+                let changes: Vec<String> = changes.iter().map(|c| c.to_string()).collect();
+                write!(f, "{}", changes.join(", "))
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -653,6 +821,26 @@ mod tests {
         assert_eq!(cfg.base.edge_detection, None);
     }
 
+    #[test]
+    fn as_output_with_bias() {
+        let mut cfg = Config::default();
+        cfg.as_input().with_edge_detection(RisingEdge);
+        cfg.as_output_with_bias(Active, PullUp);
+        assert_eq!(cfg.base.direction, Some(Output));
+        assert_eq!(cfg.base.value, Some(Active));
+        assert_eq!(cfg.base.bias, Some(PullUp));
+        assert_eq!(cfg.base.edge_detection, None);
+
+        cfg.with_lines(&[3, 5])
+            .as_output_with_bias(Inactive, PullDown);
+        for offset in [3, 5] {
+            let lcfg = cfg.lcfg.get(&offset).unwrap();
+            assert_eq!(lcfg.direction, Some(Output));
+            assert_eq!(lcfg.value, Some(Inactive));
+            assert_eq!(lcfg.bias, Some(PullDown));
+        }
+    }
+
     #[test]
     fn as_active_low() {
         let mut cfg = Config::default();
@@ -958,6 +1146,20 @@ mod tests {
         assert!(cfg.with_found_line(&7.into()).is_ok());
         assert_eq!(cfg.offsets, &[3, 7, 5]);
         assert_eq!(cfg.selected, &[7]);
+
+        // and one from another chip
+        let mut cfg = Config::default();
+        cfg.on_chip("/dev/gpiochip0").with_line(3);
+        let mut other: crate::FoundLine = 4.into();
+        other.chip = PathBuf::from("/dev/gpiochip1");
+        assert_eq!(
+            cfg.with_found_line(&other).unwrap_err(),
+            Error::MultipleChips(vec![
+                (PathBuf::from("/dev/gpiochip0"), vec![3]),
+                (PathBuf::from("/dev/gpiochip1"), vec![4])
+            ])
+        );
+        assert_eq!(cfg.offsets, &[3]);
     }
 
     fn sorted(s: &[u32]) -> Vec<u32> {
@@ -1011,6 +1213,43 @@ mod tests {
         assert!(cfg.lcfg.contains_key(&3));
         assert!(cfg.lcfg.contains_key(&5));
         assert!(cfg.lcfg.contains_key(&7));
+
+        // add some from multiple chips
+        let mut cfg = Config::default();
+        let mut lines: HashMap<&str, FoundLine> = [
+            ("two", 2.into()),
+            ("four", 4.into()),
+            ("six", 6.into()),
+            ("eight", 8.into()),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        lines.get_mut("two").unwrap().chip = PathBuf::from("/dev/gpiochip10");
+        lines.get_mut("four").unwrap().chip = PathBuf::from("/dev/gpiochip10");
+        lines.get_mut("six").unwrap().chip = PathBuf::from("/dev/gpiochip2");
+        lines.get_mut("eight").unwrap().chip = PathBuf::from("/dev/gpiochip2");
+        assert_eq!(
+            cfg.with_found_lines(&lines).unwrap_err(),
+            Error::MultipleChips(vec![
+                (PathBuf::from("/dev/gpiochip2"), vec![6, 8]),
+                (PathBuf::from("/dev/gpiochip10"), vec![2, 4])
+            ])
+        );
+        // and nothing is added
+        assert!(cfg.chip.as_os_str().is_empty());
+        assert!(cfg.offsets.is_empty());
+
+        // and with the config chip already set
+        cfg.on_chip("/dev/gpiochip2").with_line(1);
+        assert_eq!(
+            cfg.with_found_lines(&lines).unwrap_err(),
+            Error::MultipleChips(vec![
+                (PathBuf::from("/dev/gpiochip2"), vec![1, 6, 8]),
+                (PathBuf::from("/dev/gpiochip10"), vec![2, 4])
+            ])
+        );
+        assert_eq!(cfg.offsets, &[1]);
     }
 
     #[test]
@@ -1154,6 +1393,62 @@ mod tests {
         assert_eq!(cfg.unique().unwrap(), lc.unwrap());
     }
 
+    #[test]
+    fn diff() {
+        let mut old = Config::default();
+        old.with_lines(&[1, 2, 3]).as_input();
+        assert!(old.diff(&old).is_empty());
+
+        let mut new = old.clone();
+        new.with_line(2).with_bias(PullUp);
+        new.with_line(4).as_output(Active);
+        new.without_lines(&[3]);
+        let lc3 = old.lcfg.get(&3).unwrap().clone();
+        let lc4 = new.lcfg.get(&4).unwrap().clone();
+        let bias = |from, to| LineConfigDiff::Changed(vec![line::ConfigChange::Bias(from, to)]);
+        assert_eq!(
+            old.diff(&new),
+            [
+                (2, bias(None, Some(PullUp))),
+                (3, LineConfigDiff::Removed(lc3.clone())),
+                (4, LineConfigDiff::Added(lc4.clone())),
+            ]
+        );
+        assert_eq!(
+            new.diff(&old),
+            [
+                (2, bias(Some(PullUp), None)),
+                (4, LineConfigDiff::Removed(lc4)),
+                (3, LineConfigDiff::Added(lc3)),
+            ]
+        );
+
+        // empty configs
+        let empty = Config::default();
+        assert_eq!(empty.diff(&old).len(), 3);
+        assert_eq!(old.diff(&empty).len(), 3);
+    }
+
+    #[test]
+    fn line_config_diff_display() {
+        assert_eq!(
+            LineConfigDiff::Added(line::Config::default()).to_string(),
+            "added"
+        );
+        assert_eq!(
+            LineConfigDiff::Removed(line::Config::default()).to_string(),
+            "removed"
+        );
+        let diff = LineConfigDiff::Changed(vec![
+            line::ConfigChange::Direction(Some(Input), Some(Output)),
+            line::ConfigChange::Value(None, Some(Active)),
+        ]);
+        assert_eq!(
+            diff.to_string(),
+            "direction: Input -> Output, value: none -> Active"
+        );
+    }
+
     #[test]
     fn overlay() {
         let mut bottom = Config::default();
@@ -1215,6 +1510,24 @@ mod tests {
         assert_eq!(values.get(2), 0);
     }
 
+    #[test]
+    fn check_num_lines() {
+        let mut cfg = Config::default();
+        let offsets: Vec<Offset> = (0..64).collect();
+        cfg.with_lines(&offsets).as_input();
+        assert!(cfg.check_num_lines().is_ok());
+
+        // lines beyond the bitmap width are rejected rather than mis-grouped
+        cfg.with_line(64).as_output(Value::Active);
+        let err =
+            Error::InvalidArgument("Requested 65 lines is greater than the maximum of 64.".into());
+        assert_eq!(cfg.check_num_lines(), Err(err.clone()));
+        #[cfg(any(feature = "uapi_v2", not(feature = "uapi_v1")))]
+        assert_eq!(cfg.to_v2().err(), Some(err.clone()));
+        #[cfg(feature = "uapi_v1")]
+        assert_eq!(cfg.to_v1_values().err(), Some(err));
+    }
+
     #[test]
     #[cfg(any(feature = "uapi_v2", not(feature = "uapi_v1")))]
     fn to_v2() {
@@ -2,10 +2,11 @@
 //
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use crate::line::EdgeEvent;
+use crate::line::{EdgeDetection, EdgeEvent, EdgeKind, Offset, Value, Values};
 use crate::{Request, Result};
 use std::cmp::max;
-use std::time::Duration;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
 
 /// A user space buffer for reading edge events in bulk from a [`Request`].
 ///
@@ -25,10 +26,14 @@ pub struct EdgeEventBuffer<'a> {
 
     /// The buffer for uAPI edge events, sized by event size and capacity
     buf: Vec<u64>,
+
+    /// True if the most recent read from the kernel filled the buffer, or if no read
+    /// has been performed, and so the kernel may hold more events.
+    last_read_full: bool,
 }
 
 impl EdgeEventBuffer<'_> {
-    pub(super) fn new(req: &Request, event_size: usize, capacity: usize) -> EdgeEventBuffer {
+    pub(super) fn new(req: &Request, event_size: usize, capacity: usize) -> EdgeEventBuffer<'_> {
         debug_assert!(event_size % 8 == 0);
         let event_u64_size = event_size / 8;
         EdgeEventBuffer {
@@ -37,6 +42,7 @@ impl EdgeEventBuffer<'_> {
             filled: 0,
             read: 0,
             buf: vec![0_u64; max(capacity, 1) * event_u64_size],
+            last_read_full: true,
         }
     }
 
@@ -60,6 +66,24 @@ impl EdgeEventBuffer<'_> {
         self.read >= self.filled
     }
 
+    /// Returns true if the kernel may have events available to read.
+    ///
+    /// This is the case when the buffer is empty and the most recent read from the
+    /// kernel filled the buffer, or no read has been performed yet.
+    ///
+    /// If this returns false then the most recent read from the kernel was short,
+    /// so the kernel had no further events at that time.
+    ///
+    /// This does not check the kernel, so is useful for edge-triggered pollers, such as
+    /// `epoll` with `EPOLLET`, which must read until the kernel is drained before
+    /// waiting for the next readiness notification.
+    /// Use [`has_event`] to check the kernel.
+    ///
+    /// [`has_event`]: #method.has_event
+    pub fn kernel_may_have_events(&self) -> bool {
+        self.is_empty() && self.last_read_full
+    }
+
     /// Returns true when either the buffer, or the request, has edge events available to read.
     pub fn has_event(&mut self) -> Result<bool> {
         if self.read < self.filled {
@@ -83,6 +107,124 @@ impl EdgeEventBuffer<'_> {
             self.read = evt_end;
             return self.req.edge_event_from_slice(evt);
         }
+        self.fill()?;
+        self.read = self.event_u64_size;
+        self.req
+            .edge_event_from_slice(&self.buf[0..self.event_u64_size])
+    }
+
+    /// Read the next event from the buffer into an existing event.
+    ///
+    /// An in-place variant of [`read_event`], which overwrites `event` rather than
+    /// returning a new event.
+    ///
+    /// `event` is unchanged if an error is returned.
+    ///
+    /// [`read_event`]: #method.read_event
+    pub fn read_event_into(&mut self, event: &mut EdgeEvent) -> Result<()> {
+        *event = self.read_event()?;
+        Ok(())
+    }
+
+    /// Returns the next event without consuming it.
+    ///
+    /// A subsequent [`read_event`] returns the same event.
+    ///
+    /// If the buffer is empty then events are read from the request, if available.
+    /// Does not block - returns None if no events are available.
+    ///
+    /// [`read_event`]: #method.read_event
+    pub fn peek_event(&mut self) -> Result<Option<EdgeEvent>> {
+        if self.is_empty() {
+            if !self.req.has_edge_event()? {
+                return Ok(None);
+            }
+            self.fill()?;
+        }
+        let evt_end = self.read + self.event_u64_size;
+        self.req
+            .edge_event_from_slice(&self.buf[self.read..evt_end])
+            .map(Some)
+    }
+
+    /// Read all the events available from the buffer and the kernel, passing each to `f`.
+    ///
+    /// Reads from the kernel until a read returns fewer events than the buffer capacity,
+    /// indicating that the kernel has no further events.
+    /// This is the correct pattern for edge-triggered pollers, such as `epoll` with
+    /// `EPOLLET`, as the kernel will not signal readiness again until new events arrive.
+    ///
+    /// Does not block if no events are available.
+    ///
+    /// Returns the number of events passed to `f`.
+    pub fn read_until_empty<F: FnMut(EdgeEvent)>(&mut self, mut f: F) -> Result<usize> {
+        let mut count = 0;
+        loop {
+            while self.read < self.filled {
+                f(self.read_event()?);
+                count += 1;
+            }
+            if !self.kernel_may_have_events() || !self.req.has_edge_event()? {
+                return Ok(count);
+            }
+            self.fill()?;
+        }
+    }
+
+    /// Read all the events currently available from the buffer and the kernel.
+    ///
+    /// Reads until [`has_event`] returns false, so events that arrive while draining
+    /// are also returned.
+    ///
+    /// Does not block - returns an empty Vec if no events are available.
+    ///
+    /// [`has_event`]: #method.has_event
+    pub fn drain(&mut self) -> Result<Vec<EdgeEvent>> {
+        let mut events = Vec::new();
+        while self.has_event()? {
+            events.push(self.read_event()?);
+        }
+        Ok(events)
+    }
+
+    /// Discard any unread events in the buffer.
+    ///
+    /// Events that may be buffered in the kernel are unaffected - use [`drain_kernel`]
+    /// to discard those as well.
+    ///
+    /// [`drain_kernel`]: #method.drain_kernel
+    pub fn clear(&mut self) {
+        // decode the events so they are not mistaken for events dropped by the kernel.
+        while self.read < self.filled {
+            _ = self.read_event();
+        }
+    }
+
+    /// Discard all the events currently available from the buffer and the kernel.
+    ///
+    /// Useful for discarding stale events, such as after reconfiguring the edge detection
+    /// of the lines.
+    ///
+    /// Reads until the kernel has no events available, so events that arrive while
+    /// draining are also discarded.
+    ///
+    /// Does not block if no events are available.
+    ///
+    /// Returns the number of events discarded from the kernel, not including any
+    /// events that were already in the buffer.
+    pub fn drain_kernel(&mut self) -> Result<usize> {
+        self.clear();
+        let mut count = 0;
+        while self.req.has_edge_event()? {
+            self.fill()?;
+            count += self.len();
+            self.clear();
+        }
+        Ok(count)
+    }
+
+    // Refill the buffer from the kernel, discarding any unread events.
+    fn fill(&mut self) -> Result<()> {
         self.read = 0;
         self.filled = 0;
         let n = self.req.read_edge_events_into_slice(&mut self.buf)?;
@@ -91,17 +233,60 @@ impl EdgeEventBuffer<'_> {
         assert!(n > 0);
         assert_eq!(n % (self.event_u64_size), 0);
         self.filled = n;
-        self.read = self.event_u64_size;
-        self.req
-            .edge_event_from_slice(&self.buf[0..self.event_u64_size])
+        self.last_read_full = n == self.buf.len();
+        Ok(())
     }
 
     /// Wait for an edge event from the request.
     ///
+    /// Returns the next event, or None if the timeout expired with no event available.
+    ///
+    /// An event already in the buffer is returned immediately, without waiting on the
+    /// request.
+    ///
     /// * `timeout` - The maximum time to wait for an event.
-    pub fn wait_event(&mut self, timeout: Duration) -> Result<EdgeEvent> {
-        self.req.wait_edge_event(timeout)?;
-        self.read_event()
+    ///   If the wait is interrupted by a signal it is resumed for the remainder
+    ///   of the timeout, so the total wait never exceeds the timeout.
+    pub fn wait_event(&mut self, timeout: Duration) -> Result<Option<EdgeEvent>> {
+        if self.is_empty() && !self.req.wait_edge_event(timeout)? {
+            return Ok(None);
+        }
+        self.read_event().map(Some)
+    }
+}
+
+impl<'a> EdgeEventBuffer<'a> {
+    /// An iterator over the events, which blocks until events are available.
+    ///
+    /// This behaves the same as using the buffer itself as an iterator, but borrows the
+    /// buffer, so the buffer can continue to be used after the iterator is dropped.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use gpiocdev::line::EdgeDetection;
+    /// # fn example() -> Result<(), gpiocdev::Error> {
+    /// let req = gpiocdev::Request::builder()
+    ///     .on_chip("/dev/gpiochip0")
+    ///     .with_line(5)
+    ///     .with_edge_detection(EdgeDetection::BothEdges)
+    ///     .request()?;
+    /// let mut buf = req.new_edge_event_buffer(4);
+    /// for evt in buf.iter().take(10) {
+    ///     println!("{:?}", evt?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter(&mut self) -> EdgeEventIter<'_, 'a> {
+        EdgeEventIter { buf: self }
+    }
+
+    /// An iterator over the events currently available, which ends, rather than
+    /// blocks, when no more events are available.
+    ///
+    /// Events that arrive during the iteration are also returned.
+    pub fn try_iter(&mut self) -> EdgeEventTryIter<'_, 'a> {
+        EdgeEventTryIter { buf: self }
     }
 }
 
@@ -113,3 +298,1007 @@ impl Iterator for EdgeEventBuffer<'_> {
         Some(self.read_event())
     }
 }
+
+/// A blocking iterator over the events in an [`EdgeEventBuffer`].
+///
+/// Created by [`EdgeEventBuffer::iter`].
+pub struct EdgeEventIter<'b, 'a> {
+    buf: &'b mut EdgeEventBuffer<'a>,
+}
+
+impl Iterator for EdgeEventIter<'_, '_> {
+    type Item = Result<EdgeEvent>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.buf.read_event())
+    }
+}
+
+/// A non-blocking iterator over the events in an [`EdgeEventBuffer`].
+///
+/// Created by [`EdgeEventBuffer::try_iter`].
+pub struct EdgeEventTryIter<'b, 'a> {
+    buf: &'b mut EdgeEventBuffer<'a>,
+}
+
+impl Iterator for EdgeEventTryIter<'_, '_> {
+    type Item = Result<EdgeEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.buf.has_event() {
+            Ok(true) => Some(self.buf.read_event()),
+            Ok(false) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// An iterator over edge events that also provides the time since the previous
+/// event on the same line.
+///
+/// Created by [`Request::edge_events_with_delta`].
+pub struct EdgeEventDeltas<'a> {
+    events: EdgeEventBuffer<'a>,
+
+    timer: EdgeEventTimer,
+}
+
+impl EdgeEventDeltas<'_> {
+    pub(super) fn new(events: EdgeEventBuffer<'_>) -> EdgeEventDeltas<'_> {
+        EdgeEventDeltas {
+            events,
+            timer: EdgeEventTimer::new(),
+        }
+    }
+}
+
+impl Iterator for EdgeEventDeltas<'_> {
+    type Item = Result<(EdgeEvent, Option<Duration>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.events.read_event().map(|evt| {
+            let delta = self.timer.delta(&evt);
+            (evt, delta)
+        }))
+    }
+}
+
+/// An iterator over edge events that are debounced in user space.
+///
+/// Created by [`Request::debounced_edge_events`].
+pub struct DebouncedEdgeEvents<'a> {
+    events: EdgeEventBuffer<'a>,
+
+    debouncer: Debouncer,
+}
+
+impl<'a> DebouncedEdgeEvents<'a> {
+    pub(super) fn new(events: EdgeEventBuffer<'a>, period: Duration) -> DebouncedEdgeEvents<'a> {
+        DebouncedEdgeEvents {
+            events,
+            debouncer: Debouncer::new(period),
+        }
+    }
+
+    /// Set the debounce period for all lines.
+    ///
+    /// Replaces any periods previously set for individual lines.
+    ///
+    /// Takes effect from the next edge on each line.
+    pub fn set_period(&mut self, period: Duration) {
+        self.debouncer.set_period(period);
+    }
+
+    /// Set the debounce period for an individual line.
+    ///
+    /// Takes effect from the next edge on the line.
+    pub fn set_line_period(&mut self, offset: Offset, period: Duration) {
+        self.debouncer.set_line_period(offset, period);
+    }
+
+    /// Limit the rate of the debounced events.
+    ///
+    /// Equivalent to [`Request::coalesced_edge_events`], with the events debounced
+    /// before being coalesced.
+    pub fn coalesced(self, max_rate: Duration) -> CoalescedEdgeEvents<'a> {
+        CoalescedEdgeEvents {
+            events: self.events,
+            coalescer: Coalescer::new(max_rate).with_debouncer(self.debouncer),
+        }
+    }
+
+    fn read_event(&mut self) -> Result<EdgeEvent> {
+        loop {
+            if let Some(evt) = self.debouncer.pop(Instant::now()) {
+                return Ok(evt);
+            }
+            match self.debouncer.next_deadline() {
+                None => {
+                    let evt = self.events.read_event()?;
+                    self.debouncer.push(evt, Instant::now());
+                }
+                Some(deadline) => {
+                    let timeout = deadline.saturating_duration_since(Instant::now());
+                    if self.events.is_empty() && !self.events.req.wait_edge_event(timeout)? {
+                        continue;
+                    }
+                    let evt = self.events.read_event()?;
+                    self.debouncer.push(evt, Instant::now());
+                }
+            }
+        }
+    }
+}
+
+impl Iterator for DebouncedEdgeEvents<'_> {
+    type Item = Result<EdgeEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.read_event())
+    }
+}
+
+// The debounce state machine, separated from the reading of events so it can be tested
+// without a request.
+pub(crate) struct Debouncer {
+    /// The period for lines without a period of their own.
+    period: Duration,
+
+    /// The periods for individual lines.
+    line_periods: HashMap<Offset, Duration>,
+
+    /// The lines that have seen an edge and are waiting to settle.
+    pending: HashMap<Offset, Burst>,
+
+    /// The events for lines that have settled, in the order they settled.
+    settled: VecDeque<EdgeEvent>,
+}
+
+// A burst of edges on a line, separated by less than the debounce period.
+struct Burst {
+    /// The first edge in the burst, which identifies the state of the line before
+    /// the burst, and when the line started to change.
+    first: EdgeEvent,
+
+    /// The most recent edge in the burst, which identifies the state of the line
+    /// after the burst.
+    latest: EdgeEvent,
+
+    /// When the line is considered settled, if no further edges are seen.
+    deadline: Instant,
+}
+
+impl Burst {
+    // A burst that returns the line to its original state does not generate an event.
+    //
+    // When only one kind of edge is being detected the state of the line cannot be
+    // determined, and every burst generates an event.
+    //
+    // The event has the timestamp and seqnos of the first edge, as that is when the
+    // line changed, and the kind of the edge that settled the line.
+    fn settle(self, settled: &mut VecDeque<EdgeEvent>) {
+        if self.latest.kind == self.first.kind {
+            settled.push_back(EdgeEvent {
+                kind: self.latest.kind,
+                ..self.first
+            });
+        }
+    }
+}
+
+impl Debouncer {
+    pub(crate) fn new(period: Duration) -> Debouncer {
+        Debouncer {
+            period,
+            line_periods: HashMap::new(),
+            pending: HashMap::new(),
+            settled: VecDeque::new(),
+        }
+    }
+
+    // Set the period for all lines, dropping any per-line periods.
+    //
+    // Lines that are already bouncing keep their current deadline.
+    pub(crate) fn set_period(&mut self, period: Duration) {
+        self.period = period;
+        self.line_periods.clear();
+    }
+
+    pub(crate) fn set_line_period(&mut self, offset: Offset, period: Duration) {
+        self.line_periods.insert(offset, period);
+    }
+
+    fn period(&self, offset: Offset) -> Duration {
+        self.line_periods
+            .get(&offset)
+            .copied()
+            .unwrap_or(self.period)
+    }
+
+    // Add an edge event, received at `now`.
+    pub(crate) fn push(&mut self, evt: EdgeEvent, now: Instant) {
+        let period = self.period(evt.offset);
+        let deadline = now + period;
+        if let Some(burst) = self.pending.get_mut(&evt.offset) {
+            // The event timestamps are a better indicator of the time between edges
+            // than when the events were received, as events may be read in bulk.
+            let gap = evt
+                .timestamp_ns
+                .checked_sub(burst.latest.timestamp_ns)
+                .map(Duration::from_nanos);
+            if gap.map_or(true, |gap| gap < period) {
+                burst.latest = evt;
+                burst.deadline = deadline;
+                return;
+            }
+            // the line settled before this edge
+            self.pending
+                .remove(&evt.offset)
+                .unwrap()
+                .settle(&mut self.settled);
+        }
+        self.pending.insert(
+            evt.offset,
+            Burst {
+                first: evt,
+                latest: evt,
+                deadline,
+            },
+        );
+    }
+
+    // Return the next settled event, if any, as of `now`.
+    pub(crate) fn pop(&mut self, now: Instant) -> Option<EdgeEvent> {
+        if self.settled.is_empty() {
+            let mut expired: Vec<Offset> = self
+                .pending
+                .iter()
+                .filter(|(_, burst)| burst.deadline <= now)
+                .map(|(offset, _)| *offset)
+                .collect();
+            expired.sort_by_key(|offset| self.pending[offset].deadline);
+            for offset in expired {
+                self.pending
+                    .remove(&offset)
+                    .unwrap()
+                    .settle(&mut self.settled);
+            }
+        }
+        self.settled.pop_front()
+    }
+
+    // The time at which the next pending line will settle, if any.
+    pub(crate) fn next_deadline(&self) -> Option<Instant> {
+        self.pending.values().map(|burst| burst.deadline).min()
+    }
+}
+
+/// An iterator over the levels of the requested lines, updated by edge events.
+///
+/// Created by [`Request::level_changes`].
+pub struct LevelChanges<'a> {
+    events: EdgeEventBuffer<'a>,
+
+    levels: Levels,
+}
+
+impl LevelChanges<'_> {
+    pub(super) fn new(events: EdgeEventBuffer<'_>) -> LevelChanges<'_> {
+        LevelChanges {
+            events,
+            levels: Levels::new(),
+        }
+    }
+
+    fn read_level(&mut self) -> Result<(Offset, Value)> {
+        self.levels.snapshot(&mut self.events)?;
+        loop {
+            if let Some(level) = self.levels.pop_initial() {
+                return Ok(level);
+            }
+            let evt = self.events.read_event()?;
+            if let Some(level) = self.levels.update(&evt) {
+                return Ok(level);
+            }
+        }
+    }
+}
+
+impl Iterator for LevelChanges<'_> {
+    type Item = Result<(Offset, Value)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.read_level())
+    }
+}
+
+// The level tracking state machine, shared by the iterator and async streams.
+pub(crate) struct Levels {
+    /// The initial levels of the lines, yet to be returned.
+    ///
+    /// None until the snapshot is taken.
+    initial: Option<VecDeque<(Offset, Value)>>,
+
+    /// The most recently reported level of each line detecting both edges.
+    tracked: HashMap<Offset, Value>,
+}
+
+impl Levels {
+    pub(crate) fn new() -> Levels {
+        Levels {
+            initial: None,
+            tracked: HashMap::new(),
+        }
+    }
+
+    // Take the snapshot of the initial levels of the lines, if not already taken.
+    //
+    // Any events already available precede the snapshot, so they are discarded.
+    // Events for edges that occur after the events are discarded but before the values
+    // are read are also reflected in the snapshot, and are filtered out by update().
+    pub(crate) fn snapshot(&mut self, events: &mut EdgeEventBuffer) -> Result<()> {
+        if self.initial.is_some() {
+            return Ok(());
+        }
+        events.drain_kernel()?;
+        let cfg = events.req.config();
+        let mut offsets = Vec::new();
+        for &offset in cfg.lines().iter() {
+            match cfg.line_config(offset).and_then(|lc| lc.edge_detection) {
+                Some(EdgeDetection::BothEdges) => {
+                    offsets.push(offset);
+                    self.tracked.insert(offset, Value::Inactive);
+                }
+                Some(_) => offsets.push(offset),
+                None => {}
+            }
+        }
+        let mut values = Values::from_offsets(&offsets);
+        events.req.values(&mut values)?;
+        let mut initial = VecDeque::new();
+        for lv in values.iter() {
+            if let Some(v) = self.tracked.get_mut(&lv.offset) {
+                *v = lv.value;
+            }
+            initial.push_back((lv.offset, lv.value));
+        }
+        self.initial = Some(initial);
+        Ok(())
+    }
+
+    pub(crate) fn pop_initial(&mut self) -> Option<(Offset, Value)> {
+        self.initial
+            .as_mut()
+            .and_then(|initial| initial.pop_front())
+    }
+
+    // Convert an edge event to the level of the line after the edge.
+    //
+    // Returns None if the line is known to already be at that level.
+    //
+    // Only lines detecting both edges are tracked - lines detecting a single kind of
+    // edge must have passed through the other level unseen, so every edge is reported.
+    pub(crate) fn update(&mut self, evt: &EdgeEvent) -> Option<(Offset, Value)> {
+        let value = match evt.kind {
+            EdgeKind::Rising => Value::Active,
+            EdgeKind::Falling => Value::Inactive,
+        };
+        if let Some(v) = self.tracked.get_mut(&evt.offset) {
+            if *v == value {
+                return None;
+            }
+            *v = value;
+        }
+        Some((evt.offset, value))
+    }
+}
+
+/// An edge event delivered by a rate limited iterator or stream.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CoalescedEdgeEvent {
+    /// The most recent event on the line.
+    pub event: EdgeEvent,
+
+    /// The number of events on the line discarded since the previous delivered event.
+    pub suppressed: u32,
+}
+
+/// An iterator over edge events that are rate limited in user space.
+///
+/// Created by [`Request::coalesced_edge_events`] or [`DebouncedEdgeEvents::coalesced`].
+pub struct CoalescedEdgeEvents<'a> {
+    events: EdgeEventBuffer<'a>,
+
+    coalescer: Coalescer,
+}
+
+impl CoalescedEdgeEvents<'_> {
+    pub(super) fn new(events: EdgeEventBuffer<'_>, max_rate: Duration) -> CoalescedEdgeEvents<'_> {
+        CoalescedEdgeEvents {
+            events,
+            coalescer: Coalescer::new(max_rate),
+        }
+    }
+
+    fn read_event(&mut self) -> Result<CoalescedEdgeEvent> {
+        loop {
+            if let Some(evt) = self.coalescer.pop(Instant::now()) {
+                return Ok(evt);
+            }
+            if let Some(deadline) = self.coalescer.next_deadline() {
+                let timeout = deadline.saturating_duration_since(Instant::now());
+                if self.events.is_empty() && !self.events.req.wait_edge_event(timeout)? {
+                    continue;
+                }
+            }
+            let evt = self.events.read_event()?;
+            self.coalescer.push(evt, Instant::now());
+        }
+    }
+}
+
+impl Iterator for CoalescedEdgeEvents<'_> {
+    type Item = Result<CoalescedEdgeEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.read_event())
+    }
+}
+
+// The rate limiting state machine, separated from the reading of events so it can be
+// tested without a request.
+//
+// The first event on a line is delivered immediately, and opens a window of the
+// period during which subsequent events on the line are coalesced.
+// When the window closes the most recent of those events, if any, is delivered and
+// a new window opened.
+// A line is idle once a window closes without any events.
+pub(crate) struct Coalescer {
+    /// The minimum time between events delivered for a line.
+    period: Duration,
+
+    /// Debounces the events before they are coalesced.
+    debouncer: Option<Debouncer>,
+
+    /// The lines with an open window.
+    windows: HashMap<Offset, Window>,
+
+    /// The events ready to be delivered, in the order they were ready.
+    ready: VecDeque<CoalescedEdgeEvent>,
+}
+
+// A window during which events on a line are coalesced.
+struct Window {
+    /// When the window closes.
+    end: Instant,
+
+    /// The most recent event in the window, if any.
+    latest: Option<EdgeEvent>,
+
+    /// The number of events in the window replaced by a later event.
+    suppressed: u32,
+}
+
+impl Coalescer {
+    pub(crate) fn new(period: Duration) -> Coalescer {
+        Coalescer {
+            period,
+            debouncer: None,
+            windows: HashMap::new(),
+            ready: VecDeque::new(),
+        }
+    }
+
+    pub(crate) fn with_debouncer(mut self, debouncer: Debouncer) -> Coalescer {
+        self.debouncer = Some(debouncer);
+        self
+    }
+
+    // Add an edge event, received at `now`.
+    pub(crate) fn push(&mut self, evt: EdgeEvent, now: Instant) {
+        match &mut self.debouncer {
+            Some(debouncer) => debouncer.push(evt, now),
+            None => self.coalesce(evt, now),
+        }
+    }
+
+    fn coalesce(&mut self, event: EdgeEvent, now: Instant) {
+        self.close_windows(now);
+        match self.windows.get_mut(&event.offset) {
+            Some(window) => {
+                if window.latest.replace(event).is_some() {
+                    window.suppressed += 1;
+                }
+            }
+            None => {
+                self.ready.push_back(CoalescedEdgeEvent {
+                    event,
+                    suppressed: 0,
+                });
+                self.windows.insert(
+                    event.offset,
+                    Window {
+                        end: now + self.period,
+                        latest: None,
+                        suppressed: 0,
+                    },
+                );
+            }
+        }
+    }
+
+    // Close any windows that end before or at `now`, in the order they end.
+    fn close_windows(&mut self, now: Instant) {
+        while let Some((&offset, _)) = self
+            .windows
+            .iter()
+            .filter(|(_, window)| window.end <= now)
+            .min_by_key(|(_, window)| window.end)
+        {
+            let window = self.windows.get_mut(&offset).unwrap();
+            match window.latest.take() {
+                Some(event) => {
+                    self.ready.push_back(CoalescedEdgeEvent {
+                        event,
+                        suppressed: window.suppressed,
+                    });
+                    window.suppressed = 0;
+                    window.end += self.period;
+                }
+                None => {
+                    self.windows.remove(&offset);
+                }
+            }
+        }
+    }
+
+    // Return the next event to be delivered, if any, as of `now`.
+    pub(crate) fn pop(&mut self, now: Instant) -> Option<CoalescedEdgeEvent> {
+        if self.ready.is_empty() {
+            if let Some(debouncer) = &mut self.debouncer {
+                let mut settled = Vec::new();
+                while let Some(evt) = debouncer.pop(now) {
+                    settled.push(evt);
+                }
+                for evt in settled {
+                    self.coalesce(evt, now);
+                }
+            }
+            self.close_windows(now);
+        }
+        self.ready.pop_front()
+    }
+
+    // The time at which the next event may be ready, if any.
+    pub(crate) fn next_deadline(&self) -> Option<Instant> {
+        let window = self
+            .windows
+            .values()
+            .filter(|window| window.latest.is_some())
+            .map(|window| window.end)
+            .min();
+        let debounce = self.debouncer.as_ref().and_then(|d| d.next_deadline());
+        match (window, debounce) {
+            (Some(w), Some(d)) => Some(w.min(d)),
+            (w, d) => w.or(d),
+        }
+    }
+}
+
+/// Determines the time between edge events on each line.
+///
+/// Provides the deltas for [`Request::edge_events_with_delta`], and can be applied to
+/// events from any source, such as the async streams or a captured trace.
+///
+/// Deltas are calculated from the event timestamps, and only between events on the
+/// same line, so lines may use different event clocks.
+///
+/// # Examples
+/// ```no_run
+/// # fn example() -> Result<(), gpiocdev::Error> {
+/// # use gpiocdev::Request;
+/// # use gpiocdev::line::EdgeDetection;
+/// # use gpiocdev::request::EdgeEventTimer;
+/// let req = Request::builder()
+///     .on_chip("/dev/gpiochip0")
+///     .with_line(23)
+///     .with_edge_detection(EdgeDetection::RisingEdge)
+///     .request()?;
+///
+/// let mut timer = EdgeEventTimer::new();
+/// for event in req.new_edge_event_buffer(64) {
+///     let event = event?;
+///     if let Some(period) = timer.delta(&event) {
+///         println!("line {} period {:?}", event.offset, period);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct EdgeEventTimer {
+    /// The timestamp of the most recent event on each line.
+    last: HashMap<Offset, u64>,
+}
+
+impl EdgeEventTimer {
+    /// Create a timer with no previous events.
+    pub fn new() -> EdgeEventTimer {
+        EdgeEventTimer::default()
+    }
+
+    /// Record the timestamp of the event and return the time since the previous event
+    /// on the same line.
+    ///
+    /// Returns None for the first event on the line, or if the timestamp has gone
+    /// backwards, as can occur with the realtime clock.
+    pub fn delta(&mut self, event: &EdgeEvent) -> Option<Duration> {
+        let prev = self.last.insert(event.offset, event.timestamp_ns)?;
+        event
+            .timestamp_ns
+            .checked_sub(prev)
+            .map(Duration::from_nanos)
+    }
+
+    /// Forget the previous events on all lines.
+    ///
+    /// Useful after changing the event clock of the lines, as timestamps from
+    /// different clocks cannot be compared.
+    pub fn reset(&mut self) {
+        self.last.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(offset: Offset, timestamp_ns: u64) -> EdgeEvent {
+        EdgeEvent {
+            timestamp_ns,
+            kind: crate::line::EdgeKind::Rising,
+            offset,
+            seqno: 0,
+            line_seqno: 0,
+        }
+    }
+
+    fn edge(offset: Offset, timestamp_ns: u64, kind: EdgeKind) -> EdgeEvent {
+        EdgeEvent {
+            kind,
+            ..event(offset, timestamp_ns)
+        }
+    }
+
+    mod debouncer {
+        use super::*;
+        use EdgeKind::*;
+
+        const PERIOD: Duration = Duration::from_millis(10);
+
+        fn seq_edge(timestamp_ns: u64, kind: EdgeKind, seqno: u32) -> EdgeEvent {
+            EdgeEvent {
+                seqno,
+                line_seqno: seqno,
+                ..edge(1, timestamp_ns, kind)
+            }
+        }
+
+        #[test]
+        fn single_edge() {
+            let mut d = Debouncer::new(PERIOD);
+            let t0 = Instant::now();
+            d.push(edge(1, 1000, Rising), t0);
+            assert_eq!(d.next_deadline(), Some(t0 + PERIOD));
+            assert_eq!(d.pop(t0), None);
+            assert_eq!(d.pop(t0 + PERIOD), Some(edge(1, 1000, Rising)));
+            assert_eq!(d.next_deadline(), None);
+            assert_eq!(d.pop(t0 + PERIOD), None);
+        }
+
+        #[test]
+        fn bounce_settles_to_new_state() {
+            let mut d = Debouncer::new(PERIOD);
+            let t0 = Instant::now();
+            d.push(seq_edge(1000, Rising, 1), t0);
+            d.push(seq_edge(2000, Falling, 2), t0 + PERIOD / 2);
+            d.push(seq_edge(3000, Rising, 3), t0 + PERIOD);
+            // deadline is extended by each edge
+            assert_eq!(d.pop(t0 + PERIOD), None);
+            assert_eq!(d.next_deadline(), Some(t0 + 2 * PERIOD));
+            // emits the settled state, timestamped from the first edge
+            let evt = d.pop(t0 + 2 * PERIOD).unwrap();
+            assert_eq!(evt.timestamp_ns, 1000);
+            assert_eq!(evt, seq_edge(1000, Rising, 1));
+
+            // settling into a falling edge
+            d.push(seq_edge(20000, Falling, 4), t0 + 2 * PERIOD);
+            d.push(seq_edge(21000, Rising, 5), t0 + 2 * PERIOD);
+            d.push(seq_edge(22000, Falling, 6), t0 + 2 * PERIOD);
+            let evt = d.pop(t0 + 3 * PERIOD).unwrap();
+            assert_eq!(evt.timestamp_ns, 20000);
+            assert_eq!(evt, seq_edge(20000, Falling, 4));
+        }
+
+        #[test]
+        fn bounce_settles_to_original_state() {
+            let mut d = Debouncer::new(PERIOD);
+            let t0 = Instant::now();
+            d.push(edge(1, 1000, Rising), t0);
+            d.push(edge(1, 2000, Falling), t0);
+            assert_eq!(d.pop(t0 + PERIOD), None);
+            assert_eq!(d.next_deadline(), None);
+        }
+
+        #[test]
+        fn single_kind_edges() {
+            let mut d = Debouncer::new(PERIOD);
+            let t0 = Instant::now();
+            d.push(edge(1, 1000, Rising), t0);
+            d.push(edge(1, 2000, Rising), t0);
+            let evt = d.pop(t0 + PERIOD).unwrap();
+            assert_eq!(evt.timestamp_ns, 1000);
+            assert_eq!(evt, edge(1, 1000, Rising));
+        }
+
+        #[test]
+        fn timestamp_gap_ends_burst() {
+            // events received together, but separated in time
+            let mut d = Debouncer::new(PERIOD);
+            let t0 = Instant::now();
+            let gap = PERIOD.as_nanos() as u64;
+            d.push(edge(1, 1000, Rising), t0);
+            d.push(edge(1, 1000 + gap, Falling), t0);
+            assert_eq!(d.pop(t0), Some(edge(1, 1000, Rising)));
+            assert_eq!(d.pop(t0), None);
+            assert_eq!(d.pop(t0 + PERIOD), Some(edge(1, 1000 + gap, Falling)));
+        }
+
+        #[test]
+        fn lines_are_independent() {
+            let mut d = Debouncer::new(PERIOD);
+            let t0 = Instant::now();
+            d.push(edge(2, 1000, Falling), t0);
+            d.push(edge(1, 1500, Rising), t0 + PERIOD / 2);
+            d.push(edge(2, 2000, Rising), t0 + PERIOD * 3 / 4);
+            d.push(edge(2, 2500, Falling), t0 + PERIOD * 3 / 4);
+            assert_eq!(d.pop(t0 + PERIOD), None);
+            // settled in deadline order
+            let t1 = t0 + 2 * PERIOD;
+            assert_eq!(d.pop(t1), Some(edge(1, 1500, Rising)));
+            assert_eq!(d.pop(t1), Some(edge(2, 1000, Falling)));
+            assert_eq!(d.pop(t1), None);
+        }
+
+        #[test]
+        fn zero_period() {
+            let mut d = Debouncer::new(Duration::ZERO);
+            let t0 = Instant::now();
+            d.push(edge(1, 1000, Rising), t0);
+            d.push(edge(1, 1000, Falling), t0);
+            assert_eq!(d.pop(t0), Some(edge(1, 1000, Rising)));
+            assert_eq!(d.pop(t0), Some(edge(1, 1000, Falling)));
+        }
+
+        #[test]
+        fn line_period() {
+            let mut d = Debouncer::new(PERIOD);
+            d.set_line_period(2, 2 * PERIOD);
+            let t0 = Instant::now();
+            d.push(edge(1, 1000, Rising), t0);
+            d.push(edge(2, 1000, Rising), t0);
+            assert_eq!(d.pop(t0 + PERIOD), Some(edge(1, 1000, Rising)));
+            assert_eq!(d.pop(t0 + PERIOD), None);
+            assert_eq!(d.next_deadline(), Some(t0 + 2 * PERIOD));
+            assert_eq!(d.pop(t0 + 2 * PERIOD), Some(edge(2, 1000, Rising)));
+
+            // the line period also determines the gap that ends a burst
+            let gap = PERIOD.as_nanos() as u64;
+            let t1 = t0 + 2 * PERIOD;
+            d.push(edge(2, 5000, Falling), t1);
+            d.push(edge(2, 5000 + gap, Rising), t1);
+            assert_eq!(d.pop(t1 + 2 * PERIOD), None);
+        }
+
+        #[test]
+        fn set_period() {
+            let mut d = Debouncer::new(PERIOD);
+            d.set_line_period(2, 2 * PERIOD);
+            let t0 = Instant::now();
+            d.push(edge(1, 1000, Rising), t0);
+            d.set_period(3 * PERIOD);
+            // pending lines keep their deadline
+            assert_eq!(d.next_deadline(), Some(t0 + PERIOD));
+            assert_eq!(d.pop(t0 + PERIOD), Some(edge(1, 1000, Rising)));
+            // and the line period is dropped
+            d.push(edge(1, 2000, Falling), t0 + PERIOD);
+            d.push(edge(2, 2000, Falling), t0 + 2 * PERIOD);
+            assert_eq!(d.pop(t0 + 3 * PERIOD), None);
+            assert_eq!(d.pop(t0 + 4 * PERIOD), Some(edge(1, 2000, Falling)));
+            assert_eq!(d.pop(t0 + 4 * PERIOD), None);
+            assert_eq!(d.pop(t0 + 5 * PERIOD), Some(edge(2, 2000, Falling)));
+        }
+    }
+
+    mod coalescer {
+        use super::*;
+        use EdgeKind::*;
+
+        const PERIOD: Duration = Duration::from_millis(10);
+        const MS: Duration = Duration::from_millis(1);
+
+        fn coalesced(event: EdgeEvent, suppressed: u32) -> Option<CoalescedEdgeEvent> {
+            Some(CoalescedEdgeEvent { event, suppressed })
+        }
+
+        #[test]
+        fn first_event_is_immediate() {
+            let mut c = Coalescer::new(PERIOD);
+            let t0 = Instant::now();
+            c.push(edge(1, 1000, Rising), t0);
+            assert_eq!(c.pop(t0), coalesced(edge(1, 1000, Rising), 0));
+            assert_eq!(c.pop(t0), None);
+            // nothing pending, so nothing to wait for
+            assert_eq!(c.next_deadline(), None);
+        }
+
+        #[test]
+        fn burst_is_coalesced() {
+            let mut c = Coalescer::new(PERIOD);
+            let t0 = Instant::now();
+            c.push(edge(1, 1000, Rising), t0);
+            assert_eq!(c.pop(t0), coalesced(edge(1, 1000, Rising), 0));
+            c.push(edge(1, 2000, Falling), t0 + MS);
+            c.push(edge(1, 3000, Rising), t0 + 2 * MS);
+            c.push(edge(1, 4000, Falling), t0 + 3 * MS);
+            assert_eq!(c.pop(t0 + 3 * MS), None);
+            assert_eq!(c.next_deadline(), Some(t0 + PERIOD));
+            // the most recent event, with the count of those it replaced
+            assert_eq!(c.pop(t0 + PERIOD), coalesced(edge(1, 4000, Falling), 2));
+            assert_eq!(c.pop(t0 + PERIOD), None);
+        }
+
+        #[test]
+        fn flush_on_quiescence() {
+            let mut c = Coalescer::new(PERIOD);
+            let t0 = Instant::now();
+            c.push(edge(1, 1000, Rising), t0);
+            assert_eq!(c.pop(t0), coalesced(edge(1, 1000, Rising), 0));
+            c.push(edge(1, 2000, Falling), t0 + MS);
+            // the final state is delivered when the window closes...
+            assert_eq!(c.pop(t0 + PERIOD), coalesced(edge(1, 2000, Falling), 0));
+            assert_eq!(c.next_deadline(), None);
+
+            // ... which opens another window, so an event within it is delayed...
+            let t1 = t0 + PERIOD + MS;
+            c.push(edge(1, 3000, Rising), t1);
+            assert_eq!(c.pop(t1), None);
+            assert_eq!(c.next_deadline(), Some(t0 + 2 * PERIOD));
+            assert_eq!(c.pop(t0 + 2 * PERIOD), coalesced(edge(1, 3000, Rising), 0));
+
+            // ... but once a window closes without events the line is idle
+            let t2 = t0 + 3 * PERIOD;
+            c.push(edge(1, 4000, Falling), t2);
+            assert_eq!(c.pop(t2), coalesced(edge(1, 4000, Falling), 0));
+        }
+
+        #[test]
+        fn lines_are_independent() {
+            let mut c = Coalescer::new(PERIOD);
+            let t0 = Instant::now();
+            c.push(edge(1, 1000, Rising), t0);
+            c.push(edge(2, 1500, Rising), t0 + MS);
+            c.push(edge(1, 2000, Falling), t0 + 2 * MS);
+            c.push(edge(2, 2500, Falling), t0 + 3 * MS);
+            c.push(edge(2, 3500, Rising), t0 + 4 * MS);
+            let t1 = t0 + 4 * MS;
+            assert_eq!(c.pop(t1), coalesced(edge(1, 1000, Rising), 0));
+            assert_eq!(c.pop(t1), coalesced(edge(2, 1500, Rising), 0));
+            assert_eq!(c.pop(t1), None);
+            // delivered in the order the windows close
+            let t2 = t0 + PERIOD + MS;
+            assert_eq!(c.pop(t2), coalesced(edge(1, 2000, Falling), 0));
+            assert_eq!(c.pop(t2), coalesced(edge(2, 3500, Rising), 1));
+            assert_eq!(c.pop(t2), None);
+        }
+
+        #[test]
+        fn with_debouncer() {
+            let mut c = Coalescer::new(4 * PERIOD).with_debouncer(Debouncer::new(PERIOD));
+            let t0 = Instant::now();
+            // bounce settling to the original state is dropped by the debouncer
+            c.push(edge(1, 1000, Rising), t0);
+            c.push(edge(1, 2000, Falling), t0);
+            assert_eq!(c.next_deadline(), Some(t0 + PERIOD));
+            assert_eq!(c.pop(t0 + PERIOD), None);
+            assert_eq!(c.next_deadline(), None);
+
+            // the first settled edge is delivered immediately...
+            let t1 = t0 + PERIOD;
+            c.push(edge(1, 3000, Rising), t1);
+            assert_eq!(c.pop(t1), None);
+            assert_eq!(c.pop(t1 + PERIOD), coalesced(edge(1, 3000, Rising), 0));
+
+            // ... and subsequent settled edges are coalesced
+            let t2 = t1 + PERIOD;
+            c.push(edge(1, 4000, Falling), t2);
+            assert_eq!(c.next_deadline(), Some(t2 + PERIOD));
+            assert_eq!(c.pop(t2 + PERIOD), None);
+            assert_eq!(c.next_deadline(), Some(t1 + 5 * PERIOD));
+            assert_eq!(c.pop(t1 + 5 * PERIOD), coalesced(edge(1, 4000, Falling), 0));
+        }
+
+        #[test]
+        fn zero_period() {
+            let mut c = Coalescer::new(Duration::ZERO);
+            let t0 = Instant::now();
+            c.push(edge(1, 1000, Rising), t0);
+            c.push(edge(1, 1000, Falling), t0);
+            assert_eq!(c.pop(t0), coalesced(edge(1, 1000, Rising), 0));
+            assert_eq!(c.pop(t0), coalesced(edge(1, 1000, Falling), 0));
+            assert_eq!(c.pop(t0), None);
+        }
+    }
+
+    #[test]
+    fn levels_update() {
+        use EdgeKind::*;
+        let mut levels = Levels {
+            initial: Some(VecDeque::new()),
+            tracked: HashMap::from([(1, Value::Inactive)]),
+        };
+        // an edge already reflected in the snapshot is filtered...
+        assert_eq!(levels.update(&edge(1, 1000, Falling)), None);
+        // ... while subsequent edges are reported
+        assert_eq!(
+            levels.update(&edge(1, 2000, Rising)),
+            Some((1, Value::Active))
+        );
+        assert_eq!(levels.update(&edge(1, 2500, Rising)), None);
+        assert_eq!(
+            levels.update(&edge(1, 3000, Falling)),
+            Some((1, Value::Inactive))
+        );
+        // untracked lines report every edge
+        assert_eq!(
+            levels.update(&edge(2, 3000, Rising)),
+            Some((2, Value::Active))
+        );
+        assert_eq!(
+            levels.update(&edge(2, 4000, Rising)),
+            Some((2, Value::Active))
+        );
+    }
+
+    #[test]
+    fn edge_event_timer() {
+        let mut timer = EdgeEventTimer::new();
+        assert_eq!(timer.delta(&event(1, 1000)), None);
+        assert_eq!(timer.delta(&event(2, 1500)), None);
+        assert_eq!(
+            timer.delta(&event(1, 3000)),
+            Some(Duration::from_nanos(2000))
+        );
+        assert_eq!(
+            timer.delta(&event(2, 1600)),
+            Some(Duration::from_nanos(100))
+        );
+        assert_eq!(timer.delta(&event(1, 3000)), Some(Duration::ZERO));
+        // backwards
+        assert_eq!(timer.delta(&event(1, 2000)), None);
+        // and forwards again, relative to the backward event
+        assert_eq!(
+            timer.delta(&event(1, 2500)),
+            Some(Duration::from_nanos(500))
+        );
+        // reset forgets previous events
+        timer.reset();
+        assert_eq!(timer.delta(&event(1, 3000)), None);
+        assert_eq!(timer.delta(&event(2, 3000)), None);
+        assert_eq!(
+            timer.delta(&event(2, 3100)),
+            Some(Duration::from_nanos(100))
+        );
+    }
+}
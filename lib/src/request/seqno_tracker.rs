@@ -0,0 +1,127 @@
+// SPDX-FileCopyrightText: 2024 Kent Gibson <warthog618@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+// Tracks the seqno of edge events decoded from a request to detect events dropped
+// by the kernel when its event buffer overflows.
+//
+// Lock free, as every decoded event is observed.
+#[derive(Debug, Default)]
+pub(super) struct SeqnoTracker {
+    // The most recent seqno observed.
+    last: AtomicU32,
+
+    // The number of events missed since last taken.
+    missed: AtomicU64,
+}
+
+impl SeqnoTracker {
+    // Record the seqno of a decoded event, accumulating any gap from the previous event.
+    //
+    // A zero seqno, as provided by uAPI v1, is ignored, as are seqnos that do not advance
+    // the sequence, such as when an event is decoded more than once.
+    pub(super) fn observe(&self, seqno: u32) {
+        if seqno == 0 {
+            return;
+        }
+        let mut last = self.last.load(Ordering::Acquire);
+        loop {
+            let delta = seqno.wrapping_sub(last);
+            if delta == 0 || delta > u32::MAX / 2 {
+                return;
+            }
+            // each gap is only accumulated by the observer that advances last over it.
+            match self
+                .last
+                .compare_exchange_weak(last, seqno, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => {
+                    self.missed
+                        .fetch_add(u64::from(delta - 1), Ordering::Relaxed);
+                    return;
+                }
+                Err(actual) => last = actual,
+            }
+        }
+    }
+
+    // Return the number of events missed since the previous take, and reset it.
+    pub(super) fn take_missed(&self) -> u64 {
+        self.missed.swap(0, Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contiguous() {
+        let t = SeqnoTracker::default();
+        for seqno in 1..10 {
+            t.observe(seqno);
+        }
+        assert_eq!(t.take_missed(), 0);
+    }
+
+    #[test]
+    fn gaps() {
+        let t = SeqnoTracker::default();
+        // events dropped before the first read
+        t.observe(4);
+        assert_eq!(t.take_missed(), 3);
+        assert_eq!(t.take_missed(), 0);
+        t.observe(5);
+        t.observe(9);
+        t.observe(12);
+        assert_eq!(t.take_missed(), 5);
+    }
+
+    #[test]
+    fn ignores_stale() {
+        let t = SeqnoTracker::default();
+        t.observe(3);
+        t.observe(3);
+        t.observe(2);
+        t.observe(0);
+        t.observe(4);
+        assert_eq!(t.take_missed(), 2);
+    }
+
+    #[test]
+    fn wraps() {
+        let t = SeqnoTracker::default();
+        t.observe(u32::MAX - 1);
+        t.take_missed();
+        t.observe(u32::MAX);
+        t.observe(2);
+        assert_eq!(t.take_missed(), 1);
+    }
+
+    #[test]
+    fn concurrent() {
+        let t = SeqnoTracker::default();
+        // interleaved observers, each missing every fourth event
+        std::thread::scope(|s| {
+            for start in [1, 2] {
+                let t = &t;
+                s.spawn(move || {
+                    for seqno in (start..1000).step_by(2) {
+                        if seqno % 4 != 0 {
+                            t.observe(seqno);
+                        }
+                    }
+                });
+            }
+        });
+        // seqnos made stale by the other observer are counted as missed, but the
+        // gaps are never double counted, and the sequence ends at the latest seqno.
+        let missed = t.take_missed();
+        assert!((249..=998).contains(&missed), "missed {}", missed);
+        t.observe(1000);
+        t.observe(1001);
+        assert_eq!(t.take_missed(), 0);
+    }
+}
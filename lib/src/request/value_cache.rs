@@ -0,0 +1,187 @@
+// SPDX-FileCopyrightText: 2024 Kent Gibson <warthog618@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::line::Values;
+use crate::Result;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// A cache of the most recently read values of the lines in a request.
+//
+// Invalidation only bumps the generation, so the hot paths that invalidate,
+// such as setting values and reading events, never take the lock.
+// Cached values are only valid if read in the current generation.
+#[derive(Debug, Default)]
+pub(super) struct ValueCache {
+    generation: AtomicU64,
+    cache: Mutex<Option<Entry>>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    generation: u64,
+    when: Instant,
+    values: Values,
+}
+
+impl ValueCache {
+    // Return the cached values if read within the ttl, else refresh the cache using `read`.
+    //
+    // The lock is held during the read, so concurrent callers share the one read.
+    pub(super) fn get_or_read<F>(&self, ttl: Duration, read: F) -> Result<Values>
+    where
+        F: FnOnce(&mut Values) -> Result<()>,
+    {
+        let mut cache = self
+            .cache
+            .lock()
+            .expect("failed to acquire lock on value cache");
+        // sampled before the read, so an invalidation during the read discards the result.
+        let generation = self.generation.load(Ordering::Acquire);
+        if let Some(entry) = &*cache {
+            if entry.generation == generation && entry.when.elapsed() < ttl {
+                return Ok(entry.values.clone());
+            }
+        }
+        // timestamp before the read, so values are never considered fresher than they are.
+        let when = Instant::now();
+        let mut values = Values::default();
+        read(&mut values)?;
+        *cache = Some(Entry {
+            generation,
+            when,
+            values: values.clone(),
+        });
+        Ok(values)
+    }
+
+    // Discard any cached values.
+    pub(super) fn invalidate(&self) {
+        self.generation.fetch_add(1, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::line::Value;
+    use crate::Error;
+    use std::cell::Cell;
+
+    // A read that counts calls and returns the count as the value of line 1.
+    fn counting_read(count: &Cell<usize>) -> impl FnOnce(&mut Values) -> Result<()> + '_ {
+        move |values: &mut Values| {
+            count.set(count.get() + 1);
+            values.set(1, Value::from(count.get() as u8 % 2));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn get_or_read() {
+        let cache = ValueCache::default();
+        let count = Cell::new(0);
+        let ttl = Duration::from_secs(60);
+
+        let values = cache.get_or_read(ttl, counting_read(&count)).unwrap();
+        assert_eq!(count.get(), 1);
+        assert_eq!(values.get(1), Some(Value::Active));
+
+        // read suppressed within ttl
+        for _ in 0..5 {
+            let values = cache.get_or_read(ttl, counting_read(&count)).unwrap();
+            assert_eq!(values.get(1), Some(Value::Active));
+        }
+        assert_eq!(count.get(), 1);
+    }
+
+    #[test]
+    fn ttl_expiry() {
+        let cache = ValueCache::default();
+        let count = Cell::new(0);
+        let ttl = Duration::from_millis(10);
+
+        cache.get_or_read(ttl, counting_read(&count)).unwrap();
+        cache.get_or_read(ttl, counting_read(&count)).unwrap();
+        assert_eq!(count.get(), 1);
+
+        std::thread::sleep(ttl);
+        let values = cache.get_or_read(ttl, counting_read(&count)).unwrap();
+        assert_eq!(count.get(), 2);
+        assert_eq!(values.get(1), Some(Value::Inactive));
+
+        // zero ttl always reads
+        cache
+            .get_or_read(Duration::ZERO, counting_read(&count))
+            .unwrap();
+        cache
+            .get_or_read(Duration::ZERO, counting_read(&count))
+            .unwrap();
+        assert_eq!(count.get(), 4);
+    }
+
+    #[test]
+    fn invalidate() {
+        let cache = ValueCache::default();
+        let count = Cell::new(0);
+        let ttl = Duration::from_secs(60);
+
+        // invalidating an empty cache is harmless
+        cache.invalidate();
+
+        cache.get_or_read(ttl, counting_read(&count)).unwrap();
+        assert_eq!(count.get(), 1);
+        cache.invalidate();
+        let values = cache.get_or_read(ttl, counting_read(&count)).unwrap();
+        assert_eq!(count.get(), 2);
+        assert_eq!(values.get(1), Some(Value::Inactive));
+        cache.get_or_read(ttl, counting_read(&count)).unwrap();
+        assert_eq!(count.get(), 2);
+    }
+
+    #[test]
+    fn invalidate_during_read() {
+        let cache = ValueCache::default();
+        let count = Cell::new(0);
+        let ttl = Duration::from_secs(60);
+
+        cache
+            .get_or_read(ttl, |values| {
+                cache.invalidate();
+                counting_read(&count)(values)
+            })
+            .unwrap();
+        assert_eq!(count.get(), 1);
+
+        // values read while being invalidated are not reused
+        cache.get_or_read(ttl, counting_read(&count)).unwrap();
+        assert_eq!(count.get(), 2);
+        cache.get_or_read(ttl, counting_read(&count)).unwrap();
+        assert_eq!(count.get(), 2);
+    }
+
+    #[test]
+    fn read_error() {
+        let cache = ValueCache::default();
+        let count = Cell::new(0);
+        let ttl = Duration::from_secs(60);
+
+        let res = cache.get_or_read(ttl, |_| Err(Error::InvalidArgument("failed".into())));
+        assert_eq!(res, Err(Error::InvalidArgument("failed".into())));
+
+        // errors are not cached
+        cache.get_or_read(ttl, counting_read(&count)).unwrap();
+        assert_eq!(count.get(), 1);
+
+        // and do not discard cached values
+        std::thread::sleep(Duration::from_millis(10));
+        let res = cache.get_or_read(Duration::from_millis(5), |_| {
+            Err(Error::InvalidArgument("failed".into()))
+        });
+        assert!(res.is_err());
+        cache.get_or_read(ttl, counting_read(&count)).unwrap();
+        assert_eq!(count.get(), 1);
+    }
+}
@@ -0,0 +1,81 @@
+// SPDX-FileCopyrightText: 2024 Kent Gibson <warthog618@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::{AbiVersion, Error, Result, UapiCall};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// The environment variable that must be set to allow ABI support to be overridden.
+pub const ABI_OVERRIDE_ENV: &str = "GPIOCDEV_TEST_ABI_OVERRIDE";
+
+// The errno returned by the kernel for unsupported ioctls.
+const ENOTTY: i32 = 25;
+
+// A bitmap of the ABI versions forced to be unsupported.
+static UNSUPPORTED: AtomicU8 = AtomicU8::new(0);
+
+fn abi_mask(abiv: AbiVersion) -> u8 {
+    match abiv {
+        AbiVersion::V1 => 0x01,
+        AbiVersion::V2 => 0x02,
+    }
+}
+
+/// Force an ABI version to appear unsupported by the kernel.
+///
+/// Applies to all chips within the current process until [`restore_abi_support`] is called.
+///
+/// Subsequent ABI version detection and support checks report the version as unsupported,
+/// and requests made using that version fail with ENOTTY, as they would on a kernel
+/// without that version.
+///
+/// Only available when the [`ABI_OVERRIDE_ENV`] environment variable is set, and returns an
+/// [`Error::InvalidArgument`] otherwise.
+pub fn force_abi_unsupported(abiv: AbiVersion) -> Result<()> {
+    do_force_abi_unsupported(abiv, std::env::var_os(ABI_OVERRIDE_ENV).is_some())
+}
+
+// Separated from the environment check so it can be tested without modifying the environment.
+fn do_force_abi_unsupported(abiv: AbiVersion, enabled: bool) -> Result<()> {
+    if !enabled {
+        return Err(Error::InvalidArgument(format!(
+            "ABI override requires {} to be set.",
+            ABI_OVERRIDE_ENV
+        )));
+    }
+    UNSUPPORTED.fetch_or(abi_mask(abiv), Ordering::SeqCst);
+    Ok(())
+}
+
+/// Restore support for all ABI versions previously forced unsupported.
+pub fn restore_abi_support() {
+    UNSUPPORTED.store(0, Ordering::SeqCst);
+}
+
+// Returns true if the ABI version has been forced unsupported.
+pub(crate) fn is_abi_unsupported(abiv: AbiVersion) -> bool {
+    UNSUPPORTED.load(Ordering::SeqCst) & abi_mask(abiv) != 0
+}
+
+// Fail a uAPI call as the kernel would if the ABI version were unsupported.
+pub(crate) fn check_uapi_call(abiv: AbiVersion, call: UapiCall) -> Result<()> {
+    if is_abi_unsupported(abiv) {
+        return Err(Error::Uapi(
+            call,
+            gpiocdev_uapi::Error::Os(gpiocdev_uapi::Errno(ENOTTY)),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn force_abi_unsupported_requires_env() {
+        assert!(do_force_abi_unsupported(AbiVersion::V1, false).is_err());
+        assert!(!is_abi_unsupported(AbiVersion::V1));
+        assert!(check_uapi_call(AbiVersion::V1, UapiCall::GetLineHandle).is_ok());
+    }
+}
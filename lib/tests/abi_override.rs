@@ -0,0 +1,248 @@
+// SPDX-FileCopyrightText: 2024 Kent Gibson <warthog618@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Tests of ABI fallback with ABI versions forced unsupported.
+//
+// The override applies to the whole process, so the tests are serialised.
+#![cfg(feature = "test_abi_override")]
+
+use gpiocdev::chip::Chip;
+use gpiocdev::request::Request;
+use gpiocdev::test::{force_abi_unsupported, restore_abi_support, ABI_OVERRIDE_ENV};
+use gpiocdev::{AbiSupportKind, AbiVersion, Error, UapiCall};
+use gpiosim::Simpleton;
+use std::sync::{Mutex, MutexGuard};
+
+static OVERRIDE_LOCK: Mutex<()> = Mutex::new(());
+
+// Serialise access to the override and ensure it is restored on completion.
+struct Override {
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl Override {
+    fn new(abivs: &[AbiVersion]) -> Override {
+        let guard = OVERRIDE_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        std::env::set_var(ABI_OVERRIDE_ENV, "1");
+        for abiv in abivs {
+            force_abi_unsupported(*abiv).unwrap();
+        }
+        Override { _guard: guard }
+    }
+}
+
+impl Drop for Override {
+    fn drop(&mut self) {
+        restore_abi_support();
+    }
+}
+
+#[test]
+fn supports_abi_version() {
+    let s = Simpleton::new(4);
+    let c = Chip::from_path(s.dev_path()).unwrap();
+
+    let _o = Override::new(&[AbiVersion::V1, AbiVersion::V2]);
+    assert_eq!(
+        c.supports_abi_version(AbiVersion::V1),
+        Err(Error::UnsupportedAbi(
+            AbiVersion::V1,
+            AbiSupportKind::Kernel
+        ))
+    );
+    assert_eq!(
+        c.supports_abi_version(AbiVersion::V2),
+        Err(Error::UnsupportedAbi(
+            AbiVersion::V2,
+            AbiSupportKind::Kernel
+        ))
+    );
+    assert_eq!(c.detect_abi_version(), Err(Error::NoAbiSupport()));
+}
+
+#[test]
+fn restore_abi_support_clears_override() {
+    let s = Simpleton::new(4);
+    let c = Chip::from_path(s.dev_path()).unwrap();
+    {
+        let _o = Override::new(&[AbiVersion::V1, AbiVersion::V2]);
+        assert_eq!(c.detect_abi_version(), Err(Error::NoAbiSupport()));
+    }
+    let _o = Override::new(&[]);
+    assert!(c.detect_abi_version().is_ok());
+}
+
+#[test]
+fn request_with_no_abi_support() {
+    let s = Simpleton::new(4);
+
+    let _o = Override::new(&[AbiVersion::V1, AbiVersion::V2]);
+    let res = Request::builder()
+        .on_chip(s.dev_path())
+        .with_line(2)
+        .as_input()
+        .request();
+    #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+    assert_eq!(res.unwrap_err(), Error::NoAbiSupport());
+    #[cfg(not(feature = "uapi_v2"))]
+    assert_eq!(res.unwrap_err(), enotty(UapiCall::GetLineHandle));
+    #[cfg(not(feature = "uapi_v1"))]
+    assert_eq!(res.unwrap_err(), enotty(UapiCall::GetLine));
+}
+
+// The error returned by the kernel for an unsupported uAPI call.
+#[allow(dead_code)]
+fn enotty(call: UapiCall) -> Error {
+    Error::Uapi(call, gpiocdev_uapi::Error::Os(gpiocdev_uapi::Errno(25)))
+}
+
+#[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+mod fallback {
+    use super::*;
+    use gpiocdev::line::EdgeDetection;
+    use std::time::Duration;
+
+    #[test]
+    fn detect_abi_version() {
+        let s = Simpleton::new(4);
+        let c = Chip::from_path(s.dev_path()).unwrap();
+
+        let _o = Override::new(&[AbiVersion::V2]);
+        assert_eq!(c.detect_abi_version(), Ok(AbiVersion::V1));
+        assert_eq!(gpiocdev::detect_abi_version(), Ok(AbiVersion::V1));
+        assert_eq!(gpiocdev::detect_abi_version_uncached(), Ok(AbiVersion::V1));
+        let xsupport = gpiocdev::AbiSupport {
+            v1: true,
+            v2: false,
+        };
+        assert_eq!(c.abi_support(), xsupport);
+        assert_eq!(gpiocdev::detect_abi_support(), Ok(xsupport));
+    }
+
+    #[test]
+    fn request_falls_back_to_v1() {
+        let s = Simpleton::new(4);
+        let c = Chip::from_path(s.dev_path()).unwrap();
+
+        let _o = Override::new(&[AbiVersion::V2]);
+        let req = Request::builder()
+            .on_chip(s.dev_path())
+            .with_line(2)
+            .as_input()
+            .request()
+            .unwrap();
+        assert!(c.line_info(2).unwrap().used);
+        drop(req);
+
+        // v1 limitations apply to the fallback
+        let res = Request::builder()
+            .on_chip(s.dev_path())
+            .with_line(2)
+            .with_edge_detection(EdgeDetection::BothEdges)
+            .with_debounce_period(Duration::from_millis(2))
+            .request();
+        assert_eq!(
+            res.unwrap_err(),
+            Error::AbiLimitation(AbiVersion::V1, "does not support debounce".into())
+        );
+    }
+
+    #[test]
+    fn request_using_unsupported_abi() {
+        let s = Simpleton::new(4);
+
+        let _o = Override::new(&[AbiVersion::V2]);
+        let res = Request::builder()
+            .on_chip(s.dev_path())
+            .using_abi_version(AbiVersion::V2)
+            .with_line(2)
+            .as_input()
+            .request();
+        assert_eq!(res.unwrap_err(), enotty(UapiCall::GetLine));
+
+        drop(_o);
+        let _o = Override::new(&[AbiVersion::V1]);
+        let res = Request::builder()
+            .on_chip(s.dev_path())
+            .using_abi_version(AbiVersion::V1)
+            .with_line(2)
+            .as_input()
+            .request();
+        assert_eq!(res.unwrap_err(), enotty(UapiCall::GetLineHandle));
+    }
+
+    #[test]
+    fn chip_falls_back_to_v1() {
+        let s = Simpleton::new(4);
+        let c = Chip::from_path(s.dev_path()).unwrap();
+
+        let _o = Override::new(&[AbiVersion::V2]);
+        assert_eq!(c.line_info(1).unwrap().offset, 1);
+        assert!(c.watch_line_info(1).is_ok());
+        assert!(c.unwatch_line_info(1).is_ok());
+    }
+
+    #[test]
+    fn chip_using_unsupported_abi() {
+        let s = Simpleton::new(4);
+        let mut c = Chip::from_path(s.dev_path()).unwrap();
+        c.using_abi_version(AbiVersion::V2);
+
+        let _o = Override::new(&[AbiVersion::V2]);
+        assert_eq!(c.line_info(1), Err(enotty(UapiCall::GetLineInfo)));
+        assert_eq!(c.name_of(1), Err(enotty(UapiCall::GetLineInfo)));
+        assert_eq!(c.watch_line_info(1), Err(enotty(UapiCall::WatchLineInfo)));
+        assert_eq!(
+            c.unwatch_line_info(1),
+            Err(enotty(UapiCall::UnwatchLineInfo))
+        );
+
+        drop(_o);
+        let _o = Override::new(&[AbiVersion::V1]);
+        assert!(c.line_info(1).is_ok());
+    }
+
+    #[test]
+    fn request_using_forced_unsupported_abi() {
+        use gpiocdev::line::{Value, Values};
+
+        let s = Simpleton::new(4);
+        for abiv in [AbiVersion::V1, AbiVersion::V2] {
+            let req = Request::builder()
+                .on_chip(s.dev_path())
+                .using_abi_version(abiv)
+                .with_line(2)
+                .as_output(Value::Active)
+                .request()
+                .unwrap();
+
+            let _o = Override::new(&[abiv]);
+            assert_eq!(req.value(2), Err(enotty(UapiCall::GetLineValues)));
+            let mut values = Values::default();
+            assert_eq!(
+                req.values(&mut values),
+                Err(enotty(UapiCall::GetLineValues))
+            );
+            assert_eq!(
+                req.set_value(2, Value::Inactive),
+                Err(enotty(UapiCall::SetLineValues))
+            );
+            values.set(2, Value::Inactive);
+            assert_eq!(
+                req.set_values(&values),
+                Err(enotty(UapiCall::SetLineValues))
+            );
+            assert_eq!(
+                req.reconfigure(&req.config()),
+                Err(enotty(UapiCall::SetLineConfig))
+            );
+            drop(_o);
+
+            // calls succeed once support is restored
+            assert_eq!(req.value(2), Ok(Value::Active));
+        }
+    }
+}
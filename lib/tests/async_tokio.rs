@@ -25,7 +25,8 @@ mod chip {
             gpiocdev::AbiVersion::V1,
             from_chip,
             read_line_info_change_event,
-            info_change_events
+            info_change_events,
+            info_change_events_filtered
         }
     }
 
@@ -35,7 +36,8 @@ mod chip {
             gpiocdev::AbiVersion::V2,
             from_chip,
             read_line_info_change_event,
-            info_change_events
+            info_change_events,
+            info_change_events_filtered
         }
     }
 
@@ -90,6 +92,41 @@ mod chip {
         assert_eq!(evt.info.offset, offset);
     }
 
+    async fn info_change_events_filtered(abiv: gpiocdev::AbiVersion) {
+        use gpiocdev::line::InfoChangeKind;
+        use gpiocdev::tokio::AsyncChip;
+        use tokio_stream::StreamExt;
+
+        let s = gpiosim::Simpleton::new(4);
+        let c = new_chip(s.dev_path(), abiv);
+
+        assert!(c.watch_line_info(1).is_ok());
+        assert!(c.watch_line_info(2).is_ok());
+
+        let ac = AsyncChip::new(c);
+        let mut events = ac.info_change_events_filtered(&[InfoChangeKind::Reconfigured], &[2]);
+        // generate events on both lines
+        for offset in [1, 2] {
+            let req = Request::builder()
+                .on_chip(s.dev_path())
+                .with_line(offset)
+                .as_input()
+                .request()
+                .unwrap();
+            let mut cfg = req.config();
+            cfg.with_bias(gpiocdev::line::Bias::PullUp);
+            req.reconfigure(&cfg).unwrap();
+            drop(req);
+        }
+
+        let evt = events.next().await.unwrap().unwrap();
+        assert_eq!(evt.kind, InfoChangeKind::Reconfigured);
+        assert_eq!(evt.info.offset, 2);
+        assert_eq!(evt.info.bias, Some(gpiocdev::line::Bias::PullUp));
+        // the following release is left unread
+        assert_eq!(ac.as_ref().has_info_change_event(), Ok(true));
+    }
+
     async fn read_line_info_change_event(abiv: gpiocdev::AbiVersion) {
         use gpiocdev::tokio::AsyncChip;
         use std::time::Duration;
@@ -165,6 +202,59 @@ mod chip {
     }
 }
 
+#[cfg(feature = "async_tokio")]
+mod chip_watcher {
+    use gpiocdev::tokio::AsyncChipWatcher;
+    use gpiocdev::{ChipEvent, ChipWatcher};
+    use std::os::unix::io::AsRawFd;
+    use tokio_stream::StreamExt;
+
+    #[tokio::test]
+    async fn from_watcher() {
+        let w = gpiocdev::chips_watch().unwrap();
+        let fd = w.as_raw_fd();
+        let aw = AsyncChipWatcher::from(w);
+        assert_eq!(aw.as_ref().as_raw_fd(), fd);
+        let w = ChipWatcher::from(aw);
+        assert_eq!(w.as_raw_fd(), fd);
+    }
+
+    #[tokio::test]
+    async fn read_event() {
+        let aw = AsyncChipWatcher::new(gpiocdev::chips_watch().unwrap());
+        let s = gpiosim::Simpleton::new(4);
+        let path = s.dev_path().to_path_buf();
+        loop {
+            match aw.read_event().await.unwrap() {
+                ChipEvent::Added(p) if p == path => break,
+                _ => continue,
+            }
+        }
+        drop(s);
+        loop {
+            match aw.read_event().await.unwrap() {
+                ChipEvent::Removed(p) if p == path => break,
+                _ => continue,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn events() {
+        let aw = AsyncChipWatcher::new(gpiocdev::chips_watch().unwrap());
+        let s = gpiosim::Simpleton::new(4);
+        let path = s.dev_path().to_path_buf();
+        let mut events = aw.events().filter_map(|r| match r.unwrap() {
+            ChipEvent::Added(p) if p == path => Some(true),
+            ChipEvent::Removed(p) if p == path => Some(false),
+            _ => None,
+        });
+        assert_eq!(events.next().await, Some(true));
+        drop(s);
+        assert_eq!(events.next().await, Some(false));
+    }
+}
+
 #[cfg(feature = "async_tokio")]
 mod request {
     use gpiocdev::line::{EdgeKind, Offset};
@@ -182,7 +272,13 @@ mod request {
             read_edge_event,
             read_edge_events_into_slice,
             new_edge_event_stream,
-            edge_events
+            edge_events,
+            debounced_edge_events,
+            coalesced_edge_events,
+            level_changes,
+            values_sink,
+            values,
+            set_values
         }
     }
 
@@ -194,7 +290,13 @@ mod request {
             read_edge_event,
             read_edge_events_into_slice,
             new_edge_event_stream,
-            edge_events
+            edge_events,
+            debounced_edge_events,
+            coalesced_edge_events,
+            level_changes,
+            values_sink,
+            values,
+            set_values
         }
     }
 
@@ -456,6 +558,187 @@ mod request {
         }
     }
 
+    #[allow(unused_variables)]
+    async fn debounced_edge_events(abiv: gpiocdev::AbiVersion) {
+        let s = gpiosim::Simpleton::new(4);
+        let offset = 0;
+        let period = Duration::from_millis(20);
+
+        let req = AsyncRequest::new(new_request(s.dev_path(), offset, abiv));
+        let mut iter = req.debounced_edge_events(period);
+
+        // bounce, settling high
+        s.pullup(offset).unwrap();
+        s.pulldown(offset).unwrap();
+        s.pullup(offset).unwrap();
+        propagation_delay().await;
+        let evt = iter.next().await.unwrap().unwrap();
+        assert_eq!(evt.kind, EdgeKind::Rising);
+        assert_eq!(evt.offset, offset);
+
+        // bounce, settling back high
+        s.pulldown(offset).unwrap();
+        s.pullup(offset).unwrap();
+        propagation_delay().await;
+        time::sleep(period).await;
+        // single clean edge
+        s.pulldown(offset).unwrap();
+        propagation_delay().await;
+        let evt = iter.next().await.unwrap().unwrap();
+        assert_eq!(evt.kind, EdgeKind::Falling);
+        assert!(!req.as_ref().has_edge_event().unwrap());
+    }
+
+    async fn coalesced_edge_events(abiv: gpiocdev::AbiVersion) {
+        let s = gpiosim::Simpleton::new(4);
+        let offset = 0;
+        let max_rate = Duration::from_millis(50);
+
+        let req = AsyncRequest::new(new_request(s.dev_path(), offset, abiv));
+        let mut iter = req.coalesced_edge_events(max_rate);
+
+        s.pullup(offset).unwrap();
+        propagation_delay().await;
+        let ce = iter.next().await.unwrap().unwrap();
+        assert_eq!(ce.event.kind, EdgeKind::Rising);
+        assert_eq!(ce.event.offset, offset);
+        assert_eq!(ce.suppressed, 0);
+
+        // burst within the window
+        s.pulldown(offset).unwrap();
+        s.pullup(offset).unwrap();
+        s.pulldown(offset).unwrap();
+        propagation_delay().await;
+        let ce = iter.next().await.unwrap().unwrap();
+        assert_eq!(ce.event.kind, EdgeKind::Falling);
+        assert_eq!(ce.suppressed, 2);
+        assert!(!req.as_ref().has_edge_event().unwrap());
+    }
+
+    async fn level_changes(abiv: gpiocdev::AbiVersion) {
+        use gpiocdev::line::Value;
+
+        let s = gpiosim::Simpleton::new(4);
+        let offset = 0;
+
+        let req = AsyncRequest::new(new_request(s.dev_path(), offset, abiv));
+
+        // edges before the snapshot are discarded
+        s.pullup(offset).unwrap();
+        s.pulldown(offset).unwrap();
+        s.pullup(offset).unwrap();
+        propagation_delay().await;
+        let mut iter = req.level_changes();
+        assert_eq!(iter.next().await.unwrap().unwrap(), (offset, Value::Active));
+
+        s.pulldown(offset).unwrap();
+        propagation_delay().await;
+        assert_eq!(
+            iter.next().await.unwrap().unwrap(),
+            (offset, Value::Inactive)
+        );
+        assert!(!req.as_ref().has_edge_event().unwrap());
+    }
+
+    #[allow(unused_variables)]
+    async fn values_sink(abiv: gpiocdev::AbiVersion) {
+        use futures::{stream, SinkExt};
+        use gpiocdev::line::{Value, Values};
+        use gpiosim::Level;
+
+        let s = gpiosim::Simpleton::new(4);
+        let mut builder = Request::builder();
+        builder
+            .on_chip(s.dev_path())
+            .with_lines(&[1, 2])
+            .as_output(Value::Inactive);
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        builder.using_abi_version(abiv);
+        let req = AsyncRequest::new(builder.request().unwrap());
+        let mut sink = req.values_sink();
+
+        // applied in the order sent
+        let values = Values::from_iter([(1, Value::Active), (2, Value::Inactive)]);
+        sink.send(values).await.unwrap();
+        assert_eq!(s.get_level(1).unwrap(), Level::High);
+        assert_eq!(s.get_level(2).unwrap(), Level::Low);
+        sink.send((2, Value::Active)).await.unwrap();
+        assert_eq!(s.get_level(1).unwrap(), Level::High);
+        assert_eq!(s.get_level(2).unwrap(), Level::High);
+
+        // from a stream
+        let waveform = [
+            Values::from_iter([(1, Value::Inactive), (2, Value::Active)]),
+            Values::from_iter([(1, Value::Active), (2, Value::Inactive)]),
+            Values::from_iter([(1, Value::Inactive), (2, Value::Inactive)]),
+        ];
+        sink.send_all(&mut stream::iter(waveform.map(Ok)))
+            .await
+            .unwrap();
+        assert_eq!(s.get_level(1).unwrap(), Level::Low);
+        assert_eq!(s.get_level(2).unwrap(), Level::Low);
+
+        // errors are returned by the send
+        assert!(sink.send((3, Value::Active)).await.is_err());
+    }
+
+    #[allow(unused_variables)]
+    async fn values(abiv: gpiocdev::AbiVersion) {
+        use gpiocdev::line::{Value, Values};
+
+        let s = gpiosim::Simpleton::new(4);
+        s.pullup(1).unwrap();
+        let mut builder = Request::builder();
+        builder.on_chip(s.dev_path()).with_lines(&[1, 2]).as_input();
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        builder.using_abi_version(abiv);
+        let req = AsyncRequest::new(builder.request().unwrap());
+
+        let mut values = Values::from_offsets(&[1, 2]);
+        req.values(&mut values).await.unwrap();
+        assert_eq!(values.get(1), Some(Value::Active));
+        assert_eq!(values.get(2), Some(Value::Inactive));
+
+        s.pulldown(1).unwrap();
+        s.pullup(2).unwrap();
+        propagation_delay().await;
+        req.values(&mut values).await.unwrap();
+        assert_eq!(values.get(1), Some(Value::Inactive));
+        assert_eq!(values.get(2), Some(Value::Active));
+    }
+
+    #[allow(unused_variables)]
+    async fn set_values(abiv: gpiocdev::AbiVersion) {
+        use gpiocdev::line::{Value, Values};
+        use gpiosim::Level;
+
+        let s = gpiosim::Simpleton::new(4);
+        let mut builder = Request::builder();
+        builder
+            .on_chip(s.dev_path())
+            .with_lines(&[1, 2])
+            .as_output(Value::Inactive);
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        builder.using_abi_version(abiv);
+        let mut req = builder.request().unwrap();
+        req.set_line_data(1, "led").unwrap();
+        let req = AsyncRequest::new(req);
+
+        let values = Values::from_iter([(1, Value::Active), (2, Value::Inactive)]);
+        req.set_values(&values).await.unwrap();
+        assert_eq!(s.get_level(1).unwrap(), Level::High);
+        assert_eq!(s.get_level(2).unwrap(), Level::Low);
+
+        let values = Values::from_iter([(1, Value::Inactive), (2, Value::Active)]);
+        req.set_values(&values).await.unwrap();
+        assert_eq!(s.get_level(1).unwrap(), Level::Low);
+        assert_eq!(s.get_level(2).unwrap(), Level::High);
+
+        // the request is shared with the blocking thread, not cloned, so retains its state
+        let req = Request::from(req);
+        assert_eq!(req.line_data::<&str>(1), Some(&"led"));
+    }
+
     #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
     fn new_request(path: &Path, offset: Offset, abiv: gpiocdev::AbiVersion) -> gpiocdev::Request {
         let mut builder = Request::builder();
@@ -108,6 +108,95 @@ fn chips() {
     }
 }
 
+#[test]
+fn chips_unique() {
+    let cc = bag_of_chips();
+    let dev_path = cc.chips()[0].dev_path();
+
+    let mut path = PathBuf::from("/dev");
+    path.push(gpiosim::unique_name("gpiocdev_chip", None));
+    let _link = Symlink::new(dev_path, &path).unwrap();
+
+    let mut path = PathBuf::from("/dev");
+    path.push(gpiosim::unique_name("gpiocdev_dangling", None));
+    let dangling = Symlink::new(Path::new("/dev/gpiochip_nonexistent"), &path).unwrap();
+
+    // a duplicate node that looks like a chip
+    let hard_link = PathBuf::from("/dev/gpiochip4000000000");
+    std::fs::hard_link(dev_path, &hard_link).unwrap();
+
+    let system_chips = gpiocdev::chip::chips();
+    _ = std::fs::remove_file(&hard_link);
+    let system_chips = system_chips.unwrap();
+
+    assert_eq!(system_chips.iter().filter(|p| *p == dev_path).count(), 1);
+    assert!(!system_chips.contains(&hard_link));
+    assert!(!system_chips.contains(&dangling.src));
+    for c in cc.chips() {
+        assert!(system_chips.contains(c.dev_path()));
+    }
+}
+
+#[test]
+fn chips_matching() {
+    let s = gpiosim::builder()
+        .with_bank(&Bank::new(8, "chips_matching alpha"))
+        .with_bank(&Bank::new(12, "chips_matching beta"))
+        .with_bank(&Bank::new(6, "chips_matching beta"))
+        .live()
+        .unwrap();
+    let cc = s.chips();
+
+    let found =
+        gpiocdev::chip::chips_matching(|info| info.label.contains("chips_matching")).unwrap();
+    assert_eq!(found.len(), 3);
+    for (c, (path, info)) in cc.iter().zip(found.iter()) {
+        assert_eq!(path, c.dev_path());
+        assert_eq!(info.name.as_str(), c.chip_name);
+        assert_eq!(info.label.as_str(), c.config().label);
+        assert_eq!(info.num_lines, c.config().num_lines);
+    }
+
+    let found = gpiocdev::chip::chips_matching(|info| info.label.ends_with("beta")).unwrap();
+    assert_eq!(found.len(), 2);
+    assert_eq!(&found[0].0, cc[1].dev_path());
+    assert_eq!(&found[1].0, cc[2].dev_path());
+
+    let found =
+        gpiocdev::chip::chips_matching(|info| info.label == "chips_matching gamma").unwrap();
+    assert!(found.is_empty());
+}
+
+#[test]
+fn find_chip_by_label() {
+    let s = gpiosim::builder()
+        .with_bank(&Bank::new(8, "find_chip_by_label alpha"))
+        .with_bank(&Bank::new(12, "find_chip_by_label beta"))
+        .with_bank(&Bank::new(6, "find_chip_by_label beta"))
+        .live()
+        .unwrap();
+    let cc = s.chips();
+
+    let (path, info) = gpiocdev::chip::find_chip_by_label("find_chip_by_label alpha")
+        .unwrap()
+        .unwrap();
+    assert_eq!(&path, cc[0].dev_path());
+    assert_eq!(info.num_lines, 8);
+
+    assert_eq!(
+        gpiocdev::chip::find_chip_by_label("find_chip_by_label beta"),
+        Err(gpiocdev::Error::NonuniqueChipLabel(
+            "find_chip_by_label beta".to_string()
+        ))
+    );
+
+    // label must match exactly
+    assert_eq!(
+        gpiocdev::chip::find_chip_by_label("find_chip_by_label"),
+        Ok(None)
+    );
+}
+
 macro_rules! common_tests {
     ($abiv:expr, $($name:ident),*) => {
         $(
@@ -159,6 +248,53 @@ mod chip {
         }
     }
 
+    #[test]
+    fn from_name_nonexistent() {
+        assert_eq!(
+            Chip::from_name("gpiochip_nonexistent").unwrap_err(),
+            gpiocdev::Error::Os(gpiocdev_uapi::Errno(2))
+        );
+    }
+
+    #[test]
+    fn from_name_not_character_device() {
+        assert_eq!(
+            Chip::from_name("shm").unwrap_err(),
+            ChipError(PathBuf::from("/dev/shm"), ErrorKind::NotCharacterDevice)
+        );
+    }
+
+    #[test]
+    fn from_name_no_gpio_device() {
+        assert_eq!(
+            Chip::from_name("null").unwrap_err(),
+            ChipError(PathBuf::from("/dev/null"), ErrorKind::NotGpioDevice)
+        );
+    }
+
+    #[test]
+    fn from_number() {
+        let s = bag_of_chips();
+        for c in s.chips() {
+            let n = c
+                .chip_name
+                .strip_prefix("gpiochip")
+                .unwrap()
+                .parse()
+                .unwrap();
+            let chip = Chip::from_number(n).unwrap();
+            assert_eq!(chip.path(), c.dev_path());
+        }
+    }
+
+    #[test]
+    fn from_number_nonexistent() {
+        assert_eq!(
+            Chip::from_number(u32::MAX).unwrap_err(),
+            gpiocdev::Error::Os(gpiocdev_uapi::Errno(2))
+        );
+    }
+
     #[test]
     fn from_path() {
         let s = bag_of_chips();
@@ -208,6 +344,49 @@ mod chip {
         );
     }
 
+    #[test]
+    fn from_fd() {
+        use std::os::unix::prelude::OwnedFd;
+
+        let s = bag_of_chips();
+        for sc in s.chips() {
+            let f = std::fs::File::open(sc.dev_path()).unwrap();
+            let fd = OwnedFd::from(f).try_clone().unwrap();
+            let c = Chip::from_fd(fd).unwrap();
+            assert_eq!(c.path(), sc.dev_path());
+            assert_eq!(c.name().as_str(), sc.chip_name);
+            let info = c.info().unwrap();
+            assert_eq!(info.name.as_str(), sc.chip_name);
+            assert_eq!(info.num_lines, sc.config().num_lines);
+            let li = c.line_info(1).unwrap();
+            assert_eq!(li.offset, 1);
+        }
+    }
+
+    #[test]
+    fn from_fd_not_character_device() {
+        use std::os::unix::prelude::OwnedFd;
+
+        let path = PathBuf::from("/tmp");
+        let fd = OwnedFd::from(std::fs::File::open(&path).unwrap());
+        assert_eq!(
+            Chip::from_fd(fd).unwrap_err(),
+            ChipError(path, ErrorKind::NotCharacterDevice)
+        );
+    }
+
+    #[test]
+    fn from_fd_no_gpio_device() {
+        use std::os::unix::prelude::OwnedFd;
+
+        let path = PathBuf::from("/dev/null");
+        let fd = OwnedFd::from(std::fs::File::open(&path).unwrap());
+        assert_eq!(
+            Chip::from_fd(fd).unwrap_err(),
+            ChipError(path, ErrorKind::NotGpioDevice)
+        );
+    }
+
     #[test]
     fn info() {
         let s = bag_of_chips();
@@ -220,6 +399,49 @@ mod chip {
         }
     }
 
+    #[test]
+    fn num_lines() {
+        let s = bag_of_chips();
+        for sc in s.chips() {
+            let c = Chip::from_path(sc.dev_path()).unwrap();
+            assert_eq!(c.num_lines().unwrap(), sc.config().num_lines);
+            // cached
+            assert_eq!(c.num_lines().unwrap(), sc.config().num_lines);
+        }
+    }
+
+    #[test]
+    fn label() {
+        let s = bag_of_chips();
+        for sc in s.chips() {
+            let c = Chip::from_path(sc.dev_path()).unwrap();
+            assert_eq!(c.label().unwrap().as_str(), sc.config().label);
+            // cached
+            assert_eq!(c.label().unwrap().as_str(), sc.config().label);
+        }
+    }
+
+    #[test]
+    fn uid() {
+        let s = bag_of_chips();
+        let mut path = PathBuf::from("/tmp");
+        path.push(gpiosim::unique_name("gpiocdev_chip", None));
+        let link = Symlink::new(s.chips()[0].dev_path(), &path).unwrap();
+        let id = Chip::from_path(&link.src).unwrap().uid().unwrap();
+        assert_eq!(id.name.as_str(), s.chips()[0].chip_name);
+        assert_eq!(id.num_lines, s.chips()[0].config().num_lines);
+        // same chip via the device and the symlink
+        assert_eq!(Chip::from_path(&link.dst).unwrap().uid().unwrap(), id);
+        // distinct chips
+        assert_ne!(
+            Chip::from_path(s.chips()[1].dev_path())
+                .unwrap()
+                .uid()
+                .unwrap(),
+            id
+        );
+    }
+
     #[test]
     fn name() {
         let s = bag_of_chips();
@@ -238,6 +460,31 @@ mod chip {
         }
     }
 
+    #[test]
+    fn reopen() {
+        let s = Simpleton::new(4);
+        let mut c = Chip::from_path(s.dev_path()).unwrap();
+        c.watch_line_info(2).unwrap();
+        assert_eq!(c.num_lines().unwrap(), 4);
+
+        c.reopen().unwrap();
+        assert_eq!(c.path(), s.dev_path());
+        assert!(c.watched_lines().is_empty());
+        let info = c.info().unwrap();
+        assert_eq!(info.num_lines, 4);
+        assert_eq!(info.name.as_str(), s.chip().chip_name);
+        // the line can be watched again with the new fd
+        c.watch_line_info(2).unwrap();
+
+        // device removed
+        let path = s.dev_path().clone();
+        drop(s);
+        assert!(c.reopen().is_err());
+        // chip unchanged
+        assert_eq!(c.path(), path);
+        assert_eq!(c.watched_lines(), [2]);
+    }
+
     #[test]
     fn find_line_info() {
         let s = detailed_sim();
@@ -254,6 +501,220 @@ mod chip {
         }
     }
 
+    #[test]
+    fn find_line() {
+        let s = detailed_sim();
+        for sc in s.chips() {
+            let c = Chip::from_path(sc.dev_path()).unwrap();
+            for (offset, name) in &sc.config().names {
+                let li = c.find_line(name).unwrap().unwrap();
+                assert_eq!(li.offset, *offset);
+                assert_eq!(li.name, *name);
+            }
+
+            // non-existent
+            assert_eq!(c.find_line("not such line"), Ok(None));
+        }
+    }
+
+    #[test]
+    fn line_offset_by_name() {
+        let s = detailed_sim();
+        for sc in s.chips() {
+            let c = Chip::from_path(sc.dev_path()).unwrap();
+            for (offset, name) in &sc.config().names {
+                assert_eq!(c.line_offset_by_name(name), Ok(Some(*offset)));
+            }
+
+            // non-existent
+            assert_eq!(c.line_offset_by_name("not such line"), Ok(None));
+        }
+
+        // first of duplicates
+        let s = gpiosim::builder()
+            .with_bank(
+                Bank::new(8, "line_offset_by_name_nonunique")
+                    .name(2, "lobn banana")
+                    .name(4, "lobn apple")
+                    .name(6, "lobn banana"),
+            )
+            .live()
+            .unwrap();
+        let c = Chip::from_path(s.chips()[0].dev_path()).unwrap();
+        assert_eq!(c.line_offset_by_name("lobn banana"), Ok(Some(2)));
+        assert_eq!(c.line_offset_by_name("lobn apple"), Ok(Some(4)));
+    }
+
+    #[test]
+    fn line_info_cache() {
+        use gpiocdev::chip::LineInfoCache;
+
+        let s = detailed_sim();
+        let sc = &s.chips()[0];
+        let c = Chip::from_path(sc.dev_path()).unwrap();
+        assert!(c.watch_line_info(3).is_ok());
+        let mut cache = LineInfoCache::new(c).unwrap();
+        assert_eq!(cache.all().len(), sc.config().num_lines as usize);
+        assert_eq!(cache.chip().watched_lines().len(), cache.all().len());
+        for (offset, li) in (0..).zip(cache.all()) {
+            assert_eq!(li.offset, offset);
+            assert_eq!(cache.get(offset), Some(li));
+        }
+        assert!(cache.get(sc.config().num_lines).is_none());
+        assert_eq!(cache.get(3).unwrap().name, "banana");
+        assert!(cache.get(2).unwrap().used);
+        assert!(!cache.get(3).unwrap().used);
+        assert_eq!(cache.process_events(), Ok(0));
+
+        let req = Request::builder()
+            .on_chip(sc.dev_path())
+            .with_lines(&[1, 3])
+            .with_consumer("lic test")
+            .request()
+            .unwrap();
+        // stale until the events are processed
+        assert!(!cache.get(3).unwrap().used);
+        assert_eq!(
+            cache.chip().wait_info_change_event(INFO_CHANGE_EVENT_WAIT),
+            Ok(true)
+        );
+        assert_eq!(cache.process_events(), Ok(2));
+        for offset in [1, 3] {
+            let li = cache.get(offset).unwrap();
+            assert!(li.used);
+            assert_eq!(li.consumer, "lic test");
+        }
+        assert!(!cache.get(4).unwrap().used);
+
+        drop(req);
+        assert_eq!(
+            cache.chip().wait_info_change_event(INFO_CHANGE_EVENT_WAIT),
+            Ok(true)
+        );
+        assert_eq!(cache.process_events(), Ok(2));
+        for offset in [1, 3] {
+            let li = cache.get(offset).unwrap();
+            assert!(!li.used);
+            assert!(li.consumer.is_empty());
+        }
+    }
+
+    #[test]
+    fn line_info_iter() {
+        let s = detailed_sim();
+        for sc in s.chips() {
+            let c = Chip::from_path(sc.dev_path()).unwrap();
+            let iter = c.line_info_iter().unwrap();
+            assert_eq!(iter.len(), sc.config().num_lines as usize);
+            let mut count = 0;
+            for (offset, li) in (0..).zip(iter) {
+                let li = li.unwrap();
+                assert_eq!(li.offset, offset);
+                match sc.config().names.get(&offset) {
+                    Some(name) => assert_eq!(&li.name, name),
+                    None => assert!(li.name.is_empty()),
+                }
+                count += 1;
+            }
+            assert_eq!(count, sc.config().num_lines);
+        }
+    }
+
+    #[test]
+    fn line_infos() {
+        let s = detailed_sim();
+        for sc in s.chips() {
+            let c = Chip::from_path(sc.dev_path()).unwrap();
+            let infos = c.line_infos().unwrap();
+            assert_eq!(infos.len(), sc.config().num_lines as usize);
+            for (offset, li) in (0..).zip(infos.iter()) {
+                assert_eq!(li, &c.line_info(offset).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn topology() {
+        let s = detailed_sim();
+        for sc in s.chips() {
+            let c = Chip::from_path(sc.dev_path()).unwrap();
+            let t = c.topology().unwrap();
+            assert_eq!(t.info, c.info().unwrap());
+            assert_eq!(t.lines.len(), sc.config().num_lines as usize);
+            assert_eq!(t.lines, c.line_infos().unwrap());
+        }
+    }
+
+    #[test]
+    fn line_names() {
+        let s = detailed_sim();
+        for sc in s.chips() {
+            let c = Chip::from_path(sc.dev_path()).unwrap();
+            let names = c.line_names().unwrap();
+            assert_eq!(names.len(), sc.config().num_lines as usize);
+            for (offset, name) in (0..).zip(names.iter()) {
+                assert_eq!(name.as_ref(), sc.config().names.get(&offset));
+                assert_eq!(&c.name_of(offset).unwrap(), name);
+            }
+        }
+    }
+
+    #[test]
+    fn name_of() {
+        let s = detailed_sim();
+        let c = Chip::from_path(s.chips()[0].dev_path()).unwrap();
+        assert_eq!(c.name_of(3).unwrap().as_deref(), Some("banana"));
+        assert_eq!(c.name_of(4).unwrap(), None);
+        assert!(c.name_of(8).is_err());
+    }
+
+    #[test]
+    fn find_lines() {
+        let s = detailed_sim();
+        for sc in s.chips() {
+            let c = Chip::from_path(sc.dev_path()).unwrap();
+            let mut names: Vec<&str> = sc.config().names.values().map(|n| n.as_str()).collect();
+            names.push("not such line");
+            for strict in [false, true] {
+                let found = c.find_lines(&names, strict).unwrap();
+                assert_eq!(found.len(), sc.config().names.len());
+                for (offset, name) in &sc.config().names {
+                    let li = found.get(name).unwrap();
+                    assert_eq!(li.offset, *offset);
+                    assert_eq!(&li.name, name);
+                }
+            }
+        }
+
+        // lines on other chips are not found
+        let c = Chip::from_path(s.chips()[0].dev_path()).unwrap();
+        assert!(c.find_lines(["piñata"], true).unwrap().is_empty());
+    }
+
+    #[test]
+    fn find_lines_nonunique() {
+        let s = gpiosim::builder()
+            .with_bank(
+                Bank::new(8, "find_lines_nonunique")
+                    .name(2, "fln banana")
+                    .name(4, "fln apple")
+                    .name(6, "fln banana"),
+            )
+            .live()
+            .unwrap();
+        let c = Chip::from_path(s.chips()[0].dev_path()).unwrap();
+
+        let found = c.find_lines(["fln banana", "fln apple"], false).unwrap();
+        assert_eq!(found.get("fln banana").unwrap().offset, 2);
+        assert_eq!(found.get("fln apple").unwrap().offset, 4);
+
+        assert_eq!(
+            c.find_lines(["fln banana"], true),
+            Err(gpiocdev::Error::NonuniqueLineName("fln banana".into()))
+        );
+        assert!(c.find_lines(["fln apple"], true).is_ok());
+    }
+
     fn line_info(abiv: gpiocdev::AbiVersion) {
         let s = detailed_sim();
         for sc in s.chips() {
@@ -446,9 +907,13 @@ mod chip {
             line_info,
             watch_line_info,
             has_line_info_change_event,
+            has_info_change_event,
             read_line_info_change_event,
             info_change_events,
-            wait_info_change_event
+            info_change_events_filtered,
+            wait_info_change_event,
+            wait_info_change_event_timeout,
+            read_info_change_event_timeout
         }
     }
 
@@ -459,9 +924,13 @@ mod chip {
             line_info,
             watch_line_info,
             has_line_info_change_event,
+            has_info_change_event,
             read_line_info_change_event,
             info_change_events,
-            wait_info_change_event
+            info_change_events_filtered,
+            wait_info_change_event,
+            wait_info_change_event_timeout,
+            read_info_change_event_timeout
         }
     }
 
@@ -559,6 +1028,130 @@ mod chip {
         );
     }
 
+    #[test]
+    fn watch_lines_info() {
+        let s = Simpleton::new(4);
+        let c = Chip::from_path(s.dev_path()).unwrap();
+
+        // invalid offset rolls back the other watches
+        assert_eq!(
+            c.watch_lines_info(&[0, 1, 5]),
+            Err(gpiocdev::Error::Uapi(
+                gpiocdev::UapiCall::WatchLineInfo,
+                gpiocdev_uapi::Error::Os(gpiocdev_uapi::Errno(22))
+            ))
+        );
+        let req = Request::builder()
+            .on_chip(s.dev_path())
+            .with_lines(&[0, 1])
+            .request()
+            .unwrap();
+        assert_eq!(
+            c.wait_line_info_change_event(INFO_CHANGE_EVENT_WAIT),
+            Ok(false)
+        );
+
+        let infos = c.watch_lines_info(&[1, 0]).unwrap();
+        assert_eq!(infos.len(), 2);
+        assert_eq!(infos[0].offset, 1);
+        assert_eq!(infos[1].offset, 0);
+        assert!(infos.iter().all(|info| info.used));
+
+        drop(req);
+        for offset in [0, 1] {
+            let evt = c.read_line_info_change_event().unwrap();
+            assert_eq!(evt.kind, gpiocdev::line::InfoChangeKind::Released);
+            assert_eq!(evt.info.offset, offset);
+        }
+    }
+
+    #[test]
+    fn unwatch_lines_info() {
+        let s = Simpleton::new(4);
+        let c = Chip::from_path(s.dev_path()).unwrap();
+
+        assert!(c.watch_lines_info(&[0, 1, 3]).is_ok());
+        assert!(c.unwatch_lines_info(&[0, 3]).is_ok());
+        let req = Request::builder()
+            .on_chip(s.dev_path())
+            .with_lines(&[0, 3])
+            .request()
+            .unwrap();
+        assert_eq!(
+            c.wait_line_info_change_event(INFO_CHANGE_EVENT_WAIT),
+            Ok(false)
+        );
+        drop(req);
+
+        // invalid offset does not prevent other lines being unwatched
+        assert_eq!(
+            c.unwatch_lines_info(&[5, 1]),
+            Err(gpiocdev::Error::Uapi(
+                gpiocdev::UapiCall::UnwatchLineInfo,
+                gpiocdev_uapi::Error::Os(gpiocdev_uapi::Errno(22))
+            ))
+        );
+        let req = Request::builder()
+            .on_chip(s.dev_path())
+            .with_line(1)
+            .request()
+            .unwrap();
+        assert_eq!(
+            c.wait_line_info_change_event(INFO_CHANGE_EVENT_WAIT),
+            Ok(false)
+        );
+        drop(req);
+    }
+
+    #[test]
+    fn unwatch_all() {
+        let s = Simpleton::new(4);
+        let c = Chip::from_path(s.dev_path()).unwrap();
+
+        assert!(c.watched_lines().is_empty());
+        assert!(c.unwatch_all().is_ok());
+
+        assert!(c.watch_lines_info(&[3, 0]).is_ok());
+        assert_eq!(c.watched_lines(), vec![0, 3]);
+
+        // duplicate watches are reported and do not alter the tracking
+        assert_eq!(
+            c.watch_line_info(3),
+            Err(gpiocdev::Error::Uapi(
+                gpiocdev::UapiCall::WatchLineInfo,
+                gpiocdev_uapi::Error::Os(gpiocdev_uapi::Errno(16))
+            ))
+        );
+        assert_eq!(c.watched_lines(), vec![0, 3]);
+
+        assert!(c.unwatch_all().is_ok());
+        assert!(c.watched_lines().is_empty());
+        let req = Request::builder()
+            .on_chip(s.dev_path())
+            .with_lines(&[0, 3])
+            .request()
+            .unwrap();
+        assert_eq!(
+            c.wait_line_info_change_event(INFO_CHANGE_EVENT_WAIT),
+            Ok(false)
+        );
+
+        // re-watch after unwatch_all
+        assert!(c.watch_line_info(3).is_ok());
+        assert_eq!(c.watched_lines(), vec![3]);
+        drop(req);
+        let evt = c.read_line_info_change_event().unwrap();
+        assert_eq!(evt.kind, gpiocdev::line::InfoChangeKind::Released);
+        assert_eq!(evt.info.offset, 3);
+        assert_eq!(
+            c.wait_line_info_change_event(INFO_CHANGE_EVENT_WAIT),
+            Ok(false)
+        );
+
+        assert!(c.unwatch_line_info(3).is_ok());
+        assert!(c.watched_lines().is_empty());
+    }
+
     fn has_line_info_change_event(abiv: gpiocdev::AbiVersion) {
         let s = Simpleton::new(4);
         let c = new_chip(s.dev_path(), abiv);
@@ -587,6 +1180,41 @@ mod chip {
         }
     }
 
+    fn has_info_change_event(abiv: gpiocdev::AbiVersion) {
+        let s = Simpleton::new(4);
+        let c = new_chip(s.dev_path(), abiv);
+        let offset = 2;
+
+        // no watches
+        let req = Request::builder()
+            .on_chip(s.dev_path())
+            .with_line(offset)
+            .request()
+            .unwrap();
+        assert_eq!(c.has_info_change_event(), Ok(false));
+        drop(req);
+        assert_eq!(c.has_info_change_event(), Ok(false));
+
+        // watched
+        assert!(c.watch_line_info(offset).is_ok());
+        assert_eq!(c.has_info_change_event(), Ok(false));
+        let req = Request::builder()
+            .on_chip(s.dev_path())
+            .with_line(offset)
+            .request()
+            .unwrap();
+        assert_eq!(c.has_info_change_event(), Ok(true));
+        // not consumed
+        assert_eq!(c.has_info_change_event(), Ok(true));
+        _ = c.read_line_info_change_event();
+        assert_eq!(c.has_info_change_event(), Ok(false));
+
+        // unwatched
+        assert!(c.unwatch_line_info(offset).is_ok());
+        drop(req);
+        assert_eq!(c.has_info_change_event(), Ok(false));
+    }
+
     fn read_line_info_change_event(abiv: gpiocdev::AbiVersion) {
         let s = Simpleton::new(4);
         let c = new_chip(s.dev_path(), abiv);
@@ -693,7 +1321,7 @@ mod chip {
                 assert_eq!(evt.info.offset, offset);
                 match count {
                     0 => {
-                        assert_eq!(evt.kind, InfoChangeKind::Requested);
+                        assert_eq!(evt.kind, gpiocdev::line::InfoChangeKind::Requested);
                         assert_eq!(evt.info.bias, None);
                     }
                     1 => {
@@ -705,7 +1333,7 @@ mod chip {
                         assert_eq!(evt.info.bias, Some(Bias::PullDown));
                     }
                     _ => {
-                        assert_eq!(evt.kind, InfoChangeKind::Released);
+                        assert_eq!(evt.kind, gpiocdev::line::InfoChangeKind::Released);
                         assert_eq!(evt.info.bias, None);
                         break;
                     }
@@ -719,6 +1347,48 @@ mod chip {
         assert!(res.is_ok());
     }
 
+    fn info_change_events_filtered(abiv: gpiocdev::AbiVersion) {
+        use gpiocdev::line::InfoChangeKind;
+
+        let s = Simpleton::new(4);
+        let c = new_chip(s.dev_path(), abiv);
+
+        assert!(c.watch_line_info(1).is_ok());
+        assert!(c.watch_line_info(2).is_ok());
+        // generate events on both lines
+        for offset in [1, 2] {
+            let req = Request::builder()
+                .on_chip(s.dev_path())
+                .with_line(offset)
+                .as_input()
+                .request()
+                .unwrap();
+            let mut cfg = req.config();
+            cfg.with_bias(gpiocdev::line::Bias::PullUp);
+            req.reconfigure(&cfg).unwrap();
+            drop(req);
+        }
+
+        let evt = c
+            .info_change_events_filtered(&[InfoChangeKind::Reconfigured], &[2])
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(evt.kind, InfoChangeKind::Reconfigured);
+        assert_eq!(evt.info.offset, 2);
+        assert_eq!(evt.info.bias, Some(gpiocdev::line::Bias::PullUp));
+
+        // empty filter matches everything
+        let evt = c
+            .info_change_events_filtered(&[], &[])
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(evt.kind, InfoChangeKind::Released);
+        assert_eq!(evt.info.offset, 2);
+        assert_eq!(c.has_info_change_event(), Ok(false));
+    }
+
     fn wait_info_change_event(abiv: gpiocdev::AbiVersion) {
         let s = Simpleton::new(4);
         let c = new_chip(s.dev_path(), abiv);
@@ -754,6 +1424,77 @@ mod chip {
         );
     }
 
+    fn wait_info_change_event_timeout(abiv: gpiocdev::AbiVersion) {
+        let s = Simpleton::new(4);
+        let c = new_chip(s.dev_path(), abiv);
+        let offset = 2;
+
+        assert!(c.watch_line_info(offset).is_ok());
+        let start = std::time::Instant::now();
+        assert_eq!(c.wait_info_change_event(INFO_CHANGE_EVENT_WAIT), Ok(false));
+        assert!(start.elapsed() >= INFO_CHANGE_EVENT_WAIT);
+
+        let req = Request::builder()
+            .on_chip(s.dev_path())
+            .with_line(offset)
+            .request()
+            .unwrap();
+
+        assert_eq!(c.wait_info_change_event(INFO_CHANGE_EVENT_WAIT), Ok(true));
+        // event is not consumed by the wait
+        assert_eq!(c.wait_info_change_event(Duration::ZERO), Ok(true));
+        let evt = c.read_line_info_change_event().unwrap();
+        assert_eq!(evt.kind, gpiocdev::line::InfoChangeKind::Requested);
+        assert_eq!(evt.info.offset, offset);
+        assert_eq!(c.wait_info_change_event(Duration::ZERO), Ok(false));
+        drop(req);
+    }
+
+    fn read_info_change_event_timeout(abiv: gpiocdev::AbiVersion) {
+        let s = Simpleton::new(4);
+        let c = new_chip(s.dev_path(), abiv);
+        let offset = 1;
+
+        assert!(c.watch_line_info(offset).is_ok());
+        assert_eq!(
+            c.read_info_change_event_timeout(INFO_CHANGE_EVENT_WAIT),
+            Ok(None)
+        );
+
+        // request and release the line from another thread while waiting
+        let path = s.dev_path().clone();
+        let t = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(10));
+            let req = Request::builder()
+                .on_chip(&path)
+                .with_line(offset)
+                .request()
+                .unwrap();
+            std::thread::sleep(Duration::from_millis(10));
+            drop(req);
+        });
+
+        let evt = c
+            .read_info_change_event_timeout(Duration::from_millis(500))
+            .unwrap()
+            .unwrap();
+        assert_eq!(evt.kind, gpiocdev::line::InfoChangeKind::Requested);
+        assert_eq!(evt.info.offset, offset);
+
+        let evt = c
+            .read_info_change_event_timeout(Duration::from_millis(500))
+            .unwrap()
+            .unwrap();
+        assert_eq!(evt.kind, gpiocdev::line::InfoChangeKind::Released);
+        assert_eq!(evt.info.offset, offset);
+
+        t.join().unwrap();
+        assert_eq!(
+            c.read_info_change_event_timeout(INFO_CHANGE_EVENT_WAIT),
+            Ok(None)
+        );
+    }
+
     #[test]
     fn detect_abi_version() {
         // assumes a kernel with both v1 and v2 supported.
@@ -765,6 +1506,38 @@ mod chip {
         assert_eq!(c.detect_abi_version(), Ok(gpiocdev::AbiVersion::V1));
     }
 
+    #[test]
+    fn abi_support() {
+        // assumes a kernel with both v1 and v2 supported.
+        let s = Simpleton::new(4);
+        let c = Chip::from_path(s.dev_path()).unwrap();
+        assert_eq!(
+            c.abi_support(),
+            gpiocdev::AbiSupport {
+                v1: cfg!(feature = "uapi_v1"),
+                v2: cfg!(feature = "uapi_v2"),
+            }
+        );
+    }
+
+    #[test]
+    fn cached_abi_version() {
+        // assumes a kernel with both v1 and v2 supported.
+        let s = Simpleton::new(4);
+        let c = Chip::from_path(s.dev_path()).unwrap();
+        #[cfg(feature = "uapi_v2")]
+        assert_eq!(c.cached_abi_version(), Ok(gpiocdev::AbiVersion::V2));
+        #[cfg(not(feature = "uapi_v2"))]
+        assert_eq!(c.cached_abi_version(), Ok(gpiocdev::AbiVersion::V1));
+
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        {
+            let mut c = Chip::from_path(s.dev_path()).unwrap();
+            c.using_abi_version(gpiocdev::AbiVersion::V1);
+            assert_eq!(c.cached_abi_version(), Ok(gpiocdev::AbiVersion::V1));
+        }
+    }
+
     #[test]
     fn supports_abi_version() {
         // assumes a kernel with both v1 and v2 supported.
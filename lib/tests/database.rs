@@ -0,0 +1,99 @@
+// SPDX-FileCopyrightText: 2024 Kent Gibson <warthog618@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use gpiocdev::{Error, LineDatabase};
+use gpiosim::{Bank, Direction};
+
+fn sim(prefix: &str) -> gpiosim::Sim {
+    gpiosim::builder()
+        .with_bank(
+            Bank::new(8, format!("{prefix} 1"))
+                .name(3, format!("{prefix} banana"))
+                .name(5, format!("{prefix} apple"))
+                .hog(5, "hogster", Direction::Input),
+        )
+        .with_bank(Bank::new(12, format!("{prefix} 2")).name(4, format!("{prefix} banana")))
+        .live()
+        .unwrap()
+}
+
+#[test]
+fn on_chips() {
+    let s = sim("ldb oc");
+    let c = s.chips();
+    let db = LineDatabase::on_chips(&[c[0].dev_path(), c[1].dev_path()]);
+    assert_eq!(db.len(), 20);
+    assert!(!db.is_empty());
+
+    let apple = db.get_unique("ldb oc apple").unwrap().unwrap();
+    assert_eq!(&apple.chip, c[0].dev_path());
+    assert_eq!(apple.info.offset, 5);
+    assert_eq!(apple.info.consumer, "hogster");
+
+    let bananas = db.get("ldb oc banana");
+    assert_eq!(bananas.len(), 2);
+    assert_eq!(&bananas[0].chip, c[0].dev_path());
+    assert_eq!(bananas[0].info.offset, 3);
+    assert_eq!(&bananas[1].chip, c[1].dev_path());
+    assert_eq!(bananas[1].info.offset, 4);
+    assert_eq!(
+        db.get_unique("ldb oc banana"),
+        Err(Error::NonuniqueLineName("ldb oc banana".to_string()))
+    );
+
+    assert!(db.get("ldb oc kiwi").is_empty());
+    assert_eq!(db.get_unique("ldb oc kiwi"), Ok(None));
+
+    let info = db.info(c[1].dev_path(), 4).unwrap();
+    assert_eq!(info.name, "ldb oc banana");
+    assert!(db.info(c[1].dev_path(), 12).is_none());
+
+    // unavailable chips are skipped
+    let db = LineDatabase::on_chips(&["/dev/gpiochip_nonexistent"]);
+    assert!(db.is_empty());
+}
+
+#[test]
+fn new() {
+    let s = sim("ldb new");
+    let db = LineDatabase::new().unwrap();
+    assert!(db.len() >= 20);
+    let apple = db.get_unique("ldb new apple").unwrap().unwrap();
+    assert_eq!(&apple.chip, s.chips()[0].dev_path());
+    assert_eq!(db.get("ldb new banana").len(), 2);
+}
+
+#[test]
+fn refresh() {
+    let s = sim("ldb refresh");
+    let mut db = LineDatabase::new().unwrap();
+    let snapshot = db.clone();
+    assert!(db.get("ldb refresh2 apple").is_empty());
+
+    // a snapshot does not track new chips...
+    let s2 = sim("ldb refresh2");
+    assert!(db.get("ldb refresh2 apple").is_empty());
+
+    // ...until refreshed
+    db.refresh().unwrap();
+    let apple = db.get_unique("ldb refresh2 apple").unwrap().unwrap();
+    assert_eq!(&apple.chip, s2.chips()[0].dev_path());
+    assert_eq!(db.get("ldb refresh banana").len(), 2);
+
+    // clones retain the previous snapshot
+    assert!(snapshot.get("ldb refresh2 apple").is_empty());
+
+    // removed chips are dropped
+    drop(s2);
+    db.refresh().unwrap();
+    assert!(db.get("ldb refresh2 apple").is_empty());
+    assert!(db.get_unique("ldb refresh apple").unwrap().is_some());
+    drop(s);
+}
+
+#[test]
+fn shareable() {
+    fn is_send_sync<T: Send + Sync>() {}
+    is_send_sync::<LineDatabase>();
+}
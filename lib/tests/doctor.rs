@@ -0,0 +1,194 @@
+// SPDX-FileCopyrightText: 2024 Kent Gibson <warthog618@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use gpiocdev::chip::Chip;
+use gpiocdev::doctor::{self, Capabilities, Code, Severity};
+use gpiocdev::line::{Bias, Config, Direction as LineDirection, InfoChangeKind, Value};
+use gpiocdev::Request;
+use gpiosim::{Bank, Direction};
+use std::time::Duration;
+
+fn sim(label: &str) -> gpiosim::Sim {
+    gpiosim::builder()
+        .with_bank(
+            Bank::new(8, label)
+                .name(1, "LED0")
+                .name(2, "BTN")
+                .name(3, "hogged")
+                .hog(3, "hogster", Direction::OutputLow),
+        )
+        .live()
+        .unwrap()
+}
+
+fn codes(findings: &[doctor::Finding]) -> Vec<Code> {
+    findings.iter().map(|f| f.code).collect()
+}
+
+#[test]
+fn check_line_clean() {
+    let s = sim("doctor");
+    let c = Chip::from_path(s.chips()[0].dev_path()).unwrap();
+
+    let f = doctor::check_line(&c, 1, &Config::default());
+    assert!(f.is_empty(), "{:?}", f);
+
+    // bias is reflected by gpio-sim
+    let mut cfg = Config::default();
+    cfg.as_input();
+    cfg.bias = Some(Bias::PullUp);
+    let f = doctor::check_line(&c, 2, &cfg);
+    assert!(f.is_empty(), "{:?}", f);
+    // and the probe released the line
+    assert!(!c.line_info(2).unwrap().used);
+}
+
+#[test]
+fn check_line_probes_as_input() {
+    let s = sim("doctor");
+    let c = Chip::from_path(s.chips()[0].dev_path()).unwrap();
+    c.watch_line_info(2).unwrap();
+
+    // direction is not set, so the line is probed as an input
+    let cfg = Config {
+        bias: Some(Bias::PullDown),
+        ..Default::default()
+    };
+    let f = doctor::check_line(&c, 2, &cfg);
+    assert!(f.is_empty(), "{:?}", f);
+
+    let evt = c.read_line_info_change_event().unwrap();
+    assert_eq!(evt.kind, InfoChangeKind::Requested);
+    assert_eq!(evt.info.direction, LineDirection::Input);
+    assert_eq!(evt.info.bias, Some(Bias::PullDown));
+    let evt = c.read_line_info_change_event().unwrap();
+    assert_eq!(evt.kind, InfoChangeKind::Released);
+    assert!(!c.has_line_info_change_event().unwrap());
+}
+
+#[test]
+fn check_line_output_not_probed() {
+    let s = sim("doctor");
+    let c = Chip::from_path(s.chips()[0].dev_path()).unwrap();
+    c.watch_line_info(1).unwrap();
+
+    let mut cfg = Config::default();
+    cfg.as_output(Value::Active);
+    cfg.bias = Some(Bias::PullUp);
+    let f = doctor::check_line(&c, 1, &cfg);
+    assert_eq!(codes(&f), [Code::BiasNotProbed]);
+    assert_eq!(f[0].severity, Severity::Info);
+
+    // the line is never requested, so can never be driven
+    assert!(!c.has_line_info_change_event().unwrap());
+    let li = c.line_info(1).unwrap();
+    assert!(!li.used);
+    assert_eq!(li.direction, LineDirection::Input);
+}
+
+#[test]
+fn check_line_idle_output_not_probed() {
+    let s = sim("doctor");
+    let c = Chip::from_path(s.chips()[0].dev_path()).unwrap();
+
+    // leave the line as an idle output
+    let req = Request::builder()
+        .on_chip(c.path())
+        .with_line(1)
+        .as_output(Value::Inactive)
+        .request()
+        .unwrap();
+    drop(req);
+    let li = c.line_info(1).unwrap();
+    assert!(!li.used);
+    assert_eq!(li.direction, LineDirection::Output);
+    c.watch_line_info(1).unwrap();
+
+    // direction is not set, so the line is not reconfigured as an input
+    let cfg = Config {
+        bias: Some(Bias::PullUp),
+        ..Default::default()
+    };
+    let f = doctor::check_line(&c, 1, &cfg);
+    assert_eq!(codes(&f), [Code::BiasNotProbed]);
+    assert!(!c.has_line_info_change_event().unwrap());
+    assert_eq!(c.line_info(1).unwrap().direction, LineDirection::Output);
+}
+
+#[test]
+fn check_line_in_use() {
+    let s = sim("doctor");
+    let c = Chip::from_path(s.chips()[0].dev_path()).unwrap();
+
+    let mut cfg = Config::default();
+    cfg.as_input();
+    cfg.bias = Some(Bias::PullUp);
+    let f = doctor::check_line(&c, 3, &cfg);
+    // no probe as the line is in use
+    assert_eq!(codes(&f), [Code::LineInUse]);
+    assert_eq!(f[0].severity, Severity::Error);
+    assert!(f[0].message.contains("hogster"), "{}", f[0].message);
+}
+
+#[test]
+fn check_line_unnamed() {
+    let s = sim("doctor");
+    let c = Chip::from_path(s.chips()[0].dev_path()).unwrap();
+
+    let f = doctor::check_line(&c, 4, &Config::default());
+    assert_eq!(codes(&f), [Code::LineUnnamed]);
+}
+
+#[test]
+fn check_line_pinctrl() {
+    let s = sim("pinctrl-sim");
+    let c = Chip::from_path(s.chips()[0].dev_path()).unwrap();
+
+    let f = doctor::check_line(&c, 1, &Config::default());
+    assert_eq!(codes(&f), [Code::PinctrlChip]);
+}
+
+#[test]
+fn check_line_debounce_unsupported() {
+    let s = sim("doctor");
+    let c = Chip::from_path(s.chips()[0].dev_path()).unwrap();
+
+    let mut cfg = Config::default();
+    cfg.with_debounce_period(Duration::from_millis(5));
+    let caps = Capabilities { debounce: false };
+    let f = doctor::check_line_with_capabilities(&c, 2, &cfg, &caps);
+    assert_eq!(codes(&f), [Code::DebounceUnsupported]);
+
+    let caps = Capabilities { debounce: true };
+    let f = doctor::check_line_with_capabilities(&c, 2, &cfg, &caps);
+    assert!(f.is_empty(), "{:?}", f);
+}
+
+#[test]
+fn check_line_invalid_offset() {
+    let s = sim("doctor");
+    let c = Chip::from_path(s.chips()[0].dev_path()).unwrap();
+
+    let f = doctor::check_line(&c, 8, &Config::default());
+    assert_eq!(codes(&f), [Code::InfoUnavailable]);
+}
+
+#[test]
+fn findings_ordered_by_severity() {
+    let s = sim("pinctrl-sim");
+    let c = Chip::from_path(s.chips()[0].dev_path()).unwrap();
+
+    let mut cfg = Config::default();
+    cfg.with_debounce_period(Duration::from_millis(5));
+    let caps = Capabilities { debounce: false };
+    let f = doctor::check_line_with_capabilities(&c, 5, &cfg, &caps);
+    assert_eq!(
+        codes(&f),
+        [
+            Code::DebounceUnsupported,
+            Code::LineUnnamed,
+            Code::PinctrlChip
+        ]
+    );
+}
@@ -0,0 +1,148 @@
+// SPDX-FileCopyrightText: 2024 Kent Gibson <warthog618@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Tests that reading and decoding edge events does not allocate.
+//
+// Allocations are counted per thread, so tests running in parallel do not interfere.
+
+use crate::common::wait_propagation_delay;
+use gpiocdev::line::{EdgeDetection, EdgeKind};
+use gpiocdev::request::Request;
+use gpiosim::{Level, Simpleton};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+mod common;
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        _ = ALLOCS.try_with(|a| a.set(a.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+// The number of allocations made by the current thread while running f.
+fn allocs<T, F: FnOnce() -> T>(f: F) -> (T, usize) {
+    let start = ALLOCS.with(|a| a.get());
+    let res = f();
+    (res, ALLOCS.with(|a| a.get()) - start)
+}
+
+fn edge_request(s: &Simpleton, offset: u32) -> Request {
+    Request::builder()
+        .on_chip(s.dev_path())
+        .with_line(offset)
+        .with_edge_detection(EdgeDetection::BothEdges)
+        .request()
+        .unwrap()
+}
+
+#[test]
+fn read_edge_event() {
+    let s = Simpleton::new(4);
+    let offset = 2;
+    let req = edge_request(&s, offset);
+
+    s.pullup(offset).unwrap();
+    wait_propagation_delay();
+    let (evt, count) = allocs(|| req.read_edge_event().unwrap());
+    assert_eq!(count, 0);
+    assert_eq!(evt.kind, EdgeKind::Rising);
+    assert_eq!(evt.offset, offset);
+
+    // events are plain values
+    let copy = evt;
+    assert_eq!(copy, evt);
+}
+
+#[test]
+fn edge_event_buffer() {
+    let s = Simpleton::new(4);
+    let offset = 1;
+    let req = edge_request(&s, offset);
+    let mut buf = req.new_edge_event_buffer(4);
+
+    s.pullup(offset).unwrap();
+    wait_propagation_delay();
+    s.pulldown(offset).unwrap();
+    wait_propagation_delay();
+    let ((evt1, evt2), count) = allocs(|| (buf.read_event().unwrap(), buf.read_event().unwrap()));
+    assert_eq!(count, 0);
+    assert_eq!(evt1.kind, EdgeKind::Rising);
+    assert_eq!(evt2.kind, EdgeKind::Falling);
+}
+
+#[test]
+fn edge_event_from_slice() {
+    let s = Simpleton::new(4);
+    let offset = 3;
+    let req = edge_request(&s, offset);
+    let mut buf = vec![0_u64; req.edge_event_u64_size()];
+
+    s.set_pull(offset, Level::High).unwrap();
+    wait_propagation_delay();
+    let n = req.read_edge_events_into_slice(&mut buf).unwrap();
+    let (evt, count) = allocs(|| req.edge_event_from_slice(&buf[0..n]).unwrap());
+    assert_eq!(count, 0);
+    assert_eq!(evt.offset, offset);
+}
+
+#[test]
+fn read_edge_event_into() {
+    let s = Simpleton::new(4);
+    let offset = 2;
+    let req = edge_request(&s, offset);
+
+    s.pullup(offset).unwrap();
+    wait_propagation_delay();
+    let mut evt = req.read_edge_event().unwrap();
+    assert_eq!(evt.kind, EdgeKind::Rising);
+
+    s.pulldown(offset).unwrap();
+    wait_propagation_delay();
+    let (_, count) = allocs(|| req.read_edge_event_into(&mut evt).unwrap());
+    assert_eq!(count, 0);
+    assert_eq!(evt.kind, EdgeKind::Falling);
+    assert_eq!(evt.offset, offset);
+}
+
+#[test]
+fn edge_event_buffer_read_event_into() {
+    let s = Simpleton::new(4);
+    let offset = 1;
+    let req = edge_request(&s, offset);
+    let mut buf = req.new_edge_event_buffer(2);
+
+    s.pullup(offset).unwrap();
+    wait_propagation_delay();
+    let mut evt = buf.read_event().unwrap();
+    assert_eq!(evt.kind, EdgeKind::Rising);
+
+    // refills reuse the buffer
+    for _ in 0..3 {
+        s.pulldown(offset).unwrap();
+        wait_propagation_delay();
+        s.pullup(offset).unwrap();
+        wait_propagation_delay();
+        let (_, count) = allocs(|| buf.read_event_into(&mut evt).unwrap());
+        assert_eq!(count, 0);
+        assert_eq!(evt.kind, EdgeKind::Falling);
+        let (_, count) = allocs(|| buf.read_event_into(&mut evt).unwrap());
+        assert_eq!(count, 0);
+        assert_eq!(evt.kind, EdgeKind::Rising);
+    }
+}
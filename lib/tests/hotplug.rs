@@ -0,0 +1,59 @@
+// SPDX-FileCopyrightText: 2024 Kent Gibson <warthog618@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use gpiocdev::{ChipEvent, ChipWatcher};
+use std::path::Path;
+use std::time::Duration;
+
+// Wait for the next event for the chip, ignoring events for chips created by other tests.
+fn next_event_for(w: &ChipWatcher, path: &Path) -> ChipEvent {
+    loop {
+        assert!(w.wait_event(Duration::from_secs(1)).unwrap());
+        let evt = w.read_event().unwrap();
+        match &evt {
+            ChipEvent::Added(p) | ChipEvent::Removed(p) if p == path => return evt,
+            _ => continue,
+        }
+    }
+}
+
+#[test]
+fn add_remove() {
+    let w = gpiocdev::chips_watch().unwrap();
+
+    let s = gpiosim::Simpleton::new(4);
+    let path = s.dev_path().to_path_buf();
+    assert_eq!(next_event_for(&w, &path), ChipEvent::Added(path.clone()));
+    // the chip is openable by the time it is reported
+    assert!(gpiocdev::Chip::from_path(&path).is_ok());
+
+    drop(s);
+    assert_eq!(next_event_for(&w, &path), ChipEvent::Removed(path.clone()));
+}
+
+#[test]
+fn existing_chips_not_reported() {
+    let s = gpiosim::Simpleton::new(4);
+    let path = s.dev_path().to_path_buf();
+    let w = gpiocdev::chips_watch().unwrap();
+
+    drop(s);
+    // the first event for the chip is the removal
+    assert_eq!(next_event_for(&w, &path), ChipEvent::Removed(path.clone()));
+}
+
+#[test]
+fn events() {
+    let w = gpiocdev::chips_watch().unwrap();
+    let s = gpiosim::Simpleton::new(4);
+    let path = s.dev_path().to_path_buf();
+    let mut iter = w.events().filter_map(|r| match r.unwrap() {
+        ChipEvent::Added(p) if p == path => Some(true),
+        ChipEvent::Removed(p) if p == path => Some(false),
+        _ => None,
+    });
+    assert_eq!(iter.next(), Some(true));
+    drop(s);
+    assert_eq!(iter.next(), Some(false));
+}
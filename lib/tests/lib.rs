@@ -10,6 +10,7 @@
 use gpiocdev::chip::path_compare;
 use gpiosim::Bank;
 use std::cmp::Ordering;
+use std::path::Path;
 
 #[test]
 fn find_named_line() {
@@ -31,10 +32,18 @@ fn find_named_line() {
     let l = gpiocdev::find_named_line("fl banana").unwrap();
     assert_eq!(l.chip, *sim.chips()[0].dev_path());
     assert_eq!(l.info.offset, 3);
+    assert_eq!(l.chip_info.label, "find_line 1");
+    assert_eq!(
+        l.chip_info.name.as_str(),
+        sim.chips()[0].dev_path().file_name().unwrap()
+    );
+    assert_eq!(l.chip_info.num_lines, 8);
 
     let l = gpiocdev::find_named_line("fl piggly").unwrap();
     assert_eq!(&l.chip, sim.chips()[1].dev_path());
     assert_eq!(l.info.offset, 4);
+    assert_eq!(l.chip_info.label, "find_line 2");
+    assert_eq!(l.chip_info.num_lines, 42);
 
     let l = gpiocdev::find_named_line("fl apple").unwrap();
     // depending on how other tests are running, the order of the sim chips is not 100% predictable.
@@ -49,6 +58,58 @@ fn find_named_line() {
     assert!(gpiocdev::find_named_line("fl nada").is_none())
 }
 
+#[test]
+fn try_find_named_line() {
+    let sim = gpiosim::builder()
+        .with_bank(Bank::new(8, "try_find_line 1").name(3, "tfl banana"))
+        .with_bank(Bank::new(12, "try_find_line 2").name(5, "tfl apple"))
+        .live()
+        .unwrap();
+
+    let l = gpiocdev::try_find_named_line("tfl banana")
+        .unwrap()
+        .unwrap();
+    assert_eq!(&l.chip, sim.chips()[0].dev_path());
+    assert_eq!(l.info.offset, 3);
+
+    let l = gpiocdev::try_find_named_line("tfl apple").unwrap().unwrap();
+    assert_eq!(&l.chip, sim.chips()[1].dev_path());
+    assert_eq!(l.info.offset, 5);
+
+    assert_eq!(gpiocdev::try_find_named_line("tfl nada"), Ok(None));
+}
+
+#[test]
+fn find_named_line_os() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    // gpiosim only supports UTF-8 names, so check the non-ASCII bytes round trip.
+    let sim = gpiosim::builder()
+        .with_bank(
+            Bank::new(8, "find_line_os")
+                .name(3, "flo piñata")
+                .name(5, "flo ça va"),
+        )
+        .live()
+        .unwrap();
+
+    let name = OsStr::from_bytes("flo piñata".as_bytes());
+    let l = gpiocdev::find_named_line_os(name).unwrap();
+    assert_eq!(&l.chip, sim.chips()[0].dev_path());
+    assert_eq!(l.info.offset, 3);
+    assert_eq!(l.info.name_os, name);
+    assert_eq!(l.info.name, "flo piñata");
+
+    let l = gpiocdev::find_named_line_os(OsStr::new("flo ça va")).unwrap();
+    assert_eq!(l.info.offset, 5);
+    assert_eq!(l.info.name_os.as_bytes(), "flo ça va".as_bytes());
+
+    // no lossy matching
+    assert!(gpiocdev::find_named_line_os(OsStr::from_bytes(b"flo pi\xc3")).is_none());
+    assert!(gpiocdev::find_named_line_os(OsStr::new("flo nada")).is_none());
+}
+
 #[test]
 fn find_named_lines() {
     let sim = gpiosim::builder()
@@ -66,21 +127,21 @@ fn find_named_lines() {
         .live()
         .unwrap();
 
-    let found = gpiocdev::find_named_lines(&["fls banana"], true).unwrap();
+    let found = gpiocdev::find_named_lines(["fls banana"], true).unwrap();
     assert_eq!(found.len(), 1);
-    let l = found.get(&"fls banana").unwrap();
+    let l = found.get("fls banana").unwrap();
     assert_eq!(&l.chip, sim.chips()[0].dev_path());
     assert_eq!(l.info.offset, 3);
 
-    let found = gpiocdev::find_named_lines(&["fls piggly"], true).unwrap();
+    let found = gpiocdev::find_named_lines(["fls piggly"], true).unwrap();
     assert_eq!(found.len(), 1);
-    let l = found.get(&"fls piggly").unwrap();
+    let l = found.get("fls piggly").unwrap();
     assert_eq!(&l.chip, sim.chips()[1].dev_path());
     assert_eq!(l.info.offset, 4);
 
-    let found = gpiocdev::find_named_lines(&["fls apple"], false).unwrap();
+    let found = gpiocdev::find_named_lines(["fls apple"], false).unwrap();
     assert_eq!(found.len(), 1);
-    let l = found.get(&"fls apple").unwrap();
+    let l = found.get("fls apple").unwrap();
     if path_compare(sim.chips()[0].dev_path(), sim.chips()[1].dev_path()) == Ordering::Less {
         assert_eq!(&l.chip, sim.chips()[0].dev_path());
         assert_eq!(l.info.offset, 6);
@@ -89,24 +150,23 @@ fn find_named_lines() {
         assert_eq!(l.info.offset, 5);
     }
 
-    let found = gpiocdev::find_named_lines(&["fls apple"], true);
+    let found = gpiocdev::find_named_lines(["fls apple"], true);
     assert_eq!(
         found,
         Err(gpiocdev::Error::NonuniqueLineName("fls apple".to_string()))
     );
 
-    let found = gpiocdev::find_named_lines(&["fls banana", "fls piggly"], true).unwrap();
+    let found = gpiocdev::find_named_lines(["fls banana", "fls piggly"], true).unwrap();
     assert_eq!(found.len(), 2);
 
-    let found = gpiocdev::find_named_lines(&["fls nada"], true).unwrap();
+    let found = gpiocdev::find_named_lines(["fls nada"], true).unwrap();
     assert_eq!(found.len(), 0);
 
-    let found =
-        gpiocdev::find_named_lines(&["fls apple", "fls banana", "fls nada"], false).unwrap();
-    let l = found.get(&"fls banana").unwrap();
+    let found = gpiocdev::find_named_lines(["fls apple", "fls banana", "fls nada"], false).unwrap();
+    let l = found.get("fls banana").unwrap();
     assert_eq!(&l.chip, sim.chips()[0].dev_path());
     assert_eq!(l.info.offset, 3);
-    let l = found.get(&"fls apple").unwrap();
+    let l = found.get("fls apple").unwrap();
     if path_compare(sim.chips()[0].dev_path(), sim.chips()[1].dev_path()) == Ordering::Less {
         assert_eq!(&l.chip, sim.chips()[0].dev_path());
         assert_eq!(l.info.offset, 6);
@@ -114,7 +174,60 @@ fn find_named_lines() {
         assert_eq!(&l.chip, sim.chips()[1].dev_path());
         assert_eq!(l.info.offset, 5);
     }
-    assert!(!found.contains_key(&"fls nada"));
+    assert!(!found.contains_key("fls nada"));
+
+    // owned names
+    let names: Vec<String> = vec!["fls banana".into(), "fls piggly".into()];
+    let found = gpiocdev::find_named_lines(&names, true).unwrap();
+    assert_eq!(found.len(), 2);
+    let l = found.get(&names[1]).unwrap();
+    assert_eq!(&l.chip, sim.chips()[1].dev_path());
+    assert_eq!(l.info.offset, 4);
+
+    // any iterator of names
+    let found = gpiocdev::find_named_lines(names.into_iter().skip(1), true).unwrap();
+    assert_eq!(found.len(), 1);
+    assert!(found.contains_key("fls piggly"));
+}
+
+#[test]
+fn find_all_named_lines() {
+    let sim = gpiosim::builder()
+        .with_bank(
+            Bank::new(8, "find_all_lines 1")
+                .name(3, "fals banana")
+                .name(6, "fals apple"),
+        )
+        .with_bank(
+            Bank::new(42, "find_all_lines 2")
+                .name(3, "fals piñata")
+                .name(5, "fals apple"),
+        )
+        .live()
+        .unwrap();
+
+    let found =
+        gpiocdev::find_all_named_lines(&["fals apple", "fals banana", "fals nada"]).unwrap();
+    assert_eq!(found.len(), 2);
+    assert!(!found.contains_key("fals nada"));
+
+    let bananas = found.get("fals banana").unwrap();
+    assert_eq!(bananas.len(), 1);
+    assert_eq!(&bananas[0].chip, sim.chips()[0].dev_path());
+    assert_eq!(bananas[0].info.offset, 3);
+
+    let apples = found.get("fals apple").unwrap();
+    assert_eq!(apples.len(), 2);
+    let (first, second) =
+        if path_compare(sim.chips()[0].dev_path(), sim.chips()[1].dev_path()) == Ordering::Less {
+            ((0, 6), (1, 5))
+        } else {
+            ((1, 5), (0, 6))
+        };
+    assert_eq!(&apples[0].chip, sim.chips()[first.0].dev_path());
+    assert_eq!(apples[0].info.offset, first.1);
+    assert_eq!(&apples[1].chip, sim.chips()[second.0].dev_path());
+    assert_eq!(apples[1].info.offset, second.1);
 }
 
 #[test]
@@ -135,6 +248,29 @@ fn detect_abi_version() {
     drop(s);
 }
 
+#[test]
+fn detect_abi_support() {
+    // assumes a kernel with both v1 and v2 supported.
+
+    // to ensure there is at least one chip
+    let s = gpiosim::Simpleton::new(4);
+
+    let xsupport = gpiocdev::AbiSupport {
+        v1: cfg!(feature = "uapi_v1"),
+        v2: cfg!(feature = "uapi_v2"),
+    };
+    assert_eq!(gpiocdev::detect_abi_support(), Ok(xsupport));
+    // cached
+    assert_eq!(gpiocdev::detect_abi_support(), Ok(xsupport));
+    assert_eq!(gpiocdev::detect_abi_support_uncached(), Ok(xsupport));
+    assert_eq!(
+        gpiocdev::detect_abi_version_uncached(),
+        gpiocdev::detect_abi_version()
+    );
+
+    drop(s);
+}
+
 #[test]
 fn supports_abi_version() {
     // assumes a kernel with both v1 and v2 supported.
@@ -171,3 +307,542 @@ fn supports_abi_version() {
 
     drop(s);
 }
+
+#[test]
+fn line_iterator_for_chip() {
+    let sim = gpiosim::builder()
+        .with_bank(Bank::new(8, "for_chip 1").name(3, "lifc banana"))
+        .with_bank(Bank::new(42, "for_chip 2").name(5, "lifc apple"))
+        .live()
+        .unwrap();
+
+    let lines: Vec<gpiocdev::FoundLine> =
+        gpiocdev::LineIterator::for_chip(sim.chips()[1].dev_path())
+            .unwrap()
+            .collect();
+    assert_eq!(lines.len(), 42);
+    assert!(lines.iter().all(|l| &l.chip == sim.chips()[1].dev_path()));
+    assert_eq!(lines[0].info.offset, 0);
+    assert_eq!(lines[5].info.name, "lifc apple");
+
+    assert!(gpiocdev::LineIterator::for_chip("/dev/gpiochip_nonexistent").is_err());
+}
+
+#[test]
+fn line_iterator_on_chip() {
+    let sim = gpiosim::builder()
+        .with_bank(Bank::new(8, "on_chip 1").name(3, "lio banana"))
+        .with_bank(Bank::new(12, "on_chip 2").name(3, "lio banana"))
+        .live()
+        .unwrap();
+
+    let lines: Vec<gpiocdev::FoundLine> =
+        gpiocdev::LineIterator::on_chip(sim.chips()[1].dev_path())
+            .unwrap()
+            .collect();
+    assert_eq!(lines.len(), 12);
+    // lines on other chips are not visited
+    assert!(lines.iter().all(|l| &l.chip == sim.chips()[1].dev_path()));
+    let found: Vec<&gpiocdev::FoundLine> = lines
+        .iter()
+        .filter(|l| l.info.name == "lio banana")
+        .collect();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].info.offset, 3);
+
+    assert!(gpiocdev::LineIterator::on_chip("/dev/gpiochip_nonexistent").is_err());
+}
+
+#[test]
+fn line_iterator_on_chips() {
+    let sim = gpiosim::builder()
+        .with_bank(Bank::new(4, "on_chips 1").name(1, "lioc banana"))
+        .with_bank(Bank::new(5, "on_chips 2").name(1, "lioc banana"))
+        .with_bank(Bank::new(6, "on_chips 3").name(1, "lioc banana"))
+        .live()
+        .unwrap();
+    let c = sim.chips();
+
+    let lines: Vec<gpiocdev::FoundLine> =
+        gpiocdev::LineIterator::on_chips(&[c[2].dev_path(), c[0].dev_path()]).collect();
+    assert_eq!(lines.len(), 10);
+    // in the provided chip order
+    assert!(lines[..6].iter().all(|l| &l.chip == c[2].dev_path()));
+    assert!(lines[6..].iter().all(|l| &l.chip == c[0].dev_path()));
+    assert_eq!(lines[6].info.offset, 0);
+    // lines on other chips are not visited
+    let found: Vec<&gpiocdev::FoundLine> = lines
+        .iter()
+        .filter(|l| l.info.name == "lioc banana")
+        .collect();
+    assert_eq!(found.len(), 2);
+    assert_eq!(&found[0].chip, c[2].dev_path());
+
+    // unavailable chips are skipped
+    let lines: Vec<gpiocdev::FoundLine> = gpiocdev::LineIterator::on_chips(&[
+        Path::new("/dev/gpiochip_nonexistent"),
+        c[1].dev_path(),
+    ])
+    .collect();
+    assert_eq!(lines.len(), 5);
+}
+
+#[test]
+fn line_iterator_starting_from() {
+    let sim = gpiosim::builder()
+        .with_bank(&Bank::new(8, "starting_from 1"))
+        .with_bank(&Bank::new(12, "starting_from 2"))
+        .live()
+        .unwrap();
+
+    let chips = gpiocdev::chip::chips().unwrap();
+    for c in sim.chips() {
+        let idx = chips.iter().position(|p| p == c.dev_path()).unwrap();
+        let l = gpiocdev::LineIterator::starting_from(idx)
+            .unwrap()
+            .next()
+            .unwrap();
+        assert_eq!(&l.chip, c.dev_path());
+        assert_eq!(l.info.offset, 0);
+    }
+
+    let mut iter = gpiocdev::LineIterator::starting_from(chips.len()).unwrap();
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn find_lines_matching() {
+    let sim = gpiosim::builder()
+        .with_bank(
+            Bank::new(8, "find_matching 1")
+                .name(1, "flm PWR_EN_3")
+                .name(3, "flm banana")
+                .name(4, "flm PWR_EN_1")
+                .name(6, "flm PWR_EN_2"),
+        )
+        .with_bank(
+            Bank::new(12, "find_matching 2")
+                .name(2, "flm PWR_EN_5")
+                .name(5, "flm PWR_EN_4")
+                .name(7, "flm PWR_EN_10")
+                .name(9, "flm piñata"),
+        )
+        .live()
+        .unwrap();
+    // other tests may be running concurrently, so ignore lines from other chips.
+    let sim_chip = |group: &Vec<gpiocdev::FoundLine>| {
+        &group[0].chip == sim.chips()[0].dev_path() || &group[0].chip == sim.chips()[1].dev_path()
+    };
+
+    // grouped by chip in chip order, then in offset order within each group
+    let found: Vec<Vec<_>> = gpiocdev::find_lines_matching(|li| li.name.starts_with("flm PWR_EN_"))
+        .unwrap()
+        .into_iter()
+        .filter(sim_chip)
+        .map(|group| group.into_iter().map(|l| (l.chip, l.info.offset)).collect())
+        .collect();
+    let first: Vec<_> = [1, 4, 6]
+        .iter()
+        .map(|offset| (sim.chips()[0].dev_path().clone(), *offset))
+        .collect();
+    let second: Vec<_> = [2, 5, 7]
+        .iter()
+        .map(|offset| (sim.chips()[1].dev_path().clone(), *offset))
+        .collect();
+    let expected =
+        if path_compare(sim.chips()[0].dev_path(), sim.chips()[1].dev_path()) == Ordering::Less {
+            vec![first, second]
+        } else {
+            vec![second, first]
+        };
+    assert_eq!(found, expected);
+
+    // predicate may use any info field
+    let found: Vec<_> = gpiocdev::find_lines_matching(|li| li.offset == 9 && !li.name.is_empty())
+        .unwrap()
+        .into_iter()
+        .filter(sim_chip)
+        .collect();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].len(), 1);
+    assert_eq!(found[0][0].info.name, "flm piñata");
+
+    let found = gpiocdev::find_lines_matching(|li| li.name == "flm nada").unwrap();
+    assert!(found.is_empty());
+}
+
+#[test]
+fn find_lines_matching_groups_per_chip() {
+    let sim = gpiosim::builder()
+        .with_bank(
+            Bank::new(4, "find_grouped 1")
+                .name(0, "flmg LED0")
+                .name(3, "flmg LED1"),
+        )
+        .with_bank(Bank::new(4, "find_grouped 2").name(1, "flmg BUTTON0"))
+        .with_bank(
+            Bank::new(4, "find_grouped 3")
+                .name(1, "flmg LED2")
+                .name(2, "flmg LED3"),
+        )
+        .live()
+        .unwrap();
+    let sim_chips: Vec<_> = sim.chips().iter().map(|c| c.dev_path().clone()).collect();
+
+    let groups: Vec<_> = gpiocdev::find_lines_matching(|li| li.name.starts_with("flmg LED"))
+        .unwrap()
+        .into_iter()
+        .filter(|group| sim_chips.contains(&group[0].chip))
+        .collect();
+    // the chip without matching lines is omitted
+    assert_eq!(groups.len(), 2);
+    for group in &groups {
+        assert!(group.iter().all(|l| l.chip == group[0].chip));
+    }
+    assert!(path_compare(&groups[0][0].chip, &groups[1][0].chip) == Ordering::Less);
+
+    // each group can be requested as a whole
+    for group in &groups {
+        let offsets: Vec<_> = group.iter().map(|l| l.info.offset).collect();
+        let req = gpiocdev::Request::builder()
+            .on_chip(&group[0].chip)
+            .with_lines(&offsets)
+            .as_input()
+            .request()
+            .unwrap();
+        assert_eq!(req.config().lines(), &offsets);
+    }
+}
+
+#[test]
+fn find_lines_glob() {
+    let sim = gpiosim::builder()
+        .with_bank(
+            Bank::new(8, "find_glob 1")
+                .name(1, "flg PWR_EN_3")
+                .name(3, "flg banana")
+                .name(4, "flg PWR_EN_1"),
+        )
+        .with_bank(
+            Bank::new(12, "find_glob 2")
+                .name(2, "flg PWR_EN_5")
+                .name(7, "flg PWR_EN_10")
+                .name(9, "flg piñata"),
+        )
+        .live()
+        .unwrap();
+    let names = |pattern: &str| {
+        let mut names: Vec<String> = gpiocdev::find_lines_glob(pattern)
+            .unwrap()
+            .into_iter()
+            .flatten()
+            .filter(|l| {
+                &l.chip == sim.chips()[0].dev_path() || &l.chip == sim.chips()[1].dev_path()
+            })
+            .map(|l| l.info.name)
+            .collect();
+        names.sort_unstable();
+        names
+    };
+
+    assert_eq!(
+        names("flg PWR_EN_*"),
+        &[
+            "flg PWR_EN_1",
+            "flg PWR_EN_10",
+            "flg PWR_EN_3",
+            "flg PWR_EN_5"
+        ]
+    );
+    assert_eq!(
+        names("flg PWR_EN_?"),
+        &["flg PWR_EN_1", "flg PWR_EN_3", "flg PWR_EN_5"]
+    );
+    assert_eq!(names("flg pi?ata"), &["flg piñata"]);
+    assert_eq!(names("flg *an*"), &["flg banana"]);
+    assert_eq!(names("flg banana"), &["flg banana"]);
+    assert!(names("flg nada*").is_empty());
+}
+
+#[test]
+fn find_named_line_with() {
+    let sim = gpiosim::builder()
+        .with_bank(
+            Bank::new(8, "find_line_with 1")
+                .name(3, "FLW Banana")
+                .name(6, " flw apple "),
+        )
+        .live()
+        .unwrap();
+    let chip = sim.chips()[0].dev_path();
+
+    // exact by default
+    let exact = gpiocdev::MatchOptions::default();
+    assert!(gpiocdev::find_named_line_with("flw banana", exact).is_none());
+    let l = gpiocdev::find_named_line_with("FLW Banana", exact).unwrap();
+    assert_eq!(&l.chip, chip);
+    assert_eq!(l.info.offset, 3);
+
+    let case_insensitive = gpiocdev::MatchOptions::default().with_case_insensitive(true);
+    let l = gpiocdev::find_named_line_with("flw BANANA", case_insensitive).unwrap();
+    assert_eq!(&l.chip, chip);
+    assert_eq!(l.info.offset, 3);
+    assert!(gpiocdev::find_named_line_with("flw apple", case_insensitive).is_none());
+
+    let trimmed = gpiocdev::MatchOptions::default().with_trim_whitespace(true);
+    let l = gpiocdev::find_named_line_with("flw apple", trimmed).unwrap();
+    assert_eq!(&l.chip, chip);
+    assert_eq!(l.info.offset, 6);
+    assert!(gpiocdev::find_named_line_with(" FLW apple", trimmed).is_none());
+
+    let both = gpiocdev::MatchOptions::default()
+        .with_case_insensitive(true)
+        .with_trim_whitespace(true);
+    let l = gpiocdev::find_named_line_with(" FLW apple", both).unwrap();
+    assert_eq!(l.info.offset, 6);
+    let l = gpiocdev::find_named_line_with("flw banana\n", both).unwrap();
+    assert_eq!(l.info.offset, 3);
+}
+
+#[test]
+fn find_named_lines_with() {
+    let sim = gpiosim::builder()
+        .with_bank(
+            Bank::new(8, "find_lines_with 1")
+                .name(3, "FLSW LED0")
+                .name(6, "flsw sensor0 "),
+        )
+        .with_bank(Bank::new(12, "find_lines_with 2").name(4, "flsw led0"))
+        .live()
+        .unwrap();
+
+    // exact by default, so no conflict
+    let exact = gpiocdev::MatchOptions::default();
+    let found = gpiocdev::find_named_lines_with(["FLSW LED0"], true, exact).unwrap();
+    assert_eq!(found.len(), 1);
+    let l = found.get("FLSW LED0").unwrap();
+    assert_eq!(&l.chip, sim.chips()[0].dev_path());
+    assert_eq!(l.info.offset, 3);
+
+    // case insensitive names conflict
+    let case_insensitive = gpiocdev::MatchOptions::default().with_case_insensitive(true);
+    assert_eq!(
+        gpiocdev::find_named_lines_with(["Flsw Led0"], true, case_insensitive),
+        Err(gpiocdev::Error::NonuniqueLineName("Flsw Led0".to_string()))
+    );
+    let found = gpiocdev::find_named_lines_with(["Flsw Led0"], false, case_insensitive).unwrap();
+    assert_eq!(found.len(), 1);
+    assert!(found.contains_key("Flsw Led0"));
+
+    // keys are the names as provided
+    let both = gpiocdev::MatchOptions::default()
+        .with_case_insensitive(true)
+        .with_trim_whitespace(true);
+    let found = gpiocdev::find_named_lines_with(["FLSW SENSOR0"], true, both).unwrap();
+    assert_eq!(found.len(), 1);
+    let l = found.get("FLSW SENSOR0").unwrap();
+    assert_eq!(&l.chip, sim.chips()[0].dev_path());
+    assert_eq!(l.info.offset, 6);
+
+    let found = gpiocdev::find_named_lines_with(["flsw sensor0"], true, exact).unwrap();
+    assert!(found.is_empty());
+}
+
+#[test]
+fn line_iterator_with_chips() {
+    let sim = gpiosim::builder()
+        .with_bank(Bank::new(4, "with_chips 1").name(1, "liwc banana"))
+        .with_bank(Bank::new(6, "with_chips 2").name(2, "liwc apple"))
+        .live()
+        .unwrap();
+    let c = sim.chips();
+
+    let chips = vec![
+        gpiocdev::Chip::from_path(c[1].dev_path()).unwrap(),
+        gpiocdev::Chip::from_path(c[0].dev_path()).unwrap(),
+    ];
+    let mut iter = gpiocdev::LineIterator::with_chips(chips);
+    let lines: Vec<gpiocdev::FoundLine> = iter.by_ref().collect();
+    assert_eq!(lines.len(), 10);
+    // in the provided chip order
+    assert!(lines[..6].iter().all(|l| &l.chip == c[1].dev_path()));
+    assert_eq!(lines[2].info.name, "liwc apple");
+    assert_eq!(lines[7].info.name, "liwc banana");
+    assert!(iter.next().is_none());
+
+    // rewind repeats the scan
+    iter.rewind();
+    let relines: Vec<gpiocdev::FoundLine> = iter.collect();
+    assert_eq!(relines, lines);
+}
+
+#[test]
+fn find_named_lines_in() {
+    let sim = gpiosim::builder()
+        .with_bank(
+            Bank::new(8, "find_lines_in 1")
+                .name(3, "flin LED0")
+                .name(5, "flin BTN0"),
+        )
+        .with_bank(
+            Bank::new(12, "find_lines_in 2")
+                .name(4, "flin LED1")
+                .name(7, "flin btn0"),
+        )
+        .live()
+        .unwrap();
+    let chips = sim
+        .chips()
+        .iter()
+        .map(|c| gpiocdev::Chip::from_path(c.dev_path()).unwrap())
+        .collect();
+    let mut lines = gpiocdev::LineIterator::with_chips(chips);
+    let exact = gpiocdev::MatchOptions::default();
+    let case_insensitive = gpiocdev::MatchOptions::default().with_case_insensitive(true);
+
+    // multiple lookups against the one source match independent scans
+    let lookups: [(&[&str], bool, gpiocdev::MatchOptions); 4] = [
+        (&["flin LED0", "flin LED1"], true, exact),
+        (&["flin BTN0"], true, exact),
+        (&["flin LED1", "flin missing"], false, exact),
+        (&["FLIN led1"], false, case_insensitive),
+    ];
+    for (names, strict, opts) in lookups {
+        let found = gpiocdev::find_named_lines_in(&mut lines, names, strict, opts).unwrap();
+        assert_eq!(
+            found,
+            gpiocdev::find_named_lines_with(names, strict, opts).unwrap()
+        );
+    }
+    let found =
+        gpiocdev::find_named_lines_in(&mut lines, ["flin LED0", "flin LED1"], true, exact).unwrap();
+    assert_eq!(found.len(), 2);
+    assert_eq!(found.get("flin LED1").unwrap().info.offset, 4);
+
+    assert_eq!(
+        gpiocdev::find_named_lines_in(&mut lines, ["flin BTN0"], true, case_insensitive),
+        Err(gpiocdev::Error::NonuniqueLineName("flin BTN0".to_string()))
+    );
+}
+
+#[test]
+fn find_named_lines_in_strict_chip_error() {
+    let sim = gpiosim::builder()
+        .with_bank(Bank::new(8, "find_lines_in_strict").name(3, "flis LED0"))
+        .live()
+        .unwrap();
+    let chip = sim.chips()[0].dev_path().as_path();
+    let exact = gpiocdev::MatchOptions::default();
+
+    // unreadable chips are skipped when lenient...
+    let mut lines = gpiocdev::LineIterator::on_chips(&[chip, Path::new("/dev/null")]);
+    let found = gpiocdev::find_named_lines_in(&mut lines, ["flis LED0"], false, exact).unwrap();
+    assert_eq!(found.get("flis LED0").unwrap().info.offset, 3);
+    let found = gpiocdev::find_named_lines_in(&mut lines, ["flis nada"], false, exact).unwrap();
+    assert!(found.is_empty());
+
+    // ...but reported when strict
+    let mut lines = gpiocdev::LineIterator::on_chips(&[chip, Path::new("/dev/null")]);
+    assert_eq!(
+        gpiocdev::find_named_lines_in(&mut lines, ["flis LED0"], true, exact),
+        Err(gpiocdev::Error::GpioChip(
+            "/dev/null".into(),
+            gpiocdev::chip::ErrorKind::NotGpioDevice
+        ))
+    );
+}
+
+#[test]
+fn strict_line_iterator() {
+    let sim = gpiosim::builder()
+        .with_bank(Bank::new(3, "strict_line_iterator 1").name(1, "sli banana"))
+        .with_bank(&Bank::new(2, "strict_line_iterator 2"))
+        .live()
+        .unwrap();
+    let c = sim.chips();
+    let nonexistent = Path::new("/dev/gpiochip_nonexistent");
+    let paths = [
+        c[0].dev_path().as_path(),
+        Path::new("/dev/null"),
+        nonexistent,
+        c[1].dev_path().as_path(),
+    ];
+
+    let mut lines = gpiocdev::StrictLineIterator::on_chips(&paths);
+    for offset in 0..3 {
+        let l = lines.next().unwrap().unwrap();
+        assert_eq!(&l.chip, c[0].dev_path());
+        assert_eq!(l.info.offset, offset);
+    }
+    assert_eq!(
+        lines.next(),
+        Some(Err(gpiocdev::Error::GpioChip(
+            "/dev/null".into(),
+            gpiocdev::chip::ErrorKind::NotGpioDevice
+        )))
+    );
+    match lines.next() {
+        Some(Err(gpiocdev::Error::ChipAccess(p, _))) => assert_eq!(p, nonexistent),
+        x => panic!("expected ChipAccess error, got {:?}", x),
+    }
+    // iteration continues past the failed chips
+    for offset in 0..2 {
+        let l = lines.next().unwrap().unwrap();
+        assert_eq!(&l.chip, c[1].dev_path());
+        assert_eq!(l.info.offset, offset);
+    }
+    assert!(lines.next().is_none());
+
+    // whereas the lenient iterator skips them
+    assert_eq!(gpiocdev::LineIterator::on_chips(&paths).count(), 5);
+}
+
+#[test]
+fn request_input() {
+    use gpiocdev::line::{Direction, Value};
+
+    let s = gpiosim::Simpleton::new(4);
+    let offset = 2;
+    s.pullup(offset).unwrap();
+
+    // from a path
+    let req = gpiocdev::request_input(s.dev_path(), offset).unwrap();
+    let info = req.line_config(offset).unwrap();
+    assert_eq!(info.direction, Some(Direction::Input));
+    assert_eq!(req.value(offset).unwrap(), Value::Active);
+    drop(req);
+
+    // from a &str
+    let path = s.dev_path().to_str().unwrap();
+    let req = gpiocdev::request_input(path, offset).unwrap();
+    assert_eq!(req.value(offset).unwrap(), Value::Active);
+    s.pulldown(offset).unwrap();
+    assert_eq!(req.value(offset).unwrap(), Value::Inactive);
+}
+
+#[test]
+fn request_output() {
+    use gpiocdev::line::{Direction, Value};
+    use gpiosim::Level;
+
+    let s = gpiosim::Simpleton::new(4);
+    let offset = 1;
+
+    // from a path
+    let req = gpiocdev::request_output(s.dev_path(), offset, Value::Active).unwrap();
+    let info = req.line_config(offset).unwrap();
+    assert_eq!(info.direction, Some(Direction::Output));
+    assert_eq!(s.get_level(offset).unwrap(), Level::High);
+    req.set_value(offset, Value::Inactive).unwrap();
+    assert_eq!(s.get_level(offset).unwrap(), Level::Low);
+    drop(req);
+
+    // from a &str
+    let path = s.dev_path().to_str().unwrap();
+    let req = gpiocdev::request_output(path, offset, Value::Inactive).unwrap();
+    assert_eq!(s.get_level(offset).unwrap(), Level::Low);
+    drop(req);
+
+    // unknown offset
+    assert!(gpiocdev::request_output(s.dev_path(), 6, Value::Active).is_err());
+}
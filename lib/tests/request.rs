@@ -48,6 +48,7 @@ mod builder {
             request_as_is,
             request_found_line,
             request_found_lines,
+            request_line_as_info,
             request_output_lines,
             request_mixed_config,
             request_invalid_offset
@@ -179,6 +180,7 @@ mod builder {
             request_as_is,
             request_found_line,
             request_found_lines,
+            request_line_as_info,
             request_output_lines,
             request_mixed_config,
             request_invalid_offset
@@ -229,6 +231,33 @@ mod builder {
             drop(req);
         }
 
+        #[test]
+        fn request_line_as_info_with_edges() {
+            let s = Simpleton::new(10);
+            let c = Chip::from_path(s.dev_path()).unwrap();
+            let offset = 1;
+
+            let req = Request::builder()
+                .on_chip(s.dev_path())
+                .with_line(offset)
+                .as_input()
+                .with_edge_detection(EdgeDetection::BothEdges)
+                .with_event_clock(EventClock::Realtime)
+                .with_debounce_period(Duration::from_millis(4))
+                .request()
+                .unwrap();
+            let info = c.line_info(offset).unwrap();
+            drop(req);
+
+            let req = Request::builder()
+                .on_chip(s.dev_path())
+                .with_line_as_info(&info)
+                .request()
+                .unwrap();
+            assert_eq!(c.line_info(offset).unwrap(), info);
+            drop(req);
+        }
+
         #[test]
         fn request_debounced_zero() {
             let s = Simpleton::new(10);
@@ -608,6 +637,7 @@ mod builder {
 
         let line = FoundLine {
             chip: s.dev_path().clone(),
+            chip_info: Default::default(),
             info: Info {
                 offset: 3,
                 ..Default::default()
@@ -633,6 +663,7 @@ mod builder {
                 "three",
                 FoundLine {
                     chip: s.dev_path().clone(),
+                    chip_info: Default::default(),
                     info: Info {
                         offset: 3,
                         ..Default::default()
@@ -643,6 +674,7 @@ mod builder {
                 "five",
                 FoundLine {
                     chip: s.dev_path().clone(),
+                    chip_info: Default::default(),
                     info: Info {
                         offset: 5,
                         ..Default::default()
@@ -680,10 +712,46 @@ mod builder {
                 .as_input()
                 .request()
                 .unwrap_err(),
-            gpiocdev::Error::InvalidArgument("Multiple chips requested.".into())
+            gpiocdev::Error::MultipleChips(vec![
+                (s.dev_path().to_owned(), vec![3]),
+                (PathBuf::from("boguschip"), vec![5])
+            ])
         );
     }
 
+    #[allow(unused_variables)]
+    fn request_line_as_info(abiv: AbiVersion) {
+        let s = Simpleton::new(4);
+        let c = Chip::from_path(s.dev_path()).unwrap();
+        let offset = 2;
+
+        let mut builder = Request::builder();
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        builder.using_abi_version(abiv);
+        let req = builder
+            .on_chip(s.dev_path())
+            .with_line(offset)
+            .as_output(Value::Active)
+            .as_active_low()
+            .with_bias(Bias::PullUp)
+            .with_drive(Drive::OpenDrain)
+            .request()
+            .unwrap();
+        let info = c.line_info(offset).unwrap();
+        drop(req);
+
+        let mut builder = Request::builder();
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        builder.using_abi_version(abiv);
+        let req = builder
+            .on_chip(s.dev_path())
+            .with_line_as_info(&info)
+            .request()
+            .unwrap();
+        assert_eq!(c.line_info(offset).unwrap(), info);
+        drop(req);
+    }
+
     #[allow(unused_variables)]
     fn request_output_lines(abiv: AbiVersion) {
         use gpiosim::Level;
@@ -796,6 +864,65 @@ mod builder {
         assert!(req.is_ok());
     }
 
+    #[test]
+    fn request_chip_handle() {
+        let s = Simpleton::new(4);
+        let c = Chip::from_path(s.dev_path()).unwrap();
+        let mut builder = Request::builder();
+        builder.on_chip_handle(&c).with_line(2).as_input();
+
+        // the builder holds its own handle to the chip
+        drop(c);
+        let req = builder.request().unwrap();
+        assert_eq!(req.chip_path(), *s.dev_path());
+        drop(req);
+
+        // and can be reused
+        let req = builder.request().unwrap();
+        assert_eq!(req.chip_path(), *s.dev_path());
+    }
+
+    #[test]
+    fn request_chip_handle_from_fd() {
+        let s = Simpleton::new(4);
+        let f = std::fs::File::open(s.dev_path()).unwrap();
+        let c = Chip::from_fd(f.into()).unwrap();
+        let req = Request::builder()
+            .on_chip_handle(&c)
+            .with_line(1)
+            .as_output(Value::Active)
+            .request()
+            .unwrap();
+        assert_eq!(req.chip_path(), c.path());
+        assert_eq!(s.get_level(1).unwrap(), gpiosim::Level::High);
+    }
+
+    #[test]
+    fn request_chip_handle_multiple() {
+        use gpiosim::Bank;
+
+        let sim = gpiosim::builder()
+            .with_bank(&Bank::new(8, "left"))
+            .with_bank(&Bank::new(4, "right"))
+            .live()
+            .unwrap();
+        let c = Chip::from_path(sim.chips()[1].dev_path()).unwrap();
+
+        let res = Request::builder()
+            .on_chip(sim.chips()[0].dev_path())
+            .with_line(5)
+            .on_chip_handle(&c)
+            .with_line(3)
+            .request();
+        assert_eq!(
+            res.unwrap_err(),
+            gpiocdev::Error::MultipleChips(vec![
+                (sim.chips()[0].dev_path().to_owned(), vec![5]),
+                (sim.chips()[1].dev_path().to_owned(), vec![])
+            ])
+        );
+    }
+
     #[test]
     fn request_chip_nonexistent() {
         let res = Request::builder()
@@ -828,7 +955,10 @@ mod builder {
             .request();
         assert_eq!(
             res.unwrap_err(),
-            gpiocdev::Error::InvalidArgument("Multiple chips requested.".into())
+            gpiocdev::Error::MultipleChips(vec![
+                (sim.chips()[0].dev_path().to_owned(), vec![5]),
+                (sim.chips()[1].dev_path().to_owned(), vec![])
+            ])
         );
     }
 
@@ -870,18 +1000,31 @@ mod request {
 
         common_tests! {
             V1,
+            consumer,
+            abi_version,
+            try_clone,
+            cached_values,
             value,
             lone_value,
             values,
             set_value,
             set_lone_value,
+            pulse,
+            direction,
             set_values,
+            set_values_from,
+            line_data,
             reconfigure,
+            refresh_config,
             has_edge_event,
             wait_edge_event,
             read_edge_event,
             new_edge_event_buffer,
-            read_edge_events_into_slice
+            read_edge_events_into_slice,
+            edge_events_with_delta,
+            debounced_edge_events,
+            coalesced_edge_events,
+            level_changes
         }
 
         #[test]
@@ -1038,6 +1181,12 @@ mod request {
             assert_eq!(evt.kind, EdgeKind::Falling);
             assert_eq!(evt.line_seqno, 0);
             assert_eq!(evt.seqno, 0);
+
+            // short slice
+            assert!(matches!(
+                req.edge_event_from_slice(&buf.as_slice()[..evt_size64 - 1]),
+                Err(gpiocdev::Error::Uapi(gpiocdev::UapiCall::LEEFromBuf, _))
+            ));
         }
 
         #[test]
@@ -1066,18 +1215,281 @@ mod request {
 
         common_tests! {
             gpiocdev::AbiVersion::V2,
+            consumer,
+            abi_version,
+            try_clone,
+            cached_values,
             value,
             lone_value,
             values,
             set_value,
             set_lone_value,
+            pulse,
+            direction,
             set_values,
+            set_values_from,
+            line_data,
             reconfigure,
+            refresh_config,
             has_edge_event,
             wait_edge_event,
             read_edge_event,
             new_edge_event_buffer,
-            read_edge_events_into_slice
+            read_edge_events_into_slice,
+            edge_events_with_delta,
+            debounced_edge_events,
+            coalesced_edge_events,
+            level_changes
+        }
+
+        #[test]
+        fn edge_events_with_delta_per_line() {
+            let s = Simpleton::new(4);
+            let offsets = &[1, 3];
+
+            let req = Request::builder()
+                .on_chip(s.dev_path())
+                .with_lines(offsets)
+                .as_input()
+                .with_edge_detection(EdgeDetection::BothEdges)
+                .request()
+                .unwrap();
+
+            s.pullup(1).unwrap();
+            wait_propagation_delay();
+            s.pullup(3).unwrap();
+            wait_propagation_delay();
+            s.pulldown(1).unwrap();
+            wait_propagation_delay();
+            s.pulldown(3).unwrap();
+            wait_propagation_delay();
+
+            let evts: Vec<_> = req
+                .edge_events_with_delta()
+                .take(4)
+                .map(|r| r.unwrap())
+                .collect();
+            assert_eq!(evts[0].0.offset, 1);
+            assert_eq!(evts[0].1, None);
+            assert_eq!(evts[1].0.offset, 3);
+            assert_eq!(evts[1].1, None);
+            assert_eq!(evts[2].0.offset, 1);
+            assert_eq!(
+                evts[2].1,
+                Some(Duration::from_nanos(
+                    evts[2].0.timestamp_ns - evts[0].0.timestamp_ns
+                ))
+            );
+            assert_eq!(evts[3].0.offset, 3);
+            assert_eq!(
+                evts[3].1,
+                Some(Duration::from_nanos(
+                    evts[3].0.timestamp_ns - evts[1].0.timestamp_ns
+                ))
+            );
+        }
+
+        #[test]
+        fn edge_event_systemtime() {
+            use gpiocdev::line::EventClock;
+            use std::time::SystemTime;
+
+            let s = Simpleton::new(4);
+
+            let req = Request::builder()
+                .on_chip(s.dev_path())
+                .with_line(1)
+                .with_edge_detection(EdgeDetection::BothEdges)
+                .with_event_clock(EventClock::Realtime)
+                .with_line(2)
+                .with_edge_detection(EdgeDetection::BothEdges)
+                .with_event_clock(EventClock::Monotonic)
+                .request()
+                .unwrap();
+
+            let start = SystemTime::now();
+            s.pullup(1).unwrap();
+            wait_propagation_delay();
+            let evt = req.read_edge_event().unwrap();
+            assert_eq!(evt.offset, 1);
+            let ts = req.edge_event_systemtime(&evt).unwrap();
+            assert_eq!(evt.systemtime(EventClock::Realtime), Some(ts));
+            let delta = match ts.duration_since(start) {
+                Ok(d) => d,
+                Err(e) => e.duration(),
+            };
+            assert!(delta < Duration::from_secs(1));
+
+            s.pullup(2).unwrap();
+            wait_propagation_delay();
+            let evt = req.read_edge_event().unwrap();
+            assert_eq!(evt.offset, 2);
+            assert_eq!(req.edge_event_systemtime(&evt), None);
+            assert!(evt.monotonic(EventClock::Monotonic).is_some());
+        }
+
+        #[test]
+        fn drain_edge_events() {
+            let s = Simpleton::new(4);
+            let offset = 2;
+
+            let req = Request::builder()
+                .on_chip(s.dev_path())
+                .with_line(offset)
+                .as_input()
+                .with_edge_detection(EdgeDetection::BothEdges)
+                .request()
+                .unwrap();
+
+            let toggle = |n| {
+                for _ in 0..n {
+                    s.toggle(offset).unwrap();
+                    wait_propagation_delay();
+                }
+            };
+
+            // rising, falling, rising
+            toggle(3);
+            let mut buf = req.new_edge_event_buffer(2);
+            assert_eq!(buf.read_event().unwrap().kind, EdgeKind::Rising);
+            assert_eq!(buf.len(), 1);
+
+            let mut cfg = req.config();
+            cfg.with_edge_detection(EdgeDetection::FallingEdge);
+            req.reconfigure(&cfg).unwrap();
+
+            // discards the buffered falling and the rising in the kernel
+            assert_eq!(buf.drain_kernel().unwrap(), 1);
+            assert!(buf.is_empty());
+            assert!(!buf.has_event().unwrap());
+            assert_eq!(buf.drain_kernel().unwrap(), 0);
+
+            // falling
+            toggle(2);
+            let evt = buf.read_event().unwrap();
+            assert_eq!(evt.kind, EdgeKind::Falling);
+            assert_eq!(evt.seqno, 4);
+            // discarded events are not reported as dropped
+            assert_eq!(req.take_overflow_count(), 0);
+
+            // two fallings
+            toggle(4);
+            assert_eq!(req.drain_edge_events().unwrap(), 2);
+            assert!(!req.has_edge_event().unwrap());
+            assert_eq!(req.drain_edge_events().unwrap(), 0);
+
+            // clear only discards the buffered events
+            // - fallings 7, 8 and 9
+            toggle(6);
+            assert_eq!(buf.read_event().unwrap().kind, EdgeKind::Falling);
+            assert_eq!(buf.len(), 1);
+            buf.clear();
+            assert!(buf.is_empty());
+            assert!(buf.has_event().unwrap());
+            let evt = buf.read_event().unwrap();
+            assert_eq!(evt.kind, EdgeKind::Falling);
+            assert_eq!(evt.seqno, 9);
+            assert_eq!(req.take_overflow_count(), 0);
+        }
+
+        #[test]
+        fn level_changes_multiple_lines() {
+            use gpiocdev::line::Value;
+
+            let s = Simpleton::new(4);
+
+            let req = Request::builder()
+                .on_chip(s.dev_path())
+                .with_lines(&[1, 2])
+                .as_input()
+                .with_edge_detection(EdgeDetection::BothEdges)
+                .with_line(3)
+                .as_input()
+                .with_edge_detection(EdgeDetection::RisingEdge)
+                .with_line(0)
+                .as_input()
+                .request()
+                .unwrap();
+
+            s.pullup(1).unwrap();
+            s.pullup(3).unwrap();
+            wait_propagation_delay();
+            let mut iter = req.level_changes();
+            // lines without edge detection are ignored
+            assert_eq!(iter.next().unwrap().unwrap(), (1, Value::Active));
+
+            // toggle between the snapshot and the first event read
+            s.pullup(2).unwrap();
+            wait_propagation_delay();
+            assert_eq!(iter.next().unwrap().unwrap(), (2, Value::Inactive));
+            assert_eq!(iter.next().unwrap().unwrap(), (3, Value::Active));
+            assert_eq!(iter.next().unwrap().unwrap(), (2, Value::Active));
+
+            s.pulldown(1).unwrap();
+            s.pullup(0).unwrap();
+            wait_propagation_delay();
+            assert_eq!(iter.next().unwrap().unwrap(), (1, Value::Inactive));
+
+            // single edge lines report each edge
+            s.pulldown(3).unwrap();
+            s.pullup(3).unwrap();
+            wait_propagation_delay();
+            assert_eq!(iter.next().unwrap().unwrap(), (3, Value::Active));
+            assert!(!req.has_edge_event().unwrap());
+        }
+
+        #[test]
+        fn take_overflow_count() {
+            let s = Simpleton::new(4);
+            let offset = 2;
+
+            let req = Request::builder()
+                .on_chip(s.dev_path())
+                .with_line(offset)
+                .as_input()
+                .with_edge_detection(EdgeDetection::BothEdges)
+                .with_kernel_event_buffer_size(2)
+                .request()
+                .unwrap();
+
+            // overflow the kernel buffer
+            for _ in 0..5 {
+                s.pullup(offset).unwrap();
+                wait_propagation_delay();
+                s.pulldown(offset).unwrap();
+                wait_propagation_delay();
+            }
+            assert_eq!(req.take_overflow_count(), 0);
+
+            // only the most recent events are retained
+            let evt = req.read_edge_event().unwrap();
+            assert_eq!(evt.seqno, 9);
+            assert_eq!(req.take_overflow_count(), 8);
+            let evt = req.read_edge_event().unwrap();
+            assert_eq!(evt.seqno, 10);
+            assert_eq!(req.take_overflow_count(), 0);
+            assert!(!req.has_edge_event().unwrap());
+
+            // no overflow
+            s.pullup(offset).unwrap();
+            wait_propagation_delay();
+            let evt = req.read_edge_event().unwrap();
+            assert_eq!(evt.kind, EdgeKind::Rising);
+            assert_eq!(evt.seqno, 11);
+            assert_eq!(req.take_overflow_count(), 0);
+
+            // tracked across clones
+            let clone = req.try_clone().unwrap();
+            for _ in 0..2 {
+                s.pulldown(offset).unwrap();
+                wait_propagation_delay();
+                s.pullup(offset).unwrap();
+                wait_propagation_delay();
+            }
+            let mut buf = clone.new_edge_event_buffer(2);
+            assert_eq!(buf.read_event().unwrap().seqno, 14);
+            assert_eq!(buf.read_event().unwrap().seqno, 15);
+            assert_eq!(req.take_overflow_count(), 2);
         }
 
         #[test]
@@ -1234,6 +1646,12 @@ mod request {
             assert_eq!(evt.kind, EdgeKind::Falling);
             assert_eq!(evt.line_seqno, 2);
             assert_eq!(evt.seqno, 4);
+
+            // short slice
+            assert!(matches!(
+                req.edge_event_from_slice(&buf.as_slice()[..evt_size64 - 1]),
+                Err(gpiocdev::Error::Uapi(gpiocdev::UapiCall::LEEFromBuf, _))
+            ));
         }
 
         #[test]
@@ -1254,90 +1672,313 @@ mod request {
         }
     }
 
-    #[allow(unused_variables)]
-    fn value(abiv: AbiVersion) {
+    fn abi_version(abiv: AbiVersion) {
         let s = Simpleton::new(3);
-        let offsets = &[0, 1, 2];
 
         let mut builder = Request::builder();
         #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
         builder.using_abi_version(abiv);
-
         let req = builder
             .on_chip(s.dev_path())
-            .with_lines(offsets)
+            .with_line(1)
             .as_input()
             .request()
             .unwrap();
-
-        for offset in offsets {
-            let v = req.value(*offset).unwrap();
-            assert_eq!(v, Value::Inactive);
-
-            s.pullup(*offset).unwrap();
-            wait_propagation_delay();
-            let v = req.value(*offset).unwrap();
-            assert_eq!(v, Value::Active);
-
-            s.pulldown(*offset).unwrap();
-            wait_propagation_delay();
-            let v = req.value(*offset).unwrap();
-            assert_eq!(v, Value::Inactive);
-        }
-
-        // invalid offset
-        let res = req.value(3);
-        assert_eq!(
-            res.unwrap_err(),
-            gpiocdev::Error::InvalidArgument("offset is not a requested line.".into())
-        );
+        assert_eq!(req.abi_version(), abiv);
     }
 
     #[allow(unused_variables)]
-    fn lone_value(abiv: AbiVersion) {
+    fn consumer(abiv: AbiVersion) {
+        use gpiocdev::chip::Chip;
+
         let s = Simpleton::new(3);
+        let c = Chip::from_path(s.dev_path()).unwrap();
         let offset = 1;
 
+        // default
         let mut builder = Request::builder();
         #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
         builder.using_abi_version(abiv);
-
         let req = builder
             .on_chip(s.dev_path())
             .with_line(offset)
             .as_input()
             .request()
             .unwrap();
-
-        let v = req.lone_value().unwrap();
-        assert_eq!(v, Value::Inactive);
-
-        s.pullup(offset).unwrap();
-        wait_propagation_delay();
-        let v = req.lone_value().unwrap();
-        assert_eq!(v, Value::Active);
-
-        s.pulldown(offset).unwrap();
-        wait_propagation_delay();
-        let v = req.lone_value().unwrap();
-        assert_eq!(v, Value::Inactive);
-
+        assert_eq!(req.consumer(), format!("gpiocdev-p{}", std::process::id()));
+        assert_eq!(c.line_info(offset).unwrap().consumer, req.consumer());
         drop(req);
 
-        // multi-line request
+        // explicit
+        let mut builder = Request::builder();
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        builder.using_abi_version(abiv);
         let req = builder
             .on_chip(s.dev_path())
-            .with_lines(&[1,2])
+            .with_consumer("banana")
+            .with_line(offset)
             .as_input()
             .request()
             .unwrap();
+        assert_eq!(req.consumer(), "banana");
+        assert_eq!(c.line_info(offset).unwrap().consumer, req.consumer());
+        drop(req);
 
-        let res = req.lone_value();
-        assert_eq!(
-            res.unwrap_err(),
-            gpiocdev::Error::InvalidArgument("request contains multiple lines.".into())
-            );
-        }
+        // longest
+        let consumer = "🦀".repeat(7) + "bar";
+        let mut builder = Request::builder();
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        builder.using_abi_version(abiv);
+        let req = builder
+            .on_chip(s.dev_path())
+            .with_consumer(consumer.as_str())
+            .with_line(offset)
+            .as_input()
+            .request()
+            .unwrap();
+        assert_eq!(c.line_info(offset).unwrap().consumer, consumer);
+        drop(req);
+
+        // too long
+        let mut builder = Request::builder();
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        builder.using_abi_version(abiv);
+        let res = builder
+            .on_chip(s.dev_path())
+            .with_consumer(consumer + "d")
+            .with_line(offset)
+            .as_input()
+            .request();
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "Consumer is 32 bytes which is greater than the maximum of 31."
+        );
+    }
+
+    #[allow(unused_variables)]
+    fn try_clone(abiv: AbiVersion) {
+        use gpiocdev::chip::Chip;
+
+        let s = Simpleton::new(3);
+        let c = Chip::from_path(s.dev_path()).unwrap();
+        let offset = 1;
+
+        let mut builder = Request::builder();
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        builder.using_abi_version(abiv);
+        let req = builder
+            .on_chip(s.dev_path())
+            .with_consumer("clone")
+            .with_line(offset)
+            .as_input()
+            .with_edge_detection(EdgeDetection::BothEdges)
+            .request()
+            .unwrap();
+        let clone = req.try_clone().unwrap();
+        assert_eq!(clone.consumer(), req.consumer());
+        assert_eq!(clone.config(), req.config());
+        assert_eq!(clone.edge_event_size(), req.edge_event_size());
+
+        // events read from either handle
+        s.pullup(offset).unwrap();
+        assert_eq!(clone.wait_edge_event(EVENT_WAIT_TIMEOUT), Ok(true));
+        assert_eq!(req.has_edge_event(), Ok(true));
+        let evt = clone.read_edge_event().unwrap();
+        assert_eq!(evt.kind, EdgeKind::Rising);
+        assert_eq!(req.has_edge_event(), Ok(false));
+        assert_eq!(req.value(offset), Ok(Value::Active));
+
+        // config is shared
+        if abiv == AbiVersion::V2 {
+            let mut cfg = req.config();
+            cfg.with_edge_detection(None);
+            req.reconfigure(&cfg).unwrap();
+            assert_eq!(clone.config(), req.config());
+        }
+
+        // lines are released when all handles are dropped
+        drop(req);
+        assert!(c.line_info(offset).unwrap().used);
+        assert_eq!(clone.value(offset), Ok(Value::Active));
+        drop(clone);
+        assert!(!c.line_info(offset).unwrap().used);
+    }
+
+    #[allow(unused_variables)]
+    fn cached_values(abiv: AbiVersion) {
+        let s = Simpleton::new(3);
+        let ttl = Duration::from_secs(60);
+
+        let mut builder = Request::builder();
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        builder.using_abi_version(abiv);
+        let req = builder
+            .on_chip(s.dev_path())
+            .with_lines(&[0, 2])
+            .as_input()
+            .request()
+            .unwrap();
+
+        let vv = req.cached_values(ttl).unwrap();
+        assert_eq!(vv.get(0), Some(Value::Inactive));
+        assert_eq!(vv.get(2), Some(Value::Inactive));
+
+        // change not visible within ttl
+        s.pullup(2).unwrap();
+        wait_propagation_delay();
+        let vv = req.cached_values(ttl).unwrap();
+        assert_eq!(vv.get(2), Some(Value::Inactive));
+
+        // but is to values
+        let mut vv = Values::default();
+        req.values(&mut vv).unwrap();
+        assert_eq!(vv.get(2), Some(Value::Active));
+
+        // and after invalidation
+        req.invalidate_value_cache();
+        let vv = req.cached_values(ttl).unwrap();
+        assert_eq!(vv.get(2), Some(Value::Active));
+
+        // and after expiry
+        s.pullup(0).unwrap();
+        wait_propagation_delay();
+        let vv = req.cached_values(ttl).unwrap();
+        assert_eq!(vv.get(0), Some(Value::Inactive));
+        let vv = req.cached_values(Duration::ZERO).unwrap();
+        assert_eq!(vv.get(0), Some(Value::Active));
+        drop(req);
+
+        // outputs are invalidated by setting values
+        let mut builder = Request::builder();
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        builder.using_abi_version(abiv);
+        let req = builder
+            .on_chip(s.dev_path())
+            .with_line(1)
+            .as_output(Value::Inactive)
+            .request()
+            .unwrap();
+        let vv = req.cached_values(ttl).unwrap();
+        assert_eq!(vv.get(1), Some(Value::Inactive));
+        req.set_lone_value(Value::Active).unwrap();
+        let vv = req.cached_values(ttl).unwrap();
+        assert_eq!(vv.get(1), Some(Value::Active));
+        req.set_value(1, Value::Inactive).unwrap();
+        let vv = req.cached_values(ttl).unwrap();
+        assert_eq!(vv.get(1), Some(Value::Inactive));
+        let mut vv = Values::default();
+        vv.set(1, Value::Active);
+        req.set_values(&vv).unwrap();
+        let vv = req.cached_values(ttl).unwrap();
+        assert_eq!(vv.get(1), Some(Value::Active));
+        drop(req);
+
+        // inputs with edge detection are invalidated by reading edges
+        let mut builder = Request::builder();
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        builder.using_abi_version(abiv);
+        let req = builder
+            .on_chip(s.dev_path())
+            .with_line(0)
+            .as_input()
+            .with_edge_detection(EdgeDetection::BothEdges)
+            .request()
+            .unwrap();
+        let vv = req.cached_values(ttl).unwrap();
+        assert_eq!(vv.get(0), Some(Value::Active));
+        s.pulldown(0).unwrap();
+        wait_propagation_delay();
+        let vv = req.cached_values(ttl).unwrap();
+        assert_eq!(vv.get(0), Some(Value::Active));
+        _ = req.read_edge_event().unwrap();
+        let vv = req.cached_values(ttl).unwrap();
+        assert_eq!(vv.get(0), Some(Value::Inactive));
+    }
+
+    #[allow(unused_variables)]
+    fn value(abiv: AbiVersion) {
+        let s = Simpleton::new(3);
+        let offsets = &[0, 1, 2];
+
+        let mut builder = Request::builder();
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        builder.using_abi_version(abiv);
+
+        let req = builder
+            .on_chip(s.dev_path())
+            .with_lines(offsets)
+            .as_input()
+            .request()
+            .unwrap();
+
+        for offset in offsets {
+            let v = req.value(*offset).unwrap();
+            assert_eq!(v, Value::Inactive);
+
+            s.pullup(*offset).unwrap();
+            wait_propagation_delay();
+            let v = req.value(*offset).unwrap();
+            assert_eq!(v, Value::Active);
+
+            s.pulldown(*offset).unwrap();
+            wait_propagation_delay();
+            let v = req.value(*offset).unwrap();
+            assert_eq!(v, Value::Inactive);
+        }
+
+        // invalid offset
+        let res = req.value(3);
+        assert_eq!(
+            res.unwrap_err(),
+            gpiocdev::Error::InvalidArgument("offset is not a requested line.".into())
+        );
+    }
+
+    #[allow(unused_variables)]
+    fn lone_value(abiv: AbiVersion) {
+        let s = Simpleton::new(3);
+        let offset = 1;
+
+        let mut builder = Request::builder();
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        builder.using_abi_version(abiv);
+
+        let req = builder
+            .on_chip(s.dev_path())
+            .with_line(offset)
+            .as_input()
+            .request()
+            .unwrap();
+
+        let v = req.lone_value().unwrap();
+        assert_eq!(v, Value::Inactive);
+
+        s.pullup(offset).unwrap();
+        wait_propagation_delay();
+        let v = req.lone_value().unwrap();
+        assert_eq!(v, Value::Active);
+
+        s.pulldown(offset).unwrap();
+        wait_propagation_delay();
+        let v = req.lone_value().unwrap();
+        assert_eq!(v, Value::Inactive);
+
+        drop(req);
+
+        // multi-line request
+        let req = builder
+            .on_chip(s.dev_path())
+            .with_lines(&[1,2])
+            .as_input()
+            .request()
+            .unwrap();
+
+        let res = req.lone_value();
+        assert_eq!(
+            res.unwrap_err(),
+            gpiocdev::Error::InvalidArgument("request contains multiple lines.".into())
+            );
+        }
 
     #[allow(unused_variables)]
     fn values(abiv: AbiVersion) {
@@ -1479,6 +2120,111 @@ mod request {
         );
     }
 
+    #[allow(unused_variables)]
+    fn pulse(abiv: AbiVersion) {
+        let s = Simpleton::new(3);
+        let offset = 1;
+        let period = Duration::from_millis(5);
+
+        let mut builder = Request::builder();
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        builder.using_abi_version(abiv);
+
+        let req = builder
+            .on_chip(s.dev_path())
+            .with_line(offset)
+            .as_output(Value::Active)
+            .request()
+            .unwrap();
+        assert_eq!(s.get_level(offset).unwrap(), gpiosim::Level::High);
+
+        // no-op
+        assert!(req.pulse(offset, 0, period, period).is_ok());
+        assert_eq!(s.get_level(offset).unwrap(), gpiosim::Level::High);
+
+        let start = std::time::Instant::now();
+        assert!(req.pulse(offset, 3, period, period).is_ok());
+        assert!(start.elapsed() >= 6 * period);
+        assert_eq!(s.get_level(offset).unwrap(), gpiosim::Level::Low);
+
+        // unrequested
+        assert_eq!(
+            req.pulse(0, 1, period, period),
+            Err(gpiocdev::Error::InvalidArgument(
+                "offset is not a requested line.".into()
+            ))
+        );
+
+        // input
+        let mut builder = Request::builder();
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        builder.using_abi_version(abiv);
+        let req = builder
+            .on_chip(s.dev_path())
+            .with_line(2)
+            .as_input()
+            .request()
+            .unwrap();
+        assert_eq!(
+            req.pulse(2, 1, period, period),
+            Err(gpiocdev::Error::InvalidArgument(
+                "line is not an output.".into()
+            ))
+        );
+    }
+
+    #[allow(unused_variables)]
+    fn direction(abiv: AbiVersion) {
+        use gpiocdev::line::Direction;
+
+        let s = Simpleton::new(4);
+
+        let mut builder = Request::builder();
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        builder.using_abi_version(abiv);
+        let req = builder
+            .on_chip(s.dev_path())
+            .with_line(1)
+            .as_output(Value::Active)
+            .request()
+            .unwrap();
+        assert_eq!(req.direction(1), Some(Direction::Output));
+        assert!(req.is_output(1));
+        assert!(!req.is_input(1));
+
+        // unrequested
+        assert_eq!(req.direction(2), None);
+        assert!(!req.is_output(2));
+        assert!(!req.is_input(2));
+
+        let mut builder = Request::builder();
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        builder.using_abi_version(abiv);
+        let req = builder
+            .on_chip(s.dev_path())
+            .with_line(2)
+            .as_input()
+            .request()
+            .unwrap();
+        assert_eq!(req.direction(2), Some(Direction::Input));
+        assert!(!req.is_output(2));
+        assert!(req.is_input(2));
+
+        // as-is
+        let mut builder = Request::builder();
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        builder.using_abi_version(abiv);
+        let req = builder
+            .on_chip(s.dev_path())
+            .with_line(3)
+            .as_is()
+            .request()
+            .unwrap();
+        assert_eq!(req.direction(3), None);
+        assert!(!req.is_output(3));
+        assert!(!req.is_input(3));
+    }
+
     #[allow(unused_variables)]
     fn set_lone_value(abiv: AbiVersion) {
         let s = Simpleton::new(3);
@@ -1628,14 +2374,123 @@ mod request {
         assert_eq!(s.get_level(3).unwrap(), Level::Low);
     }
 
-    #[allow(unused_variables)]
-    fn reconfigure(abiv: AbiVersion) {
-        use gpiocdev::line::{Bias, Direction, Drive};
+    fn set_values_from(abiv: AbiVersion) {
         use gpiosim::Level;
 
         let s = Simpleton::new(5);
-        let c = gpiocdev::chip::Chip::from_path(s.dev_path()).unwrap();
-        let offset = 1;
+        let offsets = &[0, 1, 3];
+
+        let mut builder = Request::builder();
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        builder.using_abi_version(abiv);
+
+        let req = builder
+            .on_chip(s.dev_path())
+            .with_lines(offsets)
+            .as_output(Value::Inactive)
+            .request()
+            .unwrap();
+
+        // full set, with a repeated offset taking the last value
+        assert!(req
+            .set_values_from(&[
+                (0, Value::Active),
+                (1, Value::Inactive),
+                (3, Value::Active),
+                (1, Value::Active)
+            ])
+            .is_ok());
+        assert_eq!(s.get_level(0).unwrap(), Level::High);
+        assert_eq!(s.get_level(1).unwrap(), Level::High);
+        assert_eq!(s.get_level(3).unwrap(), Level::High);
+
+        // invalid offset - ignored
+        assert!(req
+            .set_values_from(&[
+                (0, Value::Inactive),
+                (1, Value::Active),
+                (2, Value::Active),
+                (3, Value::Inactive)
+            ])
+            .is_ok());
+        assert_eq!(s.get_level(0).unwrap(), Level::Low);
+        assert_eq!(s.get_level(1).unwrap(), Level::High);
+        assert_eq!(s.get_level(2).unwrap(), Level::Low);
+        assert_eq!(s.get_level(3).unwrap(), Level::Low);
+
+        // subset
+        let res = req.set_values_from(&[(1, Value::Inactive), (2, Value::Active)]);
+        if abiv == AbiVersion::V2 {
+            assert!(res.is_ok());
+            assert_eq!(s.get_level(0).unwrap(), Level::Low);
+            assert_eq!(s.get_level(1).unwrap(), Level::Low);
+            assert_eq!(s.get_level(3).unwrap(), Level::Low);
+        } else {
+            assert_eq!(
+                res,
+                Err(gpiocdev::Error::AbiLimitation(
+                    AbiVersion::V1,
+                    "requires all requested lines".into()
+                ))
+            );
+            assert_eq!(s.get_level(1).unwrap(), Level::High);
+        }
+    }
+
+    #[allow(unused_variables)]
+    fn line_data(abiv: AbiVersion) {
+        let s = Simpleton::new(5);
+
+        let mut builder = Request::builder();
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        builder.using_abi_version(abiv);
+        let mut req = builder
+            .on_chip(s.dev_path())
+            .with_lines(&[1, 3])
+            .as_input()
+            .request()
+            .unwrap();
+
+        // unset
+        assert!(req.line_data::<&str>(1).is_none());
+
+        assert!(req.set_line_data(1, "door").is_ok());
+        assert!(req.set_line_data(3, 42_u32).is_ok());
+        assert_eq!(req.line_data::<&str>(1), Some(&"door"));
+        assert_eq!(req.line_data::<u32>(3), Some(&42));
+
+        // wrong type
+        assert!(req.line_data::<u32>(1).is_none());
+
+        // mutate and replace
+        *req.line_data_mut::<u32>(3).unwrap() += 1;
+        assert_eq!(req.line_data::<u32>(3), Some(&43));
+        assert!(req.set_line_data(1, String::from("window")).is_ok());
+        assert!(req.line_data::<&str>(1).is_none());
+        assert_eq!(req.line_data::<String>(1).unwrap(), "window");
+
+        // not a requested line
+        assert_eq!(
+            req.set_line_data(2, "nope"),
+            Err(gpiocdev::Error::InvalidArgument(
+                "offset is not a requested line.".into()
+            ))
+        );
+        assert!(req.line_data::<&str>(2).is_none());
+
+        // not shared with clones
+        let clone = req.try_clone().unwrap();
+        assert!(clone.line_data::<u32>(3).is_none());
+    }
+
+    #[allow(unused_variables)]
+    fn reconfigure(abiv: AbiVersion) {
+        use gpiocdev::line::{Bias, Direction, Drive};
+        use gpiosim::Level;
+
+        let s = Simpleton::new(5);
+        let c = gpiocdev::chip::Chip::from_path(s.dev_path()).unwrap();
+        let offset = 1;
 
         let mut builder = Request::builder();
         #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
@@ -1675,6 +2530,51 @@ mod request {
         assert_eq!(s.get_level(offset).unwrap(), Level::High);
     }
 
+    #[allow(unused_variables)]
+    fn refresh_config(abiv: AbiVersion) {
+        use gpiocdev::line::{Bias, Direction, EventClock};
+
+        let s = Simpleton::new(5);
+
+        let mut builder = Request::builder();
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        builder.using_abi_version(abiv);
+
+        let req = builder
+            .on_chip(s.dev_path())
+            .with_line(1)
+            .as_output(Value::Active)
+            .with_bias(Bias::PullDown)
+            .with_line(3)
+            .as_input()
+            .with_edge_detection(EdgeDetection::RisingEdge)
+            .request()
+            .unwrap();
+
+        assert!(req.refresh_config().is_ok());
+
+        // output value is retained
+        let lcfg = req.line_config(1).unwrap();
+        assert_eq!(lcfg.direction, Some(Direction::Output));
+        assert_eq!(lcfg.bias, Some(Bias::PullDown));
+        assert_eq!(lcfg.value, Some(Value::Active));
+
+        // the event clock is reported by the kernel
+        let lcfg = req.line_config(3).unwrap();
+        assert_eq!(lcfg.direction, Some(Direction::Input));
+        assert_eq!(lcfg.edge_detection, Some(EdgeDetection::RisingEdge));
+        if abiv == AbiVersion::V2 {
+            assert_eq!(lcfg.event_clock, Some(EventClock::Monotonic));
+        } else {
+            assert_eq!(lcfg.event_clock, None);
+        }
+        assert_eq!(lcfg.value, None);
+
+        // a clone shares the refreshed config
+        let clone = req.try_clone().unwrap();
+        assert_eq!(clone.config(), req.config());
+    }
+
     #[allow(unused_variables)]
     fn has_edge_event(abiv: AbiVersion) {
         let s = Simpleton::new(3);
@@ -1821,6 +2721,197 @@ mod request {
         let mut buf = req.new_edge_event_buffer(4);
         assert_eq!(buf.has_event(), Ok(false));
         assert_eq!(buf.capacity(), 4);
+
+        // events are read from the kernel in bulk
+        for _ in 0..6 {
+            s.toggle(offset).unwrap();
+            wait_propagation_delay();
+        }
+        assert_eq!(buf.next().unwrap().unwrap().kind, EdgeKind::Rising);
+        assert_eq!(buf.len(), 3);
+        for _ in 0..3 {
+            buf.read_event().unwrap();
+        }
+        assert!(buf.is_empty());
+        assert_eq!(buf.next().unwrap().unwrap().kind, EdgeKind::Rising);
+        assert_eq!(buf.len(), 1);
+        assert!(!buf.kernel_may_have_events());
+
+        // zero capacity is clamped
+        let buf = req.new_edge_event_buffer(0);
+        assert_eq!(buf.capacity(), 1);
+
+        // while edge_events reads individually by default
+        for _ in 0..2 {
+            s.toggle(offset).unwrap();
+            wait_propagation_delay();
+        }
+        let mut buf = req.edge_events();
+        assert_eq!(buf.capacity(), 1);
+        assert!(buf.next().unwrap().is_ok());
+        assert!(buf.is_empty());
+    }
+
+    #[allow(unused_variables)]
+    fn edge_events_with_delta(abiv: AbiVersion) {
+        let s = Simpleton::new(3);
+        let offset = 2;
+        let period = Duration::from_millis(20);
+
+        let mut builder = Request::builder();
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        builder.using_abi_version(abiv);
+
+        let req = builder
+            .on_chip(s.dev_path())
+            .with_line(offset)
+            .as_input()
+            .with_edge_detection(EdgeDetection::BothEdges)
+            .request()
+            .unwrap();
+
+        s.pullup(offset).unwrap();
+        std::thread::sleep(period);
+        s.pulldown(offset).unwrap();
+        std::thread::sleep(period);
+        s.pullup(offset).unwrap();
+        wait_propagation_delay();
+
+        let mut iter = req.edge_events_with_delta();
+        let (evt, delta) = iter.next().unwrap().unwrap();
+        assert_eq!(evt.kind, EdgeKind::Rising);
+        assert_eq!(evt.offset, offset);
+        assert_eq!(delta, None);
+        let prev_ts = evt.timestamp_ns;
+
+        let (evt, delta) = iter.next().unwrap().unwrap();
+        assert_eq!(evt.kind, EdgeKind::Falling);
+        let delta = delta.unwrap();
+        assert_eq!(delta, Duration::from_nanos(evt.timestamp_ns - prev_ts));
+        assert!(delta >= period);
+        let prev_ts = evt.timestamp_ns;
+
+        let (evt, delta) = iter.next().unwrap().unwrap();
+        assert_eq!(evt.kind, EdgeKind::Rising);
+        let delta = delta.unwrap();
+        assert_eq!(delta, Duration::from_nanos(evt.timestamp_ns - prev_ts));
+        assert!(delta >= period);
+    }
+
+    #[allow(unused_variables)]
+    fn debounced_edge_events(abiv: AbiVersion) {
+        let s = Simpleton::new(3);
+        let offset = 2;
+        let period = Duration::from_millis(20);
+
+        let mut builder = Request::builder();
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        builder.using_abi_version(abiv);
+
+        let req = builder
+            .on_chip(s.dev_path())
+            .with_line(offset)
+            .as_input()
+            .with_edge_detection(EdgeDetection::BothEdges)
+            .request()
+            .unwrap();
+
+        // bounce, settling high
+        s.pullup(offset).unwrap();
+        s.pulldown(offset).unwrap();
+        s.pullup(offset).unwrap();
+        wait_propagation_delay();
+        let mut iter = req.debounced_edge_events(period);
+        let evt = iter.next().unwrap().unwrap();
+        assert_eq!(evt.kind, EdgeKind::Rising);
+        assert_eq!(evt.offset, offset);
+        // identified by the first edge - seqnos are zero for uAPI v1
+        assert!(evt.line_seqno <= 1, "line_seqno {}", evt.line_seqno);
+
+        // bounce, settling back high
+        s.pulldown(offset).unwrap();
+        s.pullup(offset).unwrap();
+        wait_propagation_delay();
+        std::thread::sleep(period);
+        // single clean edge
+        s.pulldown(offset).unwrap();
+        wait_propagation_delay();
+        let evt = iter.next().unwrap().unwrap();
+        assert_eq!(evt.kind, EdgeKind::Falling);
+        assert!(!req.has_edge_event().unwrap());
+    }
+
+    #[allow(unused_variables)]
+    fn coalesced_edge_events(abiv: AbiVersion) {
+        let s = Simpleton::new(3);
+        let offset = 2;
+        let max_rate = Duration::from_millis(50);
+
+        let mut builder = Request::builder();
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        builder.using_abi_version(abiv);
+
+        let req = builder
+            .on_chip(s.dev_path())
+            .with_line(offset)
+            .as_input()
+            .with_edge_detection(EdgeDetection::BothEdges)
+            .request()
+            .unwrap();
+
+        let mut iter = req.coalesced_edge_events(max_rate);
+        s.pullup(offset).unwrap();
+        wait_propagation_delay();
+        let ce = iter.next().unwrap().unwrap();
+        assert_eq!(ce.event.kind, EdgeKind::Rising);
+        assert_eq!(ce.event.offset, offset);
+        assert_eq!(ce.suppressed, 0);
+
+        // burst within the window
+        s.pulldown(offset).unwrap();
+        s.pullup(offset).unwrap();
+        s.pulldown(offset).unwrap();
+        wait_propagation_delay();
+        let ce = iter.next().unwrap().unwrap();
+        assert_eq!(ce.event.kind, EdgeKind::Falling);
+        assert_eq!(ce.suppressed, 2);
+        assert!(!req.has_edge_event().unwrap());
+    }
+
+    #[allow(unused_variables)]
+    fn level_changes(abiv: AbiVersion) {
+        let s = Simpleton::new(3);
+        let offset = 2;
+
+        let mut builder = Request::builder();
+        #[cfg(all(feature = "uapi_v1", feature = "uapi_v2"))]
+        builder.using_abi_version(abiv);
+
+        let req = builder
+            .on_chip(s.dev_path())
+            .with_line(offset)
+            .as_input()
+            .with_edge_detection(EdgeDetection::BothEdges)
+            .request()
+            .unwrap();
+
+        // edges before the snapshot are discarded
+        s.pullup(offset).unwrap();
+        s.pulldown(offset).unwrap();
+        s.pullup(offset).unwrap();
+        wait_propagation_delay();
+        let mut iter = req.level_changes();
+        assert_eq!(iter.next().unwrap().unwrap(), (offset, Value::Active));
+
+        // toggle between the snapshot and the first event read
+        s.pulldown(offset).unwrap();
+        wait_propagation_delay();
+        assert_eq!(iter.next().unwrap().unwrap(), (offset, Value::Inactive));
+
+        s.pullup(offset).unwrap();
+        wait_propagation_delay();
+        assert_eq!(iter.next().unwrap().unwrap(), (offset, Value::Active));
+        assert!(!req.has_edge_event().unwrap());
     }
 }
 
@@ -1924,6 +3015,261 @@ mod edge_event_buffer {
         assert!(buf.is_empty());
     }
 
+    #[test]
+    fn kernel_may_have_events() {
+        let s = Simpleton::new(3);
+        let offset = 2;
+
+        let req = Request::builder()
+            .on_chip(s.dev_path())
+            .with_line(offset)
+            .as_input()
+            .with_edge_detection(EdgeDetection::BothEdges)
+            .request()
+            .unwrap();
+        let mut buf = req.new_edge_event_buffer(2);
+        // unknown until read
+        assert!(buf.kernel_may_have_events());
+
+        // create three events
+        s.toggle(offset).unwrap();
+        wait_propagation_delay();
+        s.toggle(offset).unwrap();
+        wait_propagation_delay();
+        s.toggle(offset).unwrap();
+        wait_propagation_delay();
+
+        // full read - one remaining in the buffer
+        _ = buf.read_event().unwrap();
+        assert!(!buf.kernel_may_have_events());
+
+        // buffer drained after a full read
+        _ = buf.read_event().unwrap();
+        assert!(buf.kernel_may_have_events());
+
+        // short read
+        _ = buf.read_event().unwrap();
+        assert!(!buf.kernel_may_have_events());
+        assert_eq!(buf.has_event(), Ok(false));
+    }
+
+    #[test]
+    fn read_until_empty() {
+        let s = Simpleton::new(3);
+        let offset = 2;
+
+        let req = Request::builder()
+            .on_chip(s.dev_path())
+            .with_line(offset)
+            .as_input()
+            .with_edge_detection(EdgeDetection::BothEdges)
+            .request()
+            .unwrap();
+        let mut buf = req.new_edge_event_buffer(4);
+
+        // nothing to read - doesn't block
+        assert_eq!(buf.read_until_empty(|_| panic!("unexpected event")), Ok(0));
+
+        // a burst spanning several reads, ending with a short read
+        for _ in 0..10 {
+            s.toggle(offset).unwrap();
+            wait_propagation_delay();
+        }
+        let mut kinds = Vec::new();
+        assert_eq!(buf.read_until_empty(|evt| kinds.push(evt.kind)), Ok(10));
+        assert_eq!(kinds.len(), 10);
+        for (i, kind) in kinds.iter().enumerate() {
+            if i % 2 == 0 {
+                assert_eq!(*kind, EdgeKind::Rising);
+            } else {
+                assert_eq!(*kind, EdgeKind::Falling);
+            }
+        }
+        assert!(buf.is_empty());
+        assert!(!buf.kernel_may_have_events());
+        assert_eq!(buf.has_event(), Ok(false));
+
+        // a burst filling the buffer exactly - the final check of the kernel
+        // must not block
+        for _ in 0..8 {
+            s.toggle(offset).unwrap();
+            wait_propagation_delay();
+        }
+        assert_eq!(buf.read_until_empty(|_| {}), Ok(8));
+        assert!(buf.is_empty());
+        assert!(buf.kernel_may_have_events());
+        assert_eq!(buf.has_event(), Ok(false));
+
+        // partially consumed buffer
+        for _ in 0..6 {
+            s.toggle(offset).unwrap();
+            wait_propagation_delay();
+        }
+        _ = buf.read_event().unwrap();
+        assert_eq!(buf.len(), 3);
+        assert_eq!(buf.read_until_empty(|_| {}), Ok(5));
+        assert!(!buf.kernel_may_have_events());
+    }
+
+    #[test]
+    fn drain() {
+        let s = Simpleton::new(3);
+        let offset = 2;
+
+        let req = Request::builder()
+            .on_chip(s.dev_path())
+            .with_line(offset)
+            .as_input()
+            .with_edge_detection(EdgeDetection::BothEdges)
+            .request()
+            .unwrap();
+        let mut buf = req.new_edge_event_buffer(4);
+
+        // nothing to read - doesn't block
+        assert_eq!(buf.drain(), Ok(Vec::new()));
+
+        // events spanning the buffer and the kernel
+        for _ in 0..6 {
+            s.toggle(offset).unwrap();
+            wait_propagation_delay();
+        }
+        _ = buf.read_event().unwrap();
+        assert_eq!(buf.len(), 3);
+        let events = buf.drain().unwrap();
+        assert_eq!(events.len(), 5);
+        for (i, evt) in events.iter().enumerate() {
+            if i % 2 == 0 {
+                assert_eq!(evt.kind, EdgeKind::Falling);
+            } else {
+                assert_eq!(evt.kind, EdgeKind::Rising);
+            }
+            assert_eq!(evt.offset, offset);
+        }
+        assert!(buf.is_empty());
+        assert_eq!(buf.has_event(), Ok(false));
+        assert_eq!(buf.drain(), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn peek_event() {
+        let s = Simpleton::new(3);
+        let offset = 2;
+
+        let req = Request::builder()
+            .on_chip(s.dev_path())
+            .with_line(offset)
+            .as_input()
+            .with_edge_detection(EdgeDetection::BothEdges)
+            .request()
+            .unwrap();
+        let mut buf = req.new_edge_event_buffer(2);
+
+        // nothing to peek - doesn't block
+        assert_eq!(buf.peek_event(), Ok(None));
+
+        // events spanning the buffer and the kernel
+        for _ in 0..3 {
+            s.toggle(offset).unwrap();
+            wait_propagation_delay();
+        }
+        let evt = buf.peek_event().unwrap().unwrap();
+        assert_eq!(evt.kind, EdgeKind::Rising);
+        assert_eq!(buf.len(), 2);
+        assert_eq!(buf.peek_event(), Ok(Some(evt)));
+        assert_eq!(buf.read_event(), Ok(evt));
+        assert_eq!(buf.len(), 1);
+
+        let evt = buf.peek_event().unwrap().unwrap();
+        assert_eq!(evt.kind, EdgeKind::Falling);
+        assert_eq!(buf.read_event(), Ok(evt));
+        assert!(buf.is_empty());
+
+        // refilled from the kernel
+        let evt = buf.peek_event().unwrap().unwrap();
+        assert_eq!(evt.kind, EdgeKind::Rising);
+        assert_eq!(buf.len(), 1);
+        assert_eq!(buf.read_event(), Ok(evt));
+
+        assert_eq!(buf.peek_event(), Ok(None));
+    }
+
+    #[test]
+    fn iter() {
+        let s = Simpleton::new(3);
+        let offset = 2;
+
+        let req = Request::builder()
+            .on_chip(s.dev_path())
+            .with_line(offset)
+            .as_input()
+            .with_edge_detection(EdgeDetection::BothEdges)
+            .request()
+            .unwrap();
+        let mut buf = req.new_edge_event_buffer(2);
+
+        for _ in 0..5 {
+            s.toggle(offset).unwrap();
+            wait_propagation_delay();
+        }
+        let events = buf.iter().take(3).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].kind, EdgeKind::Rising);
+        assert_eq!(events[1].kind, EdgeKind::Falling);
+        assert_eq!(events[2].kind, EdgeKind::Rising);
+
+        // buffer still usable after the iterator is dropped
+        let evt = buf.read_event().unwrap();
+        assert_eq!(evt.kind, EdgeKind::Falling);
+        let evt = buf.iter().next().unwrap().unwrap();
+        assert_eq!(evt.kind, EdgeKind::Rising);
+        assert_eq!(evt.offset, offset);
+        assert_eq!(buf.has_event(), Ok(false));
+    }
+
+    #[test]
+    fn try_iter() {
+        let s = Simpleton::new(3);
+        let offset = 2;
+
+        let req = Request::builder()
+            .on_chip(s.dev_path())
+            .with_line(offset)
+            .as_input()
+            .with_edge_detection(EdgeDetection::BothEdges)
+            .request()
+            .unwrap();
+        let mut buf = req.new_edge_event_buffer(2);
+
+        // nothing to read - doesn't block
+        assert!(buf.try_iter().next().is_none());
+
+        // events spanning the buffer and the kernel
+        for _ in 0..5 {
+            s.toggle(offset).unwrap();
+            wait_propagation_delay();
+        }
+        let mut count = 0;
+        for (i, evt) in buf.try_iter().enumerate() {
+            let evt = evt.unwrap();
+            if i % 2 == 0 {
+                assert_eq!(evt.kind, EdgeKind::Rising);
+            } else {
+                assert_eq!(evt.kind, EdgeKind::Falling);
+            }
+            assert_eq!(evt.offset, offset);
+            count += 1;
+        }
+        assert_eq!(count, 5);
+        assert!(buf.try_iter().next().is_none());
+
+        // resumes when more events arrive
+        s.toggle(offset).unwrap();
+        wait_propagation_delay();
+        let events = buf.try_iter().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, EdgeKind::Falling);
+    }
+
     #[test]
     fn has_event() {
         let s = Simpleton::new(3);
@@ -2001,7 +3347,7 @@ mod edge_event_buffer {
         assert_eq!(buf.has_event(), Ok(false));
 
         s.pullup(offset).unwrap();
-        let evt = buf.wait_event(EVENT_WAIT_TIMEOUT).unwrap();
+        let evt = buf.wait_event(EVENT_WAIT_TIMEOUT).unwrap().unwrap();
         assert_eq!(evt.kind, EdgeKind::Rising);
         assert_eq!(evt.offset, offset);
         #[cfg(feature = "uapi_v2")]
@@ -2011,13 +3357,46 @@ mod edge_event_buffer {
         }
 
         s.pulldown(offset).unwrap();
-        let evt = buf.wait_event(EVENT_WAIT_TIMEOUT).unwrap();
+        let evt = buf.wait_event(EVENT_WAIT_TIMEOUT).unwrap().unwrap();
         assert_eq!(evt.kind, EdgeKind::Falling);
         #[cfg(feature = "uapi_v2")]
         {
             assert_eq!(evt.line_seqno, 2);
             assert_eq!(evt.seqno, 2);
         }
+
+        // timeout
+        assert_eq!(buf.wait_event(Duration::from_millis(10)), Ok(None));
+    }
+
+    #[test]
+    fn wait_event_buffered() {
+        let s = Simpleton::new(3);
+        let offset = 2;
+
+        let req = Request::builder()
+            .on_chip(s.dev_path())
+            .with_line(offset)
+            .as_input()
+            .with_edge_detection(EdgeDetection::BothEdges)
+            .request()
+            .unwrap();
+        let mut buf = req.new_edge_event_buffer(4);
+
+        s.pullup(offset).unwrap();
+        wait_propagation_delay();
+        s.pulldown(offset).unwrap();
+        wait_propagation_delay();
+        // fills the buffer with both events
+        let evt = buf.read_event().unwrap();
+        assert_eq!(evt.kind, EdgeKind::Rising);
+        assert_eq!(buf.len(), 1);
+        assert_eq!(req.has_edge_event(), Ok(false));
+
+        // buffered event is returned without waiting
+        let evt = buf.wait_event(Duration::ZERO).unwrap().unwrap();
+        assert_eq!(evt.kind, EdgeKind::Falling);
+        assert_eq!(buf.wait_event(Duration::ZERO), Ok(None));
     }
 }
 
@@ -2,49 +2,85 @@
 //
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use std::ffi::OsStr;
-use std::fs::File;
-use std::os::unix::prelude::{AsRawFd, OsStrExt};
-use std::ptr;
-use std::slice;
-use std::time::Duration;
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::{
+    ffi::OsStr,
+    fs::File,
+    os::unix::prelude::{AsRawFd, OsStrExt},
+    ptr, slice,
+    time::{Duration, Instant},
+};
 
 /// Check if the file has an event available to read.
 ///
 /// For gpiochip files the events are LineInfoChangeEvent.
 /// For line request files the events are LineEdgeEvent.
+#[cfg(feature = "std")]
 #[inline]
 pub fn has_event(f: &File) -> Result<bool> {
     wait_event(f, Duration::ZERO)
 }
 
 // workaround musl and android libc::ioctl() having a different signature
-#[cfg(any(target_env = "musl", target_os = "android"))]
+#[cfg(all(feature = "std", any(target_env = "musl", target_os = "android")))]
 pub(crate) type IoctlRequestType = libc::c_int;
-#[cfg(not(any(target_env = "musl", target_os = "android")))]
+#[cfg(all(feature = "std", not(any(target_env = "musl", target_os = "android"))))]
 pub(crate) type IoctlRequestType = libc::c_ulong;
 
+#[cfg(feature = "std")]
 macro_rules! ior {
     ($nr:expr, $dty:ty) => {
         ioctl_sys::ior!(IOCTL_MAGIC, $nr, std::mem::size_of::<$dty>()) as IoctlRequestType
     };
 }
 
+#[cfg(feature = "std")]
 macro_rules! iorw {
     ($nr:expr, $dty:ty) => {
         ioctl_sys::iorw!(IOCTL_MAGIC, $nr, std::mem::size_of::<$dty>()) as IoctlRequestType
     };
 }
+#[cfg(feature = "std")]
 pub(crate) use iorw;
 
+// Repeat a system call while it is interrupted by a signal.
+//
+// The call is expected to return -1 and set errno on error, so any other error is
+// left in errno for the caller.
+#[cfg(feature = "std")]
+#[inline]
+pub(crate) fn retry_eintr<T, F>(mut f: F) -> T
+where
+    T: Copy + PartialEq + From<i8>,
+    F: FnMut() -> T,
+{
+    loop {
+        let rc = f();
+        if rc != T::from(-1) || !interrupted() {
+            return rc;
+        }
+    }
+}
+
+// Check if errno indicates the last system call was interrupted by a signal.
+#[cfg(feature = "std")]
+#[inline]
+fn interrupted() -> bool {
+    std::io::Error::last_os_error().raw_os_error() == Some(libc::EINTR)
+}
+
 /// Read an event from a chip or request file descriptor.
 ///
 /// Returns the number of u64 words read.
+#[cfg(feature = "std")]
 #[inline]
 pub fn read_event(f: &File, buf: &mut [u64]) -> Result<usize> {
     unsafe {
         let bufptr: *mut libc::c_void = std::ptr::addr_of_mut!(*buf) as *mut libc::c_void;
-        match libc::read(f.as_raw_fd(), bufptr, buf.len() * 8) {
+        match retry_eintr(|| libc::read(f.as_raw_fd(), bufptr, buf.len() * 8)) {
             -1 => Err(Error::from_errno()),
             x => {
                 let size: usize = x.try_into().unwrap();
@@ -63,7 +99,29 @@ pub fn read_event(f: &File, buf: &mut [u64]) -> Result<usize> {
 }
 
 /// Wait for the file to have an event available to read.
+///
+/// If the wait is interrupted by a signal it is resumed for the remainder of the
+/// timeout.
+#[cfg(feature = "std")]
 pub fn wait_event(f: &File, d: Duration) -> Result<bool> {
+    let deadline = Instant::now().checked_add(d);
+    let mut timeout = d;
+    loop {
+        match poll_event(f, timeout) {
+            -1 if interrupted() => {
+                if let Some(deadline) = deadline {
+                    timeout = deadline.saturating_duration_since(Instant::now());
+                }
+            }
+            -1 => return Err(Error::from_errno()),
+            0 => return Ok(false),
+            _ => return Ok(true),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+fn poll_event(f: &File, d: Duration) -> libc::c_int {
     let mut pfd = libc::pollfd {
         fd: f.as_raw_fd(),
         events: libc::POLLIN,
@@ -81,21 +139,19 @@ pub fn wait_event(f: &File, d: Duration) -> Result<bool> {
         tv_nsec: d.subsec_nanos() as libc::c_long,
     };
     unsafe {
-        match libc::ppoll(
+        libc::ppoll(
             std::ptr::addr_of_mut!(pfd),
             1,
             std::ptr::addr_of!(timeout),
             ptr::null(),
-        ) {
-            -1 => Err(Error::from_errno()),
-            0 => Ok(false),
-            _ => Ok(true),
-        }
+        )
     }
 }
 
+#[cfg(feature = "std")]
 pub(crate) const IOCTL_MAGIC: u8 = 0xb4;
 
+#[cfg(feature = "std")]
 #[repr(u8)]
 enum Ioctl {
     GetChipInfo = 1,
@@ -121,14 +177,17 @@ pub struct ChipInfo {
 /// Get the publicly available information for a chip.
 ///
 /// * `cf` - The open gpiochip device file.
+#[cfg(feature = "std")]
 pub fn get_chip_info(cf: &File) -> Result<ChipInfo> {
     let mut chip = std::mem::MaybeUninit::<ChipInfo>::uninit();
     unsafe {
-        match libc::ioctl(
-            cf.as_raw_fd(),
-            ior!(Ioctl::GetChipInfo, ChipInfo),
-            chip.as_mut_ptr(),
-        ) {
+        match retry_eintr(|| {
+            libc::ioctl(
+                cf.as_raw_fd(),
+                ior!(Ioctl::GetChipInfo, ChipInfo),
+                chip.as_mut_ptr(),
+            )
+        }) {
             0 => Ok(chip.assume_init()),
             _ => Err(Error::from_errno()),
         }
@@ -141,63 +200,98 @@ pub fn get_chip_info(cf: &File) -> Result<ChipInfo> {
 /// * `offset` - The offset of the line to unwatch.
 ///
 /// [`LineInfo`]: struct.LineInfo.html
+#[cfg(feature = "std")]
 pub fn unwatch_line_info(cf: &File, offset: Offset) -> Result<()> {
-    match unsafe { libc::ioctl(cf.as_raw_fd(), iorw!(Ioctl::UnwatchLineInfo, u32), &offset) } {
+    match unsafe {
+        retry_eintr(|| libc::ioctl(cf.as_raw_fd(), iorw!(Ioctl::UnwatchLineInfo, u32), &offset))
+    } {
         0 => Ok(()),
         _ => Err(Error::from_errno()),
     }
 }
 
 /// An error number returned by a kernel ioctl call.
-#[derive(Clone, Debug, thiserror::Error, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Errno(pub i32);
 
+#[cfg(feature = "std")]
+impl std::error::Error for Errno {}
+
+#[cfg(feature = "std")]
 impl From<&std::io::Error> for Errno {
     fn from(e: &std::io::Error) -> Self {
         Errno(e.raw_os_error().unwrap_or(0))
     }
 }
-impl std::fmt::Display for Errno {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl fmt::Display for Errno {
+    #[cfg(feature = "std")]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", std::io::Error::from_raw_os_error(self.0))
     }
+    #[cfg(not(feature = "std"))]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "os error {}", self.0)
+    }
 }
 
 /// The result returned by [`gpiocdev_uapi`] functions.
 ///
 /// [`gpiocdev_uapi`]: crate
-pub type Result<T> = std::result::Result<T, Error>;
+pub type Result<T> = core::result::Result<T, Error>;
 
 /// Result returned by struct validators.
-pub type ValidationResult = std::result::Result<(), ValidationError>;
+pub type ValidationResult = core::result::Result<(), ValidationError>;
 
 /// Errors returned by [`gpiocdev_uapi`] functions.
 ///
 /// [`gpiocdev_uapi`]: crate
-#[derive(Clone, Debug, thiserror::Error, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Error {
     /// An error returned from an underlying system call.
-    #[error(transparent)]
     Os(Errno),
 
     /// An error indicating insufficient data read for the expected object.
-    #[error(transparent)]
-    UnderRead(#[from] UnderReadError),
+    UnderRead(UnderReadError),
 
     /// An error validating an data structure retuned from the kernel
-    #[error(transparent)]
-    Validation(#[from] ValidationError),
+    Validation(ValidationError),
+}
+
+// Error is only implemented with std, see the crate features.
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Os(e) => e.fmt(f),
+            Error::UnderRead(e) => e.fmt(f),
+            Error::Validation(e) => e.fmt(f),
+        }
+    }
+}
+
+impl From<UnderReadError> for Error {
+    fn from(e: UnderReadError) -> Self {
+        Error::UnderRead(e)
+    }
+}
+
+impl From<ValidationError> for Error {
+    fn from(e: ValidationError) -> Self {
+        Error::Validation(e)
+    }
 }
 
 impl Error {
     /// Create an error from the current errno value.
     #[inline]
-    #[cfg(target_os = "linux")]
+    #[cfg(all(feature = "std", target_os = "linux"))]
     pub fn from_errno() -> Error {
         Error::Os(Errno(unsafe { *libc::__errno_location() }))
     }
     #[inline]
-    #[cfg(target_os = "android")]
+    #[cfg(all(feature = "std", target_os = "android"))]
     pub fn from_errno() -> Error {
         Error::Os(Errno(std::io::Error::last_os_error().raw_os_error().unwrap()))
     }
@@ -206,8 +300,7 @@ impl Error {
 /// A failure to read sufficient bytes to construct an object.
 //
 // This should never happen - but is checked to be safe.
-#[derive(Clone, Debug, thiserror::Error, Eq, PartialEq)]
-#[error("Reading {obj} returned {found} bytes, expected {expected}.")]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct UnderReadError {
     /// The struct that under read.
     pub obj: &'static str,
@@ -217,6 +310,19 @@ pub struct UnderReadError {
     pub found: usize,
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for UnderReadError {}
+
+impl fmt::Display for UnderReadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Reading {} returned {} bytes, expected {}.",
+            self.obj, self.found, self.expected
+        )
+    }
+}
+
 impl UnderReadError {
     /// Create an UnderReadError.
     pub(crate) fn new(obj: &'static str, expected: usize, found: usize) -> UnderReadError {
@@ -231,21 +337,46 @@ impl UnderReadError {
 /// A failure to validate a struct returned from a system call.
 //
 // Should only be seen if a kernel update adds an enum value we are unaware of.
-#[derive(Clone, Debug, thiserror::Error, Eq, PartialEq)]
-#[error("Kernel returned invalid {field}: {msg}")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
 pub struct ValidationError {
     /// The field that failed to validate.
     pub field: String,
     /// The details of the validation failure.
     pub msg: String,
+    /// The raw value of the field, if the field is an identifier unknown to this crate.
+    pub value: Option<u32>,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ValidationError {}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Kernel returned invalid {}: {}", self.field, self.msg)
+    }
 }
 
 impl ValidationError {
     /// Create a ValidationError.
+    #[cfg(all(
+        any(feature = "std", test),
+        any(feature = "uapi_v2", not(feature = "uapi_v1"))
+    ))]
     pub(crate) fn new<S: Into<String>, T: Into<String>>(field: S, msg: T) -> ValidationError {
         ValidationError {
             field: field.into(),
             msg: msg.into(),
+            value: None,
+        }
+    }
+
+    /// Create a ValidationError for a field containing an unknown identifier.
+    pub(crate) fn invalid_value<S: Into<String>>(field: S, value: u32) -> ValidationError {
+        ValidationError {
+            field: field.into(),
+            msg: format!("invalid value: {}", value),
+            value: Some(value),
         }
     }
 }
@@ -272,6 +403,7 @@ impl Name {
     }
 
     /// Convert the contained name to a OsString slice.
+    #[cfg(feature = "std")]
     #[inline]
     pub fn as_os_str(&self) -> &OsStr {
         unsafe { OsStr::from_bytes(slice::from_raw_parts(&self.0[0], self.strlen())) }
@@ -304,7 +436,7 @@ impl Name {
 }
 impl From<&Name> for String {
     fn from(s: &Name) -> Self {
-        String::from(s.as_os_str().to_string_lossy())
+        String::from_utf8_lossy(&s.0[..s.strlen()]).into_owned()
     }
 }
 impl From<&str> for Name {
@@ -352,7 +484,7 @@ impl Offsets {
 
     /// Copy offsets from an iterable list.
     pub fn copy_from_slice(&mut self, s: &[u32]) {
-        let extent = std::cmp::min(NUM_LINES_MAX, s.len());
+        let extent = core::cmp::min(NUM_LINES_MAX, s.len());
         self.0[0..extent].copy_from_slice(s);
     }
 }
@@ -407,7 +539,7 @@ pub enum LineInfoChangeKind {
 impl TryFrom<u32> for LineInfoChangeKind {
     type Error = String;
 
-    fn try_from(v: u32) -> std::result::Result<Self, Self::Error> {
+    fn try_from(v: u32) -> core::result::Result<Self, Self::Error> {
         Ok(match v {
             x if x == LineInfoChangeKind::Requested as u32 => LineInfoChangeKind::Requested,
             x if x == LineInfoChangeKind::Released as u32 => LineInfoChangeKind::Released,
@@ -419,8 +551,11 @@ impl TryFrom<u32> for LineInfoChangeKind {
 
 impl LineInfoChangeKind {
     /// Confirm that the value read from the kernel is valid in Rust.
-    pub(crate) fn validate(&self) -> std::result::Result<(), String> {
-        LineInfoChangeKind::try_from(*self as u32).map(|_i| ())
+    ///
+    /// Returns the raw value if it is not valid.
+    pub(crate) fn validate(&self) -> core::result::Result<(), u32> {
+        let v = *self as u32;
+        LineInfoChangeKind::try_from(v).map(|_i| ()).map_err(|_| v)
     }
 }
 
@@ -440,7 +575,7 @@ pub enum LineEdgeEventKind {
 impl TryFrom<u32> for LineEdgeEventKind {
     type Error = String;
 
-    fn try_from(v: u32) -> std::result::Result<Self, Self::Error> {
+    fn try_from(v: u32) -> core::result::Result<Self, Self::Error> {
         Ok(match v {
             x if x == LineEdgeEventKind::RisingEdge as u32 => LineEdgeEventKind::RisingEdge,
             x if x == LineEdgeEventKind::FallingEdge as u32 => LineEdgeEventKind::FallingEdge,
@@ -451,8 +586,11 @@ impl TryFrom<u32> for LineEdgeEventKind {
 
 impl LineEdgeEventKind {
     /// Confirm that the value read from the kernel is valid in Rust.
-    pub(crate) fn validate(&self) -> std::result::Result<(), String> {
-        LineEdgeEventKind::try_from(*self as u32).map(|_i| ())
+    ///
+    /// Returns the raw value if it is not valid.
+    pub(crate) fn validate(&self) -> core::result::Result<(), u32> {
+        let v = *self as u32;
+        LineEdgeEventKind::try_from(v).map(|_i| ()).map_err(|_| v)
     }
 }
 
@@ -460,6 +598,92 @@ impl LineEdgeEventKind {
 mod tests {
     use super::*;
 
+    // A pipe, returning the read and write ends.
+    #[cfg(feature = "std")]
+    fn pipe() -> (File, File) {
+        use std::os::unix::prelude::FromRawFd;
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+        unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) }
+    }
+
+    // Interrupt the calling thread with a signal after the delay.
+    //
+    // The signal handler is installed without SA_RESTART, so blocking system calls
+    // return EINTR.
+    #[cfg(feature = "std")]
+    fn interrupt_after(delay: Duration) -> std::thread::JoinHandle<()> {
+        extern "C" fn handler(_: libc::c_int) {}
+        unsafe {
+            let mut sa = std::mem::zeroed::<libc::sigaction>();
+            sa.sa_sigaction = handler as *const () as usize;
+            assert_eq!(libc::sigaction(libc::SIGUSR1, &sa, ptr::null_mut()), 0);
+        }
+        let thread = unsafe { libc::pthread_self() };
+        std::thread::spawn(move || {
+            std::thread::sleep(delay);
+            unsafe { libc::pthread_kill(thread, libc::SIGUSR1) };
+        })
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", target_os = "linux"))]
+    fn retry_eintr() {
+        let mut calls = 0;
+        let rc = super::retry_eintr(|| {
+            calls += 1;
+            if calls < 3 {
+                unsafe { *libc::__errno_location() = libc::EINTR };
+                return -1;
+            }
+            0
+        });
+        assert_eq!(rc, 0);
+        assert_eq!(calls, 3);
+
+        // other errors are returned
+        calls = 0;
+        let rc = super::retry_eintr(|| {
+            calls += 1;
+            unsafe { *libc::__errno_location() = libc::EBUSY };
+            -1
+        });
+        assert_eq!(rc, -1);
+        assert_eq!(calls, 1);
+        assert_eq!(Error::from_errno(), Error::Os(Errno(libc::EBUSY)));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn wait_event_interrupted() {
+        let (r, _w) = pipe();
+        let timeout = Duration::from_millis(100);
+        let start = Instant::now();
+        let t = interrupt_after(Duration::from_millis(20));
+        assert_eq!(wait_event(&r, timeout), Ok(false));
+        // the wait is resumed, but not extended
+        let elapsed = start.elapsed();
+        assert!(elapsed >= timeout);
+        assert!(elapsed < 2 * timeout);
+        t.join().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn read_event_interrupted() {
+        use std::io::Write;
+        let (r, mut w) = pipe();
+        let t = interrupt_after(Duration::from_millis(20));
+        let writer = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            w.write_all(&[0; 8]).unwrap();
+        });
+        let mut buf = [0_u64; 1];
+        assert_eq!(read_event(&r, &mut buf), Ok(1));
+        t.join().unwrap();
+        writer.join().unwrap();
+    }
+
     #[test]
     fn size_of_chip_info() {
         assert_eq!(
@@ -475,9 +699,9 @@ mod tests {
         assert!(a.validate().is_ok());
         unsafe {
             a = *(&0 as *const i32 as *const LineInfoChangeKind);
-            assert_eq!(a.validate().unwrap_err(), "invalid value: 0");
+            assert_eq!(a.validate().unwrap_err(), 0);
             a = *(&4 as *const i32 as *const LineInfoChangeKind);
-            assert_eq!(a.validate().unwrap_err(), "invalid value: 4");
+            assert_eq!(a.validate().unwrap_err(), 4);
             a = *(&3 as *const i32 as *const LineInfoChangeKind);
             assert!(a.validate().is_ok());
         }
@@ -489,15 +713,16 @@ mod tests {
         assert!(a.validate().is_ok());
         unsafe {
             a = *(&0 as *const i32 as *const LineInfoChangeKind);
-            assert_eq!(a.validate().unwrap_err(), "invalid value: 0");
+            assert_eq!(a.validate().unwrap_err(), 0);
             a = *(&4 as *const i32 as *const LineInfoChangeKind);
-            assert_eq!(a.validate().unwrap_err(), "invalid value: 4");
+            assert_eq!(a.validate().unwrap_err(), 4);
             a = *(&3 as *const i32 as *const LineInfoChangeKind);
             assert!(a.validate().is_ok());
         }
     }
 
     #[test]
+    #[cfg(feature = "std")]
     fn name_from_str() {
         let mut x = [0u8; 32];
         x[0] = 98;
@@ -541,6 +766,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "std")]
     fn name_as_os_str() {
         let mut a = Name::default();
         assert_eq!(a.as_os_str(), "");
@@ -549,6 +775,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "std")]
     fn name_from_bytes() {
         // empty
         let mut a = Name::from_bytes("some bytes".as_bytes());
@@ -603,6 +830,16 @@ mod tests {
         assert_eq!(a.as_os_str(), "overly long truncated name -𝄞");
     }
 
+    #[test]
+    fn name_to_string() {
+        let mut a = Name::default();
+        assert_eq!(String::from(&a), "");
+        a = "banana".into();
+        assert_eq!(String::from(&a), "banana");
+        a = "an overly long truncated name -><- cut here".into();
+        assert_eq!(String::from(&a), "an overly long truncated name ->");
+    }
+
     #[test]
     fn name_default() {
         assert_eq!(Name::default().0, [0u8; NAME_LEN_MAX]);
@@ -3,14 +3,27 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 //! A thin but safe Rust layer around the Linux GPIO uAPI.
+//!
+//! # Features
+//!
+//! The functions that perform system calls on the GPIO character device files
+//! are only available with the `std` feature, which is enabled by default.
+//!
+//! Without `std` the crate is `no_std`, though still requires `alloc`, and provides
+//! only the uAPI data structures, so they may be used with other runtimes.
+//! The error types then implement `Display`, but not `Error`, as `core::error::Error`
+//! requires a later Rust than the crate supports.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
 
 pub(crate) mod common;
 
 // move ops into v1/v2??
-pub use common::{
-    has_event, read_event, wait_event, Errno, Error, Name, Result, ValidationError, NAME_LEN_MAX,
-    NUM_LINES_MAX,
-};
+#[cfg(feature = "std")]
+pub use common::{has_event, read_event, wait_event};
+pub use common::{Errno, Error, Name, Result, ValidationError, NAME_LEN_MAX, NUM_LINES_MAX};
 
 /// This module implements GPIO ABI v1 which was released in Linux v4.8.
 ///
@@ -3,12 +3,15 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use bitflags::bitflags;
+#[cfg(feature = "std")]
 use std::fs::File;
+#[cfg(feature = "std")]
 use std::os::unix::prelude::{AsRawFd, FromRawFd};
 
 // common to ABI v1 and v2.
 pub use super::common::*;
 
+#[cfg(feature = "std")]
 #[repr(u8)]
 enum Ioctl {
     GetLineInfo = 2,
@@ -84,13 +87,16 @@ bitflags! {
 /// * 'cf' - The open gpiochip device file.
 /// * `offset` - The offset of the line.
 #[inline]
+#[cfg(feature = "std")]
 pub fn get_line_info(cf: &File, offset: Offset) -> Result<LineInfo> {
     let li = LineInfo {
         offset,
         ..Default::default()
     };
     // SAFETY: returned struct contains raw byte arrays and bitfields that are safe to decode.
-    match unsafe { libc::ioctl(cf.as_raw_fd(), iorw!(Ioctl::GetLineInfo, LineInfo), &li) } {
+    match unsafe {
+        retry_eintr(|| libc::ioctl(cf.as_raw_fd(), iorw!(Ioctl::GetLineInfo, LineInfo), &li))
+    } {
         0 => Ok(li),
         _ => Err(Error::from_errno()),
     }
@@ -105,13 +111,16 @@ pub fn get_line_info(cf: &File, offset: Offset) -> Result<LineInfo> {
 /// * 'cf' - The open gpiochip device file.
 /// * `offset` - The offset of the line to watch.
 #[inline]
+#[cfg(feature = "std")]
 pub fn watch_line_info(cf: &File, offset: Offset) -> Result<LineInfo> {
     let li = LineInfo {
         offset,
         ..Default::default()
     };
     // SAFETY: returned struct contains raw byte arrays and bitfields that are safe to decode.
-    match unsafe { libc::ioctl(cf.as_raw_fd(), iorw!(Ioctl::WatchLineInfo, LineInfo), &li) } {
+    match unsafe {
+        retry_eintr(|| libc::ioctl(cf.as_raw_fd(), iorw!(Ioctl::WatchLineInfo, LineInfo), &li))
+    } {
         0 => Ok(li),
         _ => Err(Error::from_errno()),
     }
@@ -141,12 +150,12 @@ impl LineInfoChangeEvent {
     /// The buffer is assumed to have been populated by a read of the chip File,
     /// so the content is validated before being returned.
     pub fn from_slice(d: &[u64]) -> Result<&LineInfoChangeEvent> {
-        debug_assert!(std::mem::size_of::<LineInfoChangeEvent>() % 8 == 0);
+        debug_assert!(core::mem::size_of::<LineInfoChangeEvent>() % 8 == 0);
         let len = d.len() * 8;
-        if len < std::mem::size_of::<LineInfoChangeEvent>() {
+        if len < core::mem::size_of::<LineInfoChangeEvent>() {
             return Err(Error::from(UnderReadError::new(
                 "LineInfoChangeEvent",
-                std::mem::size_of::<LineInfoChangeEvent>(),
+                core::mem::size_of::<LineInfoChangeEvent>(),
                 len,
             )));
         }
@@ -159,12 +168,12 @@ impl LineInfoChangeEvent {
     fn validate(&self) -> ValidationResult {
         self.kind
             .validate()
-            .map_err(|e| ValidationError::new("kind", e))
+            .map_err(|v| ValidationError::invalid_value("kind", v))
     }
 
     /// The number of u64 words required to store a LineInfoChangeEvent.
     pub fn u64_size() -> usize {
-        std::mem::size_of::<LineInfoChangeEvent>() / 8
+        core::mem::size_of::<LineInfoChangeEvent>() / 8
     }
 }
 
@@ -245,14 +254,17 @@ bitflags! {
 /// * 'cf' - The open gpiochip device file.
 /// * `hr` - The line handle request.
 #[inline]
+#[cfg(feature = "std")]
 pub fn get_line_handle(cf: &File, hr: HandleRequest) -> Result<File> {
     // SAFETY: hr is consumed and the returned file is drawn from the returned fd.
     unsafe {
-        match libc::ioctl(
-            cf.as_raw_fd(),
-            iorw!(Ioctl::GetLineHandle, HandleRequest),
-            &hr,
-        ) {
+        match retry_eintr(|| {
+            libc::ioctl(
+                cf.as_raw_fd(),
+                iorw!(Ioctl::GetLineHandle, HandleRequest),
+                &hr,
+            )
+        }) {
             0 => Ok(File::from_raw_fd(hr.fd)),
             _ => Err(Error::from_errno()),
         }
@@ -284,10 +296,13 @@ pub struct HandleConfig {
 /// * `lf` - The request file returned by [`get_line_handle`].
 /// * `hc` - The configuration to be applied.
 #[inline]
+#[cfg(feature = "std")]
 pub fn set_line_config(lf: &File, hc: HandleConfig) -> Result<()> {
     // SAFETY: hc is consumed.
     unsafe {
-        match libc::ioctl(lf.as_raw_fd(), iorw!(Ioctl::SetConfig, HandleConfig), &hc) {
+        match retry_eintr(|| {
+            libc::ioctl(lf.as_raw_fd(), iorw!(Ioctl::SetConfig, HandleConfig), &hc)
+        }) {
             0 => Ok(()),
             _ => Err(Error::from_errno()),
         }
@@ -325,7 +340,7 @@ impl LineValues {
 
     /// Copy values from an iterable list - in order of requested offsets.
     pub fn copy_from_slice(&mut self, s: &[u8]) {
-        let extent = std::cmp::min(64usize, s.len());
+        let extent = core::cmp::min(64usize, s.len());
         self.0[0..extent].copy_from_slice(s);
     }
 
@@ -367,14 +382,17 @@ impl Default for LineValues {
 /// * `lf` - The request file returned by [`get_line_handle`] or [`get_line_event`].
 /// * `vals` - The line values to be populated.
 #[inline]
+#[cfg(feature = "std")]
 pub fn get_line_values(lf: &File, vals: &mut LineValues) -> Result<()> {
     // SAFETY: vals are raw integers that are safe to decode.
     match unsafe {
-        libc::ioctl(
-            lf.as_raw_fd(),
-            iorw!(Ioctl::GetLineValues, LineValues),
-            vals.0.as_mut_ptr(),
-        )
+        retry_eintr(|| {
+            libc::ioctl(
+                lf.as_raw_fd(),
+                iorw!(Ioctl::GetLineValues, LineValues),
+                vals.0.as_mut_ptr(),
+            )
+        })
     } {
         0 => Ok(()),
         _ => Err(Error::from_errno()),
@@ -386,14 +404,17 @@ pub fn get_line_values(lf: &File, vals: &mut LineValues) -> Result<()> {
 /// * `lf` - The request file returned by [`get_line_handle`].
 /// * `vals` - The line values to be set.
 #[inline]
+#[cfg(feature = "std")]
 pub fn set_line_values(lf: &File, vals: &LineValues) -> Result<()> {
     // SAFETY: vals is not modified.
     match unsafe {
-        libc::ioctl(
-            lf.as_raw_fd(),
-            iorw!(Ioctl::SetLineValues, LineValues),
-            vals.0.as_ptr(),
-        )
+        retry_eintr(|| {
+            libc::ioctl(
+                lf.as_raw_fd(),
+                iorw!(Ioctl::SetLineValues, LineValues),
+                vals.0.as_ptr(),
+            )
+        })
     } {
         0 => Ok(()),
         _ => Err(Error::from_errno()),
@@ -448,14 +469,17 @@ bitflags! {
 /// * 'cf' - The open gpiochip device file.
 /// * `er` - The line event request.
 #[inline]
+#[cfg(feature = "std")]
 pub fn get_line_event(cf: &File, er: EventRequest) -> Result<File> {
     // SAFETY: er is consumed and the returned file is drawn from the returned fd.
     unsafe {
-        match libc::ioctl(
-            cf.as_raw_fd(),
-            iorw!(Ioctl::GetLineEvent, EventRequest),
-            &er,
-        ) {
+        match retry_eintr(|| {
+            libc::ioctl(
+                cf.as_raw_fd(),
+                iorw!(Ioctl::GetLineEvent, EventRequest),
+                &er,
+            )
+        }) {
             0 => Ok(File::from_raw_fd(er.fd)),
             _ => Err(Error::from_errno()),
         }
@@ -478,12 +502,12 @@ impl LineEdgeEvent {
     /// The buffer is assumed to have been populated by a read of the line request File,
     /// so the content is validated before being returned.
     pub fn from_slice(d: &[u64]) -> Result<&LineEdgeEvent> {
-        debug_assert!(std::mem::size_of::<LineEdgeEvent>() % 8 == 0);
+        debug_assert!(core::mem::size_of::<LineEdgeEvent>() % 8 == 0);
         let len = d.len() * 8;
-        if len < std::mem::size_of::<LineEdgeEvent>() {
+        if len < core::mem::size_of::<LineEdgeEvent>() {
             return Err(Error::from(UnderReadError::new(
                 "LineEdgeEvent",
-                std::mem::size_of::<LineEdgeEvent>(),
+                core::mem::size_of::<LineEdgeEvent>(),
                 len,
             )));
         }
@@ -496,12 +520,12 @@ impl LineEdgeEvent {
     fn validate(&self) -> ValidationResult {
         self.kind
             .validate()
-            .map_err(|e| ValidationError::new("kind", e))
+            .map_err(|v| ValidationError::invalid_value("kind", v))
     }
 
     /// The number of u64 words required to store a LineEdgeEvent.
     pub fn u64_size() -> usize {
-        std::mem::size_of::<LineEdgeEvent>() / 8
+        core::mem::size_of::<LineEdgeEvent>() / 8
     }
 }
 
@@ -552,6 +576,7 @@ mod tests {
                 let e = a.validate().unwrap_err();
                 assert_eq!(e.field, "kind");
                 assert_eq!(e.msg, "invalid value: 0");
+                assert_eq!(e.value, Some(0));
                 a.kind = *(&4 as *const i32 as *const LineInfoChangeKind);
                 let e = a.validate().unwrap_err();
                 assert_eq!(e.field, "kind");
@@ -628,6 +653,7 @@ mod tests {
                 let e = a.validate().unwrap_err();
                 assert_eq!(e.field, "kind");
                 assert_eq!(e.msg, "invalid value: 0");
+                assert_eq!(e.value, Some(0));
                 a.kind = *(&3 as *const i32 as *const LineEdgeEventKind);
                 let e = a.validate().unwrap_err();
                 assert_eq!(e.field, "kind");
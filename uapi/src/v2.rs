@@ -2,15 +2,20 @@
 //
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+use alloc::format;
+use alloc::string::String;
 use bitflags::bitflags;
-use std::fmt;
+use core::fmt;
+use core::time::Duration;
+#[cfg(feature = "std")]
 use std::fs::File;
+#[cfg(feature = "std")]
 use std::os::unix::prelude::{AsRawFd, FromRawFd};
-use std::time::Duration;
 
 // common to ABI v1 and v2.
 pub use super::common::*;
 
+#[cfg(feature = "std")]
 #[repr(u8)]
 enum Ioctl {
     GetLineInfo = 5,
@@ -98,7 +103,7 @@ impl LineValues {
 
     /// Copy values from an iterable list - in order of requested offsets.
     pub fn copy_from_slice(&mut self, s: &[bool]) {
-        let extent = std::cmp::min(64usize, s.len());
+        let extent = core::cmp::min(64usize, s.len());
         for (i, v) in s.iter().enumerate().take(extent) {
             self.set(i, *v);
         }
@@ -165,9 +170,18 @@ impl LineValues {
 /// * `lf` - The request file returned by [`get_line`].
 /// * `lv` - The line values to be populated.
 #[inline]
+#[cfg(feature = "std")]
 pub fn get_line_values(lf: &File, lv: &mut LineValues) -> Result<()> {
     // SAFETY: returned struct contains raw byte arrays and bitfields that are safe to decode.
-    match unsafe { libc::ioctl(lf.as_raw_fd(), iorw!(Ioctl::GetLineValues, LineValues), lv) } {
+    match unsafe {
+        retry_eintr(|| {
+            libc::ioctl(
+                lf.as_raw_fd(),
+                iorw!(Ioctl::GetLineValues, LineValues),
+                &mut *lv,
+            )
+        })
+    } {
         0 => Ok(()),
         _ => Err(Error::from_errno()),
     }
@@ -180,9 +194,12 @@ pub fn get_line_values(lf: &File, lv: &mut LineValues) -> Result<()> {
 /// * `lf` - The request file returned by [`get_line`].
 /// * `lv` - The line values to be set.
 #[inline]
+#[cfg(feature = "std")]
 pub fn set_line_values(lf: &File, lv: &LineValues) -> Result<()> {
     // SAFETY: lv is not modified.
-    match unsafe { libc::ioctl(lf.as_raw_fd(), iorw!(Ioctl::SetLineValues, LineValues), lv) } {
+    match unsafe {
+        retry_eintr(|| libc::ioctl(lf.as_raw_fd(), iorw!(Ioctl::SetLineValues, LineValues), lv))
+    } {
         0 => Ok(()),
         _ => Err(Error::from_errno()),
     }
@@ -209,7 +226,7 @@ pub enum LineAttributeKind {
 impl TryFrom<u32> for LineAttributeKind {
     type Error = String;
 
-    fn try_from(v: u32) -> std::result::Result<Self, Self::Error> {
+    fn try_from(v: u32) -> core::result::Result<Self, Self::Error> {
         use LineAttributeKind::*;
         Ok(match v {
             x if x == Unused as u32 => Unused,
@@ -223,7 +240,8 @@ impl TryFrom<u32> for LineAttributeKind {
 
 impl LineAttributeKind {
     /// Confirm that the value read from the kernel is valid in Rust.
-    fn validate(&self) -> std::result::Result<(), String> {
+    #[cfg(any(feature = "std", test))]
+    fn validate(&self) -> core::result::Result<(), String> {
         LineAttributeKind::try_from(*self as u32).map(|_i| ())
     }
 }
@@ -447,10 +465,13 @@ impl LineConfig {
 /// * `lf` - The request file returned by [`get_line`].
 /// * `lc` - The configuration to be applied.
 #[inline]
+#[cfg(feature = "std")]
 pub fn set_line_config(lf: &File, lc: LineConfig) -> Result<()> {
     // SAFETY: lc is consumed.
     unsafe {
-        match libc::ioctl(lf.as_raw_fd(), iorw!(Ioctl::SetLineConfig, LineConfig), &lc) {
+        match retry_eintr(|| {
+            libc::ioctl(lf.as_raw_fd(), iorw!(Ioctl::SetLineConfig, LineConfig), &lc)
+        }) {
             0 => Ok(()),
             _ => Err(Error::from_errno()),
         }
@@ -500,10 +521,11 @@ pub struct LineRequest {
 /// * `cf` - The open gpiochip device file.
 /// * `lr` - The line request.
 #[inline]
+#[cfg(feature = "std")]
 pub fn get_line(cf: &File, lr: LineRequest) -> Result<File> {
     // SAFETY: lr is consumed and the returned file is drawn from the returned fd.
     unsafe {
-        match libc::ioctl(cf.as_raw_fd(), iorw!(Ioctl::GetLine, LineRequest), &lr) {
+        match retry_eintr(|| libc::ioctl(cf.as_raw_fd(), iorw!(Ioctl::GetLine, LineRequest), &lr)) {
             0 => Ok(File::from_raw_fd(lr.fd)),
             _ => Err(Error::from_errno()),
         }
@@ -564,6 +586,7 @@ impl LineInfo {
         &self.attrs.0[idx]
     }
     /// Check that a LineInfo read from the kernel is valid in Rust.
+    #[cfg(any(feature = "std", test))]
     fn validate(&self) -> ValidationResult {
         if self.num_attrs > NUM_ATTRS_MAX as u32 {
             return Err(ValidationError::new(
@@ -588,13 +611,16 @@ impl LineInfo {
 /// * `cf` - The open gpiochip device file.
 /// * `offset` - The offset of the line.
 #[inline]
+#[cfg(feature = "std")]
 pub fn get_line_info(cf: &File, offset: Offset) -> Result<LineInfo> {
     let li = LineInfo {
         offset,
         ..Default::default()
     };
     // SAFETY: returned struct is explicitly validated before being returned.
-    match unsafe { libc::ioctl(cf.as_raw_fd(), iorw!(Ioctl::GetLineInfo, LineInfo), &li) } {
+    match unsafe {
+        retry_eintr(|| libc::ioctl(cf.as_raw_fd(), iorw!(Ioctl::GetLineInfo, LineInfo), &li))
+    } {
         0 => li.validate().map(|_| li).map_err(Error::from),
         _ => Err(Error::from_errno()),
     }
@@ -610,13 +636,16 @@ pub fn get_line_info(cf: &File, offset: Offset) -> Result<LineInfo> {
 /// * `cf` - The open gpiochip device file.
 /// * `offset` - The offset of the line to watch.
 #[inline]
+#[cfg(feature = "std")]
 pub fn watch_line_info(cf: &File, offset: Offset) -> Result<LineInfo> {
     let li = LineInfo {
         offset,
         ..Default::default()
     };
     // SAFETY: returned struct is explicitly validated before being returned.
-    match unsafe { libc::ioctl(cf.as_raw_fd(), iorw!(Ioctl::WatchLineInfo, LineInfo), &li) } {
+    match unsafe {
+        retry_eintr(|| libc::ioctl(cf.as_raw_fd(), iorw!(Ioctl::WatchLineInfo, LineInfo), &li))
+    } {
         0 => li.validate().map(|_| li).map_err(Error::from),
         _ => Err(Error::from_errno()),
     }
@@ -646,12 +675,12 @@ impl LineInfoChangeEvent {
     /// The buffer is assumed to have been populated by a read of the chip File,
     /// so the content is validated before being returned.
     pub fn from_slice(d: &[u64]) -> Result<&LineInfoChangeEvent> {
-        debug_assert!(std::mem::size_of::<LineInfoChangeEvent>() % 8 == 0);
+        debug_assert!(core::mem::size_of::<LineInfoChangeEvent>() % 8 == 0);
         let len = d.len() * 8;
-        if len < std::mem::size_of::<LineInfoChangeEvent>() {
+        if len < core::mem::size_of::<LineInfoChangeEvent>() {
             return Err(Error::from(UnderReadError::new(
                 "LineInfoChangeEvent",
-                std::mem::size_of::<LineInfoChangeEvent>(),
+                core::mem::size_of::<LineInfoChangeEvent>(),
                 len,
             )));
         }
@@ -664,12 +693,12 @@ impl LineInfoChangeEvent {
     fn validate(&self) -> ValidationResult {
         self.kind
             .validate()
-            .map_err(|e| ValidationError::new("kind", e))
+            .map_err(|v| ValidationError::invalid_value("kind", v))
     }
 
     /// The number of u64 words required to store a LineInfoChangeEvent.
     pub fn u64_size() -> usize {
-        std::mem::size_of::<LineInfoChangeEvent>() / 8
+        core::mem::size_of::<LineInfoChangeEvent>() / 8
     }
 }
 
@@ -713,12 +742,12 @@ impl LineEdgeEvent {
     /// so the content is validated before being returned.
     #[inline]
     pub fn from_slice(d: &[u64]) -> Result<&LineEdgeEvent> {
-        debug_assert!(std::mem::size_of::<LineEdgeEvent>() % 8 == 0);
+        debug_assert!(core::mem::size_of::<LineEdgeEvent>() % 8 == 0);
         let len = d.len() * 8;
-        if len < std::mem::size_of::<LineEdgeEvent>() {
+        if len < core::mem::size_of::<LineEdgeEvent>() {
             return Err(Error::from(UnderReadError::new(
                 "LineEdgeEvent",
-                std::mem::size_of::<LineEdgeEvent>(),
+                core::mem::size_of::<LineEdgeEvent>(),
                 len,
             )));
         }
@@ -731,12 +760,12 @@ impl LineEdgeEvent {
     fn validate(&self) -> ValidationResult {
         self.kind
             .validate()
-            .map_err(|e| ValidationError::new("kind", e))
+            .map_err(|v| ValidationError::invalid_value("kind", v))
     }
 
     /// The number of u64 words required to store a LineEdgeEvent.
     pub fn u64_size() -> usize {
-        std::mem::size_of::<LineEdgeEvent>() / 8
+        core::mem::size_of::<LineEdgeEvent>() / 8
     }
 }
 
@@ -950,6 +979,7 @@ mod tests {
                 let e = a.validate().unwrap_err();
                 assert_eq!(e.field, "kind");
                 assert_eq!(e.msg, "invalid value: 0");
+                assert_eq!(e.value, Some(0));
 
                 a.kind = *(&4 as *const i32 as *const LineInfoChangeKind);
                 let e = a.validate().unwrap_err();
@@ -992,6 +1022,7 @@ mod tests {
                 let e = a.validate().unwrap_err();
                 assert_eq!(e.field, "kind");
                 assert_eq!(e.msg, "invalid value: 0");
+                assert_eq!(e.value, Some(0));
 
                 a.kind = *(&3 as *const i32 as *const LineEdgeEventKind);
                 let e = a.validate().unwrap_err();